@@ -0,0 +1,109 @@
+//! Automatic replies to incoming chat messages (bot mode).
+//!
+//! With an [`AutoReply`] in the [`ClientConfig`](crate::ClientConfig), the client answers
+//! every chat message from another participant with a reply rendered from a template.
+//! To avoid reply loops, it never answers its own messages nor those of other bots,
+//! recognized by the prefix of their client ID.
+
+/// Default prefix of the client IDs of bots, whose messages are never answered
+pub const DEFAULT_BOT_PREFIX: &str = "bot";
+
+/// Placeholder replaced by the client ID of the sender
+const FROM_PLACEHOLDER: &str = "{from}";
+
+/// Placeholder replaced by the content of the received message
+const CONTENT_PLACEHOLDER: &str = "{content}";
+
+/// Template of the reply sent to each incoming chat message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AutoReply {
+    /// Reply text, where `{from}` and `{content}` stand for the received message
+    template: String,
+    /// Messages from client IDs starting with this prefix are not answered (empty: answer all)
+    bot_prefix: String,
+}
+
+impl AutoReply {
+    /// Reply with `template`, where `{from}` and `{content}` stand for the received message
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            template: template.into(),
+            bot_prefix: DEFAULT_BOT_PREFIX.to_string(),
+        }
+    }
+
+    /// Set the client ID prefix of the bots not to answer (default: [`DEFAULT_BOT_PREFIX`])
+    ///
+    /// An empty prefix answers every other participant.
+    pub fn with_bot_prefix(mut self, bot_prefix: impl Into<String>) -> Self {
+        self.bot_prefix = bot_prefix.into();
+        self
+    }
+
+    /// Render the reply to a chat message, if it should be answered
+    ///
+    /// # Arguments
+    ///
+    /// * `own_client_id` - The client ID of this client
+    /// * `from` - The client ID of the sender of the message
+    /// * `content` - The content of the message
+    ///
+    /// # Returns
+    ///
+    /// `None` for messages from this client or from a bot, and for empty replies
+    pub fn reply_to(&self, own_client_id: &str, from: &str, content: &str) -> Option<String> {
+        if from == own_client_id
+            || (!self.bot_prefix.is_empty() && from.starts_with(&self.bot_prefix))
+        {
+            return None;
+        }
+
+        // Placeholders in the inserted values are left as they are
+        let reply = self
+            .template
+            .split(FROM_PLACEHOLDER)
+            .map(|part| part.replace(CONTENT_PLACEHOLDER, content))
+            .collect::<Vec<_>>()
+            .join(from);
+        (!reply.trim().is_empty()).then_some(reply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_fills_placeholders_once() {
+        // テスト項目: テンプレートの {from} と {content} が受信したメッセージで置き換えられ、
+        //            差し込んだ値に含まれるプレースホルダーは置き換えられない
+        // given (前提条件):
+        let auto_reply = AutoReply::new("@{from} you said: {content}");
+
+        // when (操作):
+        let reply = auto_reply.reply_to("echo", "alice", "hi {from}");
+
+        // then (期待する結果):
+        assert_eq!(reply, Some("@alice you said: hi {from}".to_string()));
+    }
+
+    #[test]
+    fn test_no_reply_to_itself_or_to_bots() {
+        // テスト項目: 自分自身と、接頭辞でボットと分かる参加者のメッセージには返信しない
+        // given (前提条件):
+        let auto_reply = AutoReply::new("{content}");
+
+        // when (操作):
+        let to_itself = auto_reply.reply_to("echo", "echo", "hi");
+        let to_bot = auto_reply.reply_to("echo", "bot-faq", "hi");
+        let to_bot_without_prefix = auto_reply
+            .clone()
+            .with_bot_prefix("")
+            .reply_to("echo", "bot-faq", "hi");
+
+        // then (期待する結果):
+        assert_eq!(to_itself, None);
+        assert_eq!(to_bot, None);
+        assert_eq!(to_bot_without_prefix, Some("hi".to_string()));
+    }
+}
@@ -9,34 +9,163 @@
 //! ```not_rust
 //! cargo run --bin client -- --client-id Alice
 //! cargo run --bin client -- -c Bob
+//! ENGAWA_CLIENT_ID=bot ENGAWA_URL=ws://chat:8080/ws cargo run --bin client
+//! cargo run --bin client -- -c notice --message-file notice.txt
+//! cargo run --bin client -- -c echo --auto-reply "@{from} you said: {content}"
 //! ```
 
+use std::{io::IsTerminal, path::PathBuf, time::Duration};
+
 use clap::Parser;
-use engawa_client::run;
-use engawa_shared::logger::setup_logger;
+use engawa_client::{
+    AutoReply, CLIENT_ID_ENV, ClientConfig, DEFAULT_BOT_PREFIX, DEFAULT_CONNECT_TIMEOUT,
+    FormatterConfig, MessageFormatter, URL_ENV, read_message_file, resolve_client_id, resolve_url,
+    run, send_message_once, validate_connection_target,
+};
+use engawa_shared::logger::{setup_logger, setup_stderr_logger};
 
 #[derive(Parser, Debug)]
 #[command(name = "client")]
 #[command(about = "WebSocket chat client with broadcast support and unique client ID", long_about = None)]
 struct Args {
-    /// Client ID for identifying messages (must be unique)
+    /// Client ID for identifying messages (must be unique) [env: ENGAWA_CLIENT_ID]
     #[arg(short = 'c', long)]
-    client_id: String,
+    client_id: Option<String>,
+
+    /// WebSocket server URL [env: ENGAWA_URL] [default: ws://127.0.0.1:8080/ws]
+    #[arg(short = 'u', long)]
+    url: Option<String>,
+
+    /// Refresh the participant list from the server every N seconds
+    #[arg(long)]
+    roster_refresh_secs: Option<u64>,
+
+    /// Send a presence heartbeat every N seconds, keeping the client active while silent
+    #[arg(long, value_name = "SECS")]
+    heartbeat_secs: Option<u64>,
+
+    /// The server echoes sent messages back; display the echo instead of a local "sent" line
+    #[arg(long)]
+    server_echo: bool,
+
+    /// Initial presence status shown to other participants
+    #[arg(long, value_parser = ["active", "away", "dnd"])]
+    status: Option<String>,
+
+    /// Maximum number of unacknowledged messages kept for re-sending after a reconnect
+    #[arg(long, default_value_t = ClientConfig::default().outbox_capacity)]
+    outbox_capacity: usize,
+
+    /// Print received messages as JSON lines and send JSON lines read from stdin
+    /// (no interactive display; logs go to stderr)
+    #[arg(long)]
+    json: bool,
+
+    /// Show received messages with their original line endings
+    /// (by default `\r\n` and `\r` are shown as `\n`)
+    #[arg(long)]
+    raw_newlines: bool,
+
+    /// Seconds allowed for establishing the connection before retrying
+    #[arg(long, value_name = "SECS", default_value_t = DEFAULT_CONNECT_TIMEOUT.as_secs())]
+    connect_timeout: u64,
+
+    /// Longest wait in seconds between reconnection attempts, even if the server suggests longer
+    #[arg(long, value_name = "SECS")]
+    max_reconnect_delay_secs: Option<u64>,
+
+    /// Give up reconnecting after this many seconds, regardless of the number of attempts
+    #[arg(long, value_name = "SECS")]
+    max_total_reconnect_secs: Option<u64>,
+
+    /// Send the contents of the file as a single message, then exit
+    #[arg(long, value_name = "PATH", conflicts_with = "json")]
+    message_file: Option<PathBuf>,
 
-    /// WebSocket server URL
-    #[arg(short = 'u', long, default_value = "ws://127.0.0.1:8080/ws")]
-    url: String,
+    /// Answer each chat message from another participant with this template
+    /// (`{from}` and `{content}` are replaced by the sender and the message)
+    #[arg(long, value_name = "TEMPLATE", conflicts_with = "json")]
+    auto_reply: Option<String>,
+
+    /// Do not auto-reply to participants whose client ID starts with this prefix
+    /// (other bots; empty answers everyone)
+    #[arg(long, value_name = "PREFIX", default_value = DEFAULT_BOT_PREFIX, requires = "auto_reply")]
+    bot_prefix: String,
 }
 
+/// Exit status when the client ID or the URL is invalid (EX_USAGE in sysexits.h)
+const EXIT_USAGE: i32 = 64;
+
+/// Exit status when the message file cannot be read (EX_NOINPUT in sysexits.h)
+const EXIT_NO_INPUT: i32 = 66;
+
 #[tokio::main]
 async fn main() {
-    // Initialize tracing
-    setup_logger(env!("CARGO_BIN_NAME"), "info");
-
     let args = Args::parse();
 
+    // Initialize tracing (stdout carries the JSON lines in JSON mode)
+    if args.json {
+        setup_stderr_logger(env!("CARGO_BIN_NAME"), "info");
+    } else {
+        setup_logger(env!("CARGO_BIN_NAME"), "info");
+    }
+
+    // The command-line flags take precedence over the environment
+    let client_id = match resolve_client_id(args.client_id, std::env::var(CLIENT_ID_ENV).ok()) {
+        Ok(client_id) => client_id,
+        Err(e) => {
+            tracing::error!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let url = resolve_url(args.url, std::env::var(URL_ENV).ok());
+
+    // Report a mistyped URL or client ID now rather than through the reconnect loop
+    if let Err(e) = validate_connection_target(&url, &client_id) {
+        tracing::error!("{}", e);
+        std::process::exit(EXIT_USAGE);
+    }
+
+    // One-shot mode: send the pre-composed message and exit
+    if let Some(path) = args.message_file {
+        let content = match read_message_file(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                tracing::error!("{}", e);
+                std::process::exit(EXIT_NO_INPUT);
+            }
+        };
+        if let Err(e) = send_message_once(&url, &client_id, &content).await {
+            tracing::error!("Failed to send message: {}", e);
+            std::process::exit(1);
+        }
+        tracing::info!("Sent message from {}", path.display());
+        return;
+    }
+
+    let config = ClientConfig {
+        roster_refresh_interval: args.roster_refresh_secs.map(Duration::from_secs),
+        heartbeat_interval: args.heartbeat_secs.map(Duration::from_secs),
+        server_echo: args.server_echo,
+        status: args.status,
+        outbox_capacity: args.outbox_capacity,
+        interactive: true,
+        json: args.json,
+        // Piped input: print only the messages, without the banner and prompt
+        prompt: std::io::stdin().is_terminal(),
+        formatter: MessageFormatter::default()
+            .with_newline_normalization(!args.raw_newlines)
+            .with_config(FormatterConfig::detect()),
+        connect_timeout: Duration::from_secs(args.connect_timeout),
+        auto_reply: args
+            .auto_reply
+            .map(|template| AutoReply::new(template).with_bot_prefix(args.bot_prefix)),
+        max_reconnect_delay: args.max_reconnect_delay_secs.map(Duration::from_secs),
+        max_total_reconnect_duration: args.max_total_reconnect_secs.map(Duration::from_secs),
+    };
+
     // Run the client
-    if let Err(e) = run(args.url, args.client_id).await {
+    if let Err(e) = run(url, client_id, config).await {
         tracing::error!("Client error: {}", e);
         std::process::exit(1);
     }
@@ -0,0 +1,253 @@
+//! JSON lines bridge between stdin/stdout and the chat server.
+//!
+//! In `--json` mode the client has no interactive display: every text frame
+//! received from the server is written to the output as one JSON line (the DTO
+//! as sent by the server), and every input line is sent to the server as is once
+//! it has been checked to be JSON. This makes the client usable in a pipe.
+
+use std::{
+    io::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use engawa_server::infrastructure::dto::websocket::{CLOSE_CODE_KICKED, CLOSE_CODE_REPLACED};
+
+use super::{
+    error::ClientError,
+    input::SharedInput,
+    session::{SessionEnd, WsStream},
+    stats::SessionStats,
+};
+
+/// Normalize a received text frame into a single JSON line
+///
+/// # Returns
+///
+/// `None` if the frame is not JSON
+pub(crate) fn to_json_line(text: &str) -> Option<String> {
+    serde_json::from_str::<serde_json::Value>(text)
+        .ok()
+        .map(|value| value.to_string())
+}
+
+/// Exchange JSON lines with the server until the input closes or the connection is lost
+///
+/// # Arguments
+///
+/// * `ws_stream` - Established connection to the server
+/// * `input` - Lines to send, each one a JSON frame
+/// * `stats` - Session statistics to update
+/// * `output` - Where received frames are written, one JSON line each
+pub(crate) async fn run_json_bridge<W>(
+    ws_stream: WsStream,
+    input: &SharedInput,
+    stats: Arc<SessionStats>,
+    mut output: W,
+) -> Result<SessionEnd, Box<dyn std::error::Error>>
+where
+    W: Write + Send + 'static,
+{
+    let (mut write, mut read) = ws_stream.split();
+
+    let stats_for_read = stats.clone();
+    let replaced = Arc::new(AtomicBool::new(false));
+    let replaced_for_read = replaced.clone();
+    let kicked = Arc::new(AtomicBool::new(false));
+    let kicked_for_read = kicked.clone();
+    let mut read_task = tokio::spawn(async move {
+        while let Some(message) = read.next().await {
+            match message {
+                Ok(Message::Text(text)) => {
+                    stats_for_read.record_received();
+                    let Some(line) = to_json_line(&text) else {
+                        tracing::warn!("Skipping non-JSON frame from server: {}", text);
+                        continue;
+                    };
+                    if writeln!(output, "{}", line)
+                        .and_then(|()| output.flush())
+                        .is_err()
+                    {
+                        // The reader of our output is gone; nothing left to bridge to
+                        return false;
+                    }
+                }
+                Ok(Message::Close(frame)) => {
+                    match frame.map(|frame| u16::from(frame.code)) {
+                        Some(CLOSE_CODE_REPLACED) => {
+                            replaced_for_read.store(true, Ordering::SeqCst)
+                        }
+                        Some(CLOSE_CODE_KICKED) => kicked_for_read.store(true, Ordering::SeqCst),
+                        _ => {}
+                    }
+                    tracing::info!("Server closed the connection");
+                    return true;
+                }
+                Err(e) => {
+                    tracing::warn!("WebSocket read error: {}", e);
+                    return true;
+                }
+                _ => {}
+            }
+        }
+        false
+    });
+
+    let input_for_write = input.clone();
+    let mut write_task = tokio::spawn(async move {
+        let mut input_rx = input_for_write.receiver().await;
+        while let Some(line) = input_rx.recv().await {
+            if let Err(e) = serde_json::from_str::<serde_json::Value>(&line) {
+                tracing::warn!("Skipping input line that is not JSON: {}", e);
+                continue;
+            }
+            if let Err(e) = write.send(Message::Text(line.into())).await {
+                tracing::warn!("Failed to send message: {}", e);
+                return true;
+            }
+            stats.record_sent();
+        }
+        false
+    });
+
+    let connection_lost = tokio::select! {
+        read_result = &mut read_task => {
+            write_task.abort();
+            read_result.unwrap_or(false)
+        }
+        write_result = &mut write_task => {
+            read_task.abort();
+            write_result.unwrap_or(false)
+        }
+    };
+
+    if replaced.load(Ordering::SeqCst) {
+        return Err(Box::new(ClientError::ConnectionReplaced));
+    }
+    if kicked.load(Ordering::SeqCst) {
+        return Err(Box::new(ClientError::Kicked));
+    }
+    if connection_lost {
+        return Err(Box::new(ClientError::ConnectionError(
+            "Connection lost".to_string(),
+        )));
+    }
+
+    Ok(SessionEnd::Exited)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{input::input_queue, session::connect, test_support::start_test_server};
+    use std::{sync::Mutex, time::Duration};
+
+    /// Output buffer shared with the test after it has been moved into the bridge
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedOutput {
+        fn lines(&self) -> Vec<serde_json::Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|line| serde_json::from_str(line).unwrap())
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_to_json_line_compacts_frames_into_one_line() {
+        // テスト項目: 受信したフレームは 1 行の JSON に正規化され、JSON でないものは除外される
+        // given (前提条件):
+        let pretty = "{\n  \"type\": \"chat\",\n  \"content\": \"hi\"\n}";
+
+        // when (操作):
+        let line = to_json_line(pretty);
+        let not_json = to_json_line("hello");
+
+        // then (期待する結果):
+        assert_eq!(line.unwrap(), r#"{"content":"hi","type":"chat"}"#);
+        assert!(not_json.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_json_lines_are_piped_to_and_from_the_server() {
+        // テスト項目: 入力した JSON 行がサーバーに送信され、受信したメッセージが JSON 行として出力される
+        // given (前提条件): alice が JSON モードで接続し、bob も接続している
+        let addr = start_test_server().await;
+        let url = format!("ws://{}/ws", addr);
+        let mut bob = connect(&format!("{}?client_id=bob", url), "bob")
+            .await
+            .unwrap();
+        let alice = connect(&format!("{}?client_id=alice", url), "alice")
+            .await
+            .unwrap();
+        let (input_tx, input_rx) = input_queue();
+        let input = SharedInput::new(&input_tx, input_rx);
+        let output = SharedOutput::default();
+        let bridge = {
+            let output = output.clone();
+            async move { run_json_bridge(alice, &input, Arc::new(SessionStats::new()), output).await }
+        };
+
+        // when (操作): JSON 行を 1 行入力し、bob からのメッセージを待ってから入力を閉じる
+        let scenario = async {
+            input_tx
+                .send(
+                    r#"{"type":"chat","client_id":"alice","content":"from the pipe","timestamp":1}"#
+                        .to_string(),
+                )
+                .await
+                .unwrap();
+            let received_by_bob = loop {
+                let frame = bob.next().await.unwrap().unwrap();
+                let value: serde_json::Value =
+                    serde_json::from_str(frame.to_text().unwrap()).unwrap();
+                if value["type"] == "chat" {
+                    break value;
+                }
+            };
+            bob.send(Message::Text(
+                r#"{"type":"chat","client_id":"bob","content":"hello alice","timestamp":2}"#.into(),
+            ))
+            .await
+            .unwrap();
+            while !output.lines().iter().any(|line| line["type"] == "chat") {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+            drop(input_tx);
+            received_by_bob
+        };
+        let (result, received_by_bob) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(bridge, scenario)
+        })
+        .await
+        .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap(), SessionEnd::Exited);
+        assert_eq!(received_by_bob["content"], "from the pipe");
+        let lines = output.lines();
+        assert_eq!(lines[0]["type"], "room-connected");
+        let chat = lines.iter().find(|line| line["type"] == "chat").unwrap();
+        assert_eq!(chat["client_id"], "bob");
+        assert_eq!(chat["content"], "hello alice");
+    }
+}
@@ -0,0 +1,270 @@
+//! Client configuration.
+
+use std::time::Duration;
+
+use engawa_server::domain::ClientId;
+use tokio_tungstenite::tungstenite::http::Uri;
+
+use super::{
+    auto_reply::AutoReply, error::ClientError, formatter::MessageFormatter,
+    outbox::DEFAULT_OUTBOX_CAPACITY,
+};
+
+/// Environment variable read for the client ID when `--client-id` is absent
+pub const CLIENT_ID_ENV: &str = "ENGAWA_CLIENT_ID";
+
+/// Environment variable read for the server URL when `--url` is absent
+pub const URL_ENV: &str = "ENGAWA_URL";
+
+/// Server URL used when given neither on the command line nor in the environment
+pub const DEFAULT_URL: &str = "ws://127.0.0.1:8080/ws";
+
+/// Time allowed for establishing the WebSocket connection
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configurable behavior of the chat client
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    /// Interval at which the participant list is fetched again from the server
+    /// (`None` disables the periodic refresh)
+    pub roster_refresh_interval: Option<Duration>,
+    /// Interval at which a presence heartbeat is sent, so that the server sees the client
+    /// as active without chat messages (`None` disables heartbeats)
+    pub heartbeat_interval: Option<Duration>,
+    /// Whether the server echoes sent messages back to the sender
+    /// (when `true`, the echoed frame replaces the optimistic "sent" display)
+    pub server_echo: bool,
+    /// Initial presence status sent at connect time (`active`, `away` or `dnd`);
+    /// `None` leaves the server default (`active`)
+    pub status: Option<String>,
+    /// Maximum number of unacknowledged messages kept for re-sending after a reconnect
+    pub outbox_capacity: usize,
+    /// Whether chat input is read from the terminal (from stdin lines in JSON mode)
+    /// (disable when embedding the client in another application)
+    pub interactive: bool,
+    /// Exchange raw JSON frames as lines on stdin/stdout instead of the human display
+    pub json: bool,
+    /// Show the banner and the `client_id> ` prompt
+    /// (disable when stdin is piped, so that the output holds only the messages)
+    pub prompt: bool,
+    /// Formatter used to display messages (set a custom `TimeFormatter` to change how times are shown)
+    pub formatter: MessageFormatter,
+    /// Time allowed for establishing the WebSocket connection before the attempt
+    /// fails (and is retried like any other connection error)
+    pub connect_timeout: Duration,
+    /// Answer each chat message from another participant with a templated reply
+    /// (bot mode; `None` disables it)
+    pub auto_reply: Option<AutoReply>,
+    /// Longest wait between reconnection attempts, also capping a server-suggested
+    /// `Retry-After` (`None` for no cap)
+    pub max_reconnect_delay: Option<Duration>,
+    /// Total time allowed for reconnecting after the connection is lost, regardless of
+    /// the number of attempts (`None` for no limit)
+    pub max_total_reconnect_duration: Option<Duration>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            roster_refresh_interval: None,
+            heartbeat_interval: None,
+            server_echo: false,
+            status: None,
+            outbox_capacity: DEFAULT_OUTBOX_CAPACITY,
+            interactive: true,
+            json: false,
+            prompt: true,
+            formatter: MessageFormatter::default(),
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            auto_reply: None,
+            max_reconnect_delay: None,
+            max_total_reconnect_duration: None,
+        }
+    }
+}
+
+/// Pick the value given on the command line, falling back to the environment.
+///
+/// An empty environment variable is treated as unset.
+fn flag_or_env(flag: Option<String>, env: Option<String>) -> Option<String> {
+    flag.or(env.filter(|value| !value.is_empty()))
+}
+
+/// Resolve the client ID from `--client-id` or [`CLIENT_ID_ENV`] (the flag takes precedence).
+///
+/// # Arguments
+///
+/// * `flag` - Value of `--client-id`, if given
+/// * `env` - Value of the [`CLIENT_ID_ENV`] environment variable, if set
+///
+/// # Errors
+///
+/// Returns `ClientError::MissingClientId` if neither is provided
+pub fn resolve_client_id(flag: Option<String>, env: Option<String>) -> Result<String, ClientError> {
+    flag_or_env(flag, env).ok_or(ClientError::MissingClientId(CLIENT_ID_ENV))
+}
+
+/// Resolve the server URL from `--url` or [`URL_ENV`] (the flag takes precedence).
+///
+/// Falls back to [`DEFAULT_URL`] if neither is provided.
+///
+/// # Arguments
+///
+/// * `flag` - Value of `--url`, if given
+/// * `env` - Value of the [`URL_ENV`] environment variable, if set
+pub fn resolve_url(flag: Option<String>, env: Option<String>) -> String {
+    flag_or_env(flag, env).unwrap_or_else(|| DEFAULT_URL.to_string())
+}
+
+/// Check the client ID and the server URL before connecting
+///
+/// Lets the command line report mistakes right away instead of through the
+/// reconnect loop.
+///
+/// # Arguments
+///
+/// * `url` - WebSocket server URL (e.g. `ws://127.0.0.1:8080/ws`)
+/// * `client_id` - Client ID to connect with
+///
+/// # Errors
+///
+/// * `ClientError::InvalidUrl` - the URL cannot be parsed, is not `ws://` or `wss://`, or has no host
+/// * `ClientError::InvalidClientId` - the client ID would be rejected by the server
+pub fn validate_connection_target(url: &str, client_id: &str) -> Result<(), ClientError> {
+    let invalid_url = |reason: &str| ClientError::InvalidUrl {
+        url: url.to_string(),
+        reason: reason.to_string(),
+    };
+    let uri = url
+        .parse::<Uri>()
+        .map_err(|e| invalid_url(&e.to_string()))?;
+    if !matches!(uri.scheme_str(), Some("ws" | "wss")) {
+        return Err(invalid_url("scheme must be ws or wss"));
+    }
+    if uri.host().is_none_or(str::is_empty) {
+        return Err(invalid_url("host is missing"));
+    }
+
+    ClientId::new(client_id.to_string()).map_err(|e| ClientError::InvalidClientId {
+        client_id: client_id.to_string(),
+        reason: e.to_string(),
+    })?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_client_id_flag_takes_precedence_over_env() {
+        // テスト項目: --client-id と環境変数の両方がある場合はフラグが優先される
+        // given (前提条件):
+        let flag = Some("alice".to_string());
+        let env = Some("bot".to_string());
+
+        // when (操作):
+        let result = resolve_client_id(flag, env);
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap(), "alice");
+    }
+
+    #[test]
+    fn test_client_id_falls_back_to_env() {
+        // テスト項目: --client-id がない場合は環境変数の値が使われる
+        // given (前提条件):
+        let env = Some("bot".to_string());
+
+        // when (操作):
+        let result = resolve_client_id(None, env);
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap(), "bot");
+    }
+
+    #[test]
+    fn test_client_id_missing_from_both_is_an_error() {
+        // テスト項目: --client-id も環境変数もない（または空の）場合はエラーになる
+        // given (前提条件):
+        let empty_env = Some(String::new());
+
+        // when (操作):
+        let missing = resolve_client_id(None, None);
+        let empty = resolve_client_id(None, empty_env);
+
+        // then (期待する結果):
+        assert!(matches!(
+            missing,
+            Err(ClientError::MissingClientId(CLIENT_ID_ENV))
+        ));
+        assert!(matches!(empty, Err(ClientError::MissingClientId(_))));
+        assert_eq!(
+            missing.unwrap_err().to_string(),
+            "Client ID is required: pass --client-id or set ENGAWA_CLIENT_ID"
+        );
+    }
+
+    #[test]
+    fn test_url_precedence_and_default() {
+        // テスト項目: URL はフラグ > 環境変数 > デフォルト値の順に解決される
+        // given (前提条件):
+        let flag = Some("ws://flag:8080/ws".to_string());
+        let env = Some("ws://env:8080/ws".to_string());
+
+        // when (操作):
+        let from_flag = resolve_url(flag, env.clone());
+        let from_env = resolve_url(None, env);
+        let default = resolve_url(None, None);
+
+        // then (期待する結果):
+        assert_eq!(from_flag, "ws://flag:8080/ws");
+        assert_eq!(from_env, "ws://env:8080/ws");
+        assert_eq!(default, DEFAULT_URL);
+    }
+
+    #[test]
+    fn test_validate_rejects_invalid_client_id() {
+        // テスト項目: サーバーが受け付けない client_id（空・長すぎる）は InvalidClientId になる
+        // given (前提条件):
+        let too_long = "a".repeat(1000);
+
+        // when (操作):
+        let empty = validate_connection_target(DEFAULT_URL, "");
+        let long = validate_connection_target(DEFAULT_URL, &too_long);
+        let valid = validate_connection_target(DEFAULT_URL, "alice");
+
+        // then (期待する結果):
+        assert!(
+            matches!(empty, Err(ClientError::InvalidClientId { client_id, .. }) if client_id.is_empty())
+        );
+        assert!(matches!(long, Err(ClientError::InvalidClientId { .. })));
+        assert!(valid.is_ok());
+    }
+
+    #[test]
+    fn test_validate_rejects_non_websocket_url() {
+        // テスト項目: ws/wss 以外のスキーム・ホストのない URL・解析できない URL は InvalidUrl になる
+        // given (前提条件):
+        let urls = ["http://127.0.0.1:8080/ws", "ws:///ws", "not a url"];
+
+        // when (操作):
+        let results: Vec<_> = urls
+            .iter()
+            .map(|url| validate_connection_target(url, "alice"))
+            .collect();
+        let secure = validate_connection_target("wss://chat.example.com/ws", "alice");
+
+        // then (期待する結果):
+        for (url, result) in urls.iter().zip(results) {
+            assert!(
+                matches!(&result, Err(ClientError::InvalidUrl { url: u, .. }) if u == url),
+                "{} should be rejected: {:?}",
+                url,
+                result
+            );
+        }
+        assert!(secure.is_ok());
+    }
+}
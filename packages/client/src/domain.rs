@@ -5,8 +5,136 @@
 
 #![allow(dead_code)]
 
+use std::time::Duration;
+
 use super::error::ClientError;
 
+/// A line entered by the user, interpreted as a command or a chat message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputCommand {
+    /// `/ping`: measure round-trip time with an application-level ping
+    Ping,
+    /// `/file <path>`: share a file with the room
+    SendFile(String),
+    /// `/save`: save the last received file to the current directory
+    Save,
+    /// `/roster`: fetch the current participant list from the server
+    Roster,
+    /// `/stats`: show connection statistics of the session
+    Stats,
+    /// `/whoami`: show the client ID, room, connection duration and server URL
+    WhoAmI,
+    /// `/reconnect`: close the session and connect again right away
+    Reconnect,
+    /// `/timestamps on|off`: show or hide the time of each chat message
+    Timestamps(bool),
+    /// Any other input is sent as a chat message
+    Message(String),
+}
+
+/// Parse a line entered by the user into an input command.
+///
+/// # Arguments
+///
+/// * `line` - The input line (already trimmed)
+///
+/// # Returns
+///
+/// The corresponding `InputCommand`
+pub fn parse_input(line: &str) -> InputCommand {
+    match line {
+        "/ping" => InputCommand::Ping,
+        "/save" => InputCommand::Save,
+        "/roster" => InputCommand::Roster,
+        "/stats" => InputCommand::Stats,
+        "/whoami" => InputCommand::WhoAmI,
+        "/reconnect" => InputCommand::Reconnect,
+        "/timestamps on" => InputCommand::Timestamps(true),
+        "/timestamps off" => InputCommand::Timestamps(false),
+        _ => match line.strip_prefix("/file ") {
+            Some(path) if !path.trim().is_empty() => {
+                InputCommand::SendFile(path.trim().to_string())
+            }
+            _ => InputCommand::Message(line.to_string()),
+        },
+    }
+}
+
+/// Guess the MIME type of a file from its extension.
+///
+/// # Arguments
+///
+/// * `filename` - The file name
+///
+/// # Returns
+///
+/// The guessed MIME type (`application/octet-stream` if unknown)
+pub fn guess_mime(filename: &str) -> &'static str {
+    let extension = filename
+        .rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "txt" | "md" => "text/plain",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Build the WebSocket URL used to connect to the server.
+///
+/// # Arguments
+///
+/// * `url` - The base WebSocket URL (e.g. `ws://127.0.0.1:8080/ws`)
+/// * `client_id` - The client ID
+/// * `status` - The initial presence status, if any
+///
+/// # Returns
+///
+/// The URL with the connect query parameters
+pub fn build_connect_url(url: &str, client_id: &str, status: Option<&str>) -> String {
+    match status {
+        Some(status) => format!("{}?client_id={}&status={}", url, client_id, status),
+        None => format!("{}?client_id={}", url, client_id),
+    }
+}
+
+/// Decide whether a sent message should be displayed optimistically.
+///
+/// When the server echoes messages back to their sender, the echoed frame is
+/// displayed instead, so the optimistic "sent" display would duplicate it.
+///
+/// # Arguments
+///
+/// * `server_echo` - Whether the server echoes messages back to the sender
+///
+/// # Returns
+///
+/// `true` if the sent confirmation should be displayed immediately
+pub fn should_display_sent_optimistically(server_echo: bool) -> bool {
+    !server_echo
+}
+
+/// Calculate the round-trip time in milliseconds.
+///
+/// # Arguments
+///
+/// * `sent_at` - Timestamp when the ping was sent (milliseconds)
+/// * `received_at` - Timestamp when the pong was received (milliseconds)
+///
+/// # Returns
+///
+/// The round-trip time in milliseconds (never negative)
+pub fn calculate_rtt_millis(sent_at: i64, received_at: i64) -> i64 {
+    (received_at - sent_at).max(0)
+}
+
 /// Check if the client should exit immediately based on the error type.
 ///
 /// # Arguments
@@ -18,7 +146,10 @@ use super::error::ClientError;
 /// `true` if the error requires immediate exit (e.g., DuplicateClientId),
 /// `false` otherwise
 pub fn should_exit_immediately(error: &ClientError) -> bool {
-    matches!(error, ClientError::DuplicateClientId(_))
+    matches!(
+        error,
+        ClientError::DuplicateClientId(_) | ClientError::ConnectionReplaced | ClientError::Kicked
+    )
 }
 
 /// Check if the client should attempt to reconnect.
@@ -46,6 +177,25 @@ pub fn should_attempt_reconnect(
     current_attempt < max_attempts
 }
 
+/// Check if the next reconnection attempt fits in the total reconnection time budget.
+///
+/// # Arguments
+///
+/// * `elapsed` - Time spent reconnecting since the connection was lost
+/// * `next_delay` - Wait before the next attempt
+/// * `max_total` - Total time allowed for reconnecting (`None` for no limit)
+///
+/// # Returns
+///
+/// `true` if the next attempt would start within the budget, `false` otherwise
+pub fn within_reconnect_time_budget(
+    elapsed: Duration,
+    next_delay: Duration,
+    max_total: Option<Duration>,
+) -> bool {
+    max_total.is_none_or(|max_total| elapsed + next_delay <= max_total)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -63,6 +213,32 @@ mod tests {
         assert!(result);
     }
 
+    #[test]
+    fn test_should_exit_immediately_when_connection_was_replaced() {
+        // テスト項目: 接続が置き換えられた場合、即座に終了すべきと判定される（再接続で取り返さない）
+        // given (前提条件):
+        let error = ClientError::ConnectionReplaced;
+
+        // when (操作):
+        let result = should_exit_immediately(&error);
+
+        // then (期待する結果):
+        assert!(result);
+    }
+
+    #[test]
+    fn test_should_exit_immediately_when_kicked() {
+        // テスト項目: 管理者に kick された場合、即座に終了すべきと判定される（再接続で戻らない）
+        // given (前提条件):
+        let error = ClientError::Kicked;
+
+        // when (操作):
+        let result = should_exit_immediately(&error);
+
+        // then (期待する結果):
+        assert!(result);
+    }
+
     #[test]
     fn test_should_exit_immediately_with_connection_error() {
         // テスト項目: ConnectionError の場合、即座に終了すべきではないと判定される
@@ -140,4 +316,251 @@ mod tests {
         // then (期待する結果):
         assert!(result);
     }
+
+    #[test]
+    fn test_time_budget_exhausted_before_attempt_count() {
+        // テスト項目: 再接続回数が上限未満でも、次の試行が時間の予算を超える場合は再接続しない
+        // given (前提条件): 4 分 58 秒再接続を試み、次の試行まで 5 秒待つ（予算は 5 分）
+        let error = ClientError::ConnectionError("network error".to_string());
+        let budget = Some(Duration::from_secs(300));
+
+        // when (操作):
+        let by_count = should_attempt_reconnect(&error, 2, 5);
+        let by_time =
+            within_reconnect_time_budget(Duration::from_secs(298), Duration::from_secs(5), budget);
+
+        // then (期待する結果):
+        assert!(by_count);
+        assert!(!by_time);
+    }
+
+    #[test]
+    fn test_time_budget_allows_attempts_within_it() {
+        // テスト項目: 予算内に始まる試行と、予算が設定されていない場合は再接続する
+        // given (前提条件):
+        let budget = Some(Duration::from_secs(300));
+
+        // when (操作):
+        let within =
+            within_reconnect_time_budget(Duration::from_secs(60), Duration::from_secs(5), budget);
+        let at_limit =
+            within_reconnect_time_budget(Duration::from_secs(295), Duration::from_secs(5), budget);
+        let unlimited =
+            within_reconnect_time_budget(Duration::from_secs(3600), Duration::from_secs(5), None);
+
+        // then (期待する結果):
+        assert!(within);
+        assert!(at_limit);
+        assert!(unlimited);
+    }
+
+    #[test]
+    fn test_parse_input_ping_command() {
+        // テスト項目: /ping が Ping コマンドとして解釈される
+        // given (前提条件):
+        let line = "/ping";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Ping);
+    }
+
+    #[test]
+    fn test_parse_input_whoami_command() {
+        // テスト項目: /whoami が WhoAmI コマンドとして解釈される
+        // given (前提条件):
+        let line = "/whoami";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::WhoAmI);
+    }
+
+    #[test]
+    fn test_parse_input_reconnect_command() {
+        // テスト項目: /reconnect が Reconnect コマンドとして解釈される
+        // given (前提条件):
+        let line = "/reconnect";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Reconnect);
+    }
+
+    #[test]
+    fn test_parse_input_timestamps_command() {
+        // テスト項目: /timestamps on|off が Timestamps コマンドとして解釈され、それ以外の引数はメッセージになる
+        // given (前提条件):
+        let lines = ["/timestamps on", "/timestamps off", "/timestamps maybe"];
+
+        // when (操作):
+        let results: Vec<InputCommand> = lines.iter().map(|line| parse_input(line)).collect();
+
+        // then (期待する結果):
+        assert_eq!(
+            results,
+            vec![
+                InputCommand::Timestamps(true),
+                InputCommand::Timestamps(false),
+                InputCommand::Message("/timestamps maybe".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_input_chat_message() {
+        // テスト項目: コマンド以外の入力はチャットメッセージとして解釈される
+        // given (前提条件):
+        let line = "hello /ping";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Message("hello /ping".to_string()));
+    }
+
+    #[test]
+    fn test_parse_input_file_command() {
+        // テスト項目: /file <path> が SendFile コマンドとして解釈される
+        // given (前提条件):
+        let line = "/file ./images/cat.png";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            InputCommand::SendFile("./images/cat.png".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_input_save_command() {
+        // テスト項目: /save が Save コマンドとして解釈される
+        // given (前提条件):
+        let line = "/save";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Save);
+    }
+
+    #[test]
+    fn test_parse_input_roster_command() {
+        // テスト項目: /roster が Roster コマンドとして解釈される
+        // given (前提条件):
+        let line = "/roster";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Roster);
+    }
+
+    #[test]
+    fn test_parse_input_stats_command() {
+        // テスト項目: /stats が Stats コマンドとして解釈される
+        // given (前提条件):
+        let line = "/stats";
+
+        // when (操作):
+        let result = parse_input(line);
+
+        // then (期待する結果):
+        assert_eq!(result, InputCommand::Stats);
+    }
+
+    #[test]
+    fn test_guess_mime() {
+        // テスト項目: 拡張子から MIME タイプが推測される
+        // given (前提条件):
+        let filenames = ["cat.PNG", "notes.txt", "archive.tar.gz", "noext"];
+
+        // when (操作):
+        let result: Vec<&str> = filenames.iter().map(|f| guess_mime(f)).collect();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            vec![
+                "image/png",
+                "text/plain",
+                "application/octet-stream",
+                "application/octet-stream"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_connect_url_with_status() {
+        // テスト項目: 初期プレゼンス状態を指定すると接続 URL のクエリに含まれる
+        // given (前提条件):
+        let url = "ws://127.0.0.1:8080/ws";
+
+        // when (操作):
+        let with_status = build_connect_url(url, "alice", Some("away"));
+        let without_status = build_connect_url(url, "alice", None);
+
+        // then (期待する結果):
+        assert_eq!(
+            with_status,
+            "ws://127.0.0.1:8080/ws?client_id=alice&status=away"
+        );
+        assert_eq!(without_status, "ws://127.0.0.1:8080/ws?client_id=alice");
+    }
+
+    #[test]
+    fn test_should_display_sent_optimistically() {
+        // テスト項目: サーバーエコーが無効なら送信時に表示し、有効ならエコーに任せる
+        // given (前提条件):
+        let without_echo = false;
+        let with_echo = true;
+
+        // when (操作):
+        let shown_without_echo = should_display_sent_optimistically(without_echo);
+        let shown_with_echo = should_display_sent_optimistically(with_echo);
+
+        // then (期待する結果):
+        assert!(shown_without_echo);
+        assert!(!shown_with_echo);
+    }
+
+    #[test]
+    fn test_calculate_rtt_millis() {
+        // テスト項目: 送信時刻と受信時刻から RTT が計算される
+        // given (前提条件):
+        let sent_at = 1000;
+        let received_at = 1042;
+
+        // when (操作):
+        let result = calculate_rtt_millis(sent_at, received_at);
+
+        // then (期待する結果):
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn test_calculate_rtt_millis_clock_skew() {
+        // テスト項目: 時計が巻き戻った場合でも RTT は負にならない
+        // given (前提条件):
+        let sent_at = 1042;
+        let received_at = 1000;
+
+        // when (操作):
+        let result = calculate_rtt_millis(sent_at, received_at);
+
+        // then (期待する結果):
+        assert_eq!(result, 0);
+    }
 }
@@ -1,5 +1,7 @@
 //! Error types for the WebSocket chat application.
 
+use std::{path::PathBuf, time::Duration};
+
 use thiserror::Error;
 
 /// Client-specific errors
@@ -9,7 +11,44 @@ pub enum ClientError {
     #[error("Client ID '{0}' is already connected")]
     DuplicateClientId(String),
 
+    /// Client ID given neither on the command line nor in the environment
+    #[error("Client ID is required: pass --client-id or set {0}")]
+    MissingClientId(&'static str),
+
+    /// Client ID that the server would reject (e.g. empty or too long)
+    #[error("Invalid client ID '{client_id}': {reason}")]
+    InvalidClientId { client_id: String, reason: String },
+
+    /// Server URL that is not a `ws://` or `wss://` URL
+    #[error("Invalid server URL '{url}': {reason}")]
+    InvalidUrl { url: String, reason: String },
+
+    /// The server rejected the connection because the room is full
+    /// (`retry_after` is the wait suggested by the `Retry-After` header, if any)
+    #[error("Server is full, try again later")]
+    ServerFull { retry_after: Option<Duration> },
+
+    /// The server closed the connection because a newer connection with the same
+    /// client ID replaced it
+    #[error("Connection was replaced by a new connection with the same client ID")]
+    ConnectionReplaced,
+
+    /// The server closed the connection because an admin kicked this client
+    #[error("Kicked from the room by an admin")]
+    Kicked,
+
     /// Connection error
     #[error("Connection error: {0}")]
     ConnectionError(String),
+
+    /// The message file could not be read
+    #[error("Cannot read message file '{}': {source}", path.display())]
+    MessageFile {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// The message cannot be sent (empty, too long, or rejected by the server)
+    #[error("Invalid message: {0}")]
+    InvalidMessage(String),
 }
@@ -0,0 +1,118 @@
+//! Connection lifecycle events for embedders.
+//!
+//! The terminal client reports its connection state through logs. A host
+//! application embedding the client subscribes to [`ConnectionEvents`] instead
+//! and updates its own UI as the client connects, drops and reconnects.
+//! It can also receive every message from the server as an [`IncomingMessages`]
+//! stream (see [`connection_events_with_messages`]).
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use tokio::sync::mpsc;
+
+use super::incoming::{IncomingMessage, IncomingMessages};
+
+/// Change in the state of the connection to the chat server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A connection attempt has started
+    Connecting,
+    /// The WebSocket handshake succeeded
+    Connected,
+    /// The session ended or the connection could not be established
+    Disconnected { reason: String },
+    /// Waiting before reconnection attempt number `attempt` (starting at 1)
+    Reconnecting { attempt: u32 },
+    /// Reconnection was abandoned; no further events follow
+    GaveUp,
+}
+
+/// Sending half of the event stream, handed to [`run_with_events`](crate::run_with_events)
+#[derive(Debug, Clone)]
+pub struct ConnectionEventSender {
+    tx: Option<mpsc::UnboundedSender<ConnectionEvent>>,
+    messages_tx: Option<mpsc::UnboundedSender<IncomingMessage>>,
+}
+
+impl ConnectionEventSender {
+    /// Sender that discards every event (used by the terminal client)
+    pub(crate) fn disabled() -> Self {
+        Self {
+            tx: None,
+            messages_tx: None,
+        }
+    }
+
+    /// Emit an event; it is dropped if nobody is listening
+    pub(crate) fn emit(&self, event: ConnectionEvent) {
+        if let Some(tx) = &self.tx {
+            let _ = tx.send(event);
+        }
+    }
+
+    /// Forward a received message; it is dropped if nobody is listening
+    pub(crate) fn emit_message(&self, message: IncomingMessage) {
+        if let Some(tx) = &self.messages_tx {
+            let _ = tx.send(message);
+        }
+    }
+}
+
+/// Stream of [`ConnectionEvent`]s in the order they happened
+#[derive(Debug)]
+pub struct ConnectionEvents {
+    rx: mpsc::UnboundedReceiver<ConnectionEvent>,
+}
+
+impl Stream for ConnectionEvents {
+    type Item = ConnectionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+/// Create a connected event sender and stream
+///
+/// The stream ends once the client has stopped and every sender is dropped.
+pub fn connection_events() -> (ConnectionEventSender, ConnectionEvents) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    (
+        ConnectionEventSender {
+            tx: Some(tx),
+            messages_tx: None,
+        },
+        ConnectionEvents { rx },
+    )
+}
+
+/// Create an event sender with both the event stream and the stream of received messages
+///
+/// ```ignore
+/// let (events_tx, _events, mut messages) = connection_events_with_messages();
+/// tokio::spawn(async move {
+///     while let Some(message) = messages.next().await {
+///         if let IncomingMessage::Chat(chat) = message {
+///             println!("{}: {}", chat.client_id, chat.content);
+///         }
+///     }
+/// });
+/// run_with_events(url, client_id, config, events_tx).await?;
+/// ```
+pub fn connection_events_with_messages()
+-> (ConnectionEventSender, ConnectionEvents, IncomingMessages) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let (messages_tx, messages_rx) = mpsc::unbounded_channel();
+    (
+        ConnectionEventSender {
+            tx: Some(tx),
+            messages_tx: Some(messages_tx),
+        },
+        ConnectionEvents { rx },
+        IncomingMessages { rx: messages_rx },
+    )
+}
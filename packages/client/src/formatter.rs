@@ -2,13 +2,186 @@
 
 #![allow(dead_code)]
 
+use std::{
+    borrow::Cow,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
 use engawa_server::infrastructure::dto::websocket::ParticipantInfo;
 use engawa_shared::time::timestamp_to_jst_rfc3339;
 
+use super::stats::StatsSnapshot;
+
+/// Idle duration from which a participant is shown as idle (milliseconds)
+const IDLE_DISPLAY_THRESHOLD_MS: u64 = 60_000;
+
+/// Separator width used when the terminal width is unknown (characters)
+pub const DEFAULT_FORMATTER_WIDTH: usize = 60;
+
+/// Layout settings of [`MessageFormatter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FormatterConfig {
+    /// Width of the separator lines around participant lists and chat messages (characters)
+    pub width: usize,
+}
+
+impl Default for FormatterConfig {
+    fn default() -> Self {
+        Self {
+            width: DEFAULT_FORMATTER_WIDTH,
+        }
+    }
+}
+
+impl FormatterConfig {
+    /// Use the width of the terminal attached to stdout, or [`DEFAULT_FORMATTER_WIDTH`]
+    /// when stdout is not a terminal
+    pub fn detect() -> Self {
+        match terminal_size::terminal_size() {
+            Some((terminal_size::Width(width), _)) if width > 0 => Self {
+                width: usize::from(width),
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Display settings that can be changed while the client is running
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayOptions {
+    /// Show when each chat message was sent (the "sent at …" lines); toggled with `/timestamps`
+    pub show_timestamps: bool,
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            show_timestamps: true,
+        }
+    }
+}
+
+/// Formats timestamps shown in client display
+///
+/// Implement this to show times in another format or timezone
+/// (e.g. relative time, 12-hour clock, or a localized format).
+pub trait TimeFormatter: Send + Sync {
+    /// Format a Unix timestamp (milliseconds)
+    fn format(&self, millis: i64) -> String;
+}
+
+/// Default [`TimeFormatter`] showing timestamps as RFC 3339 in JST
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JstRfc3339Formatter;
+
+impl TimeFormatter for JstRfc3339Formatter {
+    fn format(&self, millis: i64) -> String {
+        timestamp_to_jst_rfc3339(millis)
+    }
+}
+
 /// Message formatter for client display
-pub struct MessageFormatter;
+///
+/// Timestamps are formatted by the injected [`TimeFormatter`]
+/// ([`JstRfc3339Formatter`] by default).
+///
+/// Line endings in received chat messages are normalized to `\n` for display
+/// (see [`MessageFormatter::with_newline_normalization`]).
+#[derive(Clone)]
+pub struct MessageFormatter {
+    time_formatter: Arc<dyn TimeFormatter>,
+    normalize_newlines: bool,
+    config: FormatterConfig,
+    /// Shared by all clones, so that a change applies to every task displaying messages
+    display: Arc<RwLock<DisplayOptions>>,
+}
+
+impl Default for MessageFormatter {
+    fn default() -> Self {
+        Self::new(JstRfc3339Formatter)
+    }
+}
+
+impl fmt::Debug for MessageFormatter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MessageFormatter")
+            .field("normalize_newlines", &self.normalize_newlines)
+            .field("config", &self.config)
+            .field("display", &self.display_options())
+            .finish_non_exhaustive()
+    }
+}
 
 impl MessageFormatter {
+    /// Create a formatter that formats timestamps with the given [`TimeFormatter`]
+    pub fn new(time_formatter: impl TimeFormatter + 'static) -> Self {
+        Self {
+            time_formatter: Arc::new(time_formatter),
+            normalize_newlines: true,
+            config: FormatterConfig::default(),
+            display: Arc::new(RwLock::new(DisplayOptions::default())),
+        }
+    }
+
+    /// Set the layout settings (the separator width is [`DEFAULT_FORMATTER_WIDTH`] by default)
+    pub fn with_config(mut self, config: FormatterConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Enable or disable line ending normalization of received messages (enabled by default)
+    ///
+    /// When enabled, `\r\n` and lone `\r` are shown as `\n` so that messages sent
+    /// from clients using CRLF line endings render without stray carriage returns.
+    /// Only the displayed text is affected; the message itself is left as received.
+    pub fn with_newline_normalization(mut self, enabled: bool) -> Self {
+        self.normalize_newlines = enabled;
+        self
+    }
+
+    /// Set the initial display settings (timestamps are shown by default)
+    ///
+    /// The returned formatter no longer shares its display settings with `self`.
+    pub fn with_display_options(mut self, options: DisplayOptions) -> Self {
+        self.display = Arc::new(RwLock::new(options));
+        self
+    }
+
+    /// Get the current display settings
+    pub fn display_options(&self) -> DisplayOptions {
+        self.display
+            .read()
+            .map(|options| *options)
+            .unwrap_or_default()
+    }
+
+    /// Change the display settings of this formatter and all of its clones
+    pub fn set_display_options(&self, options: DisplayOptions) {
+        if let Ok(mut display) = self.display.write() {
+            *display = options;
+        }
+    }
+
+    /// Format a timestamp with the configured [`TimeFormatter`]
+    fn format_time(&self, millis: i64) -> String {
+        self.time_formatter.format(millis)
+    }
+
+    /// Build a separator line of the configured width
+    fn separator(&self, c: char) -> String {
+        c.to_string().repeat(self.config.width)
+    }
+
+    /// Prepare received message content for display
+    fn display_content<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        if self.normalize_newlines && content.contains('\r') {
+            Cow::Owned(content.replace("\r\n", "\n").replace('\r', "\n"))
+        } else {
+            Cow::Borrowed(content)
+        }
+    }
+
     /// Format the room-connected message showing all participants
     ///
     /// # Arguments
@@ -20,11 +193,33 @@ impl MessageFormatter {
     ///
     /// A formatted string with participant list
     pub fn format_room_connected(
+        &self,
+        participants: &[ParticipantInfo],
+        current_client_id: &str,
+    ) -> String {
+        self.format_room_connected_with_total(participants, participants.len(), current_client_id)
+    }
+
+    /// Format the room-connected message when the server may have truncated the list
+    ///
+    /// # Arguments
+    ///
+    /// * `participants` - Participants listed by the server
+    /// * `total` - Total number of participants in the room
+    /// * `current_client_id` - The current client's ID (to mark as "me")
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the participant list and the number of unlisted participants
+    pub fn format_room_connected_with_total(
+        &self,
         participants: &[ParticipantInfo],
+        total: usize,
         current_client_id: &str,
     ) -> String {
         let mut output = String::new();
-        output.push_str("\n\n============================================================\n");
+        let separator = self.separator('=');
+        output.push_str(&format!("\n\n{}\n", separator));
         output.push_str("Participants:\n");
 
         if participants.is_empty() {
@@ -33,15 +228,24 @@ impl MessageFormatter {
             for participant in participants {
                 let is_me = participant.client_id == current_client_id;
                 let me_suffix = if is_me { " (me)" } else { "" };
-                let timestamp_str = timestamp_to_jst_rfc3339(participant.connected_at);
+                let timestamp_str = self.format_time(participant.connected_at);
                 output.push_str(&format!(
-                    "{}{} - entered at {}\n",
-                    participant.client_id, me_suffix, timestamp_str
+                    "{}{}{}{} - entered at {}\n",
+                    participant.client_id,
+                    me_suffix,
+                    Self::format_status_suffix(&participant.status),
+                    Self::format_idle_suffix(participant.idle_ms),
+                    timestamp_str
                 ));
             }
         }
 
-        output.push_str("============================================================\n\n");
+        let unlisted = total.saturating_sub(participants.len());
+        if unlisted > 0 {
+            output.push_str(&format!("…and {} more\n", unlisted));
+        }
+
+        output.push_str(&format!("{}\n\n", separator));
         output
     }
 
@@ -51,13 +255,51 @@ impl MessageFormatter {
     ///
     /// * `client_id` - The ID of the participant who joined
     /// * `connected_at` - Unix timestamp when the participant connected (milliseconds)
+    /// * `status` - Presence status of the participant
     ///
     /// # Returns
     ///
     /// A formatted string with the join notification
-    pub fn format_participant_joined(client_id: &str, connected_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(connected_at);
-        format!("\n+ {} entered at {}\n", client_id, timestamp_str)
+    pub fn format_participant_joined(
+        &self,
+        client_id: &str,
+        connected_at: i64,
+        status: &str,
+    ) -> String {
+        let timestamp_str = self.format_time(connected_at);
+        format!(
+            "\n+ {}{} entered at {}\n",
+            client_id,
+            Self::format_status_suffix(status),
+            timestamp_str
+        )
+    }
+
+    /// Format a presence status as a suffix shown after a participant name
+    ///
+    /// The default `active` status is not shown.
+    fn format_status_suffix(status: &str) -> String {
+        if status == "active" {
+            String::new()
+        } else {
+            format!(" [{}]", status)
+        }
+    }
+
+    /// Format an idle duration as a suffix shown after a participant name
+    ///
+    /// Participants idle for less than a minute are not marked.
+    fn format_idle_suffix(idle_ms: u64) -> String {
+        if idle_ms < IDLE_DISPLAY_THRESHOLD_MS {
+            return String::new();
+        }
+
+        let minutes = idle_ms / 60_000;
+        if minutes < 60 {
+            format!(" (idle {}m)", minutes)
+        } else {
+            format!(" (idle {}h)", minutes / 60)
+        }
     }
 
     /// Format a participant-left notification
@@ -70,11 +312,53 @@ impl MessageFormatter {
     /// # Returns
     ///
     /// A formatted string with the leave notification
-    pub fn format_participant_left(client_id: &str, disconnected_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(disconnected_at);
+    pub fn format_participant_left(&self, client_id: &str, disconnected_at: i64) -> String {
+        let timestamp_str = self.format_time(disconnected_at);
         format!("\n- {} left at {}\n", client_id, timestamp_str)
     }
 
+    /// Format a participant count update
+    ///
+    /// # Arguments
+    ///
+    /// * `count` - The current number of participants in the room
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the participant count
+    pub fn format_participant_count(count: usize) -> String {
+        format!("\n* {} participants in the room\n", count)
+    }
+
+    /// Format the notice that a moderator purged the message history
+    ///
+    /// # Arguments
+    ///
+    /// * `purged` - The number of messages removed from the history
+    ///
+    /// # Returns
+    ///
+    /// A formatted string marking where the history was cleared
+    pub fn format_history_cleared(purged: usize) -> String {
+        format!(
+            "\n* Message history was cleared by a moderator ({} messages removed)\n",
+            purged
+        )
+    }
+
+    /// Format the notice that a message was removed from the history (e.g. it expired)
+    ///
+    /// # Arguments
+    ///
+    /// * `message_id` - The ID of the removed message
+    ///
+    /// # Returns
+    ///
+    /// A formatted string telling that the message is gone
+    pub fn format_message_deleted(message_id: &str) -> String {
+        format!("\n* Message {} was removed from the history\n", message_id)
+    }
+
     /// Format a chat message
     ///
     /// # Arguments
@@ -86,14 +370,20 @@ impl MessageFormatter {
     /// # Returns
     ///
     /// A formatted string with the chat message
-    pub fn format_chat_message(from: &str, content: &str, sent_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
+    pub fn format_chat_message(&self, from: &str, content: &str, sent_at: i64) -> String {
+        let time_line = if self.display_options().show_timestamps {
+            format!("sent at {}\n", self.format_time(sent_at))
+        } else {
+            String::new()
+        };
+        let separator = self.separator('-');
         format!(
-            "\n\n------------------------------------------------------------\n\
-             @{}: {}\n\
-             sent at {}\n\
-             ------------------------------------------------------------\n\n",
-            from, content, timestamp_str
+            "\n\n{}\n@{}: {}\n{}{}\n\n",
+            separator,
+            from,
+            self.display_content(content),
+            time_line,
+            separator
         )
     }
 
@@ -105,12 +395,242 @@ impl MessageFormatter {
     ///
     /// # Returns
     ///
-    /// A formatted string with the sent confirmation
-    pub fn format_sent_confirmation(sent_at: i64) -> String {
-        let timestamp_str = timestamp_to_jst_rfc3339(sent_at);
+    /// A formatted string with the sent confirmation (without the time when timestamps are off)
+    pub fn format_sent_confirmation(&self, sent_at: i64) -> String {
+        if !self.display_options().show_timestamps {
+            return "sent\n".to_string();
+        }
+        let timestamp_str = self.format_time(sent_at);
         format!("sent at {}\n", timestamp_str)
     }
 
+    /// Format the confirmation of a `/timestamps` command
+    ///
+    /// # Arguments
+    ///
+    /// * `show` - Whether timestamps are now shown
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the new setting
+    pub fn format_timestamps_setting(show: bool) -> String {
+        format!("Timestamps {}\n", if show { "on" } else { "off" })
+    }
+
+    /// Format a message typed while disconnected, to be sent after reconnecting
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The message content
+    ///
+    /// # Returns
+    ///
+    /// A formatted string marking the message as pending
+    pub fn format_pending(content: &str) -> String {
+        format!("pending (sent after reconnecting): {}\n", content)
+    }
+
+    /// Format a command that cannot run while disconnected
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - The command as typed
+    ///
+    /// # Returns
+    ///
+    /// A formatted notice that the command was skipped
+    pub fn format_unavailable_while_disconnected(command: &str) -> String {
+        format!("Not connected, skipped '{}'\n", command)
+    }
+
+    /// Format the round-trip time measured by an application-level ping
+    ///
+    /// # Arguments
+    ///
+    /// * `rtt_millis` - The measured round-trip time (milliseconds)
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the round-trip time
+    pub fn format_pong(rtt_millis: i64) -> String {
+        format!("\n← pong: rtt {} ms\n", rtt_millis)
+    }
+
+    /// Format the connection statistics shown by `/stats`
+    ///
+    /// # Arguments
+    ///
+    /// * `stats` - Snapshot of the session counters
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with one statistic per line
+    pub fn format_stats(stats: &StatsSnapshot) -> String {
+        let connected = stats
+            .connected_millis
+            .map(Self::format_duration)
+            .unwrap_or_else(|| "not connected".to_string());
+        let last_rtt = stats
+            .last_rtt_millis
+            .map(|rtt| format!("{} ms", rtt))
+            .unwrap_or_else(|| "- (use /ping)".to_string());
+        format!(
+            "\nSession statistics:\n\
+             \x20 connected:  {}\n\
+             \x20 sent:       {}\n\
+             \x20 received:   {}\n\
+             \x20 reconnects: {}\n\
+             \x20 last RTT:   {}\n",
+            connected, stats.messages_sent, stats.messages_received, stats.reconnects, last_rtt
+        )
+    }
+
+    /// Format the identity of this client shown by `/whoami`
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - The client ID of this client
+    /// * `room_id` - The room reported by the server on connect, if any
+    /// * `connected_millis` - Time since the current connection was established
+    /// * `server_url` - The WebSocket URL of the server
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with one field per line
+    pub fn format_whoami(
+        client_id: &str,
+        room_id: Option<&str>,
+        connected_millis: Option<i64>,
+        server_url: &str,
+    ) -> String {
+        let connected = connected_millis
+            .map(Self::format_duration)
+            .unwrap_or_else(|| "not connected".to_string());
+        format!(
+            "\nYou are:\n\
+             \x20 client_id: {}\n\
+             \x20 room:      {}\n\
+             \x20 connected: {}\n\
+             \x20 server:    {}\n",
+            client_id,
+            room_id.unwrap_or("-"),
+            connected,
+            server_url
+        )
+    }
+
+    /// Format a duration in milliseconds (e.g. "1h 02m 05s", "3m 07s", "42s")
+    fn format_duration(millis: i64) -> String {
+        let total_secs = millis / 1000;
+        let (hours, minutes, seconds) = (total_secs / 3600, total_secs / 60 % 60, total_secs % 60);
+        if hours > 0 {
+            format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+        } else if minutes > 0 {
+            format!("{}m {:02}s", minutes, seconds)
+        } else {
+            format!("{}s", seconds)
+        }
+    }
+
+    /// Format a file shared by another participant
+    ///
+    /// # Arguments
+    ///
+    /// * `from` - The client ID of the sender
+    /// * `filename` - The name of the shared file
+    /// * `byte_count` - The size of the file in bytes
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the file notification (e.g. "alice shared file.png (12 KB)")
+    pub fn format_file_shared(from: &str, filename: &str, byte_count: usize) -> String {
+        format!(
+            "\n{} shared {} ({}) - type /save to save it\n",
+            from,
+            filename,
+            Self::format_file_size(byte_count)
+        )
+    }
+
+    /// Format an updated reaction count on a chat message
+    ///
+    /// # Arguments
+    ///
+    /// * `by` - The client ID of the participant who added or removed the reaction
+    /// * `message_id` - The ID of the chat message reacted to
+    /// * `emoji` - The reaction emoji
+    /// * `count` - The number of participants now reacting with `emoji`
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the reaction tally (e.g. "bob reacted to <id>: 👍 × 2")
+    pub fn format_reaction_updated(
+        by: &str,
+        message_id: &str,
+        emoji: &str,
+        count: usize,
+    ) -> String {
+        format!(
+            "
+{} reacted to {}: {} × {}
+",
+            by, message_id, emoji, count
+        )
+    }
+
+    /// Format a file size in a human-readable unit
+    ///
+    /// # Arguments
+    ///
+    /// * `byte_count` - The size in bytes
+    ///
+    /// # Returns
+    ///
+    /// A formatted string such as "512 B", "12 KB" or "3 MB"
+    pub fn format_file_size(byte_count: usize) -> String {
+        const KB: usize = 1024;
+        const MB: usize = 1024 * KB;
+        if byte_count >= MB {
+            format!("{} MB", byte_count.div_ceil(MB))
+        } else if byte_count >= KB {
+            format!("{} KB", byte_count.div_ceil(KB))
+        } else {
+            format!("{} B", byte_count)
+        }
+    }
+
+    /// Format an error notification from the server
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Machine-readable error code
+    /// * `message` - Human-readable error description
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with the error
+    pub fn format_error(code: &str, message: &str) -> String {
+        format!("\n! error ({}): {}\n", code, message)
+    }
+
+    /// Format a message from the server itself (e.g. the message of the day)
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The text sent by the server
+    ///
+    /// # Returns
+    ///
+    /// A formatted string with each line of the message marked as coming from the server
+    pub fn format_system_message(content: &str) -> String {
+        let mut output = String::from("\n");
+        for line in content.lines() {
+            output.push_str(&format!("# {}\n", line));
+        }
+        output.push('\n');
+        output
+    }
+
     /// Format a binary message notification
     ///
     /// # Arguments
@@ -150,7 +670,8 @@ mod tests {
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("Participants:"));
@@ -165,11 +686,14 @@ mod tests {
         let participants = vec![ParticipantInfo {
             client_id: "alice".to_string(),
             connected_at: 1672498800000,
+            status: "active".to_string(),
+            idle_ms: 0,
         }];
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("alice (me)"));
@@ -185,16 +709,21 @@ mod tests {
             ParticipantInfo {
                 client_id: "alice".to_string(),
                 connected_at: 1672498800000,
+                status: "active".to_string(),
+                idle_ms: 0,
             },
             ParticipantInfo {
                 client_id: "bob".to_string(),
                 connected_at: 1672498900000,
+                status: "active".to_string(),
+                idle_ms: 0,
             },
         ];
         let current_client_id = "alice";
 
         // when (操作):
-        let result = MessageFormatter::format_room_connected(&participants, current_client_id);
+        let result =
+            MessageFormatter::default().format_room_connected(&participants, current_client_id);
 
         // then (期待する結果):
         assert!(result.contains("alice (me)"));
@@ -210,14 +739,87 @@ mod tests {
         let connected_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_participant_joined(client_id, connected_at);
+        let result = MessageFormatter::default().format_participant_joined(
+            client_id,
+            connected_at,
+            "active",
+        );
 
         // then (期待する結果):
-        assert!(result.contains("+ bob"));
-        assert!(result.contains("entered at"));
+        assert!(result.contains("+ bob entered at"));
         assert!(result.contains("2023-01-01"));
     }
 
+    #[test]
+    fn test_format_room_connected_shows_non_active_status() {
+        // テスト項目: active 以外のプレゼンス状態は参加者名の後に表示される
+        // given (前提条件):
+        let participants = vec![ParticipantInfo {
+            client_id: "bob".to_string(),
+            connected_at: 1672498800000,
+            status: "away".to_string(),
+            idle_ms: 0,
+        }];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_room_connected(&participants, "alice");
+
+        // then (期待する結果):
+        assert!(result.contains("bob [away] - entered at"));
+    }
+
+    #[test]
+    fn test_format_room_connected_indicates_truncated_roster() {
+        // テスト項目: 切り詰められた参加者リストでは表示されていない人数が示される
+        // given (前提条件): 500 人中 80 人のみが含まれる
+        let participants: Vec<ParticipantInfo> = (0..80)
+            .map(|i| ParticipantInfo {
+                client_id: format!("user{:03}", i),
+                connected_at: 1672498800000,
+                status: "active".to_string(),
+                idle_ms: 0,
+            })
+            .collect();
+
+        // when (操作):
+        let result = MessageFormatter::default().format_room_connected_with_total(
+            &participants,
+            500,
+            "alice",
+        );
+
+        // then (期待する結果):
+        assert!(result.contains("user079 - entered at"));
+        assert!(result.contains("…and 420 more"));
+    }
+
+    #[test]
+    fn test_format_room_connected_marks_only_long_idle_participants() {
+        // テスト項目: 長くアイドル状態の参加者にだけアイドル時間が表示される
+        // given (前提条件): alice は直前に発言、bob は 3 分間発言なし
+        let participants = vec![
+            ParticipantInfo {
+                client_id: "alice".to_string(),
+                connected_at: 1672498800000,
+                status: "active".to_string(),
+                idle_ms: 5_000,
+            },
+            ParticipantInfo {
+                client_id: "bob".to_string(),
+                connected_at: 1672498800000,
+                status: "active".to_string(),
+                idle_ms: 180_000,
+            },
+        ];
+
+        // when (操作):
+        let result = MessageFormatter::default().format_room_connected(&participants, "carol");
+
+        // then (期待する結果):
+        assert!(result.contains("alice - entered at"));
+        assert!(result.contains("bob (idle 3m) - entered at"));
+    }
+
     #[test]
     fn test_format_participant_left() {
         // テスト項目: 参加者退出通知が正しくフォーマットされる
@@ -226,7 +828,8 @@ mod tests {
         let disconnected_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_participant_left(client_id, disconnected_at);
+        let result =
+            MessageFormatter::default().format_participant_left(client_id, disconnected_at);
 
         // then (期待する結果):
         assert!(result.contains("- charlie"));
@@ -234,6 +837,47 @@ mod tests {
         assert!(result.contains("2023-01-01"));
     }
 
+    #[test]
+    fn test_format_participant_count() {
+        // テスト項目: 参加者数の更新が正しくフォーマットされる
+        // given (前提条件):
+        let count = 120;
+
+        // when (操作):
+        let result = MessageFormatter::format_participant_count(count);
+
+        // then (期待する結果):
+        assert!(result.contains("120 participants"));
+    }
+
+    #[test]
+    fn test_format_history_cleared() {
+        // テスト項目: 履歴の削除の通知に削除件数が表示される
+        // given (前提条件):
+        let purged = 42;
+
+        // when (操作):
+        let result = MessageFormatter::format_history_cleared(purged);
+
+        // then (期待する結果):
+        assert!(result.contains("history was cleared"));
+        assert!(result.contains("42 messages removed"));
+    }
+
+    #[test]
+    fn test_format_message_deleted() {
+        // テスト項目: メッセージの削除の通知に、削除されたメッセージの ID が表示される
+        // given (前提条件):
+        let message_id = "0190a5e4-0000-7000-8000-000000000001";
+
+        // when (操作):
+        let result = MessageFormatter::format_message_deleted(message_id);
+
+        // then (期待する結果):
+        assert!(result.contains(message_id));
+        assert!(result.contains("removed from the history"));
+    }
+
     #[test]
     fn test_format_chat_message() {
         // テスト項目: チャットメッセージが正しくフォーマットされる
@@ -243,7 +887,7 @@ mod tests {
         let sent_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_chat_message(from, content, sent_at);
+        let result = MessageFormatter::default().format_chat_message(from, content, sent_at);
 
         // then (期待する結果):
         assert!(result.contains("@alice:"));
@@ -253,6 +897,28 @@ mod tests {
         assert!(result.contains("------------------------------------------------------------"));
     }
 
+    #[test]
+    fn test_format_chat_message_without_timestamps() {
+        // テスト項目: タイムスタンプを非表示にすると "sent at" 行が省かれ、クローンにも反映される
+        // given (前提条件):
+        let formatter = MessageFormatter::default();
+        let clone = formatter.clone();
+        let sent_at = 1672498800000;
+
+        // when (操作):
+        formatter.set_display_options(DisplayOptions {
+            show_timestamps: false,
+        });
+        let result = clone.format_chat_message("alice", "Hello, world!", sent_at);
+        let sent = clone.format_sent_confirmation(sent_at);
+
+        // then (期待する結果):
+        assert!(result.contains("@alice: Hello, world!"));
+        assert!(!result.contains("sent at"));
+        assert!(!result.contains("2023-01-01"));
+        assert_eq!(sent, "sent\n");
+    }
+
     #[test]
     fn test_format_sent_confirmation() {
         // テスト項目: 送信確認メッセージが正しくフォーマットされる
@@ -260,13 +926,279 @@ mod tests {
         let sent_at = 1672498800000;
 
         // when (操作):
-        let result = MessageFormatter::format_sent_confirmation(sent_at);
+        let result = MessageFormatter::default().format_sent_confirmation(sent_at);
 
         // then (期待する結果):
         assert!(result.contains("sent at"));
         assert!(result.contains("2023-01-01"));
     }
 
+    #[test]
+    fn test_format_chat_message_normalizes_crlf() {
+        // テスト項目: CRLF を含むメッセージは LF に正規化して表示され、無効にするとそのまま表示される
+        // given (前提条件):
+        let content = "line 1\r\nline 2\rline 3";
+
+        // when (操作):
+        let normalized = MessageFormatter::default().format_chat_message("alice", content, 0);
+        let raw = MessageFormatter::default()
+            .with_newline_normalization(false)
+            .format_chat_message("alice", content, 0);
+
+        // then (期待する結果):
+        assert!(normalized.contains("@alice: line 1\nline 2\nline 3\n"));
+        assert!(!normalized.contains('\r'));
+        assert!(raw.contains("@alice: line 1\r\nline 2\rline 3\n"));
+    }
+
+    #[test]
+    fn test_separators_match_configured_width() {
+        // テスト項目: 区切り線の長さが設定した幅になる（デフォルトは DEFAULT_FORMATTER_WIDTH）
+        // given (前提条件):
+        let narrow = MessageFormatter::default().with_config(FormatterConfig { width: 20 });
+        let participants = vec![ParticipantInfo {
+            client_id: "alice".to_string(),
+            connected_at: 0,
+            status: "active".to_string(),
+            idle_ms: 0,
+        }];
+
+        // when (操作):
+        let chat = narrow.format_chat_message("alice", "hi", 0);
+        let roster = narrow.format_room_connected(&participants, "alice");
+        let default_chat = MessageFormatter::default().format_chat_message("alice", "hi", 0);
+
+        // then (期待する結果):
+        let line_lengths = |text: &str, c: char| -> Vec<usize> {
+            text.lines()
+                .filter(|line| !line.is_empty() && line.chars().all(|ch| ch == c))
+                .map(|line| line.chars().count())
+                .collect()
+        };
+        assert_eq!(line_lengths(&chat, '-'), vec![20, 20]);
+        assert_eq!(line_lengths(&roster, '='), vec![20, 20]);
+        assert_eq!(
+            line_lengths(&default_chat, '-'),
+            vec![DEFAULT_FORMATTER_WIDTH, DEFAULT_FORMATTER_WIDTH]
+        );
+    }
+
+    /// Test formatter that shows the raw milliseconds
+    struct MillisFormatter;
+
+    impl TimeFormatter for MillisFormatter {
+        fn format(&self, millis: i64) -> String {
+            format!("<{}ms>", millis)
+        }
+    }
+
+    #[test]
+    fn test_custom_time_formatter_is_used_for_timestamps() {
+        // テスト項目: 注入した TimeFormatter でタイムスタンプがフォーマットされる
+        // given (前提条件):
+        let formatter = MessageFormatter::new(MillisFormatter);
+        let participants = vec![ParticipantInfo {
+            client_id: "alice".to_string(),
+            connected_at: 1000,
+            status: "active".to_string(),
+            idle_ms: 0,
+        }];
+
+        // when (操作):
+        let connected = formatter.format_room_connected(&participants, "alice");
+        let joined = formatter.format_participant_joined("bob", 2000, "active");
+        let left = formatter.format_participant_left("bob", 3000);
+        let chat = formatter.format_chat_message("alice", "hi", 4000);
+        let sent = formatter.format_sent_confirmation(5000);
+
+        // then (期待する結果):
+        assert!(connected.contains("alice (me) - entered at <1000ms>"));
+        assert_eq!(joined, "\n+ bob entered at <2000ms>\n");
+        assert_eq!(left, "\n- bob left at <3000ms>\n");
+        assert!(chat.contains("sent at <4000ms>"));
+        assert_eq!(sent, "sent at <5000ms>\n");
+        assert!(!chat.contains("2023"));
+    }
+
+    #[test]
+    fn test_default_time_formatter_is_jst_rfc3339() {
+        // テスト項目: デフォルトの TimeFormatter は JST の RFC 3339 形式
+        // given (前提条件):
+        let millis = 1672498800000;
+
+        // when (操作):
+        let result = JstRfc3339Formatter.format(millis);
+
+        // then (期待する結果):
+        assert_eq!(result, timestamp_to_jst_rfc3339(millis));
+        assert!(result.ends_with("+09:00"));
+    }
+
+    #[test]
+    fn test_format_pending() {
+        // テスト項目: 切断中に入力したメッセージが送信待ちとして表示される
+        // given (前提条件):
+        let content = "Hello!";
+
+        // when (操作):
+        let result = MessageFormatter::format_pending(content);
+
+        // then (期待する結果):
+        assert!(result.starts_with("pending"));
+        assert!(result.contains("Hello!"));
+    }
+
+    #[test]
+    fn test_format_pong() {
+        // テスト項目: pong の RTT が正しくフォーマットされる
+        // given (前提条件):
+        let rtt_millis = 42;
+
+        // when (操作):
+        let result = MessageFormatter::format_pong(rtt_millis);
+
+        // then (期待する結果):
+        assert!(result.contains("pong"));
+        assert!(result.contains("42 ms"));
+    }
+
+    #[test]
+    fn test_format_stats() {
+        // テスト項目: 接続統計が 1 行ずつフォーマットされる
+        // given (前提条件):
+        let stats = StatsSnapshot {
+            connected_millis: Some(3_725_000),
+            messages_sent: 3,
+            messages_received: 10,
+            reconnects: 1,
+            last_rtt_millis: Some(42),
+        };
+
+        // when (操作):
+        let result = MessageFormatter::format_stats(&stats);
+
+        // then (期待する結果):
+        assert!(result.contains("Session statistics:"));
+        assert!(result.contains("  connected:  1h 02m 05s\n"));
+        assert!(result.contains("  sent:       3\n"));
+        assert!(result.contains("  received:   10\n"));
+        assert!(result.contains("  reconnects: 1\n"));
+        assert!(result.contains("  last RTT:   42 ms\n"));
+    }
+
+    #[test]
+    fn test_format_stats_without_connection_or_rtt() {
+        // テスト項目: 未接続・RTT 未計測の場合はその旨が表示される
+        // given (前提条件):
+        let stats = StatsSnapshot {
+            connected_millis: None,
+            messages_sent: 0,
+            messages_received: 0,
+            reconnects: 0,
+            last_rtt_millis: None,
+        };
+
+        // when (操作):
+        let result = MessageFormatter::format_stats(&stats);
+
+        // then (期待する結果):
+        assert!(result.contains("connected:  not connected"));
+        assert!(result.contains("last RTT:   - (use /ping)"));
+    }
+
+    #[test]
+    fn test_format_whoami() {
+        // テスト項目: client_id、ルーム、接続時間、サーバーの URL が 1 行ずつフォーマットされ、
+        //            ルームが不明な場合は "-" になる
+        // given (前提条件):
+        let url = "ws://127.0.0.1:8080/ws";
+
+        // when (操作):
+        let result = MessageFormatter::format_whoami("alice", Some("room-1"), Some(187_000), url);
+        let without_room = MessageFormatter::format_whoami("alice", None, None, url);
+
+        // then (期待する結果):
+        assert!(result.contains("You are:"));
+        assert!(result.contains("  client_id: alice\n"));
+        assert!(result.contains("  room:      room-1\n"));
+        assert!(result.contains("  connected: 3m 07s\n"));
+        assert!(result.contains("  server:    ws://127.0.0.1:8080/ws\n"));
+        assert!(without_room.contains("  room:      -\n"));
+        assert!(without_room.contains("  connected: not connected\n"));
+    }
+
+    #[test]
+    fn test_format_file_shared() {
+        // テスト項目: ファイル共有通知が正しくフォーマットされる
+        // given (前提条件):
+        let from = "alice";
+        let filename = "file.png";
+        let byte_count = 12 * 1024;
+
+        // when (操作):
+        let result = MessageFormatter::format_file_shared(from, filename, byte_count);
+
+        // then (期待する結果):
+        assert!(result.contains("alice shared file.png (12 KB)"));
+    }
+
+    #[test]
+    fn test_format_reaction_updated() {
+        // テスト項目: リアクション数の更新通知が絵文字ごとの数とともにフォーマットされる
+        // given (前提条件):
+        let message_id = "0b6f3c1e-0000-4000-8000-000000000000";
+
+        // when (操作):
+        let result = MessageFormatter::format_reaction_updated("bob", message_id, "👍", 2);
+
+        // then (期待する結果):
+        assert!(result.contains("bob reacted to 0b6f3c1e-0000-4000-8000-000000000000: 👍 × 2"));
+    }
+
+    #[test]
+    fn test_format_file_size() {
+        // テスト項目: ファイルサイズが適切な単位でフォーマットされる
+        // given (前提条件):
+        let sizes = [512, 12 * 1024, 3 * 1024 * 1024];
+
+        // when (操作):
+        let result: Vec<String> = sizes
+            .iter()
+            .map(|s| MessageFormatter::format_file_size(*s))
+            .collect();
+
+        // then (期待する結果):
+        assert_eq!(result, vec!["512 B", "12 KB", "3 MB"]);
+    }
+
+    #[test]
+    fn test_format_system_message() {
+        // テスト項目: サーバーからのメッセージは各行に印を付けて表示される
+        // given (前提条件):
+        let content = "Welcome!\nBe kind.";
+
+        // when (操作):
+        let result = MessageFormatter::format_system_message(content);
+
+        // then (期待する結果):
+        assert_eq!(result, "\n# Welcome!\n# Be kind.\n\n");
+    }
+
+    #[test]
+    fn test_format_error() {
+        // テスト項目: エラー通知が正しくフォーマットされる
+        // given (前提条件):
+        let code = "file-too-large";
+        let message = "File exceeds 1024 bytes";
+
+        // when (操作):
+        let result = MessageFormatter::format_error(code, message);
+
+        // then (期待する結果):
+        assert!(result.contains("file-too-large"));
+        assert!(result.contains("File exceeds 1024 bytes"));
+    }
+
     #[test]
     fn test_format_binary_message() {
         // テスト項目: バイナリメッセージ通知が正しくフォーマットされる
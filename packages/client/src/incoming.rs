@@ -0,0 +1,216 @@
+//! Typed messages received from the chat server.
+//!
+//! Every text frame is parsed into an [`IncomingMessage`] once; the terminal
+//! display and embedders (through [`IncomingMessages`]) both work from it.
+
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_util::Stream;
+use tokio::sync::mpsc;
+
+use engawa_server::infrastructure::dto::websocket::{
+    AckMessage, AppPongMessage, ChatMessage, ErrorMessage, FileMessage, HistoryClearedMessage,
+    MessageDeletedMessage, MessageType, ParticipantCountMessage, ParticipantJoinedMessage,
+    ParticipantLeftMessage, ReactionUpdatedMessage, ReadyMessage, RoomConnectedMessage,
+    RosterMessage, SystemMessage,
+};
+
+/// Message received from the chat server
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum IncomingMessage {
+    /// Chat message from a participant
+    Chat(ChatMessage),
+    /// A participant entered the room
+    Joined(ParticipantJoinedMessage),
+    /// A participant left the room
+    Left(ParticipantLeftMessage),
+    /// Participant list sent right after connecting (possibly truncated)
+    RoomConnected(RoomConnectedMessage),
+    /// End of the initial frames sent on connect; input can be enabled after it
+    Ready(ReadyMessage),
+    /// Participant list sent in response to a roster request
+    Roster(RosterMessage),
+    /// Participant count, sent instead of join/leave notifications in large rooms
+    ParticipantCount(ParticipantCountMessage),
+    /// The message history was purged by a moderator; clear the displayed messages
+    HistoryCleared(HistoryClearedMessage),
+    /// A message was removed from the history (e.g. it expired); stop displaying it
+    MessageDeleted(MessageDeletedMessage),
+    /// File shared by a participant
+    File(FileMessage),
+    /// Updated reaction count on a chat message
+    ReactionUpdated(ReactionUpdatedMessage),
+    /// Message from the server itself (e.g. the message of the day)
+    System(SystemMessage),
+    /// Error reported by the server
+    Error(ErrorMessage),
+    /// Acknowledgement of a sent chat message
+    Ack(AckMessage),
+    /// Reply to an application-level ping
+    Pong(AppPongMessage),
+    /// Text frame that is none of the above
+    Raw(String),
+}
+
+/// Parse a text frame received from the server
+///
+/// Frames that carry a `type` are matched on it first; the remaining DTOs are
+/// told apart by their fields, so the order of the checks matters.
+pub fn parse_incoming(text: &str) -> IncomingMessage {
+    if let Ok(msg) = serde_json::from_str::<AppPongMessage>(text)
+        && matches!(msg.r#type, MessageType::AppPong)
+    {
+        IncomingMessage::Pong(msg)
+    } else if let Ok(msg) = serde_json::from_str::<AckMessage>(text)
+        && matches!(msg.r#type, MessageType::Ack)
+    {
+        IncomingMessage::Ack(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ReadyMessage>(text)
+        && matches!(msg.r#type, MessageType::Ready)
+    {
+        IncomingMessage::Ready(msg)
+    } else if let Ok(msg) = serde_json::from_str::<RosterMessage>(text)
+        && matches!(msg.r#type, MessageType::Roster)
+    {
+        IncomingMessage::Roster(msg)
+    } else if let Ok(msg) = serde_json::from_str::<FileMessage>(text)
+        && matches!(msg.r#type, MessageType::File)
+    {
+        IncomingMessage::File(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ReactionUpdatedMessage>(text)
+        && matches!(msg.r#type, MessageType::ReactionUpdated)
+    {
+        IncomingMessage::ReactionUpdated(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ErrorMessage>(text)
+        && matches!(msg.r#type, MessageType::Error)
+    {
+        IncomingMessage::Error(msg)
+    } else if let Ok(msg) = serde_json::from_str::<SystemMessage>(text)
+        && matches!(msg.r#type, MessageType::System)
+    {
+        IncomingMessage::System(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ParticipantCountMessage>(text)
+        && matches!(msg.r#type, MessageType::ParticipantCount)
+    {
+        IncomingMessage::ParticipantCount(msg)
+    } else if let Ok(msg) = serde_json::from_str::<HistoryClearedMessage>(text)
+        && matches!(msg.r#type, MessageType::HistoryCleared)
+    {
+        IncomingMessage::HistoryCleared(msg)
+    } else if let Ok(msg) = serde_json::from_str::<MessageDeletedMessage>(text)
+        && matches!(msg.r#type, MessageType::MessageDeleted)
+    {
+        IncomingMessage::MessageDeleted(msg)
+    } else if let Ok(msg) = serde_json::from_str::<RoomConnectedMessage>(text) {
+        IncomingMessage::RoomConnected(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ParticipantJoinedMessage>(text) {
+        IncomingMessage::Joined(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ParticipantLeftMessage>(text) {
+        IncomingMessage::Left(msg)
+    } else if let Ok(msg) = serde_json::from_str::<ChatMessage>(text) {
+        IncomingMessage::Chat(msg)
+    } else {
+        IncomingMessage::Raw(text.to_string())
+    }
+}
+
+/// Stream of [`IncomingMessage`]s in the order they were received
+///
+/// Created with [`connection_events_with_messages`](crate::connection_events_with_messages).
+#[derive(Debug)]
+pub struct IncomingMessages {
+    pub(crate) rx: mpsc::UnboundedReceiver<IncomingMessage>,
+}
+
+impl Stream for IncomingMessages {
+    type Item = IncomingMessage;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ClientConfig, ConnectionEvent, connection_events_with_messages, run_with_events,
+        session::connect, test_support::start_test_server,
+    };
+    use futures_util::{SinkExt, StreamExt};
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::protocol::Message;
+
+    #[test]
+    fn test_parse_incoming_chat_and_joined() {
+        // テスト項目: チャット・入室通知・準備完了がそれぞれの種類として解析され、それ以外は Raw になる
+        // given (前提条件):
+        let chat = r#"{"type":"chat","client_id":"bob","content":"hi","timestamp":1}"#;
+        let joined = r#"{"type":"participant-joined","client_id":"bob","connected_at":2}"#;
+
+        // when (操作):
+        let chat = parse_incoming(chat);
+        let joined = parse_incoming(joined);
+        let ready = parse_incoming(r#"{"type":"ready"}"#);
+        let raw = parse_incoming("hello");
+
+        // then (期待する結果):
+        assert!(matches!(chat, IncomingMessage::Chat(msg) if msg.content == "hi"));
+        assert!(matches!(joined, IncomingMessage::Joined(msg) if msg.client_id == "bob"));
+        assert!(matches!(ready, IncomingMessage::Ready(_)));
+        assert!(matches!(raw, IncomingMessage::Raw(text) if text == "hello"));
+    }
+
+    #[tokio::test]
+    async fn test_incoming_messages_stream_receives_join_and_chat() {
+        // テスト項目: 埋め込み用のストリームから、参加者リスト・準備完了・入室通知・チャットが受信順に届く
+        // given (前提条件): alice がストリーム付きで接続している
+        let addr = start_test_server().await;
+        let url = format!("ws://{}/ws", addr);
+        let (events_tx, mut events, mut messages) = connection_events_with_messages();
+        let config = ClientConfig {
+            interactive: false,
+            ..ClientConfig::default()
+        };
+        let client = run_with_events(url.clone(), "alice".to_string(), config, events_tx);
+
+        // when (操作): bob が入室してチャットを送る
+        let scenario = async {
+            while events.next().await != Some(ConnectionEvent::Connected) {}
+            let first = messages.next().await.unwrap();
+            let ready = messages.next().await.unwrap();
+            let mut bob = connect(&format!("{}?client_id=bob", url), "bob")
+                .await
+                .unwrap();
+            let second = messages.next().await.unwrap();
+            bob.send(Message::Text(
+                r#"{"type":"chat","client_id":"bob","content":"hello alice","timestamp":1}"#.into(),
+            ))
+            .await
+            .unwrap();
+            let third = messages.next().await.unwrap();
+            [first, ready, second, third]
+        };
+        let received = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::select! {
+                _ = client => panic!("client stopped before the scenario finished"),
+                received = scenario => received,
+            }
+        })
+        .await
+        .unwrap();
+
+        // then (期待する結果):
+        let [first, ready, second, third] = received;
+        assert!(matches!(first, IncomingMessage::RoomConnected(_)));
+        assert!(matches!(ready, IncomingMessage::Ready(_)));
+        assert!(matches!(second, IncomingMessage::Joined(msg) if msg.client_id == "bob"));
+        assert!(
+            matches!(third, IncomingMessage::Chat(msg) if msg.client_id == "bob" && msg.content == "hello alice")
+        );
+    }
+}
@@ -0,0 +1,209 @@
+//! Bounded queue between the readline thread and the WebSocket write task.
+//!
+//! The write task awaits `write.send` for every line, so a slow socket slows
+//! down how fast lines are taken off the queue. With a bounded queue the
+//! readline thread blocks once it is full instead of letting memory grow
+//! without limit, and periodic commands are skipped while the socket catches up.
+//!
+//! The readline thread and the queue live as long as the client, not a single
+//! session, so lines typed while reconnecting are not lost.
+
+use std::sync::Arc;
+
+#[cfg(feature = "cli")]
+use rustyline::{DefaultEditor, error::ReadlineError};
+use tokio::sync::{
+    Mutex, OwnedMutexGuard,
+    mpsc::{self, error::TrySendError},
+};
+
+/// Maximum number of input lines waiting to be sent
+pub const INPUT_QUEUE_CAPACITY: usize = 32;
+
+/// Create the bounded input queue
+pub fn input_queue() -> (mpsc::Sender<String>, mpsc::Receiver<String>) {
+    mpsc::channel(INPUT_QUEUE_CAPACITY)
+}
+
+/// Input queue shared by the successive sessions of one client run
+///
+/// Only a weak sender is kept, so the queue closes once the readline thread exits.
+#[derive(Debug, Clone)]
+pub struct SharedInput {
+    tx: mpsc::WeakSender<String>,
+    rx: Arc<Mutex<mpsc::Receiver<String>>>,
+}
+
+impl SharedInput {
+    /// Share the receiving end of the queue fed through `tx`
+    pub fn new(tx: &mpsc::Sender<String>, rx: mpsc::Receiver<String>) -> Self {
+        Self {
+            tx: tx.downgrade(),
+            rx: Arc::new(Mutex::new(rx)),
+        }
+    }
+
+    /// Sender for commands generated by the client, or `None` once the queue is closed
+    pub fn sender(&self) -> Option<mpsc::Sender<String>> {
+        self.tx.upgrade()
+    }
+
+    /// Take the receiving end for the duration of the returned guard
+    pub async fn receiver(&self) -> OwnedMutexGuard<mpsc::Receiver<String>> {
+        self.rx.clone().lock_owned().await
+    }
+}
+
+/// Spawn a blocking thread that reads chat input from the terminal with rustyline
+#[cfg(feature = "cli")]
+pub fn spawn_readline(input_tx: mpsc::Sender<String>, client_id: String) {
+    std::thread::spawn(move || {
+        let mut rl = match DefaultEditor::new() {
+            Ok(rl) => rl,
+            Err(e) => {
+                eprintln!("Failed to initialize readline: {}", e);
+                return;
+            }
+        };
+
+        let prompt = format!("{}> ", client_id);
+
+        loop {
+            match rl.readline(&prompt) {
+                Ok(line) => {
+                    let line = line.trim();
+                    if !line.is_empty() {
+                        rl.add_history_entry(line).ok();
+                        if !submit_line_blocking(&input_tx, line.to_string()) {
+                            // Channel closed, exit thread
+                            break;
+                        }
+                    }
+                }
+                Err(ReadlineError::Interrupted) => {
+                    // Ctrl+C
+                    tracing::info!("Interrupted");
+                    break;
+                }
+                Err(ReadlineError::Eof) => {
+                    // Ctrl+D
+                    tracing::info!("EOF");
+                    break;
+                }
+                Err(err) => {
+                    tracing::error!("Readline error: {}", err);
+                    break;
+                }
+            }
+        }
+    });
+}
+
+/// Spawn a blocking thread that reads input lines from stdin (no prompt or line editing)
+///
+/// Used in JSON mode, where stdin is usually a pipe rather than a terminal,
+/// and for interactive input when built without the `cli` feature.
+pub fn spawn_stdin_reader(input_tx: mpsc::Sender<String>) {
+    std::thread::spawn(move || {
+        for line in std::io::stdin().lines() {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => {
+                    tracing::error!("Failed to read stdin: {}", e);
+                    break;
+                }
+            };
+            let line = line.trim();
+            if !line.is_empty() && !submit_line_blocking(&input_tx, line.to_string()) {
+                // Channel closed, exit thread
+                break;
+            }
+        }
+        tracing::info!("EOF");
+    });
+}
+
+/// Enqueue a line typed by the user, blocking the calling thread while the queue is full.
+///
+/// Must be called from a non-async thread (the readline thread).
+///
+/// # Returns
+///
+/// `false` if the write task is gone and the queue is closed
+pub fn submit_line_blocking(tx: &mpsc::Sender<String>, line: String) -> bool {
+    tx.blocking_send(line).is_ok()
+}
+
+/// Enqueue a command generated by the client itself without waiting.
+///
+/// The command is dropped with a warning if the queue is full.
+///
+/// # Returns
+///
+/// `false` if the write task is gone and the queue is closed
+pub fn submit_command(tx: &mpsc::Sender<String>, command: &str) -> bool {
+    match tx.try_send(command.to_string()) {
+        Ok(()) => true,
+        Err(TrySendError::Full(_)) => {
+            tracing::warn!("Input queue full, skipped '{}'", command);
+            true
+        }
+        Err(TrySendError::Closed(_)) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_slow_sink_blocks_readline_thread_instead_of_buffering() {
+        // テスト項目: 送信が遅い場合、キューは容量を超えず入力スレッドが待たされる
+        // given (前提条件): 容量 2 のキューと、1 件ごとに待つ遅い送信側
+        let (tx, mut rx) = mpsc::channel::<String>(2);
+        let observer = tx.clone();
+        let producer = std::thread::spawn(move || {
+            for i in 0..10 {
+                assert!(submit_line_blocking(&tx, format!("line {}", i)));
+            }
+        });
+
+        // when (操作):
+        let mut received = Vec::new();
+        let mut max_queued = 0;
+        while let Some(line) = rx.recv().await {
+            max_queued = max_queued.max(observer.max_capacity() - observer.capacity());
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            received.push(line);
+            if received.len() == 10 {
+                break;
+            }
+        }
+        producer.join().unwrap();
+
+        // then (期待する結果): 全行が順番通りに届き、キューに溜まるのは容量まで
+        let expected: Vec<String> = (0..10).map(|i| format!("line {}", i)).collect();
+        assert_eq!(received, expected);
+        assert!(max_queued <= 2);
+    }
+
+    #[tokio::test]
+    async fn test_submit_command_skips_when_queue_is_full() {
+        // テスト項目: キューが満杯のとき、クライアント生成のコマンドは待たずに破棄される
+        // given (前提条件):
+        let (tx, mut rx) = mpsc::channel::<String>(1);
+        assert!(submit_command(&tx, "/roster"));
+
+        // when (操作):
+        let kept_open = submit_command(&tx, "/roster");
+
+        // then (期待する結果):
+        assert!(kept_open);
+        assert_eq!(rx.recv().await.unwrap(), "/roster");
+        assert!(rx.try_recv().is_err());
+
+        drop(rx);
+        assert!(!submit_command(&tx, "/roster"));
+    }
+}
@@ -1,8 +1,36 @@
+mod auto_reply;
+mod bridge;
+mod config;
 mod domain;
 mod error;
+mod events;
 mod formatter;
+mod incoming;
+mod input;
+mod outbox;
+mod probe;
 mod runner;
+mod send_once;
 mod session;
+mod stats;
+#[cfg(test)]
+mod test_support;
 mod ui;
 
-pub use runner::run;
+pub use auto_reply::{AutoReply, DEFAULT_BOT_PREFIX};
+pub use config::{
+    CLIENT_ID_ENV, ClientConfig, DEFAULT_CONNECT_TIMEOUT, URL_ENV, resolve_client_id, resolve_url,
+    validate_connection_target,
+};
+pub use events::{
+    ConnectionEvent, ConnectionEventSender, ConnectionEvents, connection_events,
+    connection_events_with_messages,
+};
+pub use formatter::{
+    DEFAULT_FORMATTER_WIDTH, DisplayOptions, FormatterConfig, JstRfc3339Formatter,
+    MessageFormatter, TimeFormatter,
+};
+pub use incoming::{IncomingMessage, IncomingMessages, parse_incoming};
+pub use probe::probe_connection;
+pub use runner::{run, run_with_events};
+pub use send_once::{read_message_file, send_message_once};
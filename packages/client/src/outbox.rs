@@ -0,0 +1,148 @@
+//! Buffer of sent chat messages awaiting acknowledgement from the server.
+//!
+//! A message stays in the outbox from the moment it is sent until the server
+//! acknowledges its idempotency key. Messages still pending when the connection
+//! drops are re-sent after reconnecting; the server ignores the ones it already
+//! accepted, so nothing is delivered twice.
+
+use std::collections::VecDeque;
+
+/// Default maximum number of unacknowledged messages kept in the outbox
+pub const DEFAULT_OUTBOX_CAPACITY: usize = 100;
+
+/// A sent message that has not been acknowledged yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingMessage {
+    /// Idempotency key attached to the message
+    pub idempotency_key: String,
+    /// Serialized chat frame, re-sent as is
+    pub json: String,
+}
+
+/// Bounded buffer of unacknowledged messages (drops the oldest on overflow)
+#[derive(Debug)]
+pub struct Outbox {
+    capacity: usize,
+    pending: VecDeque<PendingMessage>,
+    next_seq: u64,
+}
+
+impl Outbox {
+    /// Create an empty outbox holding at most `capacity` messages
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            pending: VecDeque::new(),
+            next_seq: 0,
+        }
+    }
+
+    /// Generate a new idempotency key for a message sent at `timestamp`
+    ///
+    /// The sequence number spans reconnections, so messages composed in the same
+    /// millisecond by different sessions never share a key.
+    pub fn next_idempotency_key(&mut self, client_id: &str, timestamp: i64) -> String {
+        let key = format!("{}-{}-{}", client_id, timestamp, self.next_seq);
+        self.next_seq += 1;
+        key
+    }
+
+    /// Record a sent message as pending.
+    ///
+    /// # Returns
+    ///
+    /// The oldest pending message if it had to be dropped to make room
+    pub fn push(&mut self, message: PendingMessage) -> Option<PendingMessage> {
+        if self.capacity == 0 {
+            return Some(message);
+        }
+        let dropped = if self.pending.len() >= self.capacity {
+            self.pending.pop_front()
+        } else {
+            None
+        };
+        self.pending.push_back(message);
+        dropped
+    }
+
+    /// Remove the message acknowledged by the server.
+    ///
+    /// # Returns
+    ///
+    /// `true` if a pending message with the key was found
+    pub fn ack(&mut self, idempotency_key: &str) -> bool {
+        let position = self
+            .pending
+            .iter()
+            .position(|m| m.idempotency_key == idempotency_key);
+        position.and_then(|i| self.pending.remove(i)).is_some()
+    }
+
+    /// Messages still awaiting acknowledgement, oldest first
+    pub fn pending(&self) -> Vec<PendingMessage> {
+        self.pending.iter().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(key: &str) -> PendingMessage {
+        PendingMessage {
+            idempotency_key: key.to_string(),
+            json: format!("{{\"idempotency_key\":\"{}\"}}", key),
+        }
+    }
+
+    #[test]
+    fn test_unacked_message_is_kept_for_resend_after_disconnect() {
+        // テスト項目: 送信後 ack を受け取る前に切断されたメッセージは再送対象として残る
+        // given (前提条件):
+        let mut outbox = Outbox::new(10);
+        outbox.push(message("alice-1"));
+        outbox.push(message("alice-2"));
+
+        // when (操作): alice-1 の ack だけを受け取った後に切断
+        let acked = outbox.ack("alice-1");
+        let to_resend = outbox.pending();
+
+        // then (期待する結果):
+        assert!(acked);
+        assert_eq!(to_resend, vec![message("alice-2")]);
+    }
+
+    #[test]
+    fn test_ack_for_unknown_key_is_ignored() {
+        // テスト項目: 未知のキーの ack は無視される
+        // given (前提条件):
+        let mut outbox = Outbox::new(10);
+        outbox.push(message("alice-1"));
+
+        // when (操作):
+        let acked = outbox.ack("alice-9");
+
+        // then (期待する結果):
+        assert!(!acked);
+        assert_eq!(outbox.pending(), vec![message("alice-1")]);
+    }
+
+    #[test]
+    fn test_push_drops_oldest_on_overflow() {
+        // テスト項目: 容量を超えると最も古いメッセージが破棄される
+        // given (前提条件):
+        let mut outbox = Outbox::new(2);
+        outbox.push(message("alice-1"));
+        outbox.push(message("alice-2"));
+
+        // when (操作):
+        let dropped = outbox.push(message("alice-3"));
+
+        // then (期待する結果):
+        assert_eq!(dropped, Some(message("alice-1")));
+        assert_eq!(
+            outbox.pending(),
+            vec![message("alice-2"), message("alice-3")]
+        );
+    }
+}
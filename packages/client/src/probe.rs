@@ -0,0 +1,123 @@
+//! One-shot connection check for liveness probes.
+//!
+//! Performs the real WebSocket handshake, waits for the initial participant list
+//! and disconnects, without the reconnect loop or the interactive prompt.
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use engawa_server::infrastructure::dto::websocket::{MessageType, RoomConnectedMessage};
+
+use super::{domain::build_connect_url, error::ClientError, session::connect};
+
+/// Maximum time to wait for the connection and the initial participant list
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Connect once, receive the initial participant list, then disconnect
+///
+/// # Arguments
+///
+/// * `url` - WebSocket server URL (e.g. `ws://127.0.0.1:8080/ws`)
+/// * `client_id` - Client ID used for the probe connection
+///
+/// # Returns
+///
+/// The `RoomConnectedMessage` sent by the server right after connecting
+///
+/// # Errors
+///
+/// * `ClientError::DuplicateClientId` - the client ID is already connected
+/// * `ClientError::ConnectionError` - the connection failed, closed early or timed out
+pub async fn probe_connection(
+    url: &str,
+    client_id: &str,
+) -> Result<RoomConnectedMessage, ClientError> {
+    tokio::time::timeout(PROBE_TIMEOUT, probe(url, client_id))
+        .await
+        .map_err(|_| {
+            ClientError::ConnectionError(format!(
+                "No participant list received within {} seconds",
+                PROBE_TIMEOUT.as_secs()
+            ))
+        })?
+}
+
+async fn probe(url: &str, client_id: &str) -> Result<RoomConnectedMessage, ClientError> {
+    let url = build_connect_url(url, client_id, None);
+    let mut ws_stream = connect(&url, client_id).await?;
+
+    let room_msg = loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => {
+                if let Ok(room_msg) = serde_json::from_str::<RoomConnectedMessage>(&text)
+                    && matches!(room_msg.r#type, MessageType::RoomConnected)
+                {
+                    break room_msg;
+                }
+            }
+            Some(Ok(Message::Close(_))) | None => {
+                return Err(ClientError::ConnectionError(
+                    "Connection closed before the participant list was received".to_string(),
+                ));
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(ClientError::ConnectionError(e.to_string())),
+        }
+    };
+
+    // The probe already succeeded; a failed close handshake is not an error
+    if let Err(e) = ws_stream.close(None).await {
+        tracing::debug!("Failed to close probe connection: {}", e);
+    }
+
+    Ok(room_msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::start_test_server;
+
+    #[tokio::test]
+    async fn test_probe_returns_roster_from_running_server() {
+        // テスト項目: 起動中のサーバーに対するプローブが参加者リストを返す
+        // given (前提条件): alice が接続中
+        let addr = start_test_server().await;
+        let url = format!("ws://{}/ws", addr);
+        let (_alice, _) = tokio_tungstenite::connect_async(format!("{}?client_id=alice", url))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = probe_connection(&url, "probe").await;
+
+        // then (期待する結果): alice とプローブ自身が含まれる
+        let room_msg = result.unwrap();
+        let mut client_ids: Vec<&str> = room_msg
+            .participants
+            .iter()
+            .map(|p| p.client_id.as_str())
+            .collect();
+        client_ids.sort();
+        assert_eq!(client_ids, vec!["alice", "probe"]);
+    }
+
+    #[tokio::test]
+    async fn test_probe_with_duplicate_client_id_returns_duplicate_error() {
+        // テスト項目: 既に接続中の client_id でのプローブは DuplicateClientId エラーになる
+        // given (前提条件):
+        let addr = start_test_server().await;
+        let url = format!("ws://{}/ws", addr);
+        let (_alice, _) = tokio_tungstenite::connect_async(format!("{}?client_id=alice", url))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = probe_connection(&url, "alice").await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(ClientError::DuplicateClientId(id)) if id == "alice"));
+    }
+}
@@ -1,15 +1,184 @@
 //! Client execution logic with reconnection support.
 
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use super::{error::ClientError, session::run_client_session};
+use super::{
+    config::ClientConfig,
+    domain::{InputCommand, parse_input, within_reconnect_time_budget},
+    error::ClientError,
+    events::{ConnectionEvent, ConnectionEventSender},
+    formatter::MessageFormatter,
+    input::{SharedInput, input_queue, spawn_stdin_reader},
+    outbox::Outbox,
+    session::{SessionEnd, compose_chat, run_client_session},
+    stats::SessionStats,
+    ui::Prompt,
+};
 
 const MAX_RECONNECT_ATTEMPTS: u32 = 5;
 const RECONNECT_INTERVAL_SECS: u64 = 5;
+/// A session lasting at least this long restores the full reconnect budget
+const SUSTAINED_SESSION: Duration = Duration::from_secs(60);
+
+/// Number of consecutive failed sessions, reset after a sustained session
+#[derive(Debug, Default)]
+struct ReconnectBudget {
+    failures: u32,
+    /// When the first of the consecutive failures was recorded
+    outage_started: Option<Instant>,
+}
+
+impl ReconnectBudget {
+    /// Record a lost session that lasted `session_duration`
+    ///
+    /// # Returns
+    ///
+    /// The number of consecutive failures, counting this one
+    fn record_failure(&mut self, session_duration: Duration) -> u32 {
+        if session_duration >= SUSTAINED_SESSION {
+            // The connection was healthy for a while; this drop starts a fresh budget
+            self.failures = 0;
+            self.outage_started = None;
+        }
+        self.failures += 1;
+        self.outage_started.get_or_insert_with(Instant::now);
+        self.failures
+    }
+
+    /// Time spent reconnecting since the first of the consecutive failures
+    fn outage_duration(&self) -> Duration {
+        self.outage_started
+            .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+}
+
+/// Wait before the next reconnection attempt after a session ended with `error`
+///
+/// A server that rejected the connection as full may suggest a wait with `Retry-After`;
+/// that wait replaces the default interval.
+fn reconnect_delay(error: &(dyn std::error::Error + 'static)) -> Duration {
+    match error.downcast_ref::<ClientError>() {
+        Some(ClientError::ServerFull {
+            retry_after: Some(retry_after),
+        }) => *retry_after,
+        _ => Duration::from_secs(RECONNECT_INTERVAL_SECS),
+    }
+}
+
+/// Wait `delay` before reconnecting, keeping chat messages typed in the meantime
+///
+/// Messages go to the outbox, from which the next session sends them in order
+/// (with their idempotency keys) before anything typed after reconnecting.
+/// `/reconnect` cuts the wait short; other commands need a connection and are skipped.
+///
+/// # Returns
+///
+/// `false` if the input was closed (the user exited) while waiting
+async fn buffer_while_disconnected(
+    delay: Duration,
+    client_id: &str,
+    prompt: &Prompt,
+    input: &SharedInput,
+    outbox: &Mutex<Outbox>,
+) -> bool {
+    let mut input_rx = input.receiver().await;
+    let wait = tokio::time::sleep(delay);
+    tokio::pin!(wait);
+
+    loop {
+        tokio::select! {
+            _ = &mut wait => return true,
+            line = input_rx.recv() => {
+                let Some(line) = line else {
+                    return false;
+                };
+                match parse_input(&line) {
+                    InputCommand::Reconnect => return true,
+                    InputCommand::Message(content) => {
+                        let formatted = MessageFormatter::format_pending(&content);
+                        if compose_chat(client_id, content, outbox).is_some() {
+                            print!("{}", formatted);
+                        }
+                    }
+                    _ => print!(
+                        "{}",
+                        MessageFormatter::format_unavailable_while_disconnected(&line)
+                    ),
+                }
+                prompt.redisplay();
+            }
+        }
+    }
+}
 
 /// Run the WebSocket client with reconnection logic
-pub async fn run(url: String, client_id: String) -> Result<(), Box<dyn std::error::Error>> {
+pub async fn run(
+    url: String,
+    client_id: String,
+    config: ClientConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    run_with_events(url, client_id, config, ConnectionEventSender::disabled()).await
+}
+
+/// Run the WebSocket client with reconnection logic, reporting lifecycle events
+///
+/// Create `events` with [`connection_events`](crate::connection_events) and consume the
+/// returned [`ConnectionEvents`](crate::ConnectionEvents) stream to follow the connection state.
+///
+/// # Errors
+///
+/// Returns an error if the client ID is already in use or reconnection was abandoned
+/// (both are preceded by [`ConnectionEvent::GaveUp`]).
+pub async fn run_with_events(
+    url: String,
+    client_id: String,
+    config: ClientConfig,
+    events: ConnectionEventSender,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // Input is read for the whole run so lines typed while reconnecting are kept
+    // (bounded: applies backpressure on a slow socket).
+    // Without a terminal, hold the sender so sessions run until the connection ends.
+    let (input_tx, input_rx) = input_queue();
+    let input = SharedInput::new(&input_tx, input_rx);
+    // Piped input is read as plain lines too: rustyline would print its own prompt
+    let _idle_input_tx = if config.interactive && (config.json || !config.prompt) {
+        spawn_stdin_reader(input_tx);
+        None
+    } else if config.interactive {
+        // Line editing needs rustyline; without the `cli` feature read plain lines
+        #[cfg(feature = "cli")]
+        super::input::spawn_readline(input_tx, client_id.clone());
+        #[cfg(not(feature = "cli"))]
+        spawn_stdin_reader(input_tx);
+        None
+    } else {
+        Some(input_tx)
+    };
+
+    run_sessions(url, client_id, config, input, events).await
+}
+
+/// Run sessions reading from `input` until the user exits or reconnection is abandoned
+async fn run_sessions(
+    url: String,
+    client_id: String,
+    config: ClientConfig,
+    input: SharedInput,
+    events: ConnectionEventSender,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut budget = ReconnectBudget::default();
     let mut reconnect_count = 0;
+    // Set after `/reconnect`: the server may not have released our previous connection yet
+    let mut reconnecting_on_request = false;
+    let prompt = Prompt::new(client_id.as_str(), config.prompt);
+
+    // Unacknowledged messages survive reconnections and are re-sent on the next session
+    let outbox = Arc::new(Mutex::new(Outbox::new(config.outbox_capacity)));
+    // Statistics shown by /stats also span reconnections
+    let stats = Arc::new(SessionStats::new());
 
     loop {
         tracing::info!(
@@ -20,47 +189,594 @@ pub async fn run(url: String, client_id: String) -> Result<(), Box<dyn std::erro
             MAX_RECONNECT_ATTEMPTS
         );
 
-        match run_client_session(&url, &client_id).await {
-            Ok(_) => {
+        // Session duration includes the handshake; a failed connect returns almost immediately
+        let session_started = Instant::now();
+        events.emit(ConnectionEvent::Connecting);
+        let result = run_client_session(
+            &url,
+            &client_id,
+            &config,
+            &input,
+            outbox.clone(),
+            stats.clone(),
+            &events,
+        )
+        .await;
+        let requested = std::mem::take(&mut reconnecting_on_request);
+        match result {
+            Ok(SessionEnd::ReconnectRequested) => {
+                tracing::info!("Reconnect requested, connecting again");
+                events.emit(ConnectionEvent::Disconnected {
+                    reason: "Reconnect requested".to_string(),
+                });
+                // A manual reconnect starts with a fresh backoff
+                budget = ReconnectBudget::default();
+                reconnect_count = 0;
+                reconnecting_on_request = true;
+                stats.record_reconnect();
+            }
+            Ok(SessionEnd::Exited) => {
                 tracing::info!("Client session ended normally");
+                events.emit(ConnectionEvent::Disconnected {
+                    reason: "Session ended".to_string(),
+                });
                 // If connection ended normally (user exit), don't reconnect
                 break;
             }
             Err(e) => {
+                events.emit(ConnectionEvent::Disconnected {
+                    reason: e.to_string(),
+                });
+
                 // Check if it's a duplicate client_id error
+                // (right after `/reconnect` it is our previous connection, so retry instead)
                 if let Some(client_err) = e.downcast_ref::<ClientError>()
                     && matches!(client_err, ClientError::DuplicateClientId(_))
+                    && !requested
                 {
                     tracing::error!("{}", e);
                     tracing::error!(
                         "Cannot connect with client_id '{}' as it is already in use. Exiting.",
                         client_id
                     );
-                    std::process::exit(1);
+                    events.emit(ConnectionEvent::GaveUp);
+                    return Err(e);
+                }
+
+                // Reconnecting would in turn replace the connection that replaced this one
+                // A kicked client would be let back in; the admin wants it out
+                if let Some(ClientError::ConnectionReplaced | ClientError::Kicked) =
+                    e.downcast_ref::<ClientError>()
+                {
+                    tracing::error!("{}. Exiting.", e);
+                    events.emit(ConnectionEvent::GaveUp);
+                    return Err(e);
                 }
 
                 tracing::warn!("Connection lost: {}", e);
-                reconnect_count += 1;
+                reconnect_count = budget.record_failure(session_started.elapsed());
+                stats.record_reconnect();
 
                 if reconnect_count >= MAX_RECONNECT_ATTEMPTS {
                     tracing::error!(
                         "Failed to reconnect after {} attempts. Exiting.",
                         MAX_RECONNECT_ATTEMPTS
                     );
-                    std::process::exit(1);
+                    events.emit(ConnectionEvent::GaveUp);
+                    return Err(Box::new(ClientError::ConnectionError(format!(
+                        "Failed to reconnect after {} attempts",
+                        MAX_RECONNECT_ATTEMPTS
+                    ))));
+                }
+
+                let delay = reconnect_delay(e.as_ref());
+                let delay = config
+                    .max_reconnect_delay
+                    .map_or(delay, |max_delay| delay.min(max_delay));
+
+                // The next attempt would start after the total reconnection time budget
+                if !within_reconnect_time_budget(
+                    budget.outage_duration(),
+                    delay,
+                    config.max_total_reconnect_duration,
+                ) {
+                    tracing::error!(
+                        "Failed to reconnect within the time budget after {} attempts. Exiting.",
+                        reconnect_count
+                    );
+                    events.emit(ConnectionEvent::GaveUp);
+                    return Err(Box::new(ClientError::ConnectionError(format!(
+                        "Failed to reconnect within the time budget after {} attempts",
+                        reconnect_count
+                    ))));
                 }
 
                 tracing::info!(
                     "Reconnecting in {} seconds... (attempt {}/{})",
-                    RECONNECT_INTERVAL_SECS,
+                    delay.as_secs(),
                     reconnect_count + 1,
                     MAX_RECONNECT_ATTEMPTS
                 );
 
-                tokio::time::sleep(Duration::from_secs(RECONNECT_INTERVAL_SECS)).await;
+                events.emit(ConnectionEvent::Reconnecting {
+                    attempt: reconnect_count,
+                });
+                if config.json {
+                    // JSON lines stay queued and are sent as is after reconnecting
+                    tokio::time::sleep(delay).await;
+                } else if !buffer_while_disconnected(delay, &client_id, &prompt, &input, &outbox)
+                    .await
+                {
+                    tracing::info!("Input closed while disconnected");
+                    break;
+                }
             }
         }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rapid_failures_exhaust_the_budget() {
+        // テスト項目: 短時間で切断が続くと失敗回数が積み上がる
+        // given (前提条件):
+        let mut budget = ReconnectBudget::default();
+
+        // when (操作):
+        let counts: Vec<u32> = (0..MAX_RECONNECT_ATTEMPTS)
+            .map(|_| budget.record_failure(Duration::from_millis(10)))
+            .collect();
+
+        // then (期待する結果):
+        assert_eq!(counts, vec![1, 2, 3, 4, 5]);
+        assert!(counts.last().copied().unwrap() >= MAX_RECONNECT_ATTEMPTS);
+    }
+
+    #[test]
+    fn test_long_session_then_drop_restores_full_budget() {
+        // テスト項目: 長時間接続していたセッションの切断後は、再接続の試行回数がリセットされる
+        // given (前提条件): 短時間の失敗が 4 回続いた
+        let mut budget = ReconnectBudget::default();
+        for _ in 0..4 {
+            budget.record_failure(Duration::from_millis(10));
+        }
+
+        // when (操作): 再接続後、2 時間接続してから切断
+        let count = budget.record_failure(Duration::from_secs(2 * 60 * 60));
+
+        // then (期待する結果): 1 回目の失敗として扱われ、残りの試行回数が元に戻る
+        assert_eq!(count, 1);
+        assert_eq!(budget.record_failure(Duration::from_millis(10)), 2);
+    }
+
+    #[tokio::test]
+    async fn test_server_full_rejection_delays_reconnect_per_retry_after() {
+        // テスト項目: 満員で拒否されたときは Retry-After の値だけ再接続を待つ
+        // given (前提条件): Retry-After: 7 を付けて 503 を返すサーバー
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            // Consume the handshake request before answering
+            let mut request = [0u8; 1024];
+            let _ = stream.read(&mut request).await.unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 503 Service Unavailable\r\nRetry-After: 7\r\nContent-Length: 0\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        // when (操作):
+        let error = crate::session::connect(&format!("ws://{}/ws", addr), "bob")
+            .await
+            .unwrap_err();
+
+        // then (期待する結果):
+        assert!(matches!(
+            error,
+            ClientError::ServerFull {
+                retry_after: Some(retry_after)
+            } if retry_after == Duration::from_secs(7)
+        ));
+        assert_eq!(reconnect_delay(&error), Duration::from_secs(7));
+    }
+
+    #[tokio::test]
+    async fn test_connect_times_out_on_unresponsive_host_and_is_retryable() {
+        // テスト項目: 応答しないホストへの接続は指定した時間でタイムアウトし、再接続の対象になる
+        // given (前提条件): TCP 接続は受け付けるが、ハンドシェイクに応答しないホスト
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connect_timeout = Duration::from_millis(200);
+
+        // when (操作):
+        let started = tokio::time::Instant::now();
+        let result = crate::session::connect_with_timeout(
+            &format!("ws://{}/ws", addr),
+            "bob",
+            connect_timeout,
+        )
+        .await;
+        let elapsed = started.elapsed();
+
+        // then (期待する結果):
+        let error = result.err().unwrap();
+        assert!(matches!(&error, ClientError::ConnectionError(msg) if msg == "timeout"));
+        assert!(elapsed >= connect_timeout);
+        assert!(elapsed < connect_timeout * 5);
+        assert!(crate::domain::should_attempt_reconnect(&error, 0, 5));
+        drop(listener);
+    }
+
+    #[test]
+    fn test_other_errors_use_default_reconnect_interval() {
+        // テスト項目: 満員以外のエラーや Retry-After のない拒否では既定の間隔で再接続する
+        // given (前提条件):
+        let lost = ClientError::ConnectionError("Connection lost".to_string());
+        let full_without_hint = ClientError::ServerFull { retry_after: None };
+
+        // when (操作):
+        let delays = [reconnect_delay(&lost), reconnect_delay(&full_without_hint)];
+
+        // then (期待する結果):
+        assert_eq!(delays, [Duration::from_secs(RECONNECT_INTERVAL_SECS); 2]);
+    }
+
+    #[tokio::test]
+    async fn test_messages_typed_while_disconnected_are_delivered_after_reconnect() {
+        // テスト項目: 切断中に入力したメッセージは再接続後に入力順・idempotency key 付きで送信される
+        // given (前提条件): 準備完了を送り、受信したテキストフレームを 2 件記録してから切断するサーバー
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            // The client sends input only after the handshake is complete
+            ws.send(Message::Text(r#"{"type":"ready"}"#.into()))
+                .await
+                .unwrap();
+            let mut frames = Vec::new();
+            while frames.len() < 2 {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    frames.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+                }
+            }
+            ws.close(None).await.ok();
+            frames
+        });
+        let (input_tx, input_rx) = input_queue();
+        let input = SharedInput::new(&input_tx, input_rx);
+        let outbox = Arc::new(Mutex::new(Outbox::new(10)));
+
+        // when (操作): 切断中に 2 行入力し、その後再接続する
+        input_tx.send("first".to_string()).await.unwrap();
+        input_tx.send("second".to_string()).await.unwrap();
+        let still_running = buffer_while_disconnected(
+            Duration::from_millis(50),
+            "alice",
+            &Prompt::new("alice", true),
+            &input,
+            &outbox,
+        )
+        .await;
+        let config = ClientConfig {
+            interactive: false,
+            ..ClientConfig::default()
+        };
+        let _ = run_client_session(
+            &format!("ws://{}/ws", addr),
+            "alice",
+            &config,
+            &input,
+            outbox.clone(),
+            Arc::new(SessionStats::new()),
+            &ConnectionEventSender::disabled(),
+        )
+        .await;
+        let frames = server.await.unwrap();
+
+        // then (期待する結果):
+        assert!(still_running);
+        let contents: Vec<&str> = frames
+            .iter()
+            .map(|frame| frame["content"].as_str().unwrap())
+            .collect();
+        assert_eq!(contents, vec!["first", "second"]);
+        let pending_keys: Vec<String> = outbox
+            .lock()
+            .unwrap()
+            .pending()
+            .into_iter()
+            .map(|message| message.idempotency_key)
+            .collect();
+        let sent_keys: Vec<String> = frames
+            .iter()
+            .map(|frame| frame["idempotency_key"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(sent_keys, pending_keys);
+        assert_ne!(sent_keys[0], sent_keys[1]);
+    }
+
+    #[tokio::test]
+    async fn test_auto_reply_answers_other_participants_but_not_itself() {
+        // テスト項目: ボットモードでは他の参加者のメッセージにテンプレートで返信し、自分自身のメッセージには返信しない
+        // given (前提条件): 準備完了の後、echo 自身と bob のチャットを順に送り、クライアントから届いた最初のフレームを返すサーバー
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.send(Message::Text(r#"{"type":"ready"}"#.into()))
+                .await
+                .unwrap();
+            for from in ["echo", "bob"] {
+                let chat = format!(
+                    r#"{{"type":"chat","client_id":"{}","content":"hi from {}","timestamp":0}}"#,
+                    from, from
+                );
+                ws.send(Message::Text(chat.into())).await.unwrap();
+            }
+            let frame = loop {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    break serde_json::from_str::<serde_json::Value>(&text).unwrap();
+                }
+            };
+            ws.close(None).await.ok();
+            frame
+        });
+        let (input_tx, input_rx) = input_queue();
+        let input = SharedInput::new(&input_tx, input_rx);
+        let config = ClientConfig {
+            interactive: false,
+            auto_reply: Some(crate::AutoReply::new("@{from} you said: {content}")),
+            ..ClientConfig::default()
+        };
+
+        // when (操作):
+        let _ = run_client_session(
+            &format!("ws://{}/ws", addr),
+            "echo",
+            &config,
+            &input,
+            Arc::new(Mutex::new(Outbox::new(10))),
+            Arc::new(SessionStats::new()),
+            &ConnectionEventSender::disabled(),
+        )
+        .await;
+        let frame = server.await.unwrap();
+
+        // then (期待する結果): 最初に送られたのは bob への返信（echo 自身への返信はその前に送られていない）
+        assert_eq!(frame["type"], "chat");
+        assert_eq!(frame["client_id"], "echo");
+        assert_eq!(frame["content"], "@bob you said: hi from bob");
+    }
+
+    #[tokio::test]
+    async fn test_heartbeats_are_sent_periodically_while_silent() {
+        // テスト項目: ハートビートを有効にすると、入力がなくても設定した間隔でハートビートが送られる
+        // given (前提条件): 準備完了の後、クライアントから届いたフレームを 2 つ返すサーバー
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.send(Message::Text(r#"{"type":"ready"}"#.into()))
+                .await
+                .unwrap();
+            let mut frames = Vec::new();
+            while frames.len() < 2 {
+                if let Some(Ok(Message::Text(text))) = ws.next().await {
+                    frames.push(serde_json::from_str::<serde_json::Value>(&text).unwrap());
+                }
+            }
+            ws.close(None).await.ok();
+            frames
+        });
+        let (input_tx, input_rx) = input_queue();
+        let input = SharedInput::new(&input_tx, input_rx);
+        let config = ClientConfig {
+            interactive: false,
+            heartbeat_interval: Some(Duration::from_millis(50)),
+            ..ClientConfig::default()
+        };
+
+        // when (操作):
+        let _ = run_client_session(
+            &format!("ws://{}/ws", addr),
+            "alice",
+            &config,
+            &input,
+            Arc::new(Mutex::new(Outbox::new(10))),
+            Arc::new(SessionStats::new()),
+            &ConnectionEventSender::disabled(),
+        )
+        .await;
+        let frames = server.await.unwrap();
+
+        // then (期待する結果):
+        assert!(
+            frames
+                .iter()
+                .all(|frame| frame == &serde_json::json!({"type": "heartbeat"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_disconnect_cycle_emits_lifecycle_events_in_order() {
+        // テスト項目: 接続後にサーバーから切断されると、Connecting → Connected → Disconnected → Reconnecting の順にイベントが届く
+        // given (前提条件): 1 回だけ接続を受け付け、すぐに切断するサーバー
+        use futures_util::StreamExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.close(None).await.ok();
+        });
+        let config = ClientConfig {
+            interactive: false,
+            ..ClientConfig::default()
+        };
+        let (sender, mut events) = crate::connection_events();
+
+        // when (操作): 最初の再接続待ちに入るまでイベントを集める
+        let run = run_with_events(
+            format!("ws://{}/ws", addr),
+            "alice".to_string(),
+            config,
+            sender,
+        );
+        let collect = async {
+            let mut received = Vec::new();
+            while let Some(event) = events.next().await {
+                let done = matches!(event, ConnectionEvent::Reconnecting { .. });
+                received.push(event);
+                if done {
+                    break;
+                }
+            }
+            received
+        };
+        let received = tokio::select! {
+            _ = run => panic!("Client stopped before reconnecting"),
+            received = collect => received,
+        };
+
+        // then (期待する結果):
+        assert_eq!(
+            received,
+            vec![
+                ConnectionEvent::Connecting,
+                ConnectionEvent::Connected,
+                ConnectionEvent::Disconnected {
+                    reason: "Connection error: Connection lost".to_string(),
+                },
+                ConnectionEvent::Reconnecting { attempt: 1 },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_time_budget_exhausted_gives_up_before_attempt_count() {
+        // テスト項目: 再接続の時間の予算を使い切ると、試行回数の上限より前に諦める
+        // given (前提条件): 接続をすぐに切断するサーバーと、既定の再接続間隔より短い時間の予算
+        use futures_util::StreamExt;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            ws.close(None).await.ok();
+        });
+        let config = ClientConfig {
+            interactive: false,
+            max_total_reconnect_duration: Some(Duration::from_secs(1)),
+            ..ClientConfig::default()
+        };
+        let (sender, events) = crate::connection_events();
+
+        // when (操作):
+        let result = tokio::time::timeout(
+            Duration::from_secs(RECONNECT_INTERVAL_SECS),
+            run_with_events(
+                format!("ws://{}/ws", addr),
+                "alice".to_string(),
+                config,
+                sender,
+            ),
+        )
+        .await
+        .expect("Client should give up without waiting to reconnect");
+        let received: Vec<ConnectionEvent> = events.collect().await;
+
+        // then (期待する結果): 1 回目の失敗の後、再接続を待たずに諦める
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Connection error: Failed to reconnect within the time budget after 1 attempts"
+        );
+        assert_eq!(received.last(), Some(&ConnectionEvent::GaveUp));
+        assert!(
+            !received
+                .iter()
+                .any(|event| matches!(event, ConnectionEvent::Reconnecting { .. }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_command_reestablishes_the_session() {
+        // テスト項目: /reconnect でセッションを閉じ、すぐに再接続する
+        // given (前提条件): 起動中のサーバーに接続したクライアント
+        use futures_util::StreamExt;
+
+        let addr = crate::test_support::start_test_server().await;
+        let (input_tx, input_rx) = input_queue();
+        let input = SharedInput::new(&input_tx, input_rx);
+        let config = ClientConfig {
+            interactive: false,
+            ..ClientConfig::default()
+        };
+        let (sender, mut events) = crate::connection_events();
+        let run = run_sessions(
+            format!("ws://{}/ws", addr),
+            "alice".to_string(),
+            config,
+            input,
+            sender,
+        );
+        let scenario = async {
+            assert_eq!(events.next().await, Some(ConnectionEvent::Connecting));
+            assert_eq!(events.next().await, Some(ConnectionEvent::Connected));
+
+            // when (操作):
+            input_tx.send("/reconnect".to_string()).await.unwrap();
+
+            let mut received = Vec::new();
+            while let Some(event) = events.next().await {
+                let done = event == ConnectionEvent::Connected;
+                received.push(event);
+                if done {
+                    break;
+                }
+            }
+            // 入力を閉じるとクライアントは終了する
+            drop(input_tx);
+            received
+        };
+        let (result, received) = tokio::time::timeout(Duration::from_secs(10), async {
+            tokio::join!(run, scenario)
+        })
+        .await
+        .unwrap();
+
+        // then (期待する結果): 切断後、再び接続が確立する
+        assert!(result.is_ok());
+        assert_eq!(
+            received.first(),
+            Some(&ConnectionEvent::Disconnected {
+                reason: "Reconnect requested".to_string(),
+            })
+        );
+        assert_eq!(received.last(), Some(&ConnectionEvent::Connected));
+        assert!(!received.contains(&ConnectionEvent::GaveUp));
+    }
+}
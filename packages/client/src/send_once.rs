@@ -0,0 +1,193 @@
+//! One-shot message sending for pre-composed notices.
+//!
+//! Connects once, sends a single chat message, waits until the server
+//! acknowledges that it was stored and disconnects, without the reconnect loop
+//! or the interactive prompt.
+
+use std::{path::Path, time::Duration};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio_tungstenite::tungstenite::protocol::Message;
+
+use engawa_server::{
+    domain::MessageContent,
+    infrastructure::dto::websocket::{AckMessage, ChatMessage, ErrorMessage, MessageType},
+};
+use engawa_shared::time::get_jst_timestamp;
+
+use super::{domain::build_connect_url, error::ClientError, session::connect};
+
+/// Maximum time to wait for the connection and the acknowledgement of the message
+const SEND_ONCE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Read a message file as the content of a single chat message
+///
+/// The trailing line ending that editors usually add is removed;
+/// other line breaks are kept as part of the message.
+///
+/// # Errors
+///
+/// * `ClientError::MessageFile` - the file does not exist or cannot be read as UTF-8
+pub fn read_message_file(path: &Path) -> Result<String, ClientError> {
+    let content = std::fs::read_to_string(path).map_err(|source| ClientError::MessageFile {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    Ok(content.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Connect once, send `content` as a chat message, then disconnect
+///
+/// Returns after the server has acknowledged the message (it was stored and broadcast).
+///
+/// # Arguments
+///
+/// * `url` - WebSocket server URL (e.g. `ws://127.0.0.1:8080/ws`)
+/// * `client_id` - Client ID used for the connection and as the sender of the message
+/// * `content` - Message content (subject to the server's content length limit)
+///
+/// # Errors
+///
+/// * `ClientError::InvalidMessage` - the content is empty, too long, or rejected by the server
+/// * `ClientError::DuplicateClientId` - the client ID is already connected
+/// * `ClientError::ConnectionError` - the connection failed, closed early or timed out
+pub async fn send_message_once(
+    url: &str,
+    client_id: &str,
+    content: &str,
+) -> Result<(), ClientError> {
+    // Check the length limit before connecting, so that an oversized message fails fast
+    MessageContent::new(content.to_string())
+        .map_err(|e| ClientError::InvalidMessage(e.to_string()))?;
+
+    tokio::time::timeout(SEND_ONCE_TIMEOUT, send_once(url, client_id, content))
+        .await
+        .map_err(|_| {
+            ClientError::ConnectionError(format!(
+                "Message was not acknowledged within {} seconds",
+                SEND_ONCE_TIMEOUT.as_secs()
+            ))
+        })?
+}
+
+async fn send_once(url: &str, client_id: &str, content: &str) -> Result<(), ClientError> {
+    let url = build_connect_url(url, client_id, None);
+    let mut ws_stream = connect(&url, client_id).await?;
+
+    // The idempotency key makes the server acknowledge the message once it is stored
+    let timestamp = get_jst_timestamp();
+    let idempotency_key = format!("{}-once-{}", client_id, timestamp);
+    let msg = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: client_id.to_string(),
+        content: content.to_string(),
+        timestamp,
+        idempotency_key: Some(idempotency_key.clone()),
+        links: Vec::new(),
+        message_id: None,
+        ttl_secs: None,
+        expires_at: None,
+    };
+    let json =
+        serde_json::to_string(&msg).map_err(|e| ClientError::InvalidMessage(e.to_string()))?;
+    ws_stream
+        .send(Message::Text(json.into()))
+        .await
+        .map_err(|e| ClientError::ConnectionError(e.to_string()))?;
+
+    loop {
+        match ws_stream.next().await {
+            Some(Ok(Message::Text(text))) => {
+                if let Ok(ack) = serde_json::from_str::<AckMessage>(&text)
+                    && matches!(ack.r#type, MessageType::Ack)
+                    && ack.idempotency_key == idempotency_key
+                {
+                    break;
+                }
+                if let Ok(error) = serde_json::from_str::<ErrorMessage>(&text)
+                    && matches!(error.r#type, MessageType::Error)
+                {
+                    return Err(ClientError::InvalidMessage(format!(
+                        "{} ({})",
+                        error.message, error.code
+                    )));
+                }
+            }
+            Some(Ok(Message::Close(_))) | None => {
+                return Err(ClientError::ConnectionError(
+                    "Connection closed before the message was acknowledged".to_string(),
+                ));
+            }
+            Some(Ok(_)) => {}
+            Some(Err(e)) => return Err(ClientError::ConnectionError(e.to_string())),
+        }
+    }
+
+    // The message was already stored; a failed close handshake is not an error
+    if let Err(e) = ws_stream.close(None).await {
+        tracing::debug!("Failed to close one-shot connection: {}", e);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::start_test_server;
+
+    fn temp_message_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!(
+            "engawa-{}-{}-{}.txt",
+            name,
+            std::process::id(),
+            get_jst_timestamp()
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_send_message_file_contents_are_stored_by_server() {
+        // テスト項目: メッセージファイルの内容が 1 件のメッセージとして送信され、サーバーに保存される
+        // given (前提条件):
+        let addr = start_test_server().await;
+        let path = temp_message_path("notice");
+        std::fs::write(&path, "Maintenance at 18:00\nPlease save your work.\n").unwrap();
+
+        // when (操作):
+        let content = read_message_file(&path).unwrap();
+        let result = send_message_once(&format!("ws://{}/ws", addr), "notice", &content).await;
+
+        // then (期待する結果): 末尾の改行だけが取り除かれて保存されている
+        result.unwrap();
+        let room: serde_json::Value = reqwest::get(format!("http://{}/debug/room", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let messages = room["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["from"], "notice");
+        assert_eq!(
+            messages[0]["content"],
+            "Maintenance at 18:00\nPlease save your work."
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_read_missing_message_file_returns_message_file_error() {
+        // テスト項目: 存在しないメッセージファイルはパスを含む MessageFile エラーになる
+        // given (前提条件):
+        let path = temp_message_path("missing");
+
+        // when (操作):
+        let result = read_message_file(&path);
+
+        // then (期待する結果):
+        let error = result.unwrap_err();
+        assert!(matches!(&error, ClientError::MessageFile { path: p, .. } if p == &path));
+        assert!(error.to_string().contains(&path.display().to_string()));
+    }
+}
@@ -1,61 +1,297 @@
 //! WebSocket client session management.
 
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
 use futures_util::{SinkExt, StreamExt};
-use rustyline::DefaultEditor;
-use rustyline::error::ReadlineError;
-use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use tokio::{
+    net::TcpStream,
+    sync::{mpsc, watch},
+    time::{Interval, MissedTickBehavior},
+};
+use tokio_tungstenite::{
+    MaybeTlsStream, WebSocketStream, connect_async,
+    tungstenite::{
+        self,
+        error::ProtocolError,
+        http::header::RETRY_AFTER,
+        protocol::{Message, frame::coding::CloseCode},
+    },
+};
 
 use engawa_server::infrastructure::dto::websocket::{
-    ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
-    RoomConnectedMessage,
+    AppPingMessage, CLOSE_CODE_KICKED, CLOSE_CODE_REPLACED, ChatMessage, FileMessage,
+    HeartbeatMessage, MessageType, RosterRequestMessage,
 };
 use engawa_shared::time::get_jst_timestamp;
 
-use super::{error::ClientError, formatter::MessageFormatter, ui::redisplay_prompt};
+use super::{
+    bridge::run_json_bridge,
+    config::ClientConfig,
+    domain::{
+        InputCommand, build_connect_url, calculate_rtt_millis, guess_mime, parse_input,
+        should_display_sent_optimistically,
+    },
+    error::ClientError,
+    events::{ConnectionEvent, ConnectionEventSender},
+    formatter::MessageFormatter,
+    incoming::{IncomingMessage, parse_incoming},
+    input::{SharedInput, submit_command},
+    outbox::{Outbox, PendingMessage},
+    stats::SessionStats,
+    ui::Prompt,
+};
+
+/// WebSocket connection to the chat server
+pub(crate) type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
-/// Run the WebSocket client session
-pub async fn run_client_session(
+/// Time to wait for the server to answer our close frame when reconnecting on request
+const CLOSE_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Number of auto-replies waiting to be sent before further ones are dropped
+const AUTO_REPLY_QUEUE_CAPACITY: usize = 16;
+
+/// How a session ended without losing the connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SessionEnd {
+    /// The input was closed (the user exited)
+    Exited,
+    /// The user asked to reconnect with `/reconnect`
+    ReconnectRequested,
+}
+
+/// Redisplay the prompt, unless the server is still sending the initial frames
+fn redisplay_prompt_when_ready(ready: &watch::Sender<bool>, prompt: &Prompt) {
+    if *ready.borrow() {
+        prompt.redisplay();
+    }
+}
+
+/// Wait for the next presence heartbeat (never completes when heartbeats are disabled)
+async fn next_heartbeat(heartbeat: &mut Option<Interval>) {
+    match heartbeat {
+        Some(ticker) => {
+            ticker.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// A file received from another participant, kept until saved with `/save`
+struct ReceivedFile {
+    filename: String,
+    data: Vec<u8>,
+}
+
+/// Build a file message from a local file path
+fn build_file_message(client_id: &str, path: &str) -> Result<FileMessage, std::io::Error> {
+    let data = std::fs::read(path)?;
+    let filename = std::path::Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string());
+    Ok(FileMessage {
+        r#type: MessageType::File,
+        client_id: client_id.to_string(),
+        mime: guess_mime(&filename).to_string(),
+        filename,
+        data: BASE64.encode(data),
+        timestamp: get_jst_timestamp(),
+    })
+}
+
+/// Save a received file to the current directory
+///
+/// Only the final path component of the filename is used, so a file can never be
+/// written outside the current directory.
+fn save_received_file(file: &ReceivedFile) -> Result<String, std::io::Error> {
+    let filename = std::path::Path::new(&file.filename)
+        .file_name()
+        .ok_or_else(|| std::io::Error::other("invalid filename"))?;
+    std::fs::write(filename, &file.data)?;
+    Ok(filename.to_string_lossy().to_string())
+}
+
+/// Aborts the wrapped task (if any) when dropped
+struct AbortOnDrop(Option<tokio::task::JoinHandle<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        if let Some(handle) = &self.0 {
+            handle.abort();
+        }
+    }
+}
+
+/// Build a chat frame and keep it in the outbox until the server acknowledges it
+///
+/// The idempotency key lets the server ignore the message if it is re-sent after a reconnect.
+///
+/// # Returns
+///
+/// The serialized frame and its timestamp, or `None` if serialization failed
+pub(crate) fn compose_chat(
+    client_id: &str,
+    content: String,
+    outbox: &Mutex<Outbox>,
+) -> Option<(String, i64)> {
+    let timestamp = get_jst_timestamp();
+    let Ok(mut outbox) = outbox.lock() else {
+        return None;
+    };
+    let idempotency_key = outbox.next_idempotency_key(client_id, timestamp);
+    let msg = ChatMessage {
+        r#type: MessageType::Chat,
+        client_id: client_id.to_string(),
+        content,
+        timestamp,
+        idempotency_key: Some(idempotency_key.clone()),
+        links: Vec::new(),
+        message_id: None,
+        ttl_secs: None,
+        expires_at: None,
+    };
+
+    let json = match serde_json::to_string(&msg) {
+        Ok(json) => json,
+        Err(e) => {
+            tracing::error!("Failed to serialize message: {}", e);
+            return None;
+        }
+    };
+
+    if let Some(dropped) = outbox.push(PendingMessage {
+        idempotency_key,
+        json: json.clone(),
+    }) {
+        tracing::warn!(
+            "Outbox full, dropped unacknowledged message '{}'",
+            dropped.idempotency_key
+        );
+    }
+    Some((json, timestamp))
+}
+
+/// Open the WebSocket connection, giving up after `timeout`
+///
+/// An unresponsive host would otherwise keep the attempt pending forever.
+/// The timeout is reported as `ClientError::ConnectionError("timeout")`, which is retried.
+pub(crate) async fn connect_with_timeout(
     url: &str,
     client_id: &str,
-) -> Result<(), Box<dyn std::error::Error>> {
-    // Construct URL with client_id as query parameter
-    let url = format!("{}?client_id={}", url, client_id);
+    timeout: Duration,
+) -> Result<WsStream, ClientError> {
+    tokio::time::timeout(timeout, connect(url, client_id))
+        .await
+        .map_err(|_| ClientError::ConnectionError("timeout".to_string()))?
+}
 
-    let (ws_stream, response) = match connect_async(&url).await {
+/// Open the WebSocket connection, mapping a rejected duplicate client_id to its own error
+pub(crate) async fn connect(url: &str, client_id: &str) -> Result<WsStream, ClientError> {
+    let (ws_stream, response) = match connect_async(url).await {
         Ok(result) => result,
+        // 503: the room is full; honor the server's hint for when to retry
+        Err(tungstenite::Error::Http(response)) if response.status().as_u16() == 503 => {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            return Err(ClientError::ServerFull { retry_after });
+        }
         Err(e) => {
             // Check if it's an HTTP error response
             let error_msg = e.to_string();
 
             // Check for HTTP 409 Conflict
             if error_msg.contains("409") || error_msg.contains("Conflict") {
-                return Err(Box::new(ClientError::DuplicateClientId(
-                    client_id.to_string(),
-                )));
+                return Err(ClientError::DuplicateClientId(client_id.to_string()));
             }
 
-            return Err(Box::new(ClientError::ConnectionError(error_msg)));
+            return Err(ClientError::ConnectionError(error_msg));
         }
     };
 
     // Check HTTP status code from response
     if response.status().as_u16() == 409 {
-        return Err(Box::new(ClientError::DuplicateClientId(
-            client_id.to_string(),
-        )));
+        return Err(ClientError::DuplicateClientId(client_id.to_string()));
     }
 
+    Ok(ws_stream)
+}
+
+/// Run the WebSocket client session
+pub async fn run_client_session(
+    url: &str,
+    client_id: &str,
+    config: &ClientConfig,
+    input: &SharedInput,
+    outbox: Arc<Mutex<Outbox>>,
+    stats: Arc<SessionStats>,
+    events: &ConnectionEventSender,
+) -> Result<SessionEnd, Box<dyn std::error::Error>> {
+    // Shown by /whoami without the connect query parameters
+    let server_url = url.to_string();
+
+    // Construct URL with client_id (and initial status) as query parameters
+    let url = build_connect_url(url, client_id, config.status.as_deref());
+
+    let ws_stream = connect_with_timeout(&url, client_id, config.connect_timeout).await?;
+
     tracing::info!("Connected to chat server!");
-    println!(
-        "\nYou are '{}'. Type messages and press Enter to send. Press Ctrl+C to exit.\n",
-        client_id
-    );
+    events.emit(ConnectionEvent::Connected);
+    stats.record_connected(get_jst_timestamp());
+
+    if config.json {
+        return run_json_bridge(ws_stream, input, stats, std::io::stdout()).await;
+    }
 
     let (mut write, mut read) = ws_stream.split();
 
     // Clone client_id for read task
     let client_id_for_read = client_id.to_string();
+    let prompt = Prompt::new(client_id, config.prompt);
+    let prompt_for_read = prompt.clone();
+
+    // Pending application-level pings (nonce -> sent_at in milliseconds)
+    let pending_pings: Arc<Mutex<HashMap<u64, i64>>> = Arc::new(Mutex::new(HashMap::new()));
+    let pending_pings_for_read = pending_pings.clone();
+
+    // Room reported by the server in the room-connected frame
+    let room_id: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let room_id_for_read = room_id.clone();
+
+    // Last file received from another participant
+    let last_received_file: Arc<Mutex<Option<ReceivedFile>>> = Arc::new(Mutex::new(None));
+    let last_received_file_for_read = last_received_file.clone();
+
+    let outbox_for_read = outbox.clone();
+    let stats_for_read = stats.clone();
+    let formatter = config.formatter.clone();
+    let formatter_for_read = formatter.clone();
+    let events_for_read = events.clone();
+
+    // Set by the read task when the server closes the connection because it was replaced
+    let replaced = Arc::new(AtomicBool::new(false));
+    let replaced_for_read = replaced.clone();
+    // Set by the read task when the server closes the connection because an admin kicked us
+    let kicked = Arc::new(AtomicBool::new(false));
+    let kicked_for_read = kicked.clone();
+
+    // Set by the read task once the server has sent all initial frames
+    let (ready_tx, mut ready_rx) = watch::channel(false);
+
+    // Replies rendered by the read task in bot mode, sent by the write task as chat messages
+    let auto_reply = config.auto_reply.clone();
+    let (auto_reply_tx, mut auto_reply_rx) = mpsc::channel::<String>(AUTO_REPLY_QUEUE_CAPACITY);
 
     // Spawn a task to handle incoming messages
     let mut read_task = tokio::spawn(async move {
@@ -64,61 +300,205 @@ pub async fn run_client_session(
         while let Some(message) = read.next().await {
             match message {
                 Ok(Message::Text(text)) => {
-                    // Try to parse as RoomConnectedMessage first
-                    if let Ok(room_msg) = serde_json::from_str::<RoomConnectedMessage>(&text) {
-                        let formatted = MessageFormatter::format_room_connected(
-                            &room_msg.participants,
-                            &client_id_for_read,
-                        );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
-                    }
-                    // Try to parse as ParticipantJoinedMessage
-                    else if let Ok(joined_msg) =
-                        serde_json::from_str::<ParticipantJoinedMessage>(&text)
-                    {
-                        let formatted = MessageFormatter::format_participant_joined(
-                            &joined_msg.client_id,
-                            joined_msg.connected_at,
-                        );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
-                    }
-                    // Try to parse as ParticipantLeftMessage
-                    else if let Ok(left_msg) =
-                        serde_json::from_str::<ParticipantLeftMessage>(&text)
-                    {
-                        let formatted = MessageFormatter::format_participant_left(
-                            &left_msg.client_id,
-                            left_msg.disconnected_at,
-                        );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
-                    }
-                    // Try to parse as ChatMessage
-                    else if let Ok(chat_msg) = serde_json::from_str::<ChatMessage>(&text) {
-                        let formatted = MessageFormatter::format_chat_message(
-                            &chat_msg.client_id,
-                            &chat_msg.content,
-                            chat_msg.timestamp,
-                        );
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
-                    }
-                    // If parsing fails, display as raw text
-                    else {
-                        let formatted = MessageFormatter::format_raw_message(&text);
-                        print!("{}", formatted);
-                        redisplay_prompt(&client_id_for_read);
+                    let incoming = parse_incoming(&text);
+                    match &incoming {
+                        IncomingMessage::Pong(pong_msg) => {
+                            let sent_at = pending_pings_for_read
+                                .lock()
+                                .ok()
+                                .and_then(|mut pings| pings.remove(&pong_msg.nonce));
+                            if let Some(sent_at) = sent_at {
+                                let rtt = calculate_rtt_millis(sent_at, get_jst_timestamp());
+                                stats_for_read.record_rtt(rtt);
+                                print!("{}", MessageFormatter::format_pong(rtt));
+                                redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                            }
+                        }
+                        IncomingMessage::Ack(ack_msg) => {
+                            if let Ok(mut outbox) = outbox_for_read.lock() {
+                                outbox.ack(&ack_msg.idempotency_key);
+                            }
+                        }
+                        IncomingMessage::Ready(_) => {
+                            ready_tx.send_replace(true);
+                            prompt_for_read.show_banner();
+                            prompt_for_read.redisplay();
+                        }
+                        IncomingMessage::Roster(roster_msg) => {
+                            let formatted = formatter_for_read.format_room_connected(
+                                &roster_msg.participants,
+                                &client_id_for_read,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::File(file_msg) => {
+                            stats_for_read.record_received();
+                            match BASE64.decode(&file_msg.data) {
+                                Ok(data) => {
+                                    let formatted = MessageFormatter::format_file_shared(
+                                        &file_msg.client_id,
+                                        &file_msg.filename,
+                                        data.len(),
+                                    );
+                                    print!("{}", formatted);
+                                    if let Ok(mut last) = last_received_file_for_read.lock() {
+                                        *last = Some(ReceivedFile {
+                                            filename: file_msg.filename.clone(),
+                                            data,
+                                        });
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::warn!("Failed to decode file data: {}", e);
+                                }
+                            }
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::ReactionUpdated(reaction_msg) => {
+                            let formatted = MessageFormatter::format_reaction_updated(
+                                &reaction_msg.by,
+                                &reaction_msg.message_id,
+                                &reaction_msg.emoji,
+                                reaction_msg.count,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::Error(error_msg) => {
+                            let formatted =
+                                MessageFormatter::format_error(&error_msg.code, &error_msg.message);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::System(system_msg) => {
+                            let formatted =
+                                MessageFormatter::format_system_message(&system_msg.content);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::ParticipantCount(count_msg) => {
+                            let formatted =
+                                MessageFormatter::format_participant_count(count_msg.count);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::HistoryCleared(cleared_msg) => {
+                            // A terminal cannot take back what it printed; mark the cut instead
+                            let formatted =
+                                MessageFormatter::format_history_cleared(cleared_msg.purged);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::MessageDeleted(deleted_msg) => {
+                            // As with a cleared history, the printed message stays on screen
+                            let formatted =
+                                MessageFormatter::format_message_deleted(&deleted_msg.message_id);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::RoomConnected(room_msg) => {
+                            if let Ok(mut room_id) = room_id_for_read.lock() {
+                                room_id.clone_from(&room_msg.room_id);
+                            }
+                            let formatted = formatter_for_read.format_room_connected_with_total(
+                                &room_msg.participants,
+                                room_msg.total,
+                                &client_id_for_read,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::Joined(joined_msg) => {
+                            let formatted = formatter_for_read.format_participant_joined(
+                                &joined_msg.client_id,
+                                joined_msg.connected_at,
+                                &joined_msg.status,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::Left(left_msg) => {
+                            let formatted = formatter_for_read.format_participant_left(
+                                &left_msg.client_id,
+                                left_msg.disconnected_at,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
+                        IncomingMessage::Chat(chat_msg) => {
+                            stats_for_read.record_received();
+                            let formatted = formatter_for_read.format_chat_message(
+                                &chat_msg.client_id,
+                                &chat_msg.content,
+                                chat_msg.timestamp,
+                            );
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+
+                            if let Some(auto_reply) = &auto_reply
+                                && let Some(reply) = auto_reply.reply_to(
+                                    &client_id_for_read,
+                                    &chat_msg.client_id,
+                                    &chat_msg.content,
+                                )
+                                && auto_reply_tx.try_send(reply).is_err()
+                            {
+                                tracing::warn!(
+                                    "Dropped the auto-reply to {}: too many pending replies",
+                                    chat_msg.client_id
+                                );
+                            }
+                        }
+                        // Not a known message: display as raw text
+                        IncomingMessage::Raw(text) => {
+                            let formatted = MessageFormatter::format_raw_message(text);
+                            print!("{}", formatted);
+                            redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                        }
                     }
+                    events_for_read.emit_message(incoming);
                 }
                 Ok(Message::Binary(data)) => {
                     let formatted = MessageFormatter::format_binary_message(data.len());
                     print!("{}", formatted);
-                    redisplay_prompt(&client_id_for_read);
+                    redisplay_prompt_when_ready(&ready_tx, &prompt_for_read);
+                }
+                Ok(Message::Close(frame)) => {
+                    if frame
+                        .as_ref()
+                        .is_some_and(|frame| u16::from(frame.code) == CLOSE_CODE_REPLACED)
+                    {
+                        tracing::info!("Connection was replaced by a new connection");
+                        replaced_for_read.store(true, Ordering::SeqCst);
+                    } else if frame
+                        .as_ref()
+                        .is_some_and(|frame| u16::from(frame.code) == CLOSE_CODE_KICKED)
+                    {
+                        tracing::warn!("Kicked from the room by an admin");
+                        kicked_for_read.store(true, Ordering::SeqCst);
+                    } else if let Some(frame) = frame
+                        && matches!(frame.code, CloseCode::Protocol | CloseCode::Size)
+                    {
+                        tracing::warn!("Server closed the connection: {}", frame.reason);
+                    } else {
+                        tracing::info!("Server closed the connection");
+                    }
+                    connection_error = true;
+                    break;
+                }
+                Err(tungstenite::Error::Protocol(ProtocolError::ResetWithoutClosingHandshake)) => {
+                    tracing::info!("Connection was reset by the server");
+                    connection_error = true;
+                    break;
                 }
-                Ok(Message::Close(_)) => {
-                    tracing::info!("Server closed the connection");
+                Err(
+                    e @ (tungstenite::Error::Protocol(_)
+                    | tungstenite::Error::Capacity(_)
+                    | tungstenite::Error::Utf8(_)
+                    | tungstenite::Error::AttackAttempt),
+                ) => {
+                    tracing::warn!("Server violated the WebSocket protocol: {}", e);
                     connection_error = true;
                     break;
                 }
@@ -136,111 +516,271 @@ pub async fn run_client_session(
 
     // Clone client_id for the input loop
     let client_id = client_id.to_string();
-    let client_id_for_prompt = client_id.clone();
-
-    // Create channel for rustyline input
-    let (input_tx, mut input_rx) = mpsc::unbounded_channel::<String>();
-
-    // Spawn a blocking thread for rustyline (synchronous readline)
-    let _readline_handle = std::thread::spawn(move || {
-        let mut rl = match DefaultEditor::new() {
-            Ok(rl) => rl,
-            Err(e) => {
-                eprintln!("Failed to initialize readline: {}", e);
-                return;
-            }
-        };
-
-        let prompt = format!("{}> ", client_id_for_prompt);
 
-        loop {
-            match rl.readline(&prompt) {
-                Ok(line) => {
-                    let line = line.trim();
-                    if !line.is_empty() {
-                        rl.add_history_entry(line).ok();
-                        if input_tx.send(line.to_string()).is_err() {
-                            // Channel closed, exit thread
-                            break;
-                        }
-                    }
-                }
-                Err(ReadlineError::Interrupted) => {
-                    // Ctrl+C
-                    tracing::info!("Interrupted");
-                    break;
-                }
-                Err(ReadlineError::Eof) => {
-                    // Ctrl+D
-                    tracing::info!("EOF");
-                    break;
-                }
-                Err(err) => {
-                    tracing::error!("Readline error: {}", err);
+    // Periodically request the participant list by injecting the /roster command
+    let server_echo = config.server_echo;
+    let roster_refresh_task = config.roster_refresh_interval.map(|interval| {
+        let input = input.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately; the roster is already sent on connect
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let submitted = input
+                    .sender()
+                    .is_some_and(|input_tx| submit_command(&input_tx, "/roster"));
+                if !submitted {
                     break;
                 }
             }
-        }
+        })
     });
 
+    // Set by the write task when the user asks to reconnect
+    let reconnect_requested = Arc::new(AtomicBool::new(false));
+    let reconnect_requested_for_write = reconnect_requested.clone();
+
+    // Presence heartbeats keep the server-side activity fresh while the user is silent
+    let heartbeat_interval = config.heartbeat_interval;
+
     // Spawn a task to handle stdin input and send to WebSocket
-    let client_id_for_write = client_id.clone();
+    let input_for_write = input.clone();
     let mut write_task = tokio::spawn(async move {
         let mut write_error = false;
+        let mut next_ping_nonce: u64 = 0;
+        // Lines typed while disconnected are queued here and sent after the re-sent messages
+        let mut input_rx = input_for_write.receiver().await;
 
-        while let Some(line) = input_rx.recv().await {
-            // Create message with type "chat" and client_id
-            let msg = ChatMessage {
-                r#type: MessageType::Chat,
-                client_id: client_id.clone(),
-                content: line,
-                timestamp: get_jst_timestamp(),
-            };
+        // Input is enabled only after the initial frames, so that it is not mixed into them
+        if ready_rx.wait_for(|ready| *ready).await.is_err() {
+            // The read task ended before the handshake completed
+            return true;
+        }
 
-            let json = match serde_json::to_string(&msg) {
-                Ok(json) => json,
-                Err(e) => {
-                    tracing::error!("Failed to serialize message: {}", e);
+        // Re-send messages that were not acknowledged before the previous connection dropped
+        let unacked = outbox
+            .lock()
+            .map(|outbox| outbox.pending())
+            .unwrap_or_default();
+        if !unacked.is_empty() {
+            tracing::info!("Re-sending {} unacknowledged message(s)", unacked.len());
+        }
+        for message in unacked {
+            if let Err(e) = write.send(Message::Text(message.json.into())).await {
+                tracing::warn!("Failed to re-send message: {}", e);
+                return true;
+            }
+        }
+
+        // The first heartbeat is sent one interval after the handshake
+        let mut heartbeat = heartbeat_interval.map(|interval| {
+            let mut ticker =
+                tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            ticker
+        });
+
+        loop {
+            // Auto-replies are always sent as chat messages, never interpreted as commands
+            let command = tokio::select! {
+                line = input_rx.recv() => match line {
+                    Some(line) => parse_input(&line),
+                    None => break,
+                },
+                Some(reply) = auto_reply_rx.recv() => InputCommand::Message(reply),
+                _ = next_heartbeat(&mut heartbeat) => {
+                    let frame = HeartbeatMessage {
+                        r#type: MessageType::Heartbeat,
+                    };
+                    let json = serde_json::to_string(&frame).unwrap();
+                    if let Err(e) = write.send(Message::Text(json.into())).await {
+                        tracing::warn!("Failed to send heartbeat: {}", e);
+                        write_error = true;
+                        break;
+                    }
                     continue;
                 }
             };
+            let content = match command {
+                InputCommand::Ping => {
+                    // Send an application-level ping and remember when it was sent
+                    let nonce = next_ping_nonce;
+                    next_ping_nonce += 1;
+                    let ping = AppPingMessage {
+                        r#type: MessageType::AppPing,
+                        nonce,
+                    };
+                    let json = match serde_json::to_string(&ping) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            tracing::error!("Failed to serialize ping: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Ok(mut pings) = pending_pings.lock() {
+                        pings.insert(nonce, get_jst_timestamp());
+                    }
+                    if let Err(e) = write.send(Message::Text(json.into())).await {
+                        tracing::warn!("Failed to send ping: {}", e);
+                        write_error = true;
+                        break;
+                    }
+                    continue;
+                }
+                InputCommand::SendFile(path) => {
+                    let file_msg = match build_file_message(&client_id, &path) {
+                        Ok(file_msg) => file_msg,
+                        Err(e) => {
+                            println!("Failed to read file '{}': {}", path, e);
+                            prompt.redisplay();
+                            continue;
+                        }
+                    };
+                    let json = match serde_json::to_string(&file_msg) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            tracing::error!("Failed to serialize file: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Err(e) = write.send(Message::Text(json.into())).await {
+                        tracing::warn!("Failed to send file: {}", e);
+                        write_error = true;
+                        break;
+                    }
+                    let formatted = formatter.format_sent_confirmation(file_msg.timestamp);
+                    println!("{}", formatted);
+                    prompt.redisplay();
+                    continue;
+                }
+                InputCommand::Roster => {
+                    let request = RosterRequestMessage {
+                        r#type: MessageType::RosterRequest,
+                        since_version: None,
+                    };
+                    let json = match serde_json::to_string(&request) {
+                        Ok(json) => json,
+                        Err(e) => {
+                            tracing::error!("Failed to serialize roster request: {}", e);
+                            continue;
+                        }
+                    };
+                    if let Err(e) = write.send(Message::Text(json.into())).await {
+                        tracing::warn!("Failed to send roster request: {}", e);
+                        write_error = true;
+                        break;
+                    }
+                    continue;
+                }
+                InputCommand::Stats => {
+                    let snapshot = stats.snapshot(get_jst_timestamp());
+                    print!("{}", MessageFormatter::format_stats(&snapshot));
+                    prompt.redisplay();
+                    continue;
+                }
+                InputCommand::WhoAmI => {
+                    let room_id = room_id.lock().ok().and_then(|room_id| room_id.clone());
+                    let snapshot = stats.snapshot(get_jst_timestamp());
+                    print!(
+                        "{}",
+                        MessageFormatter::format_whoami(
+                            &client_id,
+                            room_id.as_deref(),
+                            snapshot.connected_millis,
+                            &server_url,
+                        )
+                    );
+                    prompt.redisplay();
+                    continue;
+                }
+                InputCommand::Timestamps(show) => {
+                    // Shared with the read task's formatter and kept across reconnections
+                    let mut options = formatter.display_options();
+                    options.show_timestamps = show;
+                    formatter.set_display_options(options);
+                    print!("{}", MessageFormatter::format_timestamps_setting(show));
+                    prompt.redisplay();
+                    continue;
+                }
+                InputCommand::Reconnect => {
+                    // Close cleanly; the runner connects again once the session has ended
+                    reconnect_requested_for_write.store(true, Ordering::SeqCst);
+                    if let Err(e) = write.send(Message::Close(None)).await {
+                        tracing::warn!("Failed to send close frame: {}", e);
+                    }
+                    break;
+                }
+                InputCommand::Save => {
+                    let saved = match last_received_file.lock() {
+                        Ok(last) => last.as_ref().map(save_received_file),
+                        Err(_) => None,
+                    };
+                    match saved {
+                        Some(Ok(filename)) => println!("Saved {}", filename),
+                        Some(Err(e)) => println!("Failed to save file: {}", e),
+                        None => println!("No file to save"),
+                    }
+                    prompt.redisplay();
+                    continue;
+                }
+                InputCommand::Message(content) => content,
+            };
+
+            let Some((json, timestamp)) = compose_chat(&client_id, content, &outbox) else {
+                continue;
+            };
 
             if let Err(e) = write.send(Message::Text(json.into())).await {
                 tracing::warn!("Failed to send message: {}", e);
                 write_error = true;
                 break;
             }
+            stats.record_sent();
 
             // Display sent timestamp and redisplay prompt
-            let formatted = MessageFormatter::format_sent_confirmation(msg.timestamp);
-            println!("{}", formatted);
-            redisplay_prompt(&client_id_for_write);
+            // (with server echo, the echoed frame is displayed by the read task instead)
+            if should_display_sent_optimistically(server_echo) {
+                let formatted = formatter.format_sent_confirmation(timestamp);
+                println!("{}", formatted);
+                prompt.redisplay();
+            }
         }
 
         write_error
     });
 
     // If any one of the tasks completes, abort the other
-    tokio::select! {
+    let _roster_refresh_guard = AbortOnDrop(roster_refresh_task);
+    let connection_lost = tokio::select! {
         read_result = &mut read_task => {
             write_task.abort();
-            let connection_error = read_result.unwrap_or(false);
-            if connection_error {
-                return Err(Box::new(ClientError::ConnectionError(
-                    "Connection lost".to_string(),
-                )));
-            }
+            read_result.unwrap_or(false)
         }
         write_result = &mut write_task => {
-            read_task.abort();
-            let write_error = write_result.unwrap_or(false);
-            if write_error {
-                return Err(Box::new(ClientError::ConnectionError(
-                    "Connection lost".to_string(),
-                )));
+            if reconnect_requested.load(Ordering::SeqCst) {
+                // Let the read task receive the server's close frame to finish the handshake
+                let _ = tokio::time::timeout(CLOSE_HANDSHAKE_TIMEOUT, &mut read_task).await;
             }
+            read_task.abort();
+            write_result.unwrap_or(false)
         }
+    };
+
+    if reconnect_requested.load(Ordering::SeqCst) {
+        return Ok(SessionEnd::ReconnectRequested);
+    }
+    if replaced.load(Ordering::SeqCst) {
+        return Err(Box::new(ClientError::ConnectionReplaced));
+    }
+    if kicked.load(Ordering::SeqCst) {
+        return Err(Box::new(ClientError::Kicked));
+    }
+    if connection_lost {
+        return Err(Box::new(ClientError::ConnectionError(
+            "Connection lost".to_string(),
+        )));
     }
 
-    Ok(())
+    Ok(SessionEnd::Exited)
 }
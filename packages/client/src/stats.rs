@@ -0,0 +1,155 @@
+//! Connection statistics shared between the read and write tasks.
+//!
+//! Counters are atomics so that both tasks (and the runner across reconnects)
+//! can update them without locking. The `/stats` command prints a snapshot.
+
+use std::sync::atomic::{AtomicI64, AtomicU64, Ordering};
+
+/// Marker stored while no value has been recorded yet
+const UNSET: i64 = -1;
+
+/// Counters for the client session
+#[derive(Debug)]
+pub struct SessionStats {
+    /// Time the current connection was established (milliseconds, `UNSET` if never connected)
+    connected_at: AtomicI64,
+    /// Chat messages sent by this client
+    messages_sent: AtomicU64,
+    /// Chat messages and files received from other participants
+    messages_received: AtomicU64,
+    /// Number of reconnections after a lost connection
+    reconnects: AtomicU64,
+    /// Last measured application-level round-trip time (milliseconds, `UNSET` if none)
+    last_rtt_millis: AtomicI64,
+}
+
+/// Point-in-time copy of [`SessionStats`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatsSnapshot {
+    /// Time since the current connection was established (milliseconds)
+    pub connected_millis: Option<i64>,
+    /// Chat messages sent by this client
+    pub messages_sent: u64,
+    /// Chat messages and files received from other participants
+    pub messages_received: u64,
+    /// Number of reconnections after a lost connection
+    pub reconnects: u64,
+    /// Last measured round-trip time (milliseconds)
+    pub last_rtt_millis: Option<i64>,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SessionStats {
+    /// Create counters with nothing recorded
+    pub fn new() -> Self {
+        Self {
+            connected_at: AtomicI64::new(UNSET),
+            messages_sent: AtomicU64::new(0),
+            messages_received: AtomicU64::new(0),
+            reconnects: AtomicU64::new(0),
+            last_rtt_millis: AtomicI64::new(UNSET),
+        }
+    }
+
+    /// Record that a connection was established at `now` (milliseconds)
+    pub fn record_connected(&self, now: i64) {
+        self.connected_at.store(now, Ordering::Relaxed);
+    }
+
+    /// Record a chat message sent by this client
+    pub fn record_sent(&self) {
+        self.messages_sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a chat message or file received from another participant
+    pub fn record_received(&self) {
+        self.messages_received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a reconnection attempt after a lost connection
+    pub fn record_reconnect(&self) {
+        self.reconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record a measured round-trip time (milliseconds)
+    pub fn record_rtt(&self, rtt_millis: i64) {
+        self.last_rtt_millis.store(rtt_millis, Ordering::Relaxed);
+    }
+
+    /// Take a snapshot of the counters at `now` (milliseconds)
+    pub fn snapshot(&self, now: i64) -> StatsSnapshot {
+        let connected_at = self.connected_at.load(Ordering::Relaxed);
+        let last_rtt_millis = self.last_rtt_millis.load(Ordering::Relaxed);
+        StatsSnapshot {
+            connected_millis: (connected_at != UNSET).then(|| (now - connected_at).max(0)),
+            messages_sent: self.messages_sent.load(Ordering::Relaxed),
+            messages_received: self.messages_received.load(Ordering::Relaxed),
+            reconnects: self.reconnects.load(Ordering::Relaxed),
+            last_rtt_millis: (last_rtt_millis != UNSET).then_some(last_rtt_millis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_snapshot_before_anything_is_recorded() {
+        // テスト項目: 何も記録されていない場合、接続時間と RTT は未設定になる
+        // given (前提条件):
+        let stats = SessionStats::new();
+
+        // when (操作):
+        let snapshot = stats.snapshot(1_000);
+
+        // then (期待する結果):
+        assert_eq!(
+            snapshot,
+            StatsSnapshot {
+                connected_millis: None,
+                messages_sent: 0,
+                messages_received: 0,
+                reconnects: 0,
+                last_rtt_millis: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_counters_are_accumulated_across_tasks() {
+        // テスト項目: 読み取り・書き込みタスクからの記録がすべて集計される
+        // given (前提条件):
+        let stats = Arc::new(SessionStats::new());
+        stats.record_connected(10_000);
+
+        // when (操作): 2 つのスレッドから送信・受信を記録し、再接続と RTT を記録
+        let writer = {
+            let stats = stats.clone();
+            std::thread::spawn(move || (0..100).for_each(|_| stats.record_sent()))
+        };
+        let reader = {
+            let stats = stats.clone();
+            std::thread::spawn(move || (0..250).for_each(|_| stats.record_received()))
+        };
+        writer.join().unwrap();
+        reader.join().unwrap();
+        stats.record_reconnect();
+        stats.record_rtt(42);
+        stats.record_rtt(17);
+        let snapshot = stats.snapshot(75_000);
+
+        // then (期待する結果): RTT は最後に計測した値
+        assert_eq!(snapshot.connected_millis, Some(65_000));
+        assert_eq!(snapshot.messages_sent, 100);
+        assert_eq!(snapshot.messages_received, 250);
+        assert_eq!(snapshot.reconnects, 1);
+        assert_eq!(snapshot.last_rtt_millis, Some(17));
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the client tests.
+
+use std::net::SocketAddr;
+
+use engawa_server::{
+    domain::{Room, RoomIdFactory, Timestamp},
+    ui::ServerBuilder,
+};
+
+/// Start an in-process chat server on an ephemeral port
+pub(crate) async fn start_test_server() -> SocketAddr {
+    let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+    let server = ServerBuilder::new().with_room(room).build();
+    let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+    let addr = bound.local_addr();
+    tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+    addr
+}
@@ -2,8 +2,78 @@
 
 use std::io::Write;
 
-/// Redisplay the prompt after receiving a message
-pub fn redisplay_prompt(client_id: &str) {
-    print!("{}> ", client_id);
-    std::io::stdout().flush().ok();
+/// The interactive prompt (`client_id> `) and the banner shown once connected
+///
+/// When disabled (stdin is piped rather than a terminal), neither is printed,
+/// so that the output holds only the message display.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    client_id: String,
+    enabled: bool,
+}
+
+impl Prompt {
+    /// Create the prompt of `client_id`, printed only if `enabled`
+    pub fn new(client_id: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            client_id: client_id.into(),
+            enabled,
+        }
+    }
+
+    /// Redisplay the prompt after receiving a message
+    pub fn redisplay(&self) {
+        let mut stdout = std::io::stdout();
+        self.write_prompt(&mut stdout);
+        stdout.flush().ok();
+    }
+
+    /// Print the banner telling the user how to chat
+    pub fn show_banner(&self) {
+        if let Some(banner) = self.banner() {
+            println!("{}", banner);
+        }
+    }
+
+    /// The banner telling the user how to chat (`None` when disabled)
+    fn banner(&self) -> Option<String> {
+        self.enabled.then(|| {
+            format!(
+                "\nYou are '{}'. Type messages and press Enter to send. Press Ctrl+C to exit.\n",
+                self.client_id
+            )
+        })
+    }
+
+    /// Write the prompt to `out` (nothing when disabled)
+    fn write_prompt(&self, out: &mut impl Write) {
+        if self.enabled {
+            write!(out, "{}> ", self.client_id).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_piped_mode_omits_banner_and_prompt() {
+        // テスト項目: 端末では banner と prompt が表示され、パイプ入力（無効）では何も表示されない
+        // given (前提条件):
+        let interactive = Prompt::new("alice", true);
+        let piped = Prompt::new("alice", false);
+
+        // when (操作):
+        let mut interactive_out = Vec::new();
+        interactive.write_prompt(&mut interactive_out);
+        let mut piped_out = Vec::new();
+        piped.write_prompt(&mut piped_out);
+
+        // then (期待する結果):
+        assert_eq!(interactive_out, b"alice> ");
+        assert!(interactive.banner().unwrap().contains("You are 'alice'"));
+        assert!(piped_out.is_empty());
+        assert_eq!(piped.banner(), None);
+    }
 }
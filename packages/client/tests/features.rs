@@ -0,0 +1,30 @@
+//! Build checks for the optional features of the client library.
+
+use std::process::Command;
+
+#[test]
+fn test_library_builds_without_default_features() {
+    // テスト項目: `cli` feature（rustyline）なしでもライブラリがビルドできる
+    // given (前提条件): 実行中のビルドとロックを取り合わないよう、別のターゲットディレクトリを使う
+    let target_dir = concat!(env!("CARGO_TARGET_TMPDIR"), "/no-default-features");
+
+    // when (操作):
+    let output = Command::new(env!("CARGO"))
+        .args([
+            "check",
+            "--lib",
+            "--no-default-features",
+            "--manifest-path",
+            concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"),
+        ])
+        .env("CARGO_TARGET_DIR", target_dir)
+        .output()
+        .expect("Failed to run cargo");
+
+    // then (期待する結果):
+    assert!(
+        output.status.success(),
+        "cargo check --no-default-features failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
@@ -8,20 +8,40 @@
 //! cargo run --bin server -- --host 0.0.0.0 --port 3000
 //! ```
 
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, num::NonZeroUsize, sync::Arc, time::Duration};
 
+use axum::http::HeaderValue;
 use clap::Parser;
 use engawa_server::{
-    domain::{Room, RoomIdFactory, Timestamp},
-    infrastructure::{message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository},
-    ui::Server,
+    domain::{
+        DeadLetterSink, MAX_ANNOUNCEMENT_CONTENT_LEN, MAX_CHAT_CONTENT_LEN, MessageContent,
+        MessageTransform, RetentionPolicy, Room, RoomIdFactory, RoomRepository, Timestamp,
+        entity::{DEFAULT_MESSAGE_CAPACITY, DEFAULT_PARTICIPANT_CAPACITY},
+    },
+    infrastructure::{
+        dead_letter::JsonlDeadLetterSink,
+        dto::{
+            conversion::TimestampPrecision,
+            websocket::{MessageDeletedMessage, MessageType},
+        },
+        message_pusher::{WebSocketMessagePusher, websocket::DEFAULT_SEND_TIMEOUT},
+        repository::InMemoryRoomRepository,
+        snapshot::FileSnapshotStore,
+    },
+    ui::{
+        AccessPolicy, AllowAllPolicy, CidrAccessPolicy, DEFAULT_DRAIN_PERIOD,
+        DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_RETRY_AFTER, DEFAULT_SHUTDOWN_TIMEOUT, ServerBuilder,
+    },
     usecase::{
-        ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-        GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+        DuplicatePolicy, PurgeMessagesUseCase, connect_participant::DEFAULT_INITIAL_ROSTER_LIMIT,
+        send_file::DEFAULT_MAX_FILE_SIZE,
     },
 };
 use engawa_shared::{logger::setup_logger, time::get_jst_timestamp};
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, Notify};
+
+/// Interval at which messages older than `--retain-message-secs` are evicted
+const RETENTION_SWEEP_INTERVAL: Duration = Duration::from_secs(60);
 
 #[derive(Parser, Debug)]
 #[command(name = "server")]
@@ -34,6 +54,256 @@ struct Args {
     /// Port number to bind the server to
     #[arg(short = 'p', long, default_value = "8080")]
     port: u16,
+
+    /// Maximum number of participants allowed in the room
+    #[arg(long, default_value_t = DEFAULT_PARTICIPANT_CAPACITY)]
+    participant_capacity: usize,
+
+    /// Maximum number of messages kept in the room history
+    #[arg(long, default_value_t = DEFAULT_MESSAGE_CAPACITY)]
+    message_capacity: usize,
+
+    /// Keep only the most recent N messages, evicting the oldest instead of rejecting new ones
+    /// (N above --message-capacity has no effect)
+    #[arg(long, value_name = "N", conflicts_with = "retain_message_secs")]
+    retain_messages: Option<usize>,
+
+    /// Keep only the messages sent within the last SECS seconds
+    #[arg(long, value_name = "SECS")]
+    retain_message_secs: Option<u64>,
+
+    /// Participant count above which join/leave notifications are replaced by a count update
+    #[arg(long)]
+    presence_notification_threshold: Option<usize>,
+
+    /// Minimum number of seconds between two messages of the same participant (slow mode)
+    #[arg(long, value_name = "SECS", conflicts_with = "no_history")]
+    slow_mode_secs: Option<u64>,
+
+    /// Broadcast chat messages without storing them in the room history
+    ///
+    /// The history stays empty, including after restoring a snapshot.
+    #[arg(long, conflicts_with_all = ["retain_messages", "retain_message_secs"])]
+    no_history: bool,
+
+    /// Unit of the Unix timestamps in WebSocket frames
+    ///
+    /// The bundled client expects milliseconds.
+    #[arg(long, value_parser = ["millis", "seconds"], default_value = "millis")]
+    timestamp_precision: String,
+
+    /// Maximum length of a chat message in bytes (capped at 100000)
+    #[arg(long, default_value_t = MAX_CHAT_CONTENT_LEN)]
+    max_message_length: usize,
+
+    /// Maximum length of a server announcement such as the MOTD in bytes (capped at 100000)
+    #[arg(long, default_value_t = MAX_ANNOUNCEMENT_CONTENT_LEN)]
+    max_announcement_length: usize,
+
+    /// Maximum size of a shared file in bytes
+    #[arg(long, default_value_t = DEFAULT_MAX_FILE_SIZE)]
+    max_file_size: usize,
+
+    /// Maximum time in milliseconds to wait when delivering a message to a single client
+    #[arg(long, default_value_t = DEFAULT_SEND_TIMEOUT.as_millis() as u64)]
+    send_timeout_ms: u64,
+
+    /// Enable debug mode (always pretty-print JSON responses of the HTTP API)
+    #[arg(long)]
+    enable_debug: bool,
+
+    /// Maximum number of participants listed in the initial room-connected message
+    #[arg(long, default_value_t = DEFAULT_INITIAL_ROSTER_LIMIT)]
+    initial_roster_limit: usize,
+
+    /// Allow several simultaneous connections with the same client_id (e.g. multiple tabs)
+    #[arg(long)]
+    multi_connection: bool,
+
+    /// What to do when a client_id that is already connected connects again:
+    /// `reject` the new connection, or let it `takeover` and close the old one
+    #[arg(
+        long,
+        value_parser = ["reject", "takeover"],
+        default_value = "reject",
+        conflicts_with = "multi_connection"
+    )]
+    duplicate_policy: String,
+
+    /// Remove leading and trailing whitespace from chat messages
+    #[arg(long)]
+    trim_content: bool,
+
+    /// Collapse runs of whitespace in chat messages into a single space
+    #[arg(long)]
+    collapse_whitespace: bool,
+
+    /// Detect URLs in chat messages and attach them as `links`
+    #[arg(long)]
+    detect_links: bool,
+
+    /// Origin allowed to call the HTTP API from a browser (repeatable; default: same-origin only)
+    #[arg(long = "allowed-origins", value_name = "ORIGIN", value_delimiter = ',', value_parser = parse_origin)]
+    allowed_origins: Vec<HeaderValue>,
+
+    /// Compress HTTP API responses (gzip or deflate, per the request's Accept-Encoding)
+    #[arg(long)]
+    compress_responses: bool,
+
+    /// Seconds sent in the Retry-After header when a connection is rejected because the room is full
+    #[arg(long, default_value_t = DEFAULT_RETRY_AFTER.as_secs())]
+    retry_after_secs: u64,
+
+    /// Seconds to keep serving after a shutdown signal while /api/ready reports 503
+    #[arg(long, default_value_t = DEFAULT_DRAIN_PERIOD.as_secs())]
+    drain_secs: u64,
+
+    /// Seconds to wait for open connections to flush their queued messages when shutting down
+    #[arg(long, default_value_t = DEFAULT_SHUTDOWN_TIMEOUT.as_secs())]
+    shutdown_timeout_secs: u64,
+
+    /// Shut down gracefully after this many seconds without any connected client (default: never)
+    #[arg(long, value_name = "SECS")]
+    idle_shutdown: Option<u64>,
+
+    /// Message of the day sent to each client right after it connects
+    #[arg(long, conflicts_with = "motd_file")]
+    motd: Option<String>,
+
+    /// File containing the message of the day
+    #[arg(long)]
+    motd_file: Option<std::path::PathBuf>,
+
+    /// Reject text frames that are not JSON instead of treating them as chat messages
+    #[arg(long)]
+    reject_plain_text: bool,
+
+    /// Close connections that send more than this many WebSocket frames per second (default: no limit)
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    max_frames_per_sec: Option<u32>,
+
+    /// Reject new connections from a source IP address with this many open connections
+    /// with 429 (default: no limit)
+    #[arg(long)]
+    max_connections_per_ip: Option<NonZeroUsize>,
+
+    /// Token that lets a connection act as admin (`/ws?client_id=...&admin_token=...`) and
+    /// send `/admin kick|mute|unmute|announce` commands; also required as a bearer token by
+    /// `DELETE /api/rooms/{room_id}/messages` (default: no admin connections or endpoints)
+    #[arg(long)]
+    admin_token: Option<String>,
+
+    /// Close connections that send a WebSocket message larger than this many bytes
+    #[arg(long, default_value_t = DEFAULT_MAX_MESSAGE_SIZE)]
+    max_websocket_message_size: usize,
+
+    /// Allow connections only from this CIDR (repeatable, e.g. 192.168.0.0/16)
+    #[arg(long = "allow", value_name = "CIDR")]
+    allow: Vec<String>,
+
+    /// Deny connections from this CIDR (repeatable, takes precedence over --allow)
+    #[arg(long = "deny", value_name = "CIDR")]
+    deny: Vec<String>,
+
+    /// File with one `allow <CIDR>` or `deny <CIDR>` rule per line
+    #[arg(long)]
+    access_policy_file: Option<std::path::PathBuf>,
+
+    /// JSON file to save the room state to on shutdown and restore message history from on startup
+    #[arg(long)]
+    snapshot_file: Option<std::path::PathBuf>,
+
+    /// JSON Lines file to append messages that could not be delivered to
+    #[arg(long)]
+    dead_letter_file: Option<std::path::PathBuf>,
+}
+
+/// Parse a CORS origin such as `http://localhost:3000`
+fn parse_origin(s: &str) -> Result<HeaderValue, String> {
+    HeaderValue::from_str(s).map_err(|e| format!("invalid origin '{}': {}", s, e))
+}
+
+/// Read the message of the day from `--motd` or `--motd-file`
+///
+/// The message is an announcement and is checked against `--max-announcement-length`.
+fn load_motd(args: &Args) -> Result<Option<String>, String> {
+    let motd = match (&args.motd, &args.motd_file) {
+        (Some(motd), _) => motd.clone(),
+        (None, Some(path)) => std::fs::read_to_string(path)
+            .map(|motd| motd.trim_end().to_string())
+            .map_err(|e| format!("{}: {}", path.display(), e))?,
+        (None, None) => return Ok(None),
+    };
+    if motd.trim().is_empty() {
+        // A blank MOTD is not sent (see `Server::with_motd`)
+        return Ok(Some(motd));
+    }
+    MessageContent::with_max_len(motd, args.max_announcement_length)
+        .map(|motd| Some(motd.into_string()))
+        .map_err(|e| e.to_string())
+}
+
+/// Build the message retention policy from `--retain-messages` or `--retain-message-secs`
+fn build_retention_policy(args: &Args) -> RetentionPolicy {
+    match (args.retain_messages, args.retain_message_secs) {
+        (Some(count), _) => RetentionPolicy::MaxCount(count),
+        (None, Some(secs)) => RetentionPolicy::MaxAge(Duration::from_secs(secs)),
+        (None, None) => RetentionPolicy::None,
+    }
+}
+
+/// Remove expired ephemeral messages from the history and tell the clients
+///
+/// Sleeps until the earliest expiry in the history, or until `expiry_notify` reports a newly
+/// stored message with an expiry; the room is not touched while no message expires.
+async fn sweep_expired_messages(usecase: PurgeMessagesUseCase, expiry_notify: Arc<Notify>) {
+    loop {
+        match usecase.next_expiry().await {
+            Some(expires_at) => {
+                let wait_ms = u64::try_from(expires_at.value() - get_jst_timestamp()).unwrap_or(0);
+                tokio::select! {
+                    _ = tokio::time::sleep(Duration::from_millis(wait_ms)) => {}
+                    // A message expiring sooner may have been stored
+                    _ = expiry_notify.notified() => continue,
+                }
+            }
+            None => {
+                expiry_notify.notified().await;
+                continue;
+            }
+        }
+
+        for message_id in usecase.remove_expired().await {
+            tracing::debug!("Message {} expired", message_id.as_str());
+            let deleted = MessageDeletedMessage {
+                r#type: MessageType::MessageDeleted,
+                message_id: message_id.as_str().to_string(),
+            };
+            match serde_json::to_string(&deleted) {
+                Ok(json) => {
+                    if let Err(e) = usecase.notify_deleted(&json).await {
+                        tracing::warn!("Failed to broadcast message-deleted: {}", e);
+                    }
+                }
+                Err(e) => tracing::error!("Failed to serialize message-deleted: {}", e),
+            }
+        }
+    }
+}
+
+/// Build the connection access policy from the command line arguments
+fn build_access_policy(args: &Args) -> Result<Arc<dyn AccessPolicy>, String> {
+    if args.allow.is_empty() && args.deny.is_empty() && args.access_policy_file.is_none() {
+        return Ok(Arc::new(AllowAllPolicy));
+    }
+
+    let mut policy =
+        CidrAccessPolicy::from_strs(&args.allow, &args.deny).map_err(|e| e.to_string())?;
+    if let Some(path) = &args.access_policy_file {
+        policy = policy.merge(CidrAccessPolicy::from_file(path).map_err(|e| e.to_string())?);
+    }
+
+    Ok(Arc::new(policy))
 }
 
 #[tokio::main]
@@ -42,54 +312,195 @@ async fn main() {
     setup_logger(env!("CARGO_BIN_NAME"), "debug");
 
     let args = Args::parse();
+    let access_policy = match build_access_policy(&args) {
+        Ok(policy) => policy,
+        Err(e) => {
+            tracing::error!("Invalid access policy: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let motd = match load_motd(&args) {
+        Ok(motd) => motd,
+        Err(e) => {
+            tracing::error!("Failed to load MOTD: {}", e);
+            std::process::exit(1);
+        }
+    };
 
     // Initialize dependencies in order:
     // 1. Repository
     // 2. MessagePusher
-    // 3. UseCases
-    // 4. AppState
-    // 5. Server
+    // 3. UseCases and Server (ServerBuilder)
 
     // 1. Create Repository (in-memory database)
-    let room = Arc::new(Mutex::new(Room::new(
+    let mut room = Room::with_capacity(
         RoomIdFactory::generate().expect("Failed to generate RoomId"),
         Timestamp::new(get_jst_timestamp()),
-    )));
+        args.participant_capacity,
+        args.message_capacity,
+    );
+    room.presence_notification_threshold = args.presence_notification_threshold;
+    room.slow_mode_interval = args.slow_mode_secs.map(Duration::from_secs);
+    let snapshot_store = args.snapshot_file.clone().map(FileSnapshotStore::new);
+    if let Some(store) = &snapshot_store {
+        match store.load().await {
+            Ok(Some(rooms)) => {
+                if let Some(snapshot) = rooms.into_iter().next() {
+                    room.restore_from_snapshot(snapshot);
+                    if args.no_history {
+                        room.clear_messages();
+                    }
+                    tracing::info!(
+                        "Restored {} messages from snapshot {}",
+                        room.messages.len(),
+                        store.path().display()
+                    );
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                tracing::error!("Failed to load snapshot {}: {}", store.path().display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let room = Arc::new(Mutex::new(room));
     tracing::info!("Room {} created!", room.lock().await.id.as_str());
-    let repository = Arc::new(InMemoryRoomRepository::new(room));
+    let retention = build_retention_policy(&args);
+    if args.no_history {
+        tracing::info!("Message history is disabled");
+    }
+    // Wakes the expiry sweeper when a message with an expiry is stored
+    let expiry_notify = Arc::new(Notify::new());
+    let repository = Arc::new(
+        InMemoryRoomRepository::new(room)
+            .with_retention_policy(retention)
+            .with_history(!args.no_history)
+            .with_expiry_notify(expiry_notify.clone()),
+    );
+    let snapshot_repository = repository.clone();
+    if let RetentionPolicy::MaxAge(max_age) = retention {
+        // Messages outlive the policy even when no new message is added
+        tracing::info!("Keeping messages for {}s", max_age.as_secs());
+        let retention_repository = repository.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(RETENTION_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                let evicted = retention_repository.apply_retention().await;
+                if evicted > 0 {
+                    tracing::info!("Evicted {} expired messages", evicted);
+                }
+            }
+        });
+    }
 
     // 2. Create MessagePusher (WebSocket implementation)
     let message_pusher_clients = Arc::new(Mutex::new(HashMap::new()));
-    let message_pusher = Arc::new(WebSocketMessagePusher::new(message_pusher_clients.clone()));
-
-    // 3. Create UseCases
-    let connect_participant_usecase = Arc::new(ConnectParticipantUseCase::new(
-        repository.clone(),
-        message_pusher.clone(),
-    ));
-    let disconnect_participant_usecase = Arc::new(DisconnectParticipantUseCase::new(
-        repository.clone(),
-        message_pusher.clone(),
-    ));
-    let send_message_usecase = Arc::new(SendMessageUseCase::new(
-        repository.clone(),
-        message_pusher.clone(),
-    ));
-    let get_room_state_usecase = Arc::new(GetRoomStateUseCase::new(repository.clone()));
-    let get_rooms_usecase = Arc::new(GetRoomsUseCase::new(repository.clone()));
-    let get_room_detail_usecase = Arc::new(GetRoomDetailUseCase::new(repository.clone()));
-
-    // 4. Create and run the server
-    let server = Server::new(
-        connect_participant_usecase,
-        disconnect_participant_usecase,
-        send_message_usecase,
-        get_room_state_usecase,
-        get_rooms_usecase,
-        get_room_detail_usecase,
+    let mut message_pusher = WebSocketMessagePusher::with_send_timeout(
+        message_pusher_clients.clone(),
+        Duration::from_millis(args.send_timeout_ms),
     );
+    let dead_letter_sink: Option<Arc<dyn DeadLetterSink>> =
+        args.dead_letter_file.as_ref().map(|path| {
+            tracing::info!("Recording undelivered messages to {}", path.display());
+            Arc::new(JsonlDeadLetterSink::new(path)) as Arc<dyn DeadLetterSink>
+        });
+    if let Some(sink) = &dead_letter_sink {
+        message_pusher = message_pusher.with_dead_letter_sink(sink.clone());
+    }
+    let message_pusher = Arc::new(message_pusher);
+
+    // Remove expired ephemeral messages from the history and tell the clients
+    // (nothing is stored without history, so nothing can expire)
+    if !args.no_history {
+        let expiry_usecase = PurgeMessagesUseCase::new(repository.clone(), message_pusher.clone());
+        tokio::spawn(sweep_expired_messages(expiry_usecase, expiry_notify));
+    }
+
+    // 3. Create UseCases and the server, and run it
+    let initial_roster_limit = args.initial_roster_limit;
+    let multi_connection = args.multi_connection;
+    let duplicate_policy = match args.duplicate_policy.as_str() {
+        "takeover" => DuplicatePolicy::Takeover,
+        _ => DuplicatePolicy::Reject,
+    };
+    let transform = MessageTransform {
+        trim: args.trim_content,
+        collapse_whitespace: args.collapse_whitespace,
+        detect_links: args.detect_links,
+    };
+    let max_message_length = args.max_message_length;
+    let server = ServerBuilder::new()
+        .with_repository(repository)
+        .with_message_pusher(message_pusher)
+        .with_max_file_size(args.max_file_size)
+        .configure_connect_participant(move |usecase| {
+            usecase
+                .with_initial_roster_limit(initial_roster_limit)
+                .with_multi_connection(multi_connection)
+                .with_duplicate_policy(duplicate_policy)
+        })
+        .configure_send_message(move |usecase| {
+            let usecase = usecase
+                .with_transform(transform)
+                .with_max_content_len(max_message_length);
+            match dead_letter_sink {
+                Some(sink) => usecase.with_dead_letter_sink(sink),
+                None => usecase,
+            }
+        })
+        .build()
+        .with_access_policy(access_policy)
+        .with_pretty_json(args.enable_debug)
+        .with_allowed_origins(args.allowed_origins)
+        .with_response_compression(args.compress_responses)
+        .with_retry_after(Duration::from_secs(args.retry_after_secs))
+        .with_plain_text_messages(!args.reject_plain_text)
+        .with_timestamp_precision(match args.timestamp_precision.as_str() {
+            "seconds" => TimestampPrecision::Seconds,
+            _ => TimestampPrecision::Millis,
+        })
+        .with_max_message_size(args.max_websocket_message_size)
+        .with_drain_period(Duration::from_secs(args.drain_secs))
+        .with_shutdown_timeout(Duration::from_secs(args.shutdown_timeout_secs));
+    let server = match motd {
+        Some(motd) => server.with_motd(motd),
+        None => server,
+    };
+    let server = match args.idle_shutdown {
+        Some(secs) => server.with_idle_shutdown(Duration::from_secs(secs)),
+        None => server,
+    };
+    let server = match args.admin_token {
+        Some(admin_token) => server.with_admin_token(admin_token),
+        None => server,
+    };
+    let server = match args.max_frames_per_sec {
+        Some(max_frames_per_sec) => server.with_max_frames_per_sec(max_frames_per_sec),
+        None => server,
+    };
+    let server = match args.max_connections_per_ip {
+        Some(max) => server.with_max_connections_per_ip(max.get()),
+        None => server,
+    };
     if let Err(e) = server.run(args.host, args.port).await {
         tracing::error!("Server error: {}", e);
         std::process::exit(1);
     }
+
+    // 4. Save the room state after graceful shutdown
+    if let Some(store) = snapshot_store {
+        let result = match snapshot_repository.get_room().await {
+            Ok(room) => store.save(&[room]).await.map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        match result {
+            Ok(()) => tracing::info!("Saved snapshot to {}", store.path().display()),
+            Err(e) => {
+                tracing::error!("Failed to save snapshot {}: {}", store.path().display(), e);
+                std::process::exit(1);
+            }
+        }
+    }
 }
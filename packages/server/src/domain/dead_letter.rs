@@ -0,0 +1,43 @@
+//! 配信できなかったメッセージ（デッドレター）の記録の抽象化
+//!
+//! ## 責務
+//!
+//! DeadLetterSink は「配信を諦めたメッセージを記録する」責務を持ちます。
+//! MessagePusher が送信に最終的に失敗したとき（チャネルが閉じている、送信がタイムアウトした）
+//! に呼び出されます。失われたメッセージを後から分析するためのものです。
+//!
+//! 記録先（ファイル、外部のキューなど）は問いません。
+
+use async_trait::async_trait;
+
+use super::{ClientId, Timestamp};
+
+/// 配信できなかった 1 件のメッセージ
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeadLetter {
+    /// 配信先のクライアント ID
+    pub client_id: ClientId,
+    /// 配信できなかったメッセージ内容（送信しようとした JSON 文字列）
+    ///
+    /// 送信されるメッセージにはメッセージ ID が含まれないため、内容そのものを記録します。
+    pub payload: String,
+    /// 配信できなかった理由
+    pub reason: String,
+    /// 配信を諦めた時刻
+    pub timestamp: Timestamp,
+}
+
+/// デッドレターの記録先の抽象化
+///
+/// ## 実装
+///
+/// - `JsonlDeadLetterSink`: JSON Lines ファイルに追記する実装（`infrastructure/dead_letter.rs`）
+#[async_trait]
+pub trait DeadLetterSink: Send + Sync {
+    /// デッドレターを 1 件記録
+    ///
+    /// # 注意
+    ///
+    /// 記録の失敗で配信処理を止めないよう、エラーは返さずに実装側でログに残します。
+    async fn record(&self, letter: DeadLetter);
+}
@@ -1,10 +1,21 @@
 //! Core domain models for the chat application.
 
+use std::{
+    collections::{BTreeMap, HashSet},
+    time::Duration,
+};
+
 use serde::{Deserialize, Serialize};
 
 use super::{
-    error::RoomError,
-    value_object::{ClientId, MessageContent, RoomId, Timestamp},
+    error::{RoomError, RoomInvariantError},
+    factory::MessageIdFactory,
+    retention::RetentionPolicy,
+    roster::{RosterChangeKind, RosterChangeLog, RosterDelta},
+    value_object::{
+        ClientId, Emoji, MessageContent, MessageId, PresenceStatus, ReactionAction, RoomId,
+        Timestamp,
+    },
 };
 
 /// Default maximum number of participants allowed in a room
@@ -13,6 +24,20 @@ pub const DEFAULT_PARTICIPANT_CAPACITY: usize = 10;
 /// Default maximum number of messages allowed in a room
 pub const DEFAULT_MESSAGE_CAPACITY: usize = 100;
 
+/// Shortest time-to-live of an ephemeral message
+pub const MIN_MESSAGE_TTL: Duration = Duration::from_secs(5);
+
+/// Longest time-to-live of an ephemeral message
+pub const MAX_MESSAGE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Expiry of an ephemeral message sent at `sent_at` with the requested `ttl`
+///
+/// The TTL is clamped to [`MIN_MESSAGE_TTL`]..=[`MAX_MESSAGE_TTL`].
+pub fn message_expiry(sent_at: Timestamp, ttl: Duration) -> Timestamp {
+    let ttl = ttl.clamp(MIN_MESSAGE_TTL, MAX_MESSAGE_TTL);
+    Timestamp::new(sent_at.value().saturating_add(ttl.as_millis() as i64))
+}
+
 /// Represents a chat room with participants and message history
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Room {
@@ -28,6 +53,17 @@ pub struct Room {
     pub participant_capacity: usize,
     /// Maximum number of messages allowed (default: 100)
     pub message_capacity: usize,
+    /// Participant count above which individual join/leave notifications are suppressed
+    /// in favor of a participant count update (default: `None`, never suppressed)
+    pub presence_notification_threshold: Option<usize>,
+    /// Minimum interval between two messages of the same participant
+    /// (default: `None`, slow mode off)
+    #[serde(default)]
+    pub slow_mode_interval: Option<Duration>,
+    /// Roster version and recent joins/leaves, for sending roster deltas
+    /// (not persisted: participants do not survive a restart)
+    #[serde(skip)]
+    roster_log: RosterChangeLog,
 }
 
 impl Room {
@@ -40,6 +76,9 @@ impl Room {
             created_at,
             participant_capacity: DEFAULT_PARTICIPANT_CAPACITY,
             message_capacity: DEFAULT_MESSAGE_CAPACITY,
+            presence_notification_threshold: None,
+            slow_mode_interval: None,
+            roster_log: RosterChangeLog::default(),
         }
     }
 
@@ -57,6 +96,9 @@ impl Room {
             created_at,
             participant_capacity,
             message_capacity,
+            presence_notification_threshold: None,
+            slow_mode_interval: None,
+            roster_log: RosterChangeLog::default(),
         }
     }
 
@@ -64,81 +106,610 @@ impl Room {
     ///
     /// # Errors
     ///
-    /// Returns `RoomError::CapacityExceeded` if the room is at full capacity
+    /// - `RoomError::DuplicateParticipant` if a participant with the same ID is already in the room
+    /// - `RoomError::ParticipantCapacityExceeded` if the room is at full capacity
     pub fn add_participant(&mut self, participant: Participant) -> Result<(), RoomError> {
+        if self.get_participant(&participant.id).is_some() {
+            return Err(RoomError::DuplicateParticipant(
+                participant.id.into_string(),
+            ));
+        }
         if self.participants.len() >= self.participant_capacity {
-            return Err(RoomError::CapacityExceeded {
+            return Err(RoomError::ParticipantCapacityExceeded {
                 capacity: self.participant_capacity,
                 current: self.participants.len(),
             });
         }
+        self.roster_log
+            .record(participant.id.clone(), RosterChangeKind::Joined);
         self.participants.push(participant);
+        debug_assert_eq!(self.validate(), Ok(()));
         Ok(())
     }
 
     /// Remove a participant from the room by ID
     pub fn remove_participant(&mut self, participant_id: &ClientId) {
+        let before = self.participants.len();
         self.participants.retain(|p| &p.id != participant_id);
+        if self.participants.len() < before {
+            self.roster_log
+                .record(participant_id.clone(), RosterChangeKind::Left);
+        }
+    }
+
+    /// Current roster version, incremented on every join and leave
+    pub fn roster_version(&self) -> u64 {
+        self.roster_log.version()
+    }
+
+    /// Joins and leaves since `since_version`, as a delta to the roster known at that version
+    ///
+    /// `None` if the version is unknown or too old to compute a delta from; the full roster
+    /// has to be sent instead.
+    pub fn roster_delta_since(&self, since_version: u64) -> Option<RosterDelta> {
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        for (client_id, was_present) in self.roster_log.changed_since(since_version)? {
+            match (was_present, self.get_participant(&client_id)) {
+                // A participant that left and joined again is sent again with its new session
+                (_, Some(participant)) => added.push(participant.clone()),
+                (true, None) => removed.push(client_id),
+                (false, None) => {}
+            }
+        }
+        added.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        removed.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+        Some(RosterDelta {
+            version: self.roster_version(),
+            added,
+            removed,
+        })
     }
 
     /// Add a message to the room history
     ///
     /// # Errors
     ///
-    /// Returns `RoomError::MessageCapacityExceeded` if the room message history is at full capacity
+    /// Returns:
+    /// - `RoomError::MessageCapacityExceeded` if the room message history is at full capacity
+    /// - `RoomError::DuplicateMessageId` if a message with the same ID is already in the history
+    /// - `RoomError::SenderMuted` if an admin muted the sender
+    /// - `RoomError::SlowMode` if the sender's previous message is more recent than the
+    ///   slow mode interval
     pub fn add_message(&mut self, message: ChatMessage) -> Result<(), RoomError> {
-        if self.messages.len() >= self.message_capacity {
+        self.check_new_message(&message, self.messages.len())?;
+        self.record_sent(&message.from, message.timestamp);
+        self.messages.push(message);
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(())
+    }
+
+    /// Add a message to the room history, first evicting the messages `policy` no longer
+    /// keeps at `now`
+    ///
+    /// The message is checked against the history as it will be after the eviction, and
+    /// nothing is evicted if it is rejected.
+    ///
+    /// # Returns
+    ///
+    /// The number of evicted messages
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Room::add_message`]
+    pub fn add_message_with_retention(
+        &mut self,
+        message: ChatMessage,
+        policy: RetentionPolicy,
+        now: Timestamp,
+    ) -> Result<usize, RoomError> {
+        let kept = self
+            .messages
+            .iter()
+            .filter(|m| !policy.is_expired(m, now))
+            .count();
+        let kept = policy
+            .max_kept_before(1)
+            .map_or(kept, |max_kept| kept.min(max_kept));
+        self.check_new_message(&message, kept)?;
+
+        let evicted = self.apply_retention(policy, now, 1);
+        self.record_sent(&message.from, message.timestamp);
+        self.messages.push(message);
+        debug_assert_eq!(self.validate(), Ok(()));
+        Ok(evicted)
+    }
+
+    /// Accept a message that is broadcast without being stored in the history
+    ///
+    /// The sender is checked as by [`Room::add_message`] (mute, slow mode) and its send time
+    /// is recorded for slow mode, but the history is left unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Room::check_sender`]
+    pub fn accept_unstored_message(
+        &mut self,
+        sender: &ClientId,
+        sent_at: Timestamp,
+    ) -> Result<(), RoomError> {
+        self.check_sender(sender, sent_at)?;
+        self.record_sent(sender, sent_at);
+        Ok(())
+    }
+
+    /// Record the send time of a participant's accepted message (no-op for non-participants)
+    fn record_sent(&mut self, sender: &ClientId, sent_at: Timestamp) {
+        if let Some(participant) = self.participants.iter_mut().find(|p| &p.id == sender) {
+            participant.last_sent = Some(sent_at);
+        }
+    }
+
+    /// Check that `message` can be added to a history of `count` messages
+    fn check_new_message(&self, message: &ChatMessage, count: usize) -> Result<(), RoomError> {
+        self.check_sender(&message.from, message.timestamp)?;
+        if count >= self.message_capacity {
             return Err(RoomError::MessageCapacityExceeded {
                 capacity: self.message_capacity,
                 current: self.messages.len(),
             });
         }
-        self.messages.push(message);
+        // ID で編集・削除・返信する操作が壊れないよう、重複した ID は受け付けない
+        if self.find_message(message.id.as_str()).is_some() {
+            return Err(RoomError::DuplicateMessageId(message.id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Check that the sender is allowed to send a message at `now`
+    ///
+    /// # Errors
+    ///
+    /// Returns:
+    /// - `RoomError::SenderMuted` if an admin muted the sender
+    /// - `RoomError::SlowMode` if the sender's previous message is more recent than the
+    ///   slow mode interval
+    pub fn check_sender(&self, sender: &ClientId, now: Timestamp) -> Result<(), RoomError> {
+        // ミュートされた参加者のメッセージは受け付けない
+        if self.participants.iter().any(|p| &p.id == sender && p.muted) {
+            return Err(RoomError::SenderMuted(sender.to_string()));
+        }
+        if let Some(retry_after) = self.slow_mode_retry_after(sender, now) {
+            return Err(RoomError::SlowMode { retry_after });
+        }
+        Ok(())
+    }
+
+    /// Time the sender still has to wait before sending a message under slow mode
+    ///
+    /// Returns `None` if slow mode is off, or if the sender's latest message in the history
+    /// is at least `slow_mode_interval` older than `now`.
+    pub fn slow_mode_retry_after(&self, sender: &ClientId, now: Timestamp) -> Option<Duration> {
+        let interval = self.slow_mode_interval?;
+        let last_sent = self.get_participant(sender)?.last_sent?;
+        let elapsed_ms = u64::try_from(now.value() - last_sent.value()).unwrap_or(0);
+        interval
+            .checked_sub(Duration::from_millis(elapsed_ms))
+            .filter(|retry_after| !retry_after.is_zero())
+    }
+
+    /// Find a message in the history by its ID
+    ///
+    /// Messages that are no longer in the history cannot be found.
+    pub fn find_message(&self, id: &str) -> Option<&ChatMessage> {
+        self.messages.iter().find(|m| m.id.as_str() == id)
+    }
+
+    /// Find a message in the history by its ID, for editing it in place
+    ///
+    /// The ID must not be changed through the returned reference
+    /// (message IDs are unique within the history).
+    pub fn find_message_mut(&mut self, id: &str) -> Option<&mut ChatMessage> {
+        self.messages.iter_mut().find(|m| m.id.as_str() == id)
+    }
+
+    /// Evict the messages `policy` no longer keeps at `now`, oldest first
+    ///
+    /// `incoming` is the number of messages about to be added; a count-based policy
+    /// leaves room for them so that the history stays within its limit once they are.
+    ///
+    /// # Returns
+    ///
+    /// The number of evicted messages
+    pub fn apply_retention(
+        &mut self,
+        policy: RetentionPolicy,
+        now: Timestamp,
+        incoming: usize,
+    ) -> usize {
+        let before = self.messages.len();
+        self.messages
+            .retain(|message| !policy.is_expired(message, now));
+        if let Some(max_kept) = policy.max_kept_before(incoming) {
+            let excess = self.messages.len().saturating_sub(max_kept);
+            self.messages.drain(..excess);
+        }
+        debug_assert_eq!(self.validate(), Ok(()));
+        before - self.messages.len()
+    }
+
+    /// Check the invariants of the room
+    ///
+    /// - each participant appears at most once
+    /// - participants and messages stay within their capacities
+    /// - message IDs are unique within the history
+    ///
+    /// Checked after every mutation in debug builds; also usable from tests.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first broken invariant found
+    pub fn validate(&self) -> Result<(), RoomInvariantError> {
+        if self.participants.len() > self.participant_capacity {
+            return Err(RoomInvariantError::ParticipantCountExceedsCapacity {
+                capacity: self.participant_capacity,
+                count: self.participants.len(),
+            });
+        }
+        if self.messages.len() > self.message_capacity {
+            return Err(RoomInvariantError::MessageCountExceedsCapacity {
+                capacity: self.message_capacity,
+                count: self.messages.len(),
+            });
+        }
+
+        let mut participant_ids = HashSet::new();
+        if let Some(duplicate) = self
+            .participants
+            .iter()
+            .find(|p| !participant_ids.insert(&p.id))
+        {
+            return Err(RoomInvariantError::DuplicateParticipant(
+                duplicate.id.as_str().to_string(),
+            ));
+        }
+
+        let mut message_ids = HashSet::new();
+        if let Some(duplicate) = self.messages.iter().find(|m| !message_ids.insert(&m.id)) {
+            return Err(RoomInvariantError::DuplicateMessageId(
+                duplicate.id.to_string(),
+            ));
+        }
+
         Ok(())
     }
 
+    /// Get the last `n` messages of the history, oldest first
+    ///
+    /// Returns the whole history if it holds fewer than `n` messages.
+    pub fn latest_messages(&self, n: usize) -> &[ChatMessage] {
+        let start = self.messages.len().saturating_sub(n);
+        &self.messages[start..]
+    }
+
+    /// Remove every message from the history
+    ///
+    /// # Returns
+    ///
+    /// The number of messages removed
+    pub fn clear_messages(&mut self) -> usize {
+        let purged = self.messages.len();
+        self.messages.clear();
+        purged
+    }
+
+    /// Remove the ephemeral messages that expired at `now` from the history
+    ///
+    /// # Returns
+    ///
+    /// The IDs of the removed messages, oldest first
+    pub fn remove_expired_messages(&mut self, now: Timestamp) -> Vec<MessageId> {
+        let mut expired = Vec::new();
+        self.messages.retain(|message| {
+            if message.is_expired(now) {
+                expired.push(message.id.clone());
+                false
+            } else {
+                true
+            }
+        });
+        expired
+    }
+
+    /// Earliest expiry among the messages in the history (`None` if no message expires)
+    pub fn next_message_expiry(&self) -> Option<Timestamp> {
+        self.messages.iter().filter_map(|m| m.expires_at).min()
+    }
+
+    /// Mute or unmute a participant
+    ///
+    /// # Returns
+    ///
+    /// `false` if no participant with the ID is in the room
+    pub fn set_muted(&mut self, participant_id: &ClientId, muted: bool) -> bool {
+        match self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)
+        {
+            Some(participant) => {
+                participant.muted = muted;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Record the latest activity of a participant
+    ///
+    /// # Returns
+    ///
+    /// `false` if no participant with the ID is in the room
+    pub fn touch_participant(&mut self, participant_id: &ClientId, timestamp: Timestamp) -> bool {
+        match self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)
+        {
+            Some(participant) => {
+                participant.last_active = timestamp;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Count one more connection for a participant already in the room
+    ///
+    /// # Returns
+    ///
+    /// The participant's original `connected_at`, or `None` if no participant with the ID is in the room
+    pub fn add_connection(&mut self, participant_id: &ClientId) -> Option<Timestamp> {
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)?;
+        participant.connections += 1;
+        Some(participant.connected_at)
+    }
+
+    /// Count one closed connection, removing the participant when it was the last one
+    ///
+    /// # Returns
+    ///
+    /// The number of connections left (`0` means the participant left the room),
+    /// or `None` if no participant with the ID is in the room
+    pub fn remove_connection(&mut self, participant_id: &ClientId) -> Option<usize> {
+        let participant = self
+            .participants
+            .iter_mut()
+            .find(|p| &p.id == participant_id)?;
+        participant.connections = participant.connections.saturating_sub(1);
+        let remaining = participant.connections;
+        if remaining == 0 {
+            self.remove_participant(participant_id);
+        }
+        Some(remaining)
+    }
+
     /// Get a participant by ID
     pub fn get_participant(&self, participant_id: &ClientId) -> Option<&Participant> {
         self.participants.iter().find(|p| &p.id == participant_id)
     }
+
+    /// Get the participants a message from `sender` is broadcast to (everyone but the sender)
+    pub fn get_broadcast_targets(&self, sender: &ClientId) -> Vec<ClientId> {
+        self.get_broadcast_targets_excluding(&HashSet::from([sender.clone()]))
+    }
+
+    /// Get the participants a broadcast is delivered to, leaving out every ID in `exclude`
+    ///
+    /// Use this when more than the sender must be left out, e.g. participants who
+    /// muted the sender or observers who do not receive chat.
+    pub fn get_broadcast_targets_excluding(&self, exclude: &HashSet<ClientId>) -> Vec<ClientId> {
+        self.participants
+            .iter()
+            .filter(|p| !exclude.contains(&p.id))
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    /// Check whether individual join/leave notifications should be suppressed
+    ///
+    /// Returns `true` when the current participant count exceeds
+    /// `presence_notification_threshold`. In that case only a participant count
+    /// update should be broadcast.
+    pub fn suppresses_presence_notifications(&self) -> bool {
+        self.presence_notification_threshold
+            .is_some_and(|threshold| self.participants.len() > threshold)
+    }
+
+    /// Restore the room identity and message history from a snapshot
+    ///
+    /// Participants in the snapshot are discarded because their connections did not
+    /// survive the restart. If the snapshot holds more messages than this room's
+    /// `message_capacity`, only the most recent ones are kept.
+    pub fn restore_from_snapshot(&mut self, snapshot: Room) {
+        let skip = snapshot
+            .messages
+            .len()
+            .saturating_sub(self.message_capacity);
+
+        self.id = snapshot.id;
+        self.created_at = snapshot.created_at;
+        self.participants.clear();
+        self.messages = snapshot.messages.into_iter().skip(skip).collect();
+    }
 }
 
 /// Represents a participant in a chat room
+///
+/// Participants are identified by their client_id: equality and hashing consider only `id`,
+/// so two snapshots of the same participant (e.g. with a different `last_active` or status)
+/// compare equal and occupy a single entry in a `HashSet`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Participant {
     /// Participant identifier (client_id)
     pub id: ClientId,
     /// Timestamp when the participant connected
     pub connected_at: Timestamp,
+    /// Presence status shown to other participants
+    #[serde(default)]
+    pub status: PresenceStatus,
+    /// Timestamp of the participant's latest activity (connection or sent message)
+    #[serde(default)]
+    pub last_active: Timestamp,
+    /// Number of open connections of the participant
+    /// (more than one only when multiple connections per client_id are allowed)
+    #[serde(default = "default_connections")]
+    pub connections: usize,
+    /// Whether an admin muted the participant (muted participants cannot send chat messages)
+    #[serde(default)]
+    pub muted: bool,
+    /// Timestamp of the participant's latest accepted chat message (used by slow mode)
+    ///
+    /// Kept apart from the history, so that slow mode also works when messages are not stored.
+    #[serde(default)]
+    pub last_sent: Option<Timestamp>,
+}
+
+/// A participant always has at least the connection it joined with
+fn default_connections() -> usize {
+    1
 }
 
 impl Participant {
-    /// Create a new participant
+    /// Create a new participant with the default (`active`) presence status
     pub fn new(id: ClientId, connected_at: Timestamp) -> Self {
-        Self { id, connected_at }
+        Self::with_status(id, connected_at, PresenceStatus::default())
+    }
+
+    /// Create a new participant with the given presence status
+    pub fn with_status(id: ClientId, connected_at: Timestamp, status: PresenceStatus) -> Self {
+        Self {
+            id,
+            connected_at,
+            status,
+            last_active: connected_at,
+            connections: 1,
+            muted: false,
+            last_sent: None,
+        }
+    }
+
+    /// Milliseconds elapsed since the participant's latest activity
+    ///
+    /// Returns `0` if `now` is earlier than `last_active`.
+    pub fn idle_ms(&self, now: Timestamp) -> u64 {
+        u64::try_from(now.value() - self.last_active.value()).unwrap_or(0)
+    }
+}
+
+impl PartialEq for Participant {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Participant {}
+
+impl std::hash::Hash for Participant {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
     }
 }
 
 /// Represents a chat message in the domain model
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
+    /// Server-assigned message ID
+    /// (snapshots saved before IDs existed are given a new ID when loaded)
+    #[serde(default = "MessageIdFactory::generate")]
+    pub id: MessageId,
     /// Sender's participant ID
     pub from: ClientId,
     /// Message content
     pub content: MessageContent,
     /// Timestamp when the message was sent
     pub timestamp: Timestamp,
+    /// Participants who reacted with each emoji, in the order they reacted
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub reactions: BTreeMap<Emoji, Vec<ClientId>>,
+    /// Time at which an ephemeral message is removed from the history (`None`: kept)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<Timestamp>,
 }
 
 impl ChatMessage {
-    /// Create a new chat message
+    /// Create a new chat message with a newly generated ID
     pub fn new(from: ClientId, content: MessageContent, timestamp: Timestamp) -> Self {
+        Self::with_id(MessageIdFactory::generate(), from, content, timestamp)
+    }
+
+    /// Create a chat message with the given ID
+    pub fn with_id(
+        id: MessageId,
+        from: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+    ) -> Self {
         Self {
+            id,
             from,
             content,
             timestamp,
+            reactions: BTreeMap::new(),
+            expires_at: None,
         }
     }
+
+    /// Make the message ephemeral, removed from the history at `expires_at`
+    pub fn with_expires_at(mut self, expires_at: Option<Timestamp>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// Whether the message is ephemeral and expired at `now`
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+
+    /// Add or remove the reaction of `by` with `emoji`
+    ///
+    /// A participant reacts at most once with each emoji; adding it again or removing
+    /// a reaction that was not added leaves the message unchanged.
+    ///
+    /// # Returns
+    ///
+    /// The number of participants who reacted with `emoji` afterwards
+    pub fn react(&mut self, emoji: Emoji, by: ClientId, action: ReactionAction) -> usize {
+        match action {
+            ReactionAction::Add => {
+                let reactors = self.reactions.entry(emoji).or_default();
+                if !reactors.contains(&by) {
+                    reactors.push(by);
+                }
+                reactors.len()
+            }
+            ReactionAction::Remove => {
+                let Some(reactors) = self.reactions.get_mut(&emoji) else {
+                    return 0;
+                };
+                reactors.retain(|reactor| reactor != &by);
+                let count = reactors.len();
+                if count == 0 {
+                    self.reactions.remove(&emoji);
+                }
+                count
+            }
+        }
+    }
+
+    /// Number of participants who reacted with `emoji`
+    pub fn reaction_count(&self, emoji: &Emoji) -> usize {
+        self.reactions.get(emoji).map_or(0, Vec::len)
+    }
 }
 
 #[cfg(test)]
@@ -163,6 +734,34 @@ mod tests {
         assert_eq!(room.created_at, created_at);
     }
 
+    #[test]
+    fn test_get_broadcast_targets_excluding() {
+        // テスト項目: 除外する ID が 0 件・1 件・複数件のとき、それ以外の参加者が配信対象になる
+        // given (前提条件): alice, bob, charlie, dave が参加している
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let ids: Vec<ClientId> = ["alice", "bob", "charlie", "dave"]
+            .iter()
+            .map(|id| ClientId::new(id.to_string()).unwrap())
+            .collect();
+        for id in &ids {
+            room.add_participant(Participant::new(id.clone(), Timestamp::new(0)))
+                .unwrap();
+        }
+
+        // when (操作):
+        let none = room.get_broadcast_targets_excluding(&HashSet::new());
+        let one = room.get_broadcast_targets_excluding(&HashSet::from([ids[1].clone()]));
+        let several =
+            room.get_broadcast_targets_excluding(&HashSet::from([ids[0].clone(), ids[2].clone()]));
+        let sender_only = room.get_broadcast_targets(&ids[1]);
+
+        // then (期待する結果):
+        assert_eq!(none, ids);
+        assert_eq!(one, vec![ids[0].clone(), ids[2].clone(), ids[3].clone()]);
+        assert_eq!(several, vec![ids[1].clone(), ids[3].clone()]);
+        assert_eq!(sender_only, one);
+    }
+
     #[test]
     fn test_room_add_participant() {
         // テスト項目: 参加者を追加できる
@@ -303,7 +902,7 @@ mod tests {
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
-            RoomError::CapacityExceeded {
+            RoomError::ParticipantCapacityExceeded {
                 capacity: 2,
                 current: 2
             }
@@ -311,6 +910,200 @@ mod tests {
         assert_eq!(room.participants.len(), 2);
     }
 
+    #[test]
+    fn test_find_message_by_id() {
+        // テスト項目: ID でメッセージを検索でき、可変参照から内容を編集できる
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let message = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+        let id = message.id.as_str().to_string();
+        room.add_message(message).unwrap();
+
+        // when (操作):
+        let found = room
+            .find_message(&id)
+            .map(|m| m.content.as_str().to_string());
+        room.find_message_mut(&id).unwrap().content =
+            MessageContent::new("Hello, edited!".to_string()).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(found.as_deref(), Some("Hello!"));
+        assert_eq!(
+            room.find_message(&id).unwrap().content.as_str(),
+            "Hello, edited!"
+        );
+    }
+
+    #[test]
+    fn test_find_message_not_found() {
+        // テスト項目: 履歴にない ID では見つからない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.add_message(ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        ))
+        .unwrap();
+
+        // when (操作):
+        let found = room
+            .find_message("00000000-0000-0000-0000-000000000000")
+            .is_some();
+        let found_mut = room.find_message_mut("no-such-id").is_some();
+
+        // then (期待する結果):
+        assert!(!found);
+        assert!(!found_mut);
+    }
+
+    #[test]
+    fn test_find_message_not_in_history_after_capacity_rejection() {
+        // テスト項目: 履歴が上限に達して保存されなかったメッセージは見つからない
+        //            （履歴は古いメッセージを追い出さず、新しいメッセージを拒否する）
+        // given (前提条件): メッセージ 1 件で上限に達したルーム
+        let mut room = Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+            10,
+            1, // message_capacity
+        );
+        let kept = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+        let kept_id = kept.id.as_str().to_string();
+        room.add_message(kept).unwrap();
+        let rejected = ChatMessage::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            MessageContent::new("Hi!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        );
+        let rejected_id = rejected.id.as_str().to_string();
+
+        // when (操作):
+        let result = room.add_message(rejected);
+
+        // then (期待する結果):
+        assert!(result.is_err());
+        assert!(room.find_message(&kept_id).is_some());
+        assert!(room.find_message(&rejected_id).is_none());
+    }
+
+    #[test]
+    fn test_rejected_message_does_not_evict_history_under_retention() {
+        // テスト項目: 保持ポリシーがあっても、拒否されたメッセージの追加では履歴が削除されない
+        // given (前提条件): 最新 2 件を保持するルームに 2 件あり、bob はミュートされている
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        room.add_participant(Participant::new(bob.clone(), Timestamp::new(0)))
+            .unwrap();
+        assert!(room.set_muted(&bob, true));
+        let policy = RetentionPolicy::MaxCount(2);
+        let message = |from: &ClientId, timestamp: i64| {
+            ChatMessage::new(
+                from.clone(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                Timestamp::new(timestamp),
+            )
+        };
+        for timestamp in [1000, 2000] {
+            room.add_message_with_retention(message(&alice, timestamp), policy, Timestamp::new(0))
+                .unwrap();
+        }
+        let before = room.messages.clone();
+
+        // when (操作):
+        let muted = room.add_message_with_retention(message(&bob, 3000), policy, Timestamp::new(0));
+        let duplicate =
+            room.add_message_with_retention(before[1].clone(), policy, Timestamp::new(0));
+        let accepted =
+            room.add_message_with_retention(message(&alice, 4000), policy, Timestamp::new(0));
+
+        // then (期待する結果):
+        assert_eq!(muted, Err(RoomError::SenderMuted("bob".to_string())));
+        assert!(matches!(duplicate, Err(RoomError::DuplicateMessageId(_))));
+        assert_eq!(accepted, Ok(1));
+        assert_eq!(room.messages.len(), 2);
+        assert_eq!(room.messages[0].id, before[1].id);
+    }
+
+    #[test]
+    fn test_add_message_enforces_slow_mode_per_sender() {
+        // テスト項目: スローモードの間隔内に同じ送信者が送ったメッセージは待ち時間とともに拒否され、
+        //            間隔が過ぎた後や、他の送信者のメッセージは受け付けられる
+        // given (前提条件): 10 秒のスローモードのルームに、alice が時刻 1000 に送信済み
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.slow_mode_interval = Some(Duration::from_secs(10));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let message = |from: &ClientId, timestamp: i64| {
+            ChatMessage::new(
+                from.clone(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                Timestamp::new(timestamp),
+            )
+        };
+        for client_id in [&alice, &bob] {
+            room.add_participant(Participant::new(client_id.clone(), Timestamp::new(0)))
+                .unwrap();
+        }
+        room.add_message(message(&alice, 1000)).unwrap();
+
+        // when (操作):
+        let too_soon = room.add_message(message(&alice, 4000));
+        let other_sender = room.add_message(message(&bob, 4000));
+        let after_interval = room.add_message(message(&alice, 11_000));
+
+        // then (期待する結果):
+        assert_eq!(
+            too_soon,
+            Err(RoomError::SlowMode {
+                retry_after: Duration::from_secs(7)
+            })
+        );
+        assert_eq!(other_sender, Ok(()));
+        assert_eq!(after_interval, Ok(()));
+        assert_eq!(room.messages.len(), 3);
+    }
+
+    #[test]
+    fn test_react_counts_each_participant_once_per_emoji() {
+        // テスト項目: 同じ絵文字のリアクションは参加者ごとに 1 回だけ数えられ、
+        //            取り消すと数が減り、0 になると絵文字ごと消える
+        // given (前提条件):
+        let mut message = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+        let thumbs_up = Emoji::new("👍".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let carol = ClientId::new("carol".to_string()).unwrap();
+
+        // when (操作):
+        let counts = [
+            message.react(thumbs_up.clone(), bob.clone(), ReactionAction::Add),
+            message.react(thumbs_up.clone(), bob.clone(), ReactionAction::Add),
+            message.react(thumbs_up.clone(), carol.clone(), ReactionAction::Add),
+            message.react(thumbs_up.clone(), bob.clone(), ReactionAction::Remove),
+        ];
+        let after_one_left = message.reaction_count(&thumbs_up);
+        let last = message.react(thumbs_up.clone(), carol, ReactionAction::Remove);
+
+        // then (期待する結果):
+        assert_eq!(counts, [1, 1, 2, 1]);
+        assert_eq!(after_one_left, 1);
+        assert_eq!(last, 0);
+        assert!(message.reactions.is_empty());
+    }
+
     #[test]
     fn test_room_message_capacity_exceeded() {
         // テスト項目: メッセージ数が上限に達したらエラーが返される
@@ -354,6 +1147,57 @@ mod tests {
         assert_eq!(room.messages.len(), 2);
     }
 
+    #[test]
+    fn test_room_add_message_assigns_distinct_ids() {
+        // テスト項目: 追加した 2 つのメッセージには異なる ID が割り当てられる
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+
+        // when (操作):
+        for (from, content) in [("alice", "Hello!"), ("bob", "Hello!")] {
+            room.add_message(ChatMessage::new(
+                ClientId::new(from.to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(1000),
+            ))
+            .unwrap();
+        }
+
+        // then (期待する結果):
+        assert_eq!(room.messages.len(), 2);
+        assert_ne!(room.messages[0].id, room.messages[1].id);
+    }
+
+    #[test]
+    fn test_room_add_message_rejects_duplicate_id() {
+        // テスト項目: 既存のメッセージと同じ ID のメッセージは拒否され、履歴に追加されない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let first = ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+        let duplicate_id = first.id.clone();
+        room.add_message(first).unwrap();
+
+        // when (操作):
+        let result = room.add_message(ChatMessage::with_id(
+            duplicate_id.clone(),
+            ClientId::new("mallory".to_string()).unwrap(),
+            MessageContent::new("Spoofed".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ));
+
+        // then (期待する結果):
+        assert_eq!(
+            result.unwrap_err(),
+            RoomError::DuplicateMessageId(duplicate_id.to_string())
+        );
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].content.as_str(), "Hello!");
+    }
+
     #[test]
     fn test_room_default_capacities() {
         // テスト項目: デフォルトの上限値が正しく設定される
@@ -364,4 +1208,396 @@ mod tests {
         assert_eq!(room.participant_capacity, DEFAULT_PARTICIPANT_CAPACITY);
         assert_eq!(room.message_capacity, DEFAULT_MESSAGE_CAPACITY);
     }
+
+    #[test]
+    fn test_room_presence_notifications_not_suppressed_by_default() {
+        // テスト項目: 閾値未設定の場合、参加者数に関わらず通知は抑制されない
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for name in ["alice", "bob", "charlie"] {
+            room.add_participant(Participant::new(
+                ClientId::new(name.to_string()).unwrap(),
+                Timestamp::new(1000),
+            ))
+            .unwrap();
+        }
+
+        // when (操作):
+        let result = room.suppresses_presence_notifications();
+
+        // then (期待する結果):
+        assert!(!result);
+    }
+
+    #[test]
+    fn test_room_presence_notifications_suppressed_above_threshold() {
+        // テスト項目: 参加者数が閾値を超えると通知が抑制される
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.presence_notification_threshold = Some(2);
+        room.add_participant(Participant::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        ))
+        .unwrap();
+        room.add_participant(Participant::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ))
+        .unwrap();
+        let at_threshold = room.suppresses_presence_notifications();
+
+        // when (操作):
+        room.add_participant(Participant::new(
+            ClientId::new("charlie".to_string()).unwrap(),
+            Timestamp::new(3000),
+        ))
+        .unwrap();
+        let above_threshold = room.suppresses_presence_notifications();
+
+        // then (期待する結果):
+        assert!(!at_threshold);
+        assert!(above_threshold);
+    }
+
+    fn room_with_messages(contents: &[&str]) -> Room {
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        for (i, content) in contents.iter().enumerate() {
+            room.add_message(ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(1000 * (i as i64 + 1)),
+            ))
+            .unwrap();
+        }
+        room
+    }
+
+    #[test]
+    fn test_room_latest_messages_returns_last_n_oldest_first() {
+        // テスト項目: 直近 n 件のメッセージが古い順に返される
+        // given (前提条件):
+        let room = room_with_messages(&["one", "two", "three", "four"]);
+
+        // when (操作):
+        let latest = room.latest_messages(2);
+
+        // then (期待する結果):
+        let contents: Vec<&str> = latest.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["three", "four"]);
+    }
+
+    #[test]
+    fn test_room_latest_messages_with_n_larger_than_history() {
+        // テスト項目: n が履歴の件数より大きい場合は全件が返される
+        // given (前提条件):
+        let room = room_with_messages(&["one", "two"]);
+
+        // when (操作):
+        let latest = room.latest_messages(10);
+
+        // then (期待する結果):
+        assert_eq!(latest.len(), 2);
+        assert_eq!(latest[0].content.as_str(), "one");
+    }
+
+    #[test]
+    fn test_room_latest_messages_with_n_zero() {
+        // テスト項目: n が 0 の場合は空のスライスが返される
+        // given (前提条件):
+        let room = room_with_messages(&["one", "two"]);
+
+        // when (操作):
+        let latest = room.latest_messages(0);
+
+        // then (期待する結果):
+        assert!(latest.is_empty());
+    }
+
+    #[test]
+    fn test_room_remove_expired_messages_keeps_the_others() {
+        // テスト項目: 期限を過ぎたメッセージだけが履歴から削除され、その ID が返される
+        // given (前提条件): 期限なし・期限切れ・期限前のメッセージがある
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let expiries = [None, Some(Timestamp::new(5000)), Some(Timestamp::new(9000))];
+        let mut ids = Vec::new();
+        for (i, expires_at) in expiries.into_iter().enumerate() {
+            let message = message_with_id(i as u128).with_expires_at(expires_at);
+            ids.push(message.id.clone());
+            room.add_message(message).unwrap();
+        }
+
+        // when (操作):
+        let expired = room.remove_expired_messages(Timestamp::new(5000));
+
+        // then (期待する結果):
+        assert_eq!(expired, vec![ids[1].clone()]);
+        let kept: Vec<&MessageId> = room.messages.iter().map(|m| &m.id).collect();
+        assert_eq!(kept, vec![&ids[0], &ids[2]]);
+    }
+
+    #[test]
+    fn test_message_expiry_clamps_the_ttl() {
+        // テスト項目: 有効期限は送信時刻に TTL を足したもので、TTL は上下限に収められる
+        // given (前提条件):
+        let sent_at = Timestamp::new(1000);
+
+        // when (操作):
+        let within = message_expiry(sent_at, Duration::from_secs(60));
+        let too_short = message_expiry(sent_at, Duration::ZERO);
+        let too_long = message_expiry(sent_at, Duration::from_secs(u64::MAX));
+
+        // then (期待する結果):
+        assert_eq!(within, Timestamp::new(61_000));
+        assert_eq!(
+            too_short,
+            Timestamp::new(1000 + MIN_MESSAGE_TTL.as_millis() as i64)
+        );
+        assert_eq!(
+            too_long,
+            Timestamp::new(1000 + MAX_MESSAGE_TTL.as_millis() as i64)
+        );
+    }
+
+    #[test]
+    fn test_participant_idle_duration_fresh_versus_long_idle() {
+        // テスト項目: 直前に発言した参加者の idle は短く、長く発言していない参加者の idle は長い
+        // given (前提条件):
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        room.add_participant(Participant::new(alice.clone(), Timestamp::new(1_000)))
+            .unwrap();
+        room.add_participant(Participant::new(bob.clone(), Timestamp::new(1_000)))
+            .unwrap();
+
+        // when (操作): alice だけが 3 分後に発言
+        let touched = room.touch_participant(&alice, Timestamp::new(181_000));
+        let now = Timestamp::new(182_000);
+
+        // then (期待する結果):
+        assert!(touched);
+        assert_eq!(room.get_participant(&alice).unwrap().idle_ms(now), 1_000);
+        assert_eq!(room.get_participant(&bob).unwrap().idle_ms(now), 181_000);
+        assert!(!room.touch_participant(
+            &ClientId::new("charlie".to_string()).unwrap(),
+            Timestamp::new(181_000)
+        ));
+    }
+
+    #[test]
+    fn test_room_restore_from_snapshot_keeps_messages_and_drops_participants() {
+        // テスト項目: スナップショットから ID とメッセージ履歴が復元され、参加者は復元されない
+        // given (前提条件):
+        let mut snapshot = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(100));
+        snapshot
+            .add_participant(Participant::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                Timestamp::new(1000),
+            ))
+            .unwrap();
+        snapshot
+            .add_message(ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                Timestamp::new(2000),
+            ))
+            .unwrap();
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(5000));
+
+        // when (操作):
+        room.restore_from_snapshot(snapshot.clone());
+
+        // then (期待する結果):
+        assert_eq!(room.id, snapshot.id);
+        assert_eq!(room.created_at, Timestamp::new(100));
+        assert!(room.participants.is_empty());
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].content.as_str(), "Hello!");
+    }
+
+    fn alice() -> Participant {
+        Participant::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        )
+    }
+
+    fn message_with_id(id: u128) -> ChatMessage {
+        ChatMessage::with_id(
+            MessageId::from_uuid(uuid::Uuid::from_u128(id)),
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        )
+    }
+
+    #[test]
+    fn test_validate_accepts_room_built_through_its_methods() {
+        // テスト項目: メソッド経由で構築した Room は不変条件を満たす
+        // given (前提条件):
+        let mut room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 1, 2);
+        room.add_participant(alice()).unwrap();
+        room.add_message(message_with_id(1)).unwrap();
+        room.add_message(message_with_id(2)).unwrap();
+
+        // when (操作):
+        let result = room.validate();
+
+        // then (期待する結果):
+        assert_eq!(result, Ok(()));
+    }
+
+    #[test]
+    fn test_participant_identity_is_its_client_id() {
+        // テスト項目: id が同じ参加者は接続時刻や状態が違っても等しく、HashSet では 1 件として扱われる
+        // given (前提条件):
+        let earlier = alice();
+        let mut later = Participant::with_status(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(5000),
+            PresenceStatus::Away,
+        );
+        later.muted = true;
+        let bob = Participant::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            Timestamp::new(1000),
+        );
+
+        // when (操作):
+        let participants: HashSet<Participant> = [earlier.clone(), later.clone(), bob.clone()]
+            .into_iter()
+            .collect();
+
+        // then (期待する結果):
+        assert_eq!(earlier, later);
+        assert_ne!(earlier, bob);
+        assert_eq!(participants.len(), 2);
+        assert!(participants.contains(&later));
+    }
+
+    #[test]
+    fn test_add_participant_rejects_duplicate_id() {
+        // テスト項目: 同じ ID の参加者の追加はエラーになり、参加者一覧と参加履歴は変わらない
+        // given (前提条件): alice が参加済み
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.add_participant(alice()).unwrap();
+        let roster_version = room.roster_version();
+
+        // when (操作):
+        let result = room.add_participant(alice());
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(RoomError::DuplicateParticipant("alice".to_string()))
+        );
+        assert_eq!(room.participants.len(), 1);
+        assert_eq!(room.roster_version(), roster_version);
+        assert_eq!(room.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_detects_duplicate_participant() {
+        // テスト項目: 同じ参加者が 2 回含まれる Room は不変条件違反になる
+        // given (前提条件): フィールドを直接操作して alice を 2 回追加する
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.participants.push(alice());
+        room.participants.push(alice());
+
+        // when (操作):
+        let result = room.validate();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(RoomInvariantError::DuplicateParticipant(
+                "alice".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_validate_detects_participants_over_capacity() {
+        // テスト項目: 参加者数が上限を超えた Room は不変条件違反になる
+        // given (前提条件): 上限 1 のルームに、フィールドを直接操作して 2 人追加する
+        let mut room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 1, 10);
+        room.participants.push(alice());
+        room.participants.push(Participant::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            Timestamp::new(1000),
+        ));
+
+        // when (操作):
+        let result = room.validate();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(RoomInvariantError::ParticipantCountExceedsCapacity {
+                capacity: 1,
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_detects_messages_over_capacity() {
+        // テスト項目: メッセージ数が上限を超えた Room は不変条件違反になる
+        // given (前提条件): 上限 1 のルームに、フィールドを直接操作して 2 件追加する
+        let mut room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 10, 1);
+        room.messages.push(message_with_id(1));
+        room.messages.push(message_with_id(2));
+
+        // when (操作):
+        let result = room.validate();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(RoomInvariantError::MessageCountExceedsCapacity {
+                capacity: 1,
+                count: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_validate_detects_duplicate_message_id() {
+        // テスト項目: 同じ ID のメッセージが 2 件含まれる Room は不変条件違反になる
+        // given (前提条件): フィールドを直接操作して同じ ID のメッセージを 2 件追加する
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.messages.push(message_with_id(1));
+        room.messages.push(message_with_id(1));
+
+        // when (操作):
+        let result = room.validate();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(RoomInvariantError::DuplicateMessageId(
+                uuid::Uuid::from_u128(1).to_string()
+            ))
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    #[cfg(debug_assertions)]
+    fn test_add_message_panics_on_broken_invariant_in_debug_builds() {
+        // テスト項目: デバッグビルドでは、変更後に不変条件が崩れていると panic する
+        // given (前提条件): フィールドを直接操作して alice を 2 回追加しておく
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.participants.push(alice());
+        room.participants.push(alice());
+
+        // when (操作): メッセージを追加する
+        let _ = room.add_message(message_with_id(1));
+
+        // then (期待する結果): panic する
+    }
 }
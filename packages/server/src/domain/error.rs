@@ -1,5 +1,7 @@
 //! Domain layer error definitions.
 
+use std::time::Duration;
+
 use thiserror::Error;
 
 // ------------------------------------------------------------------------------------------------
@@ -32,6 +34,34 @@ pub enum ValueObjectError {
     /// MessageContent too long error
     #[error("MessageContent cannot exceed {max} characters (got {actual})")]
     MessageContentTooLong { max: usize, actual: usize },
+
+    /// FileAttachment filename validation error
+    #[error("FileAttachment filename cannot be empty")]
+    FileNameEmpty,
+
+    /// FileAttachment filename invalid error (contains path separators)
+    #[error("FileAttachment filename must not contain path separators (got: {0})")]
+    FileNameInvalid(String),
+
+    /// PresenceStatus invalid value error
+    #[error("PresenceStatus must be one of active, away, dnd (got: {0})")]
+    PresenceStatusInvalid(String),
+
+    /// Emoji validation error
+    #[error("Emoji cannot be empty")]
+    EmojiEmpty,
+
+    /// Emoji too long error
+    #[error("Emoji cannot exceed {max} bytes (got {actual})")]
+    EmojiTooLong { max: usize, actual: usize },
+
+    /// Emoji invalid error (contains whitespace or control characters)
+    #[error("Emoji must not contain whitespace (got: {0:?})")]
+    EmojiInvalid(String),
+
+    /// ReactionAction invalid value error
+    #[error("ReactionAction must be one of add, remove (got: {0})")]
+    ReactionActionInvalid(String),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -41,13 +71,51 @@ pub enum ValueObjectError {
 /// Errors related to Room domain logic
 #[derive(Debug, Error, Clone, PartialEq, Eq)]
 pub enum RoomError {
-    /// Room capacity exceeded error
-    #[error("Room capacity exceeded: maximum {capacity} participants allowed (current: {current})")]
-    CapacityExceeded { capacity: usize, current: usize },
+    /// Participant capacity exceeded error
+    #[error(
+        "Participant capacity exceeded: maximum {capacity} participants allowed (current: {current})"
+    )]
+    ParticipantCapacityExceeded { capacity: usize, current: usize },
+
+    /// A participant with the same ID is already in the room
+    #[error("Duplicate participant: {0}")]
+    DuplicateParticipant(String),
 
     /// Message capacity exceeded error
     #[error("Message capacity exceeded: maximum {capacity} messages allowed (current: {current})")]
     MessageCapacityExceeded { capacity: usize, current: usize },
+
+    /// A message with the same ID is already in the history
+    #[error("Duplicate message ID: {0}")]
+    DuplicateMessageId(String),
+
+    /// The sender has been muted by an admin
+    #[error("Sender is muted: {0}")]
+    SenderMuted(String),
+
+    /// The sender sent a message too soon after their previous one under slow mode
+    #[error("Slow mode: retry after {retry_after:?}")]
+    SlowMode { retry_after: Duration },
+}
+
+/// Room invariants found broken by [`Room::validate`](super::entity::Room::validate)
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum RoomInvariantError {
+    /// The same participant appears more than once
+    #[error("Duplicate participant: {0}")]
+    DuplicateParticipant(String),
+
+    /// More participants than the room capacity
+    #[error("Participant count {count} exceeds capacity {capacity}")]
+    ParticipantCountExceedsCapacity { capacity: usize, count: usize },
+
+    /// More messages than the history capacity
+    #[error("Message count {count} exceeds capacity {capacity}")]
+    MessageCountExceedsCapacity { capacity: usize, count: usize },
+
+    /// The same message ID appears more than once in the history
+    #[error("Duplicate message ID: {0}")]
+    DuplicateMessageId(String),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -68,6 +136,30 @@ pub enum RepositoryError {
     /// Room not found error
     #[error("Room not found")]
     RoomNotFound,
+
+    /// Message not found in the history error
+    #[error("Message not found: {0}")]
+    MessageNotFound(String),
+
+    /// Room domain rule violation (e.g. capacity exceeded)
+    #[error(transparent)]
+    Room(#[from] RoomError),
+}
+
+/// Errors returned when adding a participant with the duplicate and capacity checks
+#[derive(Debug, Error)]
+pub enum AddParticipantError {
+    /// A participant with the same ID is already in the room
+    #[error("Participant already in the room: {0}")]
+    Duplicate(String),
+
+    /// The room is at full capacity
+    #[error("Participant capacity exceeded: maximum {capacity} participants allowed")]
+    CapacityExceeded { capacity: usize },
+
+    /// Any other repository failure
+    #[error(transparent)]
+    Repository(#[from] RepositoryError),
 }
 
 // ------------------------------------------------------------------------------------------------
@@ -75,7 +167,7 @@ pub enum RepositoryError {
 // ------------------------------------------------------------------------------------------------
 
 /// Errors related to MessagePusher operations
-#[derive(Debug, Error)]
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
 pub enum MessagePushError {
     /// Client not found error
     #[error("Client not found: {0}")]
@@ -84,4 +176,43 @@ pub enum MessagePushError {
     /// Push failed error
     #[error("Push failed: {0}")]
     PushFailed(String),
+
+    /// Delivery timed out because the client's channel stayed full
+    #[error("Delivery to client timed out: {0}")]
+    DeliveryTimeout(String),
+}
+
+impl MessagePushError {
+    /// Machine-readable error code (used as the `code` of error frames)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::ClientNotFound(_) => "client-not-found",
+            Self::PushFailed(_) => "push-failed",
+            Self::DeliveryTimeout(_) => "delivery-timeout",
+        }
+    }
+}
+
+// ------------------------------------------------------------------------------------------------
+// IdentityVerifier errors
+// ------------------------------------------------------------------------------------------------
+
+/// Errors returned when the claimed identity of a client cannot be verified
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AuthError {
+    /// The client did not present a token
+    #[error("Missing identity token")]
+    MissingToken,
+
+    /// The identity provider rejected the token
+    #[error("Invalid identity token: {0}")]
+    InvalidToken(String),
+
+    /// The token belongs to another client_id than the one claimed
+    #[error("Token was issued for '{verified}', not '{claimed}'")]
+    ClientIdMismatch { claimed: String, verified: String },
+
+    /// The identity provider could not be reached
+    #[error("Identity provider unavailable: {0}")]
+    ProviderUnavailable(String),
 }
@@ -1,6 +1,6 @@
 //! Domain factories for creating domain entities and value objects.
 
-use super::{RoomId, error::ValueObjectError};
+use super::{MessageId, RoomId, error::ValueObjectError};
 
 /// Factory for generating RoomId instances.
 ///
@@ -25,6 +25,19 @@ impl RoomIdFactory {
     }
 }
 
+/// Factory for generating MessageId instances.
+pub struct MessageIdFactory;
+
+impl MessageIdFactory {
+    /// Generate a new MessageId with a random UUID v4.
+    ///
+    /// UUID v4 は 122 ビットの乱数を持つため、実用上 ID が衝突することはありません。
+    /// 万一衝突した場合は [`Room::add_message`](super::Room::add_message) が拒否します。
+    pub fn generate() -> MessageId {
+        MessageId::from_uuid(uuid::Uuid::new_v4())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -54,4 +67,15 @@ mod tests {
         // then (期待する結果):
         assert_ne!(room_id1, room_id2);
     }
+
+    #[test]
+    fn test_message_id_factory_generate_uniqueness() {
+        // テスト項目: MessageIdFactory::generate() は大量に生成しても重複しない
+        // when (操作):
+        let ids: std::collections::HashSet<MessageId> =
+            (0..10_000).map(|_| MessageIdFactory::generate()).collect();
+
+        // then (期待する結果):
+        assert_eq!(ids.len(), 10_000);
+    }
 }
@@ -0,0 +1,60 @@
+//! 接続するクライアントの身元確認の抽象化
+//!
+//! ## 責務
+//!
+//! IdentityVerifier は「クライアントが名乗る client_id が本人のものかを確かめる」責務を持ちます。
+//! 参加者の登録前に、接続時に提示されたトークンを外部の ID プロバイダーなどで検証します。
+//!
+//! 検証方法（OIDC、社内の認証 API、署名付きトークンなど）は問いません。
+
+use async_trait::async_trait;
+
+use super::{AuthError, ClientId};
+
+/// 身元を確認できたクライアント
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedIdentity {
+    /// トークンの持ち主として確認できた client_id
+    pub client_id: ClientId,
+}
+
+/// クライアントの身元確認の抽象化
+///
+/// ## 実装
+///
+/// - `AllowAllVerifier`: 検証せず、名乗った client_id をそのまま受け入れる実装（デフォルト）
+#[async_trait]
+pub trait IdentityVerifier: Send + Sync {
+    /// 名乗った client_id と提示されたトークンを検証
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - クライアントが名乗った client_id
+    /// * `token` - 接続時に提示されたトークン（提示されなかった場合は `None`）
+    ///
+    /// # エラー
+    ///
+    /// - `AuthError`: 身元を確認できない（接続は拒否される）
+    async fn verify(
+        &self,
+        client_id: &ClientId,
+        token: Option<&str>,
+    ) -> Result<VerifiedIdentity, AuthError>;
+}
+
+/// 検証せずにすべてのクライアントを受け入れる IdentityVerifier（デフォルト）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAllVerifier;
+
+#[async_trait]
+impl IdentityVerifier for AllowAllVerifier {
+    async fn verify(
+        &self,
+        client_id: &ClientId,
+        _token: Option<&str>,
+    ) -> Result<VerifiedIdentity, AuthError> {
+        Ok(VerifiedIdentity {
+            client_id: client_id.clone(),
+        })
+    }
+}
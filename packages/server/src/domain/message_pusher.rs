@@ -14,6 +14,8 @@
 //! - ADR: `docs/adr/0001-message-pusher-abstraction-and-placement.md`
 //! - タスク: `docs/tasks/20251112-032514_introduce-message-pusher.md`
 
+use std::collections::HashSet;
+
 use async_trait::async_trait;
 
 use super::{ClientId, MessagePushError};
@@ -21,8 +23,33 @@ use super::{ClientId, MessagePushError};
 /// メッセージ送信用のチャネル型
 ///
 /// WebSocket や他の通信プロトコルでメッセージを送信するための抽象化。
-/// 実装詳細（tokio の UnboundedSender）を隠蔽し、将来的な変更を容易にします。
-pub type PusherChannel = tokio::sync::mpsc::UnboundedSender<String>;
+/// 実装詳細（tokio の Sender）を隠蔽し、将来的な変更を容易にします。
+///
+/// 容量付きのチャネルのため、受信側が滞留すると送信側は待たされます。
+/// 送信側は待ち時間に上限（タイムアウト）を設ける必要があります。
+pub type PusherChannel = tokio::sync::mpsc::Sender<String>;
+
+/// PusherChannel のデフォルト容量（クライアントごとの未送信メッセージの上限）
+pub const PUSHER_CHANNEL_CAPACITY: usize = 256;
+
+/// ブロードキャストの配信結果
+///
+/// ブロードキャストは一部の送信先への失敗を許容するため、
+/// どの送信先に届き、どの送信先に届かなかったかを返します。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BroadcastReport {
+    /// 配信できた送信先（複数の接続を持つ場合は、すべての接続に配信できたもの）
+    pub delivered: Vec<ClientId>,
+    /// 配信できなかった送信先（未登録、接続が閉じている、送信がタイムアウトした）
+    pub failed: Vec<ClientId>,
+}
+
+impl BroadcastReport {
+    /// すべての送信先に配信できたかどうか
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
 
 /// メッセージ送信（通知）の抽象化
 ///
@@ -59,8 +86,46 @@ pub trait MessagePusher: Send + Sync {
     /// 実装によっては、この操作は no-op（何もしない）になる場合があります。
     async fn unregister_client(&self, client_id: &ClientId);
 
+    /// クライアントの既存の接続をすべて新しい接続に置き換える
+    ///
+    /// 重複時の扱いが takeover のときに使います。並行して置き換えても、
+    /// クライアントに残る接続は最後に置き換えた 1 つだけです。
+    ///
+    /// # 引数
+    ///
+    /// - `client_id`: クライアント ID（Domain Model）
+    /// - `sender`: 新しい接続のメッセージ送信用の channel sender
+    ///
+    /// # 注意
+    ///
+    /// デフォルト実装は登録解除と登録を順に行うだけで、不可分ではありません。
+    /// 接続を保持する実装は、1 つのロックの中で置き換えるよう上書きしてください。
+    async fn replace_client(&self, client_id: ClientId, sender: PusherChannel) {
+        self.unregister_client(&client_id).await;
+        self.register_client(client_id, sender).await;
+    }
+
+    /// クライアントの 1 つの接続だけを登録解除
+    ///
+    /// 同じクライアントが複数の接続を持つ場合（複数接続モード）に、
+    /// 閉じた接続の channel だけを取り除きます。
+    ///
+    /// # 引数
+    ///
+    /// - `client_id`: クライアント ID（Domain Model）
+    /// - `sender`: 登録時に渡した channel sender
+    ///
+    /// # 注意
+    ///
+    /// デフォルト実装はクライアントのすべての接続を登録解除します。
+    async fn unregister_connection(&self, client_id: &ClientId, _sender: &PusherChannel) {
+        self.unregister_client(client_id).await;
+    }
+
     /// 特定のクライアントにメッセージを送信
     ///
+    /// クライアントが複数の接続を持つ場合は、すべての接続に送信します。
+    ///
     /// # 引数
     ///
     /// - `client_id`: 送信先のクライアント ID
@@ -70,15 +135,22 @@ pub trait MessagePusher: Send + Sync {
     ///
     /// - `MessagePushError::ClientNotFound`: クライアントが存在しない
     /// - `MessagePushError::PushFailed`: 送信に失敗
+    /// - `MessagePushError::DeliveryTimeout`: 送信がタイムアウトした（クライアントが滞留している）
     async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError>;
 
     /// 複数のクライアントにメッセージをブロードキャスト
     ///
+    /// クライアントが複数の接続を持つ場合は、すべての接続に送信します。
+    ///
     /// # 引数
     ///
     /// - `targets`: 送信先のクライアント ID のリスト
     /// - `content`: 送信するメッセージ内容（JSON 文字列など）
     ///
+    /// # 戻り値
+    ///
+    /// 送信先ごとの配信結果（[`BroadcastReport`]）
+    ///
     /// # エラー
     ///
     /// - `MessagePushError::PushFailed`: 送信に失敗（一部の送信失敗は許容される実装もある）
@@ -86,10 +158,38 @@ pub trait MessagePusher: Send + Sync {
     /// # 注意
     ///
     /// ブロードキャストの実装によっては、一部のクライアントへの送信が失敗しても
-    /// 他のクライアントへの送信は継続される場合があります。
+    /// 他のクライアントへの送信は継続される場合があります。その場合、失敗した送信先は
+    /// エラーではなく [`BroadcastReport::failed`] に含まれます。
     async fn broadcast(
         &self,
         targets: Vec<ClientId>,
         content: &str,
-    ) -> Result<(), MessagePushError>;
+    ) -> Result<BroadcastReport, MessagePushError>;
+
+    /// 除外するクライアントを除いてブロードキャスト
+    ///
+    /// 送信者やミュート中のクライアントなど、配信時に除外する送信先をまとめて指定します。
+    /// 除外したクライアントは [`BroadcastReport`] にも含まれません。
+    ///
+    /// # 引数
+    ///
+    /// - `targets`: 送信先の候補（ルームの全員など）
+    /// - `exclude`: 送信しないクライアント ID の集合
+    /// - `content`: 送信するメッセージ内容（JSON 文字列など）
+    ///
+    /// # 注意
+    ///
+    /// デフォルト実装は `targets` から `exclude` を取り除き、[`MessagePusher::broadcast`] を呼び出します。
+    async fn broadcast_except(
+        &self,
+        targets: Vec<ClientId>,
+        exclude: &HashSet<ClientId>,
+        content: &str,
+    ) -> Result<BroadcastReport, MessagePushError> {
+        let targets = targets
+            .into_iter()
+            .filter(|client_id| !exclude.contains(client_id))
+            .collect();
+        self.broadcast(targets, content).await
+    }
 }
@@ -0,0 +1,179 @@
+//! Normalization applied to chat message content before it is stored and broadcast.
+//!
+//! Each transform is a pure function on the content string and can be enabled
+//! individually through [`MessageTransform`]. All transforms are disabled by default,
+//! so the content is stored exactly as sent unless the server opts in.
+
+use super::value_object::MessageContent;
+
+/// URL schemes recognized by link detection
+const LINK_SCHEMES: [&str; 2] = ["http://", "https://"];
+
+/// Punctuation stripped from the end of a detected link (e.g. "see https://example.com.")
+const LINK_TRAILING_PUNCTUATION: &[char] =
+    &['.', ',', ';', ':', '!', '?', ')', ']', '}', '"', '\''];
+
+/// Configuration of the content transforms (all disabled by default)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MessageTransform {
+    /// Remove leading and trailing whitespace
+    pub trim: bool,
+    /// Replace runs of whitespace (including newlines) with a single space
+    pub collapse_whitespace: bool,
+    /// Extract `http(s)://` URLs into [`TransformedContent::links`]
+    pub detect_links: bool,
+}
+
+/// Canonical content produced by [`MessageTransform::apply`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformedContent {
+    /// Content to store and broadcast
+    pub content: MessageContent,
+    /// URLs found in the content (empty unless link detection is enabled)
+    pub links: Vec<String>,
+}
+
+impl MessageTransform {
+    /// Apply the enabled transforms to validated content
+    ///
+    /// If normalization would leave the content empty (e.g. whitespace only),
+    /// the original content is kept so that a valid message never becomes invalid.
+    pub fn apply(&self, content: MessageContent) -> TransformedContent {
+        let mut text = content.as_str().to_string();
+        if self.collapse_whitespace {
+            text = collapse_whitespace(&text);
+        }
+        if self.trim {
+            text = text.trim().to_string();
+        }
+
+        let content = if text == content.as_str() {
+            content
+        } else {
+            MessageContent::new(text).unwrap_or(content)
+        };
+        let links = if self.detect_links {
+            extract_links(content.as_str())
+        } else {
+            Vec::new()
+        };
+
+        TransformedContent { content, links }
+    }
+}
+
+/// Replace every run of whitespace with a single space
+///
+/// Leading and trailing whitespace is collapsed as well, but not removed.
+pub fn collapse_whitespace(text: &str) -> String {
+    let mut collapsed = String::with_capacity(text.len());
+    let mut in_whitespace = false;
+    for c in text.chars() {
+        if c.is_whitespace() {
+            if !in_whitespace {
+                collapsed.push(' ');
+            }
+            in_whitespace = true;
+        } else {
+            collapsed.push(c);
+            in_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+/// Extract `http://` and `https://` URLs in order of appearance
+pub fn extract_links(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .filter_map(|word| {
+            let start = LINK_SCHEMES
+                .iter()
+                .filter_map(|scheme| word.find(scheme))
+                .min()?;
+            let link = word[start..].trim_end_matches(LINK_TRAILING_PUNCTUATION);
+            let has_host = LINK_SCHEMES
+                .iter()
+                .any(|scheme| link.len() > scheme.len() && link.starts_with(scheme));
+            has_host.then(|| link.to_string())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn content(text: &str) -> MessageContent {
+        MessageContent::new(text.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        // テスト項目: 連続する空白（改行・タブを含む）が 1 つの空白にまとめられる
+        // given (前提条件):
+        let text = "hello   world\n\n\tagain ";
+
+        // when (操作):
+        let result = collapse_whitespace(text);
+
+        // then (期待する結果):
+        assert_eq!(result, "hello world again ");
+    }
+
+    #[test]
+    fn test_extract_links() {
+        // テスト項目: http(s) の URL が出現順に抽出され、末尾の句読点は含まれない
+        // given (前提条件):
+        let text = "see https://example.com/docs?a=1, (and http://foo.test/x) or ftp://no https://";
+
+        // when (操作):
+        let links = extract_links(text);
+
+        // then (期待する結果):
+        assert_eq!(
+            links,
+            vec![
+                "https://example.com/docs?a=1".to_string(),
+                "http://foo.test/x".to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_enabled_transforms() {
+        // テスト項目: 有効化した変換のみが適用される
+        // given (前提条件):
+        let transform = MessageTransform {
+            trim: true,
+            collapse_whitespace: true,
+            detect_links: true,
+        };
+
+        // when (操作):
+        let transformed = transform.apply(content("  read   https://example.com  "));
+        let untouched =
+            MessageTransform::default().apply(content("  read   https://example.com  "));
+
+        // then (期待する結果):
+        assert_eq!(transformed.content.as_str(), "read https://example.com");
+        assert_eq!(transformed.links, vec!["https://example.com".to_string()]);
+        assert_eq!(untouched.content.as_str(), "  read   https://example.com  ");
+        assert!(untouched.links.is_empty());
+    }
+
+    #[test]
+    fn test_apply_keeps_original_when_result_would_be_empty() {
+        // テスト項目: 変換後に空になる場合は元の内容が維持される
+        // given (前提条件):
+        let transform = MessageTransform {
+            trim: true,
+            ..MessageTransform::default()
+        };
+
+        // when (操作):
+        let transformed = transform.apply(content("   "));
+
+        // then (期待する結果):
+        assert_eq!(transformed.content.as_str(), "   ");
+    }
+}
@@ -3,16 +3,35 @@
 //! This module contains business logic that is independent of
 //! data transfer objects (DTOs) and infrastructure concerns.
 
+pub mod dead_letter;
 pub mod entity;
 pub mod error;
 pub mod factory;
+pub mod identity;
 pub mod message_pusher;
+pub mod message_transform;
 pub mod repository;
+pub mod retention;
+pub mod roster;
 pub mod value_object;
 
-pub use entity::{ChatMessage, Participant, Room};
-pub use error::{MessagePushError, RepositoryError, RoomError, ValueObjectError};
-pub use factory::RoomIdFactory;
-pub use message_pusher::{MessagePusher, PusherChannel};
+pub use dead_letter::{DeadLetter, DeadLetterSink};
+pub use entity::{
+    ChatMessage, MAX_MESSAGE_TTL, MIN_MESSAGE_TTL, Participant, Room, message_expiry,
+};
+pub use error::{
+    AddParticipantError, AuthError, MessagePushError, RepositoryError, RoomError,
+    RoomInvariantError, ValueObjectError,
+};
+pub use factory::{MessageIdFactory, RoomIdFactory};
+pub use identity::{AllowAllVerifier, IdentityVerifier, VerifiedIdentity};
+pub use message_pusher::{BroadcastReport, MessagePusher, PUSHER_CHANNEL_CAPACITY, PusherChannel};
+pub use message_transform::{MessageTransform, TransformedContent};
 pub use repository::RoomRepository;
-pub use value_object::{ClientId, MessageContent, RoomId, Timestamp};
+pub use retention::RetentionPolicy;
+pub use roster::RosterDelta;
+pub use value_object::{
+    ClientId, Emoji, FileAttachment, MAX_ANNOUNCEMENT_CONTENT_LEN, MAX_CHAT_CONTENT_LEN,
+    MAX_CONTENT_LEN_CEILING, MAX_EMOJI_LEN, MessageContent, MessageId, PresenceStatus,
+    ReactionAction, RoomId, Timestamp,
+};
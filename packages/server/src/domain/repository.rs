@@ -5,7 +5,10 @@
 
 use async_trait::async_trait;
 
-use super::{ClientId, MessageContent, Participant, RepositoryError, Room, Timestamp};
+use super::{
+    AddParticipantError, ChatMessage, ClientId, Emoji, MessageContent, MessageId, Participant,
+    PresenceStatus, ReactionAction, RepositoryError, Room, RoomId, RosterDelta, Timestamp,
+};
 
 /// Room Repository trait
 ///
@@ -22,16 +25,86 @@ pub trait RoomRepository: Send + Sync {
     /// Room エンティティを取得
     async fn get_room(&self) -> Result<Room, RepositoryError>;
 
-    /// 参加者を追加
+    /// 参加者を追加（プレゼンス状態は `active`）
     async fn add_participant(
         &self,
         client_id: ClientId,
         timestamp: Timestamp,
+    ) -> Result<(), RepositoryError> {
+        self.add_participant_with_status(client_id, timestamp, PresenceStatus::default())
+            .await
+    }
+
+    /// プレゼンス状態を指定して参加者を追加
+    async fn add_participant_with_status(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+        status: PresenceStatus,
     ) -> Result<(), RepositoryError>;
 
+    /// 重複と定員を確認したうえで参加者を追加（プレゼンス状態は `active`）
+    async fn try_add_participant(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), AddParticipantError> {
+        self.try_add_participant_with_status(client_id, timestamp, PresenceStatus::default())
+            .await
+    }
+
+    /// プレゼンス状態を指定し、重複と定員を確認したうえで参加者を追加
+    ///
+    /// 確認と追加を不可分に行うため、同じ ID で並行して接続しても
+    /// 追加に成功するのは 1 つだけです。
+    ///
+    /// # エラー
+    ///
+    /// - `AddParticipantError::Duplicate`: 同じ ID の参加者が既に Room にいる
+    /// - `AddParticipantError::CapacityExceeded`: Room が定員に達している
+    async fn try_add_participant_with_status(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+        status: PresenceStatus,
+    ) -> Result<(), AddParticipantError>;
+
+    /// Room にいる参加者の接続数を 1 増やす（複数接続モード）
+    ///
+    /// # 戻り値
+    ///
+    /// 参加者が最初に接続した時刻
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::ParticipantNotFound`: 参加者が Room に存在しない
+    async fn add_connection(&self, client_id: &ClientId) -> Result<Timestamp, RepositoryError>;
+
+    /// 参加者の接続数を 1 減らし、最後の接続であれば参加者を削除
+    ///
+    /// # 戻り値
+    ///
+    /// 残りの接続数（`0` の場合は参加者が Room から削除された）
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::ParticipantNotFound`: 参加者が Room に存在しない
+    async fn remove_connection(&self, client_id: &ClientId) -> Result<usize, RepositoryError>;
+
     /// 参加者を削除
     async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError>;
 
+    /// 参加者の最終アクティブ時刻を更新
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::ParticipantNotFound`: 参加者が Room に存在しない
+    async fn update_last_active(
+        &self,
+        client_id: &ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), RepositoryError>;
+
     /// 接続中の全てのクライアント ID を取得
     async fn get_all_connected_client_ids(&self) -> Vec<ClientId>;
 
@@ -43,9 +116,144 @@ pub trait RoomRepository: Send + Sync {
         timestamp: Timestamp,
     ) -> Result<(), RepositoryError>;
 
+    /// メッセージを Room に追加し、同一のロック区間で配信対象を取得
+    ///
+    /// メッセージ履歴への追加と配信対象（送信者以外の参加者）のスナップショットを
+    /// 不可分に行います。これにより「メッセージが履歴に追加された時点で Room にいた参加者」
+    /// だけが配信対象となり、並行する join との順序が一意に定まります。
+    ///
+    /// メッセージ ID は配信するフレームに含めるため、呼び出し側で採番して渡します。
+    /// `expires_at` を指定したメッセージは、その時刻を過ぎると
+    /// [`remove_expired_messages`](Self::remove_expired_messages) で履歴から削除されます。
+    async fn add_message_and_snapshot_targets(
+        &self,
+        message_id: MessageId,
+        from_client_id: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+        expires_at: Option<Timestamp>,
+    ) -> Result<Vec<ClientId>, RepositoryError>;
+
+    /// 履歴中のメッセージへのリアクションを追加・取り消し
+    ///
+    /// # 戻り値
+    ///
+    /// 操作後にその絵文字でリアクションしている参加者の数
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::MessageNotFound`: 履歴に指定した ID のメッセージがない
+    async fn react_to_message(
+        &self,
+        message_id: &str,
+        emoji: Emoji,
+        by: ClientId,
+        action: ReactionAction,
+    ) -> Result<usize, RepositoryError>;
+
+    /// Room のメッセージ履歴から直近のメッセージを取得
+    ///
+    /// Room 全体を複製せず、条件に一致する範囲のメッセージだけを返します。
+    ///
+    /// # 引数
+    ///
+    /// - `room_id`: 対象の Room ID
+    /// - `since`: 指定した場合、この時刻より後（この時刻を含まない）のメッセージのみを返す
+    /// - `limit`: 返すメッセージの最大件数（条件に一致するもののうち新しい順に最大 `limit` 件）
+    ///
+    /// # 戻り値
+    ///
+    /// 古い順に並んだメッセージのリスト
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::RoomNotFound`: Room が存在しない
+    async fn get_messages(
+        &self,
+        room_id: &RoomId,
+        since: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError>;
+
+    /// Room のメッセージ履歴を全て削除
+    ///
+    /// # 戻り値
+    ///
+    /// 削除したメッセージ数
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::RoomNotFound`: Room が存在しない
+    async fn purge_messages(&self, room_id: &RoomId) -> Result<usize, RepositoryError>;
+
+    /// クライアントが Room に接続中かどうかを判定
+    ///
+    /// Room 全体を複製せずに、参加者の有無だけを確認します。
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::RoomNotFound`: Room が存在しない
+    async fn is_connected(
+        &self,
+        room_id: &RoomId,
+        client_id: &ClientId,
+    ) -> Result<bool, RepositoryError>;
+
     /// 接続中のクライアント数を取得
     async fn count_connected_clients(&self) -> usize;
 
     /// Room の参加者リストを取得
     async fn get_participants(&self) -> Vec<Participant>;
+
+    /// Room の参加者リストを、それに対応するロスターバージョンとともに取得
+    ///
+    /// 参加者リストとバージョンは同じ時点のものです（差分の起点として使えます）。
+    async fn get_versioned_participants(&self) -> (u64, Vec<Participant>);
+
+    /// 指定したロスターバージョン以降の参加・退出を差分として取得
+    ///
+    /// バージョンが不明か、古すぎて差分を計算できない場合は `None` を返します。
+    async fn get_roster_delta(&self, since_version: u64) -> Option<RosterDelta>;
+
+    /// 参加者のミュート状態を設定
+    ///
+    /// # エラー
+    ///
+    /// - `RepositoryError::ParticipantNotFound`: 参加者が存在しない
+    async fn set_muted(&self, client_id: &ClientId, muted: bool) -> Result<(), RepositoryError>;
+
+    /// 1 人の参加者を取得（参加していない場合は `None`）
+    ///
+    /// 参加者リスト全体を複製せずに、指定した参加者だけを返します。
+    async fn get_participant(&self, client_id: &ClientId) -> Option<Participant>;
+
+    /// 保持ポリシーで保持しなくなったメッセージを履歴から削除
+    ///
+    /// メッセージ追加時にも適用されますが、期間による保持ではメッセージが
+    /// 追加されなくても古くなるため、定期的に呼び出します。
+    ///
+    /// # 戻り値
+    ///
+    /// 削除したメッセージ数
+    async fn apply_retention(&self) -> usize {
+        0
+    }
+
+    /// 有効期限を過ぎたメッセージを履歴から削除
+    ///
+    /// # 戻り値
+    ///
+    /// 削除したメッセージの ID（古い順）
+    async fn remove_expired_messages(&self) -> Vec<MessageId> {
+        Vec::new()
+    }
+
+    /// 履歴にあるメッセージのうち、最も早い有効期限を取得
+    ///
+    /// # 戻り値
+    ///
+    /// 最も早い有効期限（有効期限付きのメッセージがない場合は `None`）
+    async fn next_message_expiry(&self) -> Option<Timestamp> {
+        None
+    }
 }
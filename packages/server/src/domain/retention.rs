@@ -0,0 +1,44 @@
+//! Retention of the message history of a room.
+//!
+//! A [`RetentionPolicy`] decides which messages the history keeps. Messages it no
+//! longer keeps are evicted oldest first, so a room with a policy never rejects a
+//! message for a full history the way the plain `message_capacity` does.
+
+use std::time::Duration;
+
+use super::{entity::ChatMessage, value_object::Timestamp};
+
+/// Which messages the history of a room keeps
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// Keep only the most recent `n` messages
+    MaxCount(usize),
+    /// Keep only the messages sent within the given duration
+    MaxAge(Duration),
+    /// Keep every message until `message_capacity` is reached (default)
+    #[default]
+    None,
+}
+
+impl RetentionPolicy {
+    /// Number of messages the history may hold before `incoming` more are added
+    ///
+    /// `None` when the policy does not limit the count.
+    pub fn max_kept_before(&self, incoming: usize) -> Option<usize> {
+        match self {
+            Self::MaxCount(max) => Some(max.saturating_sub(incoming)),
+            Self::MaxAge(_) | Self::None => None,
+        }
+    }
+
+    /// Whether `message` has outlived the policy at `now`
+    pub fn is_expired(&self, message: &ChatMessage, now: Timestamp) -> bool {
+        match self {
+            Self::MaxAge(max_age) => {
+                let max_age_ms = i64::try_from(max_age.as_millis()).unwrap_or(i64::MAX);
+                now.value().saturating_sub(message.timestamp.value()) > max_age_ms
+            }
+            Self::MaxCount(_) | Self::None => false,
+        }
+    }
+}
@@ -0,0 +1,155 @@
+//! Versioning of the participant list of a room.
+//!
+//! Every join and leave increments the roster version and is recorded in a bounded
+//! [`RosterChangeLog`]. A client that knows the roster at some version can then be sent
+//! only what changed since, as a [`RosterDelta`]. Changes older than the log are
+//! forgotten; a client that last saw the roster before them needs the full roster again.
+
+use std::collections::{HashSet, VecDeque};
+
+use super::{entity::Participant, value_object::ClientId};
+
+/// Number of roster changes remembered for computing deltas
+pub const ROSTER_CHANGE_LOG_CAPACITY: usize = 256;
+
+/// Kind of a change to the participant list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterChangeKind {
+    /// A participant joined the room
+    Joined,
+    /// A participant left the room
+    Left,
+}
+
+/// A recorded change to the participant list
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RosterChange {
+    /// Roster version after the change
+    version: u64,
+    client_id: ClientId,
+    kind: RosterChangeKind,
+}
+
+/// Roster version and the most recent changes to the participant list
+#[derive(Debug, Clone, Default)]
+pub struct RosterChangeLog {
+    /// Current roster version (0 until the first change)
+    version: u64,
+    /// Most recent changes, oldest first
+    changes: VecDeque<RosterChange>,
+}
+
+impl RosterChangeLog {
+    /// Current roster version
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Record a change, incrementing the roster version
+    pub fn record(&mut self, client_id: ClientId, kind: RosterChangeKind) {
+        self.version += 1;
+        self.changes.push_back(RosterChange {
+            version: self.version,
+            client_id,
+            kind,
+        });
+        if self.changes.len() > ROSTER_CHANGE_LOG_CAPACITY {
+            self.changes.pop_front();
+        }
+    }
+
+    /// Participants whose membership changed after `since_version`
+    ///
+    /// Each participant appears once, with whether it was in the roster at `since_version`.
+    /// `None` if `since_version` is newer than the current version or the changes after it
+    /// are no longer all remembered.
+    pub fn changed_since(&self, since_version: u64) -> Option<Vec<(ClientId, bool)>> {
+        if since_version > self.version {
+            return None;
+        }
+        let oldest_remembered = self
+            .changes
+            .front()
+            .map_or(self.version + 1, |change| change.version);
+        if since_version < self.version && oldest_remembered > since_version + 1 {
+            return None;
+        }
+
+        let mut seen = HashSet::new();
+        Some(
+            self.changes
+                .iter()
+                .filter(|change| change.version > since_version)
+                .filter(|change| seen.insert(change.client_id.clone()))
+                // The first change after `since_version` tells whether it was in the roster then
+                .map(|change| {
+                    (
+                        change.client_id.clone(),
+                        change.kind == RosterChangeKind::Left,
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Changes to the participant list since a given roster version
+#[derive(Debug, Clone)]
+pub struct RosterDelta {
+    /// Roster version the delta brings the client to
+    pub version: u64,
+    /// Participants that joined (or left and joined again), sorted by client_id
+    pub added: Vec<Participant>,
+    /// Participants that left, sorted by client_id
+    pub removed: Vec<ClientId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn client(id: &str) -> ClientId {
+        ClientId::new(id.to_string()).unwrap()
+    }
+
+    #[test]
+    fn test_changed_since_reports_each_participant_once_with_its_earlier_membership() {
+        // テスト項目: 指定したバージョン以降に変化した参加者が 1 回ずつ、当時の在室状態とともに返る
+        // given (前提条件): alice が参加した後（バージョン 1）、bob が参加し、alice が退出して再参加した
+        let mut log = RosterChangeLog::default();
+        log.record(client("alice"), RosterChangeKind::Joined);
+        log.record(client("bob"), RosterChangeKind::Joined);
+        log.record(client("alice"), RosterChangeKind::Left);
+        log.record(client("alice"), RosterChangeKind::Joined);
+
+        // when (操作):
+        let changed = log.changed_since(1);
+
+        // then (期待する結果):
+        assert_eq!(log.version(), 4);
+        assert_eq!(
+            changed,
+            Some(vec![(client("bob"), false), (client("alice"), true)])
+        );
+        assert_eq!(log.changed_since(4), Some(vec![]));
+        assert_eq!(log.changed_since(5), None);
+    }
+
+    #[test]
+    fn test_changed_since_forgotten_version_is_none() {
+        // テスト項目: 記録から押し出された変更より前のバージョンからの差分は計算できない
+        // given (前提条件):
+        let mut log = RosterChangeLog::default();
+        for _ in 0..=ROSTER_CHANGE_LOG_CAPACITY {
+            log.record(client("alice"), RosterChangeKind::Joined);
+        }
+
+        // when (操作):
+        let from_start = log.changed_since(0);
+        let from_oldest_remembered = log.changed_since(1);
+
+        // then (期待する結果):
+        assert_eq!(from_start, None);
+        assert!(from_oldest_remembered.is_some());
+    }
+}
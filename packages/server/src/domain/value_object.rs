@@ -128,14 +128,49 @@ impl fmt::Display for RoomId {
     }
 }
 
+/// Message identifier value object.
+///
+/// Server-assigned identifier of a chat message, unique within a room.
+/// New identifiers are generated by [`MessageIdFactory`](super::factory::MessageIdFactory).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct MessageId(String);
+
+impl MessageId {
+    /// Create a MessageId from a UUID.
+    pub fn from_uuid(uuid: uuid::Uuid) -> Self {
+        Self(uuid.to_string())
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for MessageId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Maximum length of a chat message, in bytes
+pub const MAX_CHAT_CONTENT_LEN: usize = 10_000;
+
+/// Maximum length of a server announcement (e.g. the message of the day), in bytes
+pub const MAX_ANNOUNCEMENT_CONTENT_LEN: usize = 50_000;
+
+/// Upper bound of any content length limit, whatever limit is configured
+pub const MAX_CONTENT_LEN_CEILING: usize = 100_000;
+
 /// Message content value object.
 ///
 /// Represents the content of a chat message with validation.
+/// The length limit depends on the kind of message (see [`MessageContent::with_max_len`]).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MessageContent(String);
 
 impl MessageContent {
-    /// Create a new MessageContent.
+    /// Create a new MessageContent within the chat message limit ([`MAX_CHAT_CONTENT_LEN`]).
     ///
     /// # Arguments
     ///
@@ -145,15 +180,29 @@ impl MessageContent {
     ///
     /// A Result containing the MessageContent or an error if validation fails
     pub fn new(content: String) -> Result<Self, ValueObjectError> {
+        Self::with_max_len(content, MAX_CHAT_CONTENT_LEN)
+    }
+
+    /// Create a new MessageContent within the given length limit.
+    ///
+    /// Limits above [`MAX_CONTENT_LEN_CEILING`] are lowered to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `content` - The message content string
+    /// * `max_len` - Maximum length in bytes for this kind of message
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the MessageContent or an error if validation fails
+    pub fn with_max_len(content: String, max_len: usize) -> Result<Self, ValueObjectError> {
         if content.is_empty() {
             return Err(ValueObjectError::MessageContentEmpty);
         }
+        let max = max_len.min(MAX_CONTENT_LEN_CEILING);
         let len = content.len();
-        if len > 10000 {
-            return Err(ValueObjectError::MessageContentTooLong {
-                max: 10000,
-                actual: len,
-            });
+        if len > max {
+            return Err(ValueObjectError::MessageContentTooLong { max, actual: len });
         }
         Ok(Self(content))
     }
@@ -183,10 +232,198 @@ impl TryFrom<String> for MessageContent {
     }
 }
 
+/// File attachment value object.
+///
+/// Represents the metadata of a file shared in a chat room.
+/// The file data itself is handled by the transport layer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileAttachment {
+    filename: String,
+    mime: String,
+    size: usize,
+}
+
+impl FileAttachment {
+    /// Create a new FileAttachment.
+    ///
+    /// # Arguments
+    ///
+    /// * `filename` - The file name (must not be empty nor contain path separators)
+    /// * `mime` - The MIME type of the file
+    /// * `size` - The size of the file data in bytes
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the FileAttachment or an error if validation fails
+    pub fn new(filename: String, mime: String, size: usize) -> Result<Self, ValueObjectError> {
+        if filename.is_empty() {
+            return Err(ValueObjectError::FileNameEmpty);
+        }
+        if filename.contains(['/', '\\']) || filename == "." || filename == ".." {
+            return Err(ValueObjectError::FileNameInvalid(filename));
+        }
+        Ok(Self {
+            filename,
+            mime,
+            size,
+        })
+    }
+
+    /// Get the file name.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// Get the MIME type.
+    pub fn mime(&self) -> &str {
+        &self.mime
+    }
+
+    /// Get the size of the file data in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+}
+
+/// Presence status value object.
+///
+/// Represents the availability a participant shows to the other participants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PresenceStatus {
+    /// Available (default)
+    #[default]
+    Active,
+    /// Away from keyboard
+    Away,
+    /// Do not disturb
+    Dnd,
+}
+
+impl PresenceStatus {
+    /// Get the string representation (`active`, `away` or `dnd`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Active => "active",
+            Self::Away => "away",
+            Self::Dnd => "dnd",
+        }
+    }
+}
+
+impl fmt::Display for PresenceStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TryFrom<&str> for PresenceStatus {
+    type Error = ValueObjectError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "active" => Ok(Self::Active),
+            "away" => Ok(Self::Away),
+            "dnd" => Ok(Self::Dnd),
+            _ => Err(ValueObjectError::PresenceStatusInvalid(value.to_string())),
+        }
+    }
+}
+
+/// Maximum length of a reaction emoji, in bytes
+///
+/// Long enough for emoji sequences joined with zero-width joiners (e.g. family emoji).
+pub const MAX_EMOJI_LEN: usize = 32;
+
+/// Emoji value object.
+///
+/// Represents the emoji of a reaction to a chat message. Any short string without
+/// whitespace is accepted, so that clients may also use shortcodes such as `:+1:`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Emoji(String);
+
+impl Emoji {
+    /// Create a new Emoji with validation.
+    ///
+    /// # Arguments
+    ///
+    /// * `emoji` - The emoji string
+    ///
+    /// # Returns
+    ///
+    /// A Result containing the Emoji or an error if validation fails
+    pub fn new(emoji: String) -> Result<Self, ValueObjectError> {
+        if emoji.is_empty() {
+            return Err(ValueObjectError::EmojiEmpty);
+        }
+        if emoji.len() > MAX_EMOJI_LEN {
+            return Err(ValueObjectError::EmojiTooLong {
+                max: MAX_EMOJI_LEN,
+                actual: emoji.len(),
+            });
+        }
+        if emoji.chars().any(|c| c.is_whitespace() || c.is_control()) {
+            return Err(ValueObjectError::EmojiInvalid(emoji));
+        }
+        Ok(Self(emoji))
+    }
+
+    /// Get the inner string value.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Emoji {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Reaction action value object.
+///
+/// Whether a reaction is added to or removed from a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReactionAction {
+    /// Add the reaction
+    Add,
+    /// Remove the reaction
+    Remove,
+}
+
+impl ReactionAction {
+    /// Get the string representation (`add` or `remove`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Remove => "remove",
+        }
+    }
+}
+
+impl fmt::Display for ReactionAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl TryFrom<&str> for ReactionAction {
+    type Error = ValueObjectError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "add" => Ok(Self::Add),
+            "remove" => Ok(Self::Remove),
+            _ => Err(ValueObjectError::ReactionActionInvalid(value.to_string())),
+        }
+    }
+}
+
 /// Timestamp value object.
 ///
 /// Represents a Unix timestamp in milliseconds (JST).
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Timestamp(i64);
 
 impl Timestamp {
@@ -225,6 +462,58 @@ impl From<i64> for Timestamp {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_emoji_validation() {
+        // テスト項目: 短く空白を含まない文字列は Emoji になり、空・長すぎる・空白を含むものはエラーになる
+        // given (前提条件):
+        let too_long = "👍".repeat(MAX_EMOJI_LEN / 4 + 1);
+
+        // when (操作):
+        let valid = Emoji::new("👍".to_string());
+        let shortcode = Emoji::new(":+1:".to_string());
+        let empty = Emoji::new(String::new());
+        let long = Emoji::new(too_long.clone());
+        let spaced = Emoji::new("👍 👍".to_string());
+
+        // then (期待する結果):
+        assert_eq!(valid.unwrap().as_str(), "👍");
+        assert!(shortcode.is_ok());
+        assert_eq!(empty, Err(ValueObjectError::EmojiEmpty));
+        assert_eq!(
+            long,
+            Err(ValueObjectError::EmojiTooLong {
+                max: MAX_EMOJI_LEN,
+                actual: too_long.len()
+            })
+        );
+        assert_eq!(
+            spaced,
+            Err(ValueObjectError::EmojiInvalid("👍 👍".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_presence_status_try_from() {
+        // テスト項目: 文字列からプレゼンス状態を作成でき、不正な値はエラーになる
+        // given (前提条件):
+        let inputs = ["active", "away", "dnd", "busy"];
+
+        // when (操作):
+        let results: Vec<_> = inputs
+            .iter()
+            .map(|s| PresenceStatus::try_from(*s))
+            .collect();
+
+        // then (期待する結果):
+        assert_eq!(results[0], Ok(PresenceStatus::Active));
+        assert_eq!(results[1], Ok(PresenceStatus::Away));
+        assert_eq!(results[2], Ok(PresenceStatus::Dnd));
+        assert_eq!(
+            results[3],
+            Err(ValueObjectError::PresenceStatusInvalid("busy".to_string()))
+        );
+    }
+
     #[test]
     fn test_client_id_new_success() {
         // テスト項目: 有効なクライアント ID を作成できる
@@ -374,6 +663,61 @@ mod tests {
         assert_eq!(result.unwrap_err(), ValueObjectError::MessageContentEmpty);
     }
 
+    #[test]
+    fn test_message_content_at_chat_limit_succeeds() {
+        // テスト項目: チャットの上限ちょうどの長さのメッセージ内容は作成できる
+        // given (前提条件):
+        let content = "a".repeat(MAX_CHAT_CONTENT_LEN);
+
+        // when (操作):
+        let result = MessageContent::new(content);
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap().as_str().len(), MAX_CHAT_CONTENT_LEN);
+    }
+
+    #[test]
+    fn test_announcement_longer_than_chat_limit_succeeds_within_its_limit() {
+        // テスト項目: チャットの上限を超えるお知らせも、お知らせの上限以内なら作成でき、
+        //            同じ内容をチャットとして作成すると失敗する
+        // given (前提条件):
+        let content = "a".repeat(MAX_CHAT_CONTENT_LEN + 1);
+
+        // when (操作):
+        let announcement =
+            MessageContent::with_max_len(content.clone(), MAX_ANNOUNCEMENT_CONTENT_LEN);
+        let chat = MessageContent::new(content);
+
+        // then (期待する結果):
+        assert!(announcement.is_ok());
+        assert!(matches!(
+            chat,
+            Err(ValueObjectError::MessageContentTooLong {
+                max: MAX_CHAT_CONTENT_LEN,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_message_content_limit_is_capped_by_ceiling() {
+        // テスト項目: 上限の全体の上限（ceiling）を超える上限を指定しても、ceiling で制限される
+        // given (前提条件):
+        let content = "a".repeat(MAX_CONTENT_LEN_CEILING + 1);
+
+        // when (操作):
+        let result = MessageContent::with_max_len(content, usize::MAX);
+
+        // then (期待する結果):
+        assert_eq!(
+            result.unwrap_err(),
+            ValueObjectError::MessageContentTooLong {
+                max: MAX_CONTENT_LEN_CEILING,
+                actual: MAX_CONTENT_LEN_CEILING + 1
+            }
+        );
+    }
+
     #[test]
     fn test_message_content_new_too_long_fails() {
         // テスト項目: 10001 文字以上のメッセージ内容は作成できない
@@ -418,4 +762,49 @@ mod tests {
         assert!(ts1 < ts2);
         assert!(ts2 > ts1);
     }
+
+    #[test]
+    fn test_file_attachment_new_success() {
+        // テスト項目: 有効なファイル添付を作成できる
+        // given (前提条件):
+        let filename = "file.png".to_string();
+
+        // when (操作):
+        let result = FileAttachment::new(filename, "image/png".to_string(), 1024);
+
+        // then (期待する結果):
+        let attachment = result.unwrap();
+        assert_eq!(attachment.filename(), "file.png");
+        assert_eq!(attachment.mime(), "image/png");
+        assert_eq!(attachment.size(), 1024);
+    }
+
+    #[test]
+    fn test_file_attachment_new_empty_filename_fails() {
+        // テスト項目: 空のファイル名ではファイル添付を作成できない
+        // given (前提条件):
+        let filename = "".to_string();
+
+        // when (操作):
+        let result = FileAttachment::new(filename, "image/png".to_string(), 1024);
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap_err(), ValueObjectError::FileNameEmpty);
+    }
+
+    #[test]
+    fn test_file_attachment_new_path_separator_fails() {
+        // テスト項目: パス区切り文字を含むファイル名ではファイル添付を作成できない
+        // given (前提条件):
+        let filename = "../etc/passwd".to_string();
+
+        // when (操作):
+        let result = FileAttachment::new(filename.clone(), "text/plain".to_string(), 10);
+
+        // then (期待する結果):
+        assert_eq!(
+            result.unwrap_err(),
+            ValueObjectError::FileNameInvalid(filename)
+        );
+    }
 }
@@ -0,0 +1,128 @@
+//! デッドレターの JSON Lines ファイルへの記録
+//!
+//! 配信できなかったメッセージを 1 件 1 行の JSON としてファイルに追記します。
+//! ファイルが存在しない場合は作成します。
+
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use crate::domain::{DeadLetter, DeadLetterSink};
+
+/// デッドレターファイルの 1 行
+#[derive(Debug, Serialize, Deserialize)]
+struct DeadLetterRecord {
+    client_id: String,
+    payload: String,
+    reason: String,
+    timestamp: i64,
+}
+
+impl From<&DeadLetter> for DeadLetterRecord {
+    fn from(letter: &DeadLetter) -> Self {
+        Self {
+            client_id: letter.client_id.as_str().to_string(),
+            payload: letter.payload.clone(),
+            reason: letter.reason.clone(),
+            timestamp: letter.timestamp.value(),
+        }
+    }
+}
+
+/// デッドレターを JSON Lines ファイルに追記する DeadLetterSink
+pub struct JsonlDeadLetterSink {
+    /// デッドレターファイルのパス
+    path: PathBuf,
+    /// 並行する追記で行が混ざらないようにするためのロック
+    write_lock: Mutex<()>,
+}
+
+impl JsonlDeadLetterSink {
+    /// 新しい JsonlDeadLetterSink を作成
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// デッドレターファイルのパスを取得
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    async fn append(&self, line: &str) -> std::io::Result<()> {
+        let _guard = self.write_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        file.flush().await
+    }
+}
+
+#[async_trait]
+impl DeadLetterSink for JsonlDeadLetterSink {
+    async fn record(&self, letter: DeadLetter) {
+        let line = match serde_json::to_string(&DeadLetterRecord::from(&letter)) {
+            Ok(line) => line,
+            Err(e) => {
+                tracing::error!("Failed to serialize dead letter: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = self.append(&line).await {
+            tracing::error!(
+                "Failed to write dead letter to {}: {}",
+                self.path.display(),
+                e
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{ClientId, Timestamp};
+
+    fn temp_dead_letter_path() -> PathBuf {
+        std::env::temp_dir().join(format!("engawa-dead-letter-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_record_appends_one_json_line_per_dead_letter() {
+        // テスト項目: デッドレターが 1 件 1 行の JSON としてファイルに追記される
+        // given (前提条件):
+        let sink = JsonlDeadLetterSink::new(temp_dead_letter_path());
+        let letter = |payload: &str| DeadLetter {
+            client_id: ClientId::new("bob".to_string()).unwrap(),
+            payload: payload.to_string(),
+            reason: "channel closed".to_string(),
+            timestamp: Timestamp::new(1000),
+        };
+
+        // when (操作):
+        sink.record(letter("first")).await;
+        sink.record(letter("second")).await;
+
+        // then (期待する結果):
+        let contents = tokio::fs::read_to_string(sink.path()).await.unwrap();
+        let records: Vec<DeadLetterRecord> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].client_id, "bob");
+        assert_eq!(records[0].payload, "first");
+        assert_eq!(records[0].reason, "channel closed");
+        assert_eq!(records[0].timestamp, 1000);
+        assert_eq!(records[1].payload, "second");
+
+        tokio::fs::remove_file(sink.path()).await.unwrap();
+    }
+}
@@ -1,32 +1,96 @@
 //! Conversion logic between DTOs and domain entities.
+//!
+//! Timestamps are milliseconds in the domain. On the wire they are milliseconds by default,
+//! or seconds with [`TimestampPrecision::Seconds`] for integrations that expect
+//! second-precision Unix timestamps.
 
 use crate::domain::{
     entity,
-    value_object::{ClientId, MessageContent, Timestamp},
+    value_object::{ClientId, MAX_CONTENT_LEN_CEILING, MessageContent, PresenceStatus, Timestamp},
 };
 use crate::infrastructure::dto::websocket as dto;
 
+// ========================================
+// Timestamp precision
+// ========================================
+
+/// Unit of the Unix timestamps in WebSocket frames
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimestampPrecision {
+    /// Seconds since epoch (sub-second precision is truncated)
+    Seconds,
+    /// Milliseconds since epoch (default, same as the domain)
+    #[default]
+    Millis,
+}
+
+impl TimestampPrecision {
+    /// Convert a domain timestamp to its value on the wire
+    pub fn to_wire(self, timestamp: Timestamp) -> i64 {
+        match self {
+            Self::Seconds => timestamp.value().div_euclid(1000),
+            Self::Millis => timestamp.value(),
+        }
+    }
+
+    /// Convert a timestamp received on the wire to a domain timestamp
+    pub fn from_wire(self, value: i64) -> Timestamp {
+        match self {
+            Self::Seconds => Timestamp::new(value.saturating_mul(1000)),
+            Self::Millis => Timestamp::new(value),
+        }
+    }
+}
+
 // ========================================
 // DTO → Domain Entity
 // ========================================
 
+/// Convert a chat message DTO whose timestamp is in `precision`
+pub fn chat_message_from_dto(
+    dto: dto::ChatMessage,
+    precision: TimestampPrecision,
+) -> entity::ChatMessage {
+    // ID はサーバーが採番する（クライアントからの指定は受け付けない）
+    // 内容は受信時に種類ごとの上限で検証済みのため、ここでは全体の上限のみ確認する
+    entity::ChatMessage::new(
+        ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
+        MessageContent::with_max_len(dto.content, MAX_CONTENT_LEN_CEILING)
+            .expect("MessageContent should be valid in DTO"),
+        precision.from_wire(dto.timestamp),
+    )
+    .with_expires_at(
+        dto.expires_at
+            .map(|expires_at| precision.from_wire(expires_at)),
+    )
+}
+
+/// Convert a participant DTO whose timestamp is in `precision`
+pub fn participant_from_dto(
+    dto: dto::ParticipantInfo,
+    precision: TimestampPrecision,
+) -> entity::Participant {
+    let connected_at = precision.from_wire(dto.connected_at);
+    entity::Participant {
+        id: ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
+        connected_at,
+        status: PresenceStatus::try_from(dto.status.as_str()).unwrap_or_default(),
+        last_active: connected_at,
+        connections: 1,
+        muted: false,
+        last_sent: None,
+    }
+}
+
 impl From<dto::ChatMessage> for entity::ChatMessage {
     fn from(dto: dto::ChatMessage) -> Self {
-        Self {
-            from: ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
-            content: MessageContent::new(dto.content)
-                .expect("MessageContent should be valid in DTO"),
-            timestamp: Timestamp::new(dto.timestamp),
-        }
+        chat_message_from_dto(dto, TimestampPrecision::Millis)
     }
 }
 
 impl From<dto::ParticipantInfo> for entity::Participant {
     fn from(dto: dto::ParticipantInfo) -> Self {
-        Self {
-            id: ClientId::new(dto.client_id).expect("ClientId should be valid in DTO"),
-            connected_at: Timestamp::new(dto.connected_at),
-        }
+        participant_from_dto(dto, TimestampPrecision::Millis)
     }
 }
 
@@ -34,23 +98,48 @@ impl From<dto::ParticipantInfo> for entity::Participant {
 // Domain Entity → DTO
 // ========================================
 
+/// Convert a chat message to a DTO with its timestamp in `precision`
+pub fn chat_message_to_dto(
+    model: entity::ChatMessage,
+    precision: TimestampPrecision,
+) -> dto::ChatMessage {
+    dto::ChatMessage {
+        r#type: dto::MessageType::Chat,
+        message_id: Some(model.id.as_str().to_string()),
+        client_id: model.from.into_string(),
+        content: model.content.into_string(),
+        timestamp: precision.to_wire(model.timestamp),
+        idempotency_key: None,
+        links: Vec::new(),
+        ttl_secs: None,
+        expires_at: model
+            .expires_at
+            .map(|expires_at| precision.to_wire(expires_at)),
+    }
+}
+
+/// Convert a participant to a DTO with its timestamp in `precision`
+pub fn participant_to_dto(
+    model: entity::Participant,
+    precision: TimestampPrecision,
+) -> dto::ParticipantInfo {
+    dto::ParticipantInfo {
+        client_id: model.id.into_string(),
+        connected_at: precision.to_wire(model.connected_at),
+        status: model.status.as_str().to_string(),
+        idle_ms: 0,
+    }
+}
+
 impl From<entity::ChatMessage> for dto::ChatMessage {
     fn from(model: entity::ChatMessage) -> Self {
-        Self {
-            r#type: dto::MessageType::Chat,
-            client_id: model.from.into_string(),
-            content: model.content.into_string(),
-            timestamp: model.timestamp.value(),
-        }
+        chat_message_to_dto(model, TimestampPrecision::Millis)
     }
 }
 
 impl From<entity::Participant> for dto::ParticipantInfo {
     fn from(model: entity::Participant) -> Self {
-        Self {
-            client_id: model.id.into_string(),
-            connected_at: model.connected_at.value(),
-        }
+        participant_to_dto(model, TimestampPrecision::Millis)
     }
 }
 
@@ -64,9 +153,14 @@ mod tests {
         // given (前提条件):
         let dto_msg = dto::ChatMessage {
             r#type: dto::MessageType::Chat,
+            message_id: None,
             client_id: "alice".to_string(),
             content: "Hello!".to_string(),
             timestamp: 1000,
+            idempotency_key: None,
+            links: Vec::new(),
+            ttl_secs: None,
+            expires_at: None,
         };
 
         // when (操作):
@@ -85,16 +179,18 @@ mod tests {
     fn test_domain_chat_message_to_dto() {
         // テスト項目: ドメインエンティティの ChatMessage が DTO に変換される
         // given (前提条件):
-        let domain_msg = entity::ChatMessage {
-            from: ClientId::new("bob".to_string()).unwrap(),
-            content: MessageContent::new("Hi!".to_string()).unwrap(),
-            timestamp: Timestamp::new(2000),
-        };
+        let domain_msg = entity::ChatMessage::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            MessageContent::new("Hi!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        );
+        let message_id = domain_msg.id.as_str().to_string();
 
         // when (操作):
         let dto_msg: dto::ChatMessage = domain_msg.into();
 
         // then (期待する結果):
+        assert_eq!(dto_msg.message_id, Some(message_id));
         assert_eq!(dto_msg.client_id, "bob");
         assert_eq!(dto_msg.content, "Hi!");
         assert_eq!(dto_msg.timestamp, 2000);
@@ -108,6 +204,8 @@ mod tests {
         let dto_participant = dto::ParticipantInfo {
             client_id: "alice".to_string(),
             connected_at: 1000,
+            status: "away".to_string(),
+            idle_ms: 0,
         };
 
         // when (操作):
@@ -119,6 +217,7 @@ mod tests {
             ClientId::new("alice".to_string()).unwrap()
         );
         assert_eq!(domain_participant.connected_at, Timestamp::new(1000));
+        assert_eq!(domain_participant.status, PresenceStatus::Away);
     }
 
     #[test]
@@ -128,6 +227,11 @@ mod tests {
         let domain_participant = entity::Participant {
             id: ClientId::new("bob".to_string()).unwrap(),
             connected_at: Timestamp::new(2000),
+            status: PresenceStatus::Dnd,
+            last_active: Timestamp::new(2000),
+            connections: 1,
+            muted: false,
+            last_sent: None,
         };
 
         // when (操作):
@@ -136,5 +240,75 @@ mod tests {
         // then (期待する結果):
         assert_eq!(dto_participant.client_id, "bob");
         assert_eq!(dto_participant.connected_at, 2000);
+        assert_eq!(dto_participant.status, "dnd");
+    }
+
+    #[test]
+    fn test_chat_message_round_trips_in_millis() {
+        // テスト項目: ミリ秒精度では、タイムスタンプがそのまま送られ、ドメインに戻しても一致する
+        // given (前提条件):
+        let domain_msg = entity::ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1_700_000_123_456),
+        );
+
+        // when (操作):
+        let dto_msg = chat_message_to_dto(domain_msg, TimestampPrecision::Millis);
+        let wire_timestamp = dto_msg.timestamp;
+        let round_tripped = chat_message_from_dto(dto_msg, TimestampPrecision::Millis);
+
+        // then (期待する結果):
+        assert_eq!(wire_timestamp, 1_700_000_123_456);
+        assert_eq!(round_tripped.timestamp, Timestamp::new(1_700_000_123_456));
+    }
+
+    #[test]
+    fn test_chat_message_round_trips_in_seconds() {
+        // テスト項目: 秒精度では、タイムスタンプが秒で送られ（ミリ秒は切り捨て）、
+        //            ドメインに戻すと秒の境界のミリ秒になる
+        // given (前提条件):
+        let domain_msg = entity::ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1_700_000_123_456),
+        );
+
+        // when (操作):
+        let dto_msg = chat_message_to_dto(domain_msg, TimestampPrecision::Seconds);
+        let wire_timestamp = dto_msg.timestamp;
+        let round_tripped = chat_message_from_dto(dto_msg, TimestampPrecision::Seconds);
+
+        // then (期待する結果):
+        assert_eq!(wire_timestamp, 1_700_000_123);
+        assert_eq!(round_tripped.timestamp, Timestamp::new(1_700_000_123_000));
+        assert_eq!(
+            TimestampPrecision::Seconds.to_wire(round_tripped.timestamp),
+            wire_timestamp
+        );
+    }
+
+    #[test]
+    fn test_participant_round_trips_in_both_precisions() {
+        // テスト項目: 参加者の接続時刻も、どちらの精度でも DTO を経由して元の値に戻る
+        // given (前提条件): 秒の境界の接続時刻
+        let participant = entity::Participant::new(
+            ClientId::new("bob".to_string()).unwrap(),
+            Timestamp::new(1_700_000_000_000),
+        );
+
+        for (precision, expected_wire) in [
+            (TimestampPrecision::Millis, 1_700_000_000_000),
+            (TimestampPrecision::Seconds, 1_700_000_000),
+        ] {
+            // when (操作):
+            let dto_participant = participant_to_dto(participant.clone(), precision);
+            let wire_timestamp = dto_participant.connected_at;
+            let round_tripped = participant_from_dto(dto_participant, precision);
+
+            // then (期待する結果):
+            assert_eq!(wire_timestamp, expected_wire, "{:?}", precision);
+            assert_eq!(round_tripped.connected_at, participant.connected_at);
+        }
     }
 }
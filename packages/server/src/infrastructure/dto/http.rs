@@ -2,12 +2,27 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Error envelope returned by the HTTP API on failure
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponseDto {
+    pub error: ErrorBodyDto,
+}
+
+/// Error details inside the error envelope
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBodyDto {
+    pub code: String,    // e.g. "room-not-found"
+    pub message: String, // human-readable description
+}
+
 /// Room summary for list endpoint
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomSummaryDto {
     pub id: String,
     pub participants: Vec<String>,
     pub created_at: String, // ISO 8601
+    /// Seconds elapsed since the room was created
+    pub age_secs: u64,
 }
 
 /// Room detail for detail endpoint
@@ -24,3 +39,66 @@ pub struct ParticipantDetailDto {
     pub client_id: String,
     pub connected_at: String, // ISO 8601
 }
+
+/// Result of the purge messages endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PurgeMessagesDto {
+    /// Number of messages removed from the history
+    pub purged: usize,
+}
+
+/// Metrics for metrics endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsDto {
+    pub rejected_connections: RejectedConnectionsDto,
+    pub receive_errors: ReceiveErrorsDto,
+}
+
+/// Rejected connection counters by reason
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectedConnectionsDto {
+    pub duplicate_id: u64,
+    pub capacity: u64,
+    pub invalid_id: u64,
+    /// Connections rejected for an invalid initial presence status
+    #[serde(default)]
+    pub invalid_status: u64,
+    pub auth_failure: u64,
+    /// Connections rejected because the access policy denies their source address
+    #[serde(default)]
+    pub access_denied: u64,
+    /// Connections rejected because their source IP address had too many connections
+    #[serde(default)]
+    pub per_ip_limit: u64,
+    /// Connections rejected by an internal server error
+    #[serde(default)]
+    pub internal_error: u64,
+}
+
+/// Counters of errors while receiving WebSocket frames, by kind
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReceiveErrorsDto {
+    /// Connections closed for a WebSocket protocol violation
+    pub protocol_violation: u64,
+    /// Connections closed for a message above the size limit
+    pub message_too_large: u64,
+    /// Connections that dropped without a close handshake
+    pub connection_reset: u64,
+    pub other: u64,
+}
+
+/// Online state for the participant online endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantOnlineDto {
+    pub online: bool,
+}
+
+/// Participant detail for participant endpoint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantStatusDto {
+    pub client_id: String,
+    pub connected_at: String, // ISO 8601
+    pub presence: String,     // "online"
+    pub status: String,       // "active" | "away" | "dnd"
+    pub last_active: String,  // ISO 8601
+}
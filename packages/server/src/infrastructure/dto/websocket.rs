@@ -2,6 +2,18 @@
 
 use serde::{Deserialize, Serialize};
 
+/// WebSocket close code sent to a connection that was replaced by a newer connection
+/// with the same client_id (duplicate policy `takeover`)
+///
+/// A client receiving it should not reconnect, or the two connections would keep
+/// replacing each other.
+pub const CLOSE_CODE_REPLACED: u16 = 4000;
+
+/// WebSocket close code sent to a connection whose participant was kicked by an admin
+///
+/// Like [`CLOSE_CODE_REPLACED`], a client receiving it should not reconnect.
+pub const CLOSE_CODE_KICKED: u16 = 4001;
+
 /// Message type enum
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -10,21 +22,99 @@ pub enum MessageType {
     ParticipantJoined,
     ParticipantLeft,
     Chat,
+    AppPing,
+    AppPong,
+    Heartbeat,
+    ParticipantCount,
+    File,
+    Error,
+    RosterRequest,
+    Roster,
+    RosterDelta,
+    Ack,
+    System,
+    Ready,
+    Reaction,
+    ReactionUpdated,
+    HistoryCleared,
+    MessageDeleted,
 }
 
 /// Participant information including client_id and connection timestamp
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ParticipantInfo {
     pub client_id: String,
-    /// Unix timestamp (milliseconds since epoch) in JST
+    /// Unix timestamp in JST, in milliseconds since epoch (or seconds, see
+    /// [`TimestampPrecision`](super::conversion::TimestampPrecision))
     pub connected_at: i64,
+    /// Presence status (`active`, `away` or `dnd`)
+    #[serde(default = "default_presence_status")]
+    pub status: String,
+    /// Milliseconds since the participant's latest activity
+    #[serde(default)]
+    pub idle_ms: u64,
+}
+
+/// Presence status assumed when a frame does not carry one
+fn default_presence_status() -> String {
+    "active".to_string()
 }
 
 /// Room connected participants message sent when a client connects (initial)
+///
+/// 大人数のルームでは初回のペイロードを抑えるため、参加者リストは先頭の一部に
+/// 切り詰められることがあります（`truncated` が `true`、`total` は全参加者数）。
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RoomConnectedMessage {
+    pub r#type: MessageType,
+    /// ID of the room the client joined
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub room_id: Option<String>,
+    pub participants: Vec<ParticipantInfo>,
+    /// Total number of participants in the room (including those not listed)
+    #[serde(default)]
+    pub total: usize,
+    /// Whether `participants` lists only part of the room
+    #[serde(default)]
+    pub truncated: bool,
+    /// Maximum number of messages the room's history holds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_capacity: Option<usize>,
+    /// Number of messages currently in the room's history
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_count: Option<usize>,
+}
+
+/// Request for the current participant list, sent by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterRequestMessage {
+    pub r#type: MessageType,
+    /// Roster version of the last `roster` or `roster-delta` frame received; when set,
+    /// the server answers with a `roster-delta` if it can
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since_version: Option<u64>,
+}
+
+/// Current participant list sent in response to a roster request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterMessage {
     pub r#type: MessageType,
     pub participants: Vec<ParticipantInfo>,
+    /// Roster version of the participant list, incremented on every join and leave
+    #[serde(default)]
+    pub version: u64,
+}
+
+/// Changes to the participant list since the version given in a roster request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RosterDeltaMessage {
+    pub r#type: MessageType,
+    /// Participants that joined since the requested version
+    pub added: Vec<ParticipantInfo>,
+    /// client_ids of the participants that left since the requested version
+    pub removed: Vec<String>,
+    /// Roster version after applying the changes
+    pub version: u64,
 }
 
 /// Participant joined notification
@@ -32,7 +122,15 @@ pub struct RoomConnectedMessage {
 pub struct ParticipantJoinedMessage {
     pub r#type: MessageType,
     pub client_id: String,
+    /// Unix timestamp in JST, in milliseconds since epoch (or seconds, see
+    /// [`TimestampPrecision`](super::conversion::TimestampPrecision))
     pub connected_at: i64,
+    /// Presence status (`active`, `away` or `dnd`)
+    #[serde(default = "default_presence_status")]
+    pub status: String,
+    /// Number of participants in the room, including the one who joined
+    #[serde(default)]
+    pub participant_count: usize,
 }
 
 /// Participant left notification
@@ -40,14 +138,169 @@ pub struct ParticipantJoinedMessage {
 pub struct ParticipantLeftMessage {
     pub r#type: MessageType,
     pub client_id: String,
+    /// Unix timestamp in JST, in milliseconds since epoch (or seconds, see
+    /// [`TimestampPrecision`](super::conversion::TimestampPrecision))
     pub disconnected_at: i64,
+    /// Number of participants remaining in the room
+    #[serde(default)]
+    pub participant_count: usize,
+}
+
+/// Participant count update
+///
+/// 大人数の Room で join/leave の個別通知が抑制されている場合に、代わりに送信される
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParticipantCountMessage {
+    pub r#type: MessageType,
+    pub count: usize,
 }
 
 /// Chat message sent and received between clients
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub r#type: MessageType,
+    /// ID assigned by the server, used to react to the message (absent in frames sent by clients)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
     pub client_id: String,
     pub content: String,
+    /// Unix timestamp in JST, in milliseconds since epoch (or seconds, see
+    /// [`TimestampPrecision`](super::conversion::TimestampPrecision))
+    pub timestamp: i64,
+    /// Key chosen by the sender to deduplicate re-sent messages (optional)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// URLs detected in the content by the server (omitted when there are none)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+    /// Time-to-live in seconds requested by the sender to make the message ephemeral
+    /// (clamped by the server; not forwarded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ttl_secs: Option<u64>,
+    /// Time at which the server removes an ephemeral message from the history, in the
+    /// unit of `timestamp` (absent in frames sent by clients)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// Acknowledgement sent only to the sender once a chat message has been accepted
+///
+/// 再送されたメッセージ（同じ idempotency_key）に対しても送信されます。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AckMessage {
+    pub r#type: MessageType,
+    pub idempotency_key: String,
+}
+
+/// Application-level ping sent by a client to measure round-trip time
+///
+/// WebSocket プロトコルの Ping/Pong とは独立したアプリケーションレベルのフレーム
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppPingMessage {
+    pub r#type: MessageType,
+    /// Arbitrary value chosen by the client, echoed back in the pong
+    pub nonce: u64,
+}
+
+/// Application-level pong sent back to the client that sent the ping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppPongMessage {
+    pub r#type: MessageType,
+    /// The nonce received in the corresponding ping
+    pub nonce: u64,
+}
+
+/// Presence heartbeat sent periodically by a client
+///
+/// サーバーは参加者の最終アクティブ時刻を更新するだけで、ブロードキャストも応答もしません。
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeartbeatMessage {
+    pub r#type: MessageType,
+}
+
+/// File shared between clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileMessage {
+    pub r#type: MessageType,
+    pub client_id: String,
+    pub filename: String,
+    pub mime: String,
+    /// File data encoded in standard base64
+    pub data: String,
+    /// Unix timestamp in JST, in milliseconds since epoch (or seconds, see
+    /// [`TimestampPrecision`](super::conversion::TimestampPrecision))
     pub timestamp: i64,
 }
+
+/// Message from the server itself (e.g. the message of the day sent on connect)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemMessage {
+    pub r#type: MessageType,
+    /// Text set by the operator
+    pub content: String,
+}
+
+/// Sent after all initial frames (participant list, message of the day), marking the end
+/// of the handshake
+///
+/// Frames received after it are live room events; clients enable input once it arrives.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadyMessage {
+    pub r#type: MessageType,
+}
+
+/// Sent when a moderator purged the message history of the room
+///
+/// Clients should clear the messages they display; they are no longer in the history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryClearedMessage {
+    pub r#type: MessageType,
+    /// Number of messages removed from the history
+    pub purged: usize,
+}
+
+/// Notification that a message was removed from the history (e.g. an expired ephemeral message)
+///
+/// Clients should stop displaying the message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDeletedMessage {
+    pub r#type: MessageType,
+    /// ID of the removed message (`message_id` of the chat frame)
+    pub message_id: String,
+}
+
+/// Reaction to a chat message, sent by a client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionMessage {
+    pub r#type: MessageType,
+    /// ID of the message reacted to (`message_id` of the chat frame)
+    pub message_id: String,
+    pub emoji: String,
+    /// `add` or `remove`
+    pub action: String,
+}
+
+/// Updated reaction count, broadcast to all clients after a reaction was added or removed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionUpdatedMessage {
+    pub r#type: MessageType,
+    pub message_id: String,
+    pub emoji: String,
+    /// Number of participants who reacted to the message with `emoji`
+    pub count: usize,
+    /// Participant who added or removed the reaction
+    pub by: String,
+}
+
+/// Error notification sent only to the client whose request was rejected
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorMessage {
+    pub r#type: MessageType,
+    /// Machine-readable error code (e.g. "file-too-large")
+    pub code: String,
+    /// Human-readable error description
+    pub message: String,
+    /// Milliseconds to wait before retrying (e.g. for "slow-mode")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
@@ -2,30 +2,70 @@
 //!
 //! ## 責務
 //!
-//! - WebSocket の `UnboundedSender` を管理
+//! - WebSocket の `Sender` を管理
 //! - クライアントへのメッセージ送信（push_to, broadcast）
 //!
 //! ## 設計ノート
 //!
 //! WebSocket の生成は UI 層（`src/ui/handler/websocket.rs`）で行われます。
-//! この実装は生成された `UnboundedSender` を受け取り、メッセージ送信に使用します。
+//! この実装は生成された `Sender` を受け取り、メッセージ送信に使用します。
 //!
 //! これにより、「WebSocket の生成」と「メッセージの送信」が分離されます：
 //! - UI 層: WebSocket 接続の受付、sender の生成
 //! - Infrastructure 層: sender の管理、メッセージ送信
-
-use std::{collections::HashMap, sync::Arc};
+//!
+//! ## 送信タイムアウト
+//!
+//! チャネルは容量付きのため、受信が滞ったクライアントへの送信は待たされます。
+//! 1 件の送信ごとに `send_timeout` を上限とし、超過した場合は配信失敗として扱い、
+//! そのクライアントを「滞留クライアント」として記録します（切断候補）。
+//! 滞留クライアントへの以降の送信は待たずに試み、チャネルに空きがなければ即座に
+//! 配信失敗とします（空きがあれば配信し、滞留クライアントの記録から外します）。
+//! そのため、タイムアウトを待つのは滞留し始めた最初の送信だけです。
+//! ブロードキャストは各クライアントへ並行に送信するため、滞留クライアントが
+//! 他のクライアントへの配信を妨げることはありません。
+//!
+//! ## デッドレター
+//!
+//! 送信に最終的に失敗したメッセージ（チャネルが閉じている、送信がタイムアウトした）は、
+//! デッドレターとして数え、[`DeadLetterSink`] が設定されていればそこに記録します。
+//! 送信先がすでに登録解除されている場合は、配信対象ではなくなったものとして記録しません。
+
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
 use async_trait::async_trait;
-use tokio::sync::Mutex;
+use futures_util::future::join_all;
+use tokio::sync::{Mutex, mpsc::error::TrySendError};
 
-use crate::domain::{ClientId, MessagePushError, MessagePusher, PusherChannel};
+use engawa_shared::time::get_jst_timestamp;
+
+use crate::domain::{
+    BroadcastReport, ClientId, DeadLetter, DeadLetterSink, MessagePushError, MessagePusher,
+    PusherChannel, Timestamp,
+};
+
+/// 1 件の送信に待つ時間のデフォルト値
+pub const DEFAULT_SEND_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// client_id ごとの WebSocket sender のマップ（接続ごとに 1 つ）
+pub type ClientChannels = HashMap<String, Vec<PusherChannel>>;
 
 /// WebSocket を使った MessagePusher 実装
 ///
 /// ## フィールド
 ///
-/// - `clients`: 接続中のクライアントと対応する WebSocket sender のマップ
+/// - `clients`: 接続中のクライアントと対応する WebSocket sender のマップ（接続ごとに 1 つ）
+/// - `send_timeout`: 1 件の送信に待つ時間の上限
+/// - `stalled_clients`: 送信がタイムアウトしたクライアント
+/// - `dead_letter_sink`: 配信できなかったメッセージの記録先（任意）
+/// - `dead_letters`: 配信できなかったメッセージの件数
 ///
 /// ## 使用例
 ///
@@ -40,13 +80,23 @@ pub struct WebSocketMessagePusher {
     /// 接続中のクライアントの WebSocket sender
     ///
     /// Key: client_id (String)
-    /// Value: PusherChannel
-    clients: Arc<Mutex<HashMap<String, PusherChannel>>>,
+    /// Value: 接続ごとの PusherChannel（複数接続モードでは複数）
+    clients: Arc<Mutex<ClientChannels>>,
+    /// 1 件の送信に待つ時間の上限
+    send_timeout: Duration,
+    /// 送信がタイムアウトしたクライアント（切断候補）
+    stalled_clients: Mutex<HashSet<String>>,
+    /// 配信できなかったメッセージの記録先
+    dead_letter_sink: Option<Arc<dyn DeadLetterSink>>,
+    /// 配信できなかったメッセージの件数
+    dead_letters: AtomicU64,
 }
 
 impl WebSocketMessagePusher {
     /// 新しい WebSocketMessagePusher を作成
     ///
+    /// 送信タイムアウトには [`DEFAULT_SEND_TIMEOUT`] を使用します。
+    ///
     /// # 引数
     ///
     /// - `clients`: 接続中のクライアントの sender マップ
@@ -55,8 +105,111 @@ impl WebSocketMessagePusher {
     ///
     /// `clients` は Repository と共有される可能性があります。
     /// これは一時的な設計であり、将来的には MessagePusher が独立して管理します。
-    pub fn new(clients: Arc<Mutex<HashMap<String, PusherChannel>>>) -> Self {
-        Self { clients }
+    pub fn new(clients: Arc<Mutex<ClientChannels>>) -> Self {
+        Self::with_send_timeout(clients, DEFAULT_SEND_TIMEOUT)
+    }
+
+    /// 送信タイムアウトを指定して WebSocketMessagePusher を作成
+    ///
+    /// # 引数
+    ///
+    /// - `clients`: 接続中のクライアントの sender マップ
+    /// - `send_timeout`: 1 件の送信に待つ時間の上限
+    pub fn with_send_timeout(clients: Arc<Mutex<ClientChannels>>, send_timeout: Duration) -> Self {
+        Self {
+            clients,
+            send_timeout,
+            stalled_clients: Mutex::new(HashSet::new()),
+            dead_letter_sink: None,
+            dead_letters: AtomicU64::new(0),
+        }
+    }
+
+    /// 配信できなかったメッセージの記録先を設定
+    pub fn with_dead_letter_sink(mut self, sink: Arc<dyn DeadLetterSink>) -> Self {
+        self.dead_letter_sink = Some(sink);
+        self
+    }
+
+    /// これまでに配信できなかったメッセージの件数を取得
+    ///
+    /// 複数接続の場合は、届かなかった接続ごとに数えます。
+    pub fn dead_letter_count(&self) -> u64 {
+        self.dead_letters.load(Ordering::Relaxed)
+    }
+
+    /// 送信がタイムアウトしたクライアントの一覧を取得
+    ///
+    /// 返されたクライアントは切断（pruning）の候補です。
+    /// クライアントの登録解除時に一覧から取り除かれます。
+    pub async fn stalled_clients(&self) -> Vec<ClientId> {
+        let stalled = self.stalled_clients.lock().await;
+        stalled
+            .iter()
+            .filter_map(|id| ClientId::new(id.clone()).ok())
+            .collect()
+    }
+
+    /// タイムアウト付きで 1 件送信
+    ///
+    /// タイムアウトした場合はクライアントを滞留クライアントとして記録します。
+    /// 滞留クライアントには待たずに送信を試み、チャネルに空きがあれば記録から外します。
+    /// 送信に失敗した場合はデッドレターとして記録します。
+    async fn send_with_timeout(
+        &self,
+        client_id: &str,
+        sender: &PusherChannel,
+        content: &str,
+    ) -> Result<(), MessagePushError> {
+        if self.stalled_clients.lock().await.contains(client_id) {
+            let error = match sender.try_send(content.to_string()) {
+                Ok(()) => {
+                    self.stalled_clients.lock().await.remove(client_id);
+                    return Ok(());
+                }
+                Err(TrySendError::Full(_)) => {
+                    MessagePushError::DeliveryTimeout(client_id.to_string())
+                }
+                Err(TrySendError::Closed(_)) => {
+                    MessagePushError::PushFailed("channel closed".to_string())
+                }
+            };
+            self.record_dead_letter(client_id, content, &error).await;
+            return Err(error);
+        }
+
+        let error =
+            match tokio::time::timeout(self.send_timeout, sender.send(content.to_string())).await {
+                Ok(Ok(())) => return Ok(()),
+                Ok(Err(e)) => MessagePushError::PushFailed(e.to_string()),
+                Err(_) => {
+                    self.stalled_clients
+                        .lock()
+                        .await
+                        .insert(client_id.to_string());
+                    MessagePushError::DeliveryTimeout(client_id.to_string())
+                }
+            };
+        self.record_dead_letter(client_id, content, &error).await;
+        Err(error)
+    }
+
+    /// 配信できなかったメッセージを数え、記録先があれば記録
+    async fn record_dead_letter(&self, client_id: &str, content: &str, error: &MessagePushError) {
+        self.dead_letters.fetch_add(1, Ordering::Relaxed);
+        let Some(sink) = &self.dead_letter_sink else {
+            return;
+        };
+        let Ok(client_id) = ClientId::new(client_id.to_string()) else {
+            return;
+        };
+        sink.record(DeadLetter {
+            client_id,
+            payload: content.to_string(),
+            reason: error.to_string(),
+            timestamp: Timestamp::new(get_jst_timestamp()),
+        })
+        .await;
     }
 }
 
@@ -64,7 +217,10 @@ impl WebSocketMessagePusher {
 impl MessagePusher for WebSocketMessagePusher {
     async fn register_client(&self, client_id: ClientId, sender: PusherChannel) {
         let mut clients = self.clients.lock().await;
-        clients.insert(client_id.as_str().to_string(), sender);
+        clients
+            .entry(client_id.as_str().to_string())
+            .or_default()
+            .push(sender);
         tracing::debug!(
             "Client '{}' registered to MessagePusher",
             client_id.as_str()
@@ -74,56 +230,120 @@ impl MessagePusher for WebSocketMessagePusher {
     async fn unregister_client(&self, client_id: &ClientId) {
         let mut clients = self.clients.lock().await;
         clients.remove(client_id.as_str());
+        self.stalled_clients.lock().await.remove(client_id.as_str());
         tracing::debug!(
             "Client '{}' unregistered from MessagePusher",
             client_id.as_str()
         );
     }
 
+    async fn replace_client(&self, client_id: ClientId, sender: PusherChannel) {
+        // 古い接続の削除と新しい接続の追加を同じロックの中で行う
+        let mut clients = self.clients.lock().await;
+        clients.insert(client_id.as_str().to_string(), vec![sender]);
+        self.stalled_clients.lock().await.remove(client_id.as_str());
+        tracing::debug!(
+            "Client '{}' replaced its connection in MessagePusher",
+            client_id.as_str()
+        );
+    }
+
+    async fn unregister_connection(&self, client_id: &ClientId, sender: &PusherChannel) {
+        let mut clients = self.clients.lock().await;
+        let Some(senders) = clients.get_mut(client_id.as_str()) else {
+            return;
+        };
+        senders.retain(|s| !s.same_channel(sender));
+        let remaining = senders.len();
+        if remaining == 0 {
+            clients.remove(client_id.as_str());
+            self.stalled_clients.lock().await.remove(client_id.as_str());
+        }
+        tracing::debug!(
+            "Connection of client '{}' unregistered from MessagePusher ({} remaining)",
+            client_id.as_str(),
+            remaining
+        );
+    }
+
     async fn push_to(&self, client_id: &ClientId, content: &str) -> Result<(), MessagePushError> {
-        let clients = self.clients.lock().await;
-
-        if let Some(sender) = clients.get(client_id.as_str()) {
-            sender
-                .send(content.to_string())
-                .map_err(|e| MessagePushError::PushFailed(e.to_string()))?;
-            tracing::debug!("Pushed message to client '{}'", client_id.as_str());
-            Ok(())
-        } else {
-            Err(MessagePushError::ClientNotFound(
+        // 送信待ちの間に他の送信を妨げないよう、sender を複製してロックを解放する
+        let senders = self.clients.lock().await.get(client_id.as_str()).cloned();
+
+        let Some(senders) = senders else {
+            return Err(MessagePushError::ClientNotFound(
                 client_id.as_str().to_string(),
-            ))
-        }
+            ));
+        };
+
+        // すべての接続に送信し、失敗があれば最初のエラーを返す
+        let results = join_all(
+            senders
+                .iter()
+                .map(|sender| self.send_with_timeout(client_id.as_str(), sender, content)),
+        )
+        .await;
+        results.into_iter().collect::<Result<Vec<()>, _>>()?;
+        tracing::debug!("Pushed message to client '{}'", client_id.as_str());
+        Ok(())
     }
 
     async fn broadcast(
         &self,
         targets: Vec<ClientId>,
         content: &str,
-    ) -> Result<(), MessagePushError> {
-        let clients = self.clients.lock().await;
-
-        for target in targets {
-            if let Some(sender) = clients.get(target.as_str()) {
-                // ブロードキャストでは一部の送信失敗を許容
-                if let Err(e) = sender.send(content.to_string()) {
+    ) -> Result<BroadcastReport, MessagePushError> {
+        // 送信待ちの間に他の送信を妨げないよう、sender を複製してロックを解放する
+        let senders: Vec<(ClientId, Option<Vec<PusherChannel>>)> = {
+            let clients = self.clients.lock().await;
+            targets
+                .into_iter()
+                .map(|target| {
+                    let sender = clients.get(target.as_str()).cloned();
+                    (target, sender)
+                })
+                .collect()
+        };
+
+        // 滞留クライアントが他のクライアントへの配信を妨げないよう、並行に送信
+        let sends = senders.iter().map(|(target, senders)| async move {
+            let Some(senders) = senders else {
+                tracing::warn!(
+                    "Client '{}' not found during broadcast, skipping",
+                    target.as_str()
+                );
+                return (target, false);
+            };
+
+            // ブロードキャストでは一部の送信失敗を許容（複数接続の場合はすべての接続へ送信）
+            let mut delivered = true;
+            for sender in senders {
+                if let Err(e) = self
+                    .send_with_timeout(target.as_str(), sender, content)
+                    .await
+                {
                     tracing::warn!(
                         "Failed to push message to client '{}': {}",
                         target.as_str(),
                         e
                     );
+                    delivered = false;
                 } else {
                     tracing::debug!("Broadcasted message to client '{}'", target.as_str());
                 }
+            }
+            (target, delivered)
+        });
+
+        let mut report = BroadcastReport::default();
+        for (target, delivered) in join_all(sends).await {
+            if delivered {
+                report.delivered.push(target.clone());
             } else {
-                tracing::warn!(
-                    "Client '{}' not found during broadcast, skipping",
-                    target.as_str()
-                );
+                report.failed.push(target.clone());
             }
         }
-
-        Ok(())
+        Ok(report)
     }
 }
 
@@ -151,12 +371,12 @@ mod tests {
     // 2. push_to の失敗ケース（クライアントが存在しない）
     // 3. broadcast の成功ケース（複数クライアント）
     // 4. broadcast の部分失敗ケース（一部のクライアントが存在しない）
+    // 5. broadcast の送信タイムアウト（受信が滞留しているクライアント）と、滞留後の送信で待たないこと
+    // 6. 同じクライアントの複数接続への送信と、接続ごとの登録解除
+    // 7. 配信できなかったメッセージのデッドレターへの記録
     // ========================================
 
-    fn create_test_pusher() -> (
-        WebSocketMessagePusher,
-        Arc<Mutex<HashMap<String, PusherChannel>>>,
-    ) {
+    fn create_test_pusher() -> (WebSocketMessagePusher, Arc<Mutex<ClientChannels>>) {
         let clients = Arc::new(Mutex::new(HashMap::new()));
         let pusher = WebSocketMessagePusher::new(clients.clone());
         (pusher, clients)
@@ -167,12 +387,12 @@ mod tests {
         // テスト項目: 特定のクライアントにメッセージを送信できる
         // given (前提条件):
         let (pusher, clients) = create_test_pusher();
-        let (tx, mut rx) = mpsc::unbounded_channel();
+        let (tx, mut rx) = mpsc::channel(16);
         let client_id = ClientId::new("alice".to_string()).unwrap();
 
         {
             let mut clients_lock = clients.lock().await;
-            clients_lock.insert(client_id.as_str().to_string(), tx);
+            clients_lock.insert(client_id.as_str().to_string(), vec![tx]);
         }
 
         // when (操作):
@@ -207,15 +427,15 @@ mod tests {
         // テスト項目: 複数のクライアントにメッセージをブロードキャストできる
         // given (前提条件):
         let (pusher, clients) = create_test_pusher();
-        let (tx1, mut rx1) = mpsc::unbounded_channel();
-        let (tx2, mut rx2) = mpsc::unbounded_channel();
+        let (tx1, mut rx1) = mpsc::channel(16);
+        let (tx2, mut rx2) = mpsc::channel(16);
         let alice = ClientId::new("alice".to_string()).unwrap();
         let bob = ClientId::new("bob".to_string()).unwrap();
 
         {
             let mut clients_lock = clients.lock().await;
-            clients_lock.insert(alice.as_str().to_string(), tx1);
-            clients_lock.insert(bob.as_str().to_string(), tx2);
+            clients_lock.insert(alice.as_str().to_string(), vec![tx1]);
+            clients_lock.insert(bob.as_str().to_string(), vec![tx2]);
         }
 
         // when (操作):
@@ -228,26 +448,63 @@ mod tests {
         assert_eq!(rx2.recv().await, Some("Broadcast message".to_string()));
     }
 
+    #[tokio::test]
+    async fn test_broadcast_except_skips_excluded_clients() {
+        // テスト項目: 除外したクライアントには送信されず、配信結果にも含まれない
+        // given (前提条件): alice, bob, carol が登録されている
+        let (pusher, clients) = create_test_pusher();
+        let (tx1, mut rx1) = mpsc::channel(16);
+        let (tx2, mut rx2) = mpsc::channel(16);
+        let (tx3, mut rx3) = mpsc::channel(16);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let carol = ClientId::new("carol".to_string()).unwrap();
+
+        {
+            let mut clients_lock = clients.lock().await;
+            clients_lock.insert(alice.as_str().to_string(), vec![tx1]);
+            clients_lock.insert(bob.as_str().to_string(), vec![tx2]);
+            clients_lock.insert(carol.as_str().to_string(), vec![tx3]);
+        }
+
+        // when (操作): 送信者 alice とミュート中の carol を除外してブロードキャストする
+        let targets = vec![alice.clone(), bob.clone(), carol.clone()];
+        let exclude = HashSet::from([alice, carol]);
+        let result = pusher
+            .broadcast_except(targets, &exclude, "Broadcast message")
+            .await;
+
+        // then (期待する結果):
+        let report = result.unwrap();
+        assert_eq!(report.delivered, vec![bob]);
+        assert!(report.failed.is_empty());
+        assert_eq!(rx2.recv().await, Some("Broadcast message".to_string()));
+        assert!(rx1.try_recv().is_err());
+        assert!(rx3.try_recv().is_err());
+    }
+
     #[tokio::test]
     async fn test_broadcast_partial_failure() {
         // テスト項目: ブロードキャスト時、一部のクライアントが存在しなくても成功する
         // given (前提条件):
         let (pusher, clients) = create_test_pusher();
-        let (tx1, mut rx1) = mpsc::unbounded_channel();
+        let (tx1, mut rx1) = mpsc::channel(16);
         let alice = ClientId::new("alice".to_string()).unwrap();
         let nonexistent = ClientId::new("nonexistent".to_string()).unwrap();
 
         {
             let mut clients_lock = clients.lock().await;
-            clients_lock.insert(alice.as_str().to_string(), tx1);
+            clients_lock.insert(alice.as_str().to_string(), vec![tx1]);
         }
 
         // when (操作):
-        let targets = vec![alice.clone(), nonexistent];
+        let targets = vec![alice.clone(), nonexistent.clone()];
         let result = pusher.broadcast(targets, "Broadcast message").await;
 
-        // then (期待する結果):
-        assert!(result.is_ok()); // ブロードキャストは部分失敗を許容
+        // then (期待する結果): ブロードキャストは部分失敗を許容し、届かなかった送信先を報告する
+        let report = result.unwrap();
+        assert_eq!(report.delivered, vec![alice]);
+        assert_eq!(report.failed, vec![nonexistent]);
         assert_eq!(rx1.recv().await, Some("Broadcast message".to_string()));
     }
 
@@ -263,4 +520,176 @@ mod tests {
         // then (期待する結果):
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_broadcast_times_out_on_stalled_client_and_flags_it() {
+        // テスト項目: 受信が滞留しているクライアントがいてもブロードキャストはタイムアウト内に完了し、
+        //            そのクライアントが滞留クライアントとして記録される
+        // given (前提条件):
+        let clients = Arc::new(Mutex::new(HashMap::new()));
+        let send_timeout = Duration::from_millis(100);
+        let pusher = WebSocketMessagePusher::with_send_timeout(clients.clone(), send_timeout);
+        let (tx_alice, mut rx_alice) = mpsc::channel(16);
+        // bob のチャネルは満杯のまま受信されない
+        let (tx_bob, _rx_bob) = mpsc::channel(1);
+        tx_bob.send("backlog".to_string()).await.unwrap();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+
+        {
+            let mut clients_lock = clients.lock().await;
+            clients_lock.insert(alice.as_str().to_string(), vec![tx_alice]);
+            clients_lock.insert(bob.as_str().to_string(), vec![tx_bob]);
+        }
+
+        // when (操作):
+        let started = tokio::time::Instant::now();
+        let result = pusher
+            .broadcast(vec![bob.clone(), alice], "Broadcast message")
+            .await;
+        let elapsed = started.elapsed();
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        assert!(elapsed < send_timeout * 3);
+        assert_eq!(rx_alice.recv().await, Some("Broadcast message".to_string()));
+        assert_eq!(pusher.stalled_clients().await, vec![bob.clone()]);
+
+        // 滞留中のクライアントへの個別送信はタイムアウトエラーになる
+        let result = pusher.push_to(&bob, "Hello").await;
+        assert!(matches!(result, Err(MessagePushError::DeliveryTimeout(_))));
+    }
+
+    #[tokio::test]
+    async fn test_stalled_client_does_not_delay_later_broadcasts() {
+        // テスト項目: 滞留クライアントとして記録された後のブロードキャストはタイムアウトを待たずに
+        //            完了し、チャネルに空きができると再び配信されて記録から外れる
+        // given (前提条件): bob のチャネルが満杯で、1 回目のブロードキャストがタイムアウトした
+        let clients = Arc::new(Mutex::new(HashMap::new()));
+        let send_timeout = Duration::from_millis(200);
+        let pusher = WebSocketMessagePusher::with_send_timeout(clients.clone(), send_timeout);
+        let (tx_bob, mut rx_bob) = mpsc::channel(1);
+        tx_bob.send("backlog".to_string()).await.unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        clients
+            .lock()
+            .await
+            .insert(bob.as_str().to_string(), vec![tx_bob]);
+        pusher.broadcast(vec![bob.clone()], "first").await.unwrap();
+
+        // when (操作): 滞留したまま 2 回目を送り、チャネルを空けてから 3 回目を送る
+        let started = tokio::time::Instant::now();
+        let second = pusher.broadcast(vec![bob.clone()], "second").await.unwrap();
+        let elapsed = started.elapsed();
+        assert_eq!(rx_bob.recv().await, Some("backlog".to_string()));
+        let third = pusher.broadcast(vec![bob.clone()], "third").await.unwrap();
+
+        // then (期待する結果):
+        assert!(elapsed < send_timeout / 2, "waited {:?}", elapsed);
+        assert_eq!(second.failed, vec![bob.clone()]);
+        assert_eq!(third.delivered, vec![bob]);
+        assert_eq!(rx_bob.recv().await, Some("third".to_string()));
+        assert!(pusher.stalled_clients().await.is_empty());
+        assert_eq!(pusher.dead_letter_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_fans_out_to_all_connections_of_a_client() {
+        // テスト項目: 同じクライアントの 2 つの接続の両方にブロードキャストが届き、
+        //            1 つを登録解除しても残りの接続には届く
+        // given (前提条件):
+        let (pusher, _clients) = create_test_pusher();
+        let (tx1, mut rx1) = mpsc::channel(16);
+        let (tx2, mut rx2) = mpsc::channel(16);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        pusher.register_client(alice.clone(), tx1.clone()).await;
+        pusher.register_client(alice.clone(), tx2).await;
+
+        // when (操作):
+        pusher
+            .broadcast(vec![alice.clone()], "to both")
+            .await
+            .unwrap();
+        pusher.unregister_connection(&alice, &tx1).await;
+        pusher.push_to(&alice, "to second").await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(rx1.recv().await, Some("to both".to_string()));
+        assert_eq!(rx2.recv().await, Some("to both".to_string()));
+        assert_eq!(rx2.recv().await, Some("to second".to_string()));
+        assert!(rx1.try_recv().is_err());
+    }
+
+    /// テスト用: 記録されたデッドレターをメモリに保持する DeadLetterSink
+    #[derive(Default)]
+    struct RecordingDeadLetterSink {
+        letters: Mutex<Vec<DeadLetter>>,
+    }
+
+    #[async_trait]
+    impl DeadLetterSink for RecordingDeadLetterSink {
+        async fn record(&self, letter: DeadLetter) {
+            self.letters.lock().await.push(letter);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replace_client_leaves_only_the_new_connection() {
+        // テスト項目: 接続を置き換えると、既存のすべての接続が閉じられ、新しい接続だけに送信される
+        // given (前提条件): alice が 2 つの接続を持っている
+        let (pusher, clients) = create_test_pusher();
+        let client_id = ClientId::new("alice".to_string()).unwrap();
+        let (tx1, mut rx1) = mpsc::channel(16);
+        let (tx2, mut rx2) = mpsc::channel(16);
+        let (tx3, mut rx3) = mpsc::channel(16);
+        pusher.register_client(client_id.clone(), tx1).await;
+        pusher.register_client(client_id.clone(), tx2).await;
+
+        // when (操作):
+        pusher.replace_client(client_id.clone(), tx3).await;
+        pusher.push_to(&client_id, "Hello").await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(clients.lock().await["alice"].len(), 1);
+        assert_eq!(rx1.recv().await, None);
+        assert_eq!(rx2.recv().await, None);
+        assert_eq!(rx3.recv().await, Some("Hello".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_undeliverable_target_is_recorded_as_dead_letter() {
+        // テスト項目: 受信側が閉じたクライアントへのブロードキャストはデッドレターとして記録され、
+        //            届いたクライアントと登録されていないクライアントは記録されない
+        // given (前提条件): bob の受信側は閉じている
+        let sink = Arc::new(RecordingDeadLetterSink::default());
+        let clients = Arc::new(Mutex::new(HashMap::new()));
+        let pusher =
+            WebSocketMessagePusher::new(clients.clone()).with_dead_letter_sink(sink.clone());
+        let (tx_alice, mut rx_alice) = mpsc::channel(16);
+        let (tx_bob, rx_bob) = mpsc::channel(16);
+        drop(rx_bob);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let nonexistent = ClientId::new("nonexistent".to_string()).unwrap();
+
+        {
+            let mut clients_lock = clients.lock().await;
+            clients_lock.insert(alice.as_str().to_string(), vec![tx_alice]);
+            clients_lock.insert(bob.as_str().to_string(), vec![tx_bob]);
+        }
+
+        // when (操作):
+        let result = pusher
+            .broadcast(vec![alice, bob.clone(), nonexistent], "Broadcast message")
+            .await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.recv().await, Some("Broadcast message".to_string()));
+        let letters = sink.letters.lock().await;
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].client_id, bob);
+        assert_eq!(letters[0].payload, "Broadcast message");
+        assert_eq!(pusher.dead_letter_count(), 1);
+    }
 }
@@ -1,3 +1,5 @@
+pub mod dead_letter;
 pub mod dto;
 pub mod message_pusher;
 pub mod repository;
+pub mod snapshot;
@@ -18,11 +18,13 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use tokio::sync::Mutex;
+use engawa_shared::time::{Clock, SystemClock};
+use tokio::sync::{Mutex, Notify};
 
 use crate::domain::{
-    ChatMessage, ClientId, MessageContent, Participant, RepositoryError, Room, RoomRepository,
-    Timestamp,
+    AddParticipantError, ChatMessage, ClientId, Emoji, MessageContent, MessageId, Participant,
+    PresenceStatus, ReactionAction, RepositoryError, RetentionPolicy, Room, RoomError, RoomId,
+    RoomRepository, RosterDelta, Timestamp,
 };
 
 /// インメモリ Room Repository 実装
@@ -31,12 +33,75 @@ use crate::domain::{
 pub struct InMemoryRoomRepository {
     /// Room ドメインモデル
     room: Arc<Mutex<Room>>,
+    /// メッセージ履歴の保持ポリシー
+    retention: RetentionPolicy,
+    /// 保持期間の判定に使う時計
+    clock: Arc<dyn Clock>,
+    /// メッセージを履歴に保存するか（`false` のときはブロードキャストのみ）
+    history_enabled: bool,
+    /// 有効期限付きのメッセージを保存したときに通知する先
+    expiry_notify: Option<Arc<Notify>>,
 }
 
 impl InMemoryRoomRepository {
     /// 新しい InMemoryRoomRepository を作成
     pub fn new(room: Arc<Mutex<Room>>) -> Self {
-        Self { room }
+        Self {
+            room,
+            retention: RetentionPolicy::None,
+            clock: Arc::new(SystemClock),
+            history_enabled: true,
+            expiry_notify: None,
+        }
+    }
+
+    /// メッセージ履歴の保持ポリシーを設定（デフォルト: `RetentionPolicy::None`）
+    pub fn with_retention_policy(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// 保持期間の判定に使う時計を設定（テスト用）
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// メッセージ履歴を保存するかを設定（デフォルト: `true`）
+    ///
+    /// `false` の場合、メッセージの追加は送信者の検証のみを行って何も保存せず、
+    /// 履歴の取得は常に空を返します。
+    pub fn with_history(mut self, enabled: bool) -> Self {
+        self.history_enabled = enabled;
+        self
+    }
+
+    /// 有効期限付きのメッセージを保存したときに `notify` へ通知する
+    ///
+    /// 期限切れのメッセージを削除するタスクは、次の有効期限まで待つ間にこの通知で起こされ、
+    /// より早く期限が切れるメッセージに間に合うよう待ち時間を計算し直せます。
+    pub fn with_expiry_notify(mut self, notify: Arc<Notify>) -> Self {
+        self.expiry_notify = Some(notify);
+        self
+    }
+
+    /// メッセージを履歴に追加（履歴が無効な場合は送信者の検証のみ）
+    ///
+    /// 保持しなくなったメッセージは、追加するメッセージが受け付けられた場合にのみ削除する
+    fn store_message(&self, room: &mut Room, message: ChatMessage) -> Result<(), RoomError> {
+        if !self.history_enabled {
+            return room.accept_unstored_message(&message.from, message.timestamp);
+        }
+        let now = Timestamp::new(self.clock.now_jst_millis());
+        let expires = message.expires_at.is_some();
+        let evicted = room.add_message_with_retention(message, self.retention, now)?;
+        if evicted > 0 {
+            tracing::debug!("Evicted {} messages by retention policy", evicted);
+        }
+        if expires && let Some(notify) = &self.expiry_notify {
+            notify.notify_one();
+        }
+        Ok(())
     }
 }
 
@@ -47,26 +112,71 @@ impl RoomRepository for InMemoryRoomRepository {
         Ok(room.clone())
     }
 
-    async fn add_participant(
+    async fn add_participant_with_status(
         &self,
         client_id: ClientId,
         timestamp: Timestamp,
+        status: PresenceStatus,
     ) -> Result<(), RepositoryError> {
-        let participant = Participant::new(client_id.clone(), timestamp);
+        let participant = Participant::with_status(client_id, timestamp, status);
 
         let mut room = self.room.lock().await;
-        room.add_participant(participant)
-            .map_err(|_| RepositoryError::ParticipantNotFound(client_id.as_str().to_string()))?;
+        room.add_participant(participant)?;
 
         Ok(())
     }
 
+    async fn try_add_participant_with_status(
+        &self,
+        client_id: ClientId,
+        timestamp: Timestamp,
+        status: PresenceStatus,
+    ) -> Result<(), AddParticipantError> {
+        // 重複チェックと追加は Room::add_participant が同一ロック区間で行う
+        let participant = Participant::with_status(client_id, timestamp, status);
+        let mut room = self.room.lock().await;
+        room.add_participant(participant).map_err(|e| match e {
+            RoomError::DuplicateParticipant(client_id) => AddParticipantError::Duplicate(client_id),
+            RoomError::ParticipantCapacityExceeded { capacity, .. } => {
+                AddParticipantError::CapacityExceeded { capacity }
+            }
+            e => AddParticipantError::Repository(e.into()),
+        })
+    }
+
+    async fn add_connection(&self, client_id: &ClientId) -> Result<Timestamp, RepositoryError> {
+        let mut room = self.room.lock().await;
+        room.add_connection(client_id)
+            .ok_or_else(|| RepositoryError::ParticipantNotFound(client_id.as_str().to_string()))
+    }
+
+    async fn remove_connection(&self, client_id: &ClientId) -> Result<usize, RepositoryError> {
+        let mut room = self.room.lock().await;
+        room.remove_connection(client_id)
+            .ok_or_else(|| RepositoryError::ParticipantNotFound(client_id.as_str().to_string()))
+    }
+
     async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
         let mut room = self.room.lock().await;
         room.remove_participant(client_id);
         Ok(())
     }
 
+    async fn update_last_active(
+        &self,
+        client_id: &ClientId,
+        timestamp: Timestamp,
+    ) -> Result<(), RepositoryError> {
+        let mut room = self.room.lock().await;
+        if room.touch_participant(client_id, timestamp) {
+            Ok(())
+        } else {
+            Err(RepositoryError::ParticipantNotFound(
+                client_id.as_str().to_string(),
+            ))
+        }
+    }
+
     async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
         let room = self.room.lock().await;
         room.participants.iter().map(|p| p.id.clone()).collect()
@@ -80,11 +190,88 @@ impl RoomRepository for InMemoryRoomRepository {
     ) -> Result<(), RepositoryError> {
         let mut room = self.room.lock().await;
         let message = ChatMessage::new(from_client_id, content, timestamp);
-        room.add_message(message)
-            .map_err(|_| RepositoryError::RoomNotFound)?;
+        self.store_message(&mut room, message)?;
         Ok(())
     }
 
+    async fn add_message_and_snapshot_targets(
+        &self,
+        message_id: MessageId,
+        from_client_id: ClientId,
+        content: MessageContent,
+        timestamp: Timestamp,
+        expires_at: Option<Timestamp>,
+    ) -> Result<Vec<ClientId>, RepositoryError> {
+        let mut room = self.room.lock().await;
+        let message = ChatMessage::with_id(message_id, from_client_id.clone(), content, timestamp)
+            .with_expires_at(expires_at);
+        self.store_message(&mut room, message)?;
+
+        // 同一ロック区間内で配信対象（送信者以外）をスナップショット
+        Ok(room.get_broadcast_targets(&from_client_id))
+    }
+
+    async fn react_to_message(
+        &self,
+        message_id: &str,
+        emoji: Emoji,
+        by: ClientId,
+        action: ReactionAction,
+    ) -> Result<usize, RepositoryError> {
+        let mut room = self.room.lock().await;
+        let message = room
+            .find_message_mut(message_id)
+            .ok_or_else(|| RepositoryError::MessageNotFound(message_id.to_string()))?;
+        Ok(message.react(emoji, by, action))
+    }
+
+    async fn get_messages(
+        &self,
+        room_id: &RoomId,
+        since: Option<Timestamp>,
+        limit: usize,
+    ) -> Result<Vec<ChatMessage>, RepositoryError> {
+        let room = self.room.lock().await;
+        if &room.id != room_id {
+            return Err(RepositoryError::RoomNotFound);
+        }
+        if !self.history_enabled {
+            return Ok(Vec::new());
+        }
+
+        // メッセージは追加順（= 時刻順）に並んでいるため、二分探索で since の位置を求める
+        let messages = room.latest_messages(limit);
+        let since_index = match since {
+            Some(since) => messages.partition_point(|m| m.timestamp <= since),
+            None => 0,
+        };
+
+        // 必要な範囲のみを複製する
+        Ok(messages[since_index..].to_vec())
+    }
+
+    async fn purge_messages(&self, room_id: &RoomId) -> Result<usize, RepositoryError> {
+        let mut room = self.room.lock().await;
+        if &room.id != room_id {
+            return Err(RepositoryError::RoomNotFound);
+        }
+
+        Ok(room.clear_messages())
+    }
+
+    async fn is_connected(
+        &self,
+        room_id: &RoomId,
+        client_id: &ClientId,
+    ) -> Result<bool, RepositoryError> {
+        let room = self.room.lock().await;
+        if &room.id != room_id {
+            return Err(RepositoryError::RoomNotFound);
+        }
+
+        Ok(room.get_participant(client_id).is_some())
+    }
+
     async fn count_connected_clients(&self) -> usize {
         let room = self.room.lock().await;
         room.participants.len()
@@ -94,12 +281,54 @@ impl RoomRepository for InMemoryRoomRepository {
         let room = self.room.lock().await;
         room.participants.clone()
     }
+
+    async fn get_versioned_participants(&self) -> (u64, Vec<Participant>) {
+        let room = self.room.lock().await;
+        (room.roster_version(), room.participants.clone())
+    }
+
+    async fn get_roster_delta(&self, since_version: u64) -> Option<RosterDelta> {
+        let room = self.room.lock().await;
+        room.roster_delta_since(since_version)
+    }
+
+    async fn set_muted(&self, client_id: &ClientId, muted: bool) -> Result<(), RepositoryError> {
+        let mut room = self.room.lock().await;
+        if room.set_muted(client_id, muted) {
+            Ok(())
+        } else {
+            Err(RepositoryError::ParticipantNotFound(
+                client_id.as_str().to_string(),
+            ))
+        }
+    }
+
+    async fn get_participant(&self, client_id: &ClientId) -> Option<Participant> {
+        let room = self.room.lock().await;
+        room.get_participant(client_id).cloned()
+    }
+
+    async fn apply_retention(&self) -> usize {
+        let mut room = self.room.lock().await;
+        let now = Timestamp::new(self.clock.now_jst_millis());
+        room.apply_retention(self.retention, now, 0)
+    }
+
+    async fn remove_expired_messages(&self) -> Vec<MessageId> {
+        let mut room = self.room.lock().await;
+        let now = Timestamp::new(self.clock.now_jst_millis());
+        room.remove_expired_messages(now)
+    }
+
+    async fn next_message_expiry(&self) -> Option<Timestamp> {
+        self.room.lock().await.next_message_expiry()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::domain::RoomIdFactory;
+    use crate::domain::{MessageIdFactory, RoomError, RoomIdFactory};
     use engawa_shared::time::get_jst_timestamp;
 
     // ========================================
@@ -235,6 +464,47 @@ mod tests {
         assert!(client_ids.contains(&bob));
     }
 
+    #[tokio::test]
+    async fn test_get_participant_returns_the_participant() {
+        // テスト項目: 参加中のクライアントを指定すると、その参加者だけが取得できる
+        // given (前提条件):
+        let repo = create_test_repository();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repo.add_participant(alice, Timestamp::new(1000))
+            .await
+            .unwrap();
+        repo.add_participant(bob.clone(), Timestamp::new(2000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let participant = repo.get_participant(&bob).await;
+
+        // then (期待する結果):
+        let participant = participant.expect("bob is a participant");
+        assert_eq!(participant.id, bob);
+        assert_eq!(participant.connected_at.value(), 2000);
+    }
+
+    #[tokio::test]
+    async fn test_get_participant_returns_none_for_absent_client() {
+        // テスト項目: 参加していないクライアントを指定すると None が返される
+        // given (前提条件):
+        let repo = create_test_repository();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repo.add_participant(alice, Timestamp::new(1000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let nonexistent = ClientId::new("nonexistent".to_string()).unwrap();
+        let participant = repo.get_participant(&nonexistent).await;
+
+        // then (期待する結果):
+        assert!(participant.is_none());
+    }
+
     #[tokio::test]
     async fn test_add_message_success() {
         // テスト項目: メッセージを Room に追加できる
@@ -261,4 +531,570 @@ mod tests {
         assert_eq!(room.messages.len(), 1);
         assert_eq!(room.messages[0].from, client_id);
     }
+
+    #[tokio::test]
+    async fn test_add_message_and_snapshot_targets() {
+        // テスト項目: メッセージ追加と同時に送信者以外の配信対象が取得できる
+        // given (前提条件):
+        let repo = create_test_repository();
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repo.add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repo.add_participant(bob.clone(), timestamp).await.unwrap();
+
+        // when (操作):
+        let content = MessageContent::new("Hello".to_string()).unwrap();
+        let result = repo
+            .add_message_and_snapshot_targets(
+                MessageIdFactory::generate(),
+                alice.clone(),
+                content,
+                timestamp,
+                None,
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap(), vec![bob]);
+        let room = repo.get_room().await.unwrap();
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].from, alice);
+    }
+
+    #[tokio::test]
+    async fn test_history_disabled_snapshots_targets_without_storing() {
+        // テスト項目: 履歴が無効な場合、メッセージの追加は配信対象を返すが履歴には保存されず、
+        //            ミュートされた送信者は引き続き拒否される
+        // given (前提条件):
+        let repo = create_test_repository().with_history(false);
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repo.add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repo.add_participant(bob.clone(), timestamp).await.unwrap();
+        let room_id = repo.get_room().await.unwrap().id;
+
+        // when (操作):
+        let targets = repo
+            .add_message_and_snapshot_targets(
+                MessageIdFactory::generate(),
+                alice.clone(),
+                MessageContent::new("Hello".to_string()).unwrap(),
+                timestamp,
+                None,
+            )
+            .await;
+        repo.set_muted(&bob, true).await.unwrap();
+        let muted = repo
+            .add_message(
+                bob.clone(),
+                MessageContent::new("Hi".to_string()).unwrap(),
+                timestamp,
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(targets.unwrap(), vec![bob]);
+        assert!(matches!(
+            muted,
+            Err(RepositoryError::Room(RoomError::SenderMuted(_)))
+        ));
+        assert!(repo.get_room().await.unwrap().messages.is_empty());
+        assert!(
+            repo.get_messages(&room_id, None, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_history_disabled_still_enforces_slow_mode() {
+        // テスト項目: 履歴が無効でも、スローモードの間隔内に同じ送信者が送った 2 件目は拒否される
+        // given (前提条件): 履歴が無効で、10 秒のスローモードのルームに alice が参加している
+        use std::time::Duration;
+
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        room.slow_mode_interval = Some(Duration::from_secs(10));
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room))).with_history(false);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repo.add_participant(alice.clone(), Timestamp::new(0))
+            .await
+            .unwrap();
+        let send = |timestamp: i64| {
+            repo.add_message_and_snapshot_targets(
+                MessageIdFactory::generate(),
+                alice.clone(),
+                MessageContent::new("Hello".to_string()).unwrap(),
+                Timestamp::new(timestamp),
+                None,
+            )
+        };
+
+        // when (操作): 1 秒後に 2 件目、間隔が過ぎた後に 3 件目を送信する
+        let first = send(1_000).await;
+        let too_soon = send(2_000).await;
+        let after_interval = send(11_000).await;
+
+        // then (期待する結果):
+        assert!(first.is_ok());
+        assert!(matches!(
+            too_soon,
+            Err(RepositoryError::Room(RoomError::SlowMode { retry_after }))
+                if retry_after == Duration::from_secs(9)
+        ));
+        assert!(after_interval.is_ok());
+        assert!(repo.get_room().await.unwrap().messages.is_empty());
+    }
+
+    async fn create_test_repository_with_messages(timestamps: &[i64]) -> InMemoryRoomRepository {
+        let repo = create_test_repository();
+        let client_id = ClientId::new("alice".to_string()).unwrap();
+        for (i, timestamp) in timestamps.iter().enumerate() {
+            let content = MessageContent::new(format!("Message {}", i)).unwrap();
+            repo.add_message(client_id.clone(), content, Timestamp::new(*timestamp))
+                .await
+                .unwrap();
+        }
+        repo
+    }
+
+    #[tokio::test]
+    async fn test_react_to_message_in_history_and_unknown_id() {
+        // テスト項目: 履歴中のメッセージへのリアクションは数が返され、
+        //            履歴にない ID へのリアクションは MessageNotFound になる
+        // given (前提条件):
+        let repo = create_test_repository();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let message_id = MessageIdFactory::generate();
+        repo.add_message_and_snapshot_targets(
+            message_id.clone(),
+            alice,
+            MessageContent::new("Hello".to_string()).unwrap(),
+            Timestamp::new(1000),
+            None,
+        )
+        .await
+        .unwrap();
+        let emoji = Emoji::new("👍".to_string()).unwrap();
+
+        // when (操作):
+        let reacted = repo
+            .react_to_message(
+                message_id.as_str(),
+                emoji.clone(),
+                bob.clone(),
+                ReactionAction::Add,
+            )
+            .await;
+        let unknown = repo
+            .react_to_message("no-such-id", emoji.clone(), bob, ReactionAction::Add)
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(reacted.unwrap(), 1);
+        assert!(matches!(
+            unknown,
+            Err(RepositoryError::MessageNotFound(id)) if id == "no-such-id"
+        ));
+        let room = repo.get_room().await.unwrap();
+        assert_eq!(room.messages[0].reaction_count(&emoji), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_with_limit() {
+        // テスト項目: limit を指定すると直近のメッセージが古い順に最大 limit 件返される
+        // given (前提条件):
+        let repo = create_test_repository_with_messages(&[1000, 2000, 3000, 4000]).await;
+        let room_id = repo.get_room().await.unwrap().id;
+
+        // when (操作):
+        let messages = repo.get_messages(&room_id, None, 2).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].timestamp, Timestamp::new(3000));
+        assert_eq!(messages[1].timestamp, Timestamp::new(4000));
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_since() {
+        // テスト項目: since を指定するとその時刻より後のメッセージのみが返される
+        // given (前提条件):
+        let repo = create_test_repository_with_messages(&[1000, 2000, 3000, 4000]).await;
+        let room_id = repo.get_room().await.unwrap().id;
+
+        // when (操作):
+        let messages = repo
+            .get_messages(&room_id, Some(Timestamp::new(2000)), 10)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[0].timestamp, Timestamp::new(3000));
+        assert_eq!(messages[1].timestamp, Timestamp::new(4000));
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_since_and_limit() {
+        // テスト項目: since と limit を両方指定すると、条件に一致する直近 limit 件が返される
+        // given (前提条件):
+        let repo = create_test_repository_with_messages(&[1000, 2000, 3000, 4000]).await;
+        let room_id = repo.get_room().await.unwrap().id;
+
+        // when (操作):
+        let messages = repo
+            .get_messages(&room_id, Some(Timestamp::new(1000)), 1)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0].timestamp, Timestamp::new(4000));
+    }
+
+    #[tokio::test]
+    async fn test_get_messages_room_not_found() {
+        // テスト項目: 存在しない Room ID を指定するとエラーが返される
+        // given (前提条件):
+        let repo = create_test_repository_with_messages(&[1000]).await;
+        let other_room_id = RoomIdFactory::generate().unwrap();
+
+        // when (操作):
+        let result = repo.get_messages(&other_room_id, None, 10).await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(RepositoryError::RoomNotFound)));
+    }
+
+    #[tokio::test]
+    async fn test_add_participant_capacity_exceeded_maps_to_room_error() {
+        // テスト項目: 参加者数の上限超過は RoomError::ParticipantCapacityExceeded として返される
+        // given (前提条件):
+        let room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 1, 10);
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room)));
+        repo.add_participant(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        )
+        .await
+        .unwrap();
+
+        // when (操作):
+        let result = repo
+            .add_participant(
+                ClientId::new("bob".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            result,
+            Err(RepositoryError::Room(
+                RoomError::ParticipantCapacityExceeded {
+                    capacity: 1,
+                    current: 1
+                }
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_add_participant_duplicate_maps_to_room_error() {
+        // テスト項目: 参加済みの ID の追加は RoomError::DuplicateParticipant として返され、参加者は増えない
+        // given (前提条件):
+        let repo = create_test_repository();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repo.add_participant(alice.clone(), Timestamp::new(1000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = repo.add_participant(alice, Timestamp::new(2000)).await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            result,
+            Err(RepositoryError::Room(RoomError::DuplicateParticipant(id))) if id == "alice"
+        ));
+        assert_eq!(repo.get_room().await.unwrap().participants.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_try_add_participant_rejects_duplicate() {
+        // テスト項目: 既に Room にいる ID での追加は Duplicate として拒否され、参加者は増えない
+        // given (前提条件):
+        let repo = create_test_repository();
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repo.try_add_participant(alice.clone(), Timestamp::new(1000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = repo.try_add_participant(alice, Timestamp::new(2000)).await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(AddParticipantError::Duplicate(id)) if id == "alice"));
+        assert_eq!(repo.count_connected_clients().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_try_add_participant_rejects_when_capacity_exceeded() {
+        // テスト項目: 定員に達した Room への追加は CapacityExceeded として拒否される
+        // given (前提条件):
+        let room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 1, 10);
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room)));
+        repo.try_add_participant(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        )
+        .await
+        .unwrap();
+
+        // when (操作):
+        let result = repo
+            .try_add_participant(
+                ClientId::new("bob".to_string()).unwrap(),
+                Timestamp::new(2000),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            result,
+            Err(AddParticipantError::CapacityExceeded { capacity: 1 })
+        ));
+        assert_eq!(repo.count_connected_clients().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_message_capacity_exceeded_maps_to_room_error() {
+        // テスト項目: メッセージ数の上限超過は RoomError::MessageCapacityExceeded として返される
+        // given (前提条件):
+        let room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 10, 1);
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room)));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repo.add_message(
+            alice.clone(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(1000),
+        )
+        .await
+        .unwrap();
+
+        // when (操作):
+        let result = repo
+            .add_message_and_snapshot_targets(
+                MessageIdFactory::generate(),
+                alice,
+                MessageContent::new("Again!".to_string()).unwrap(),
+                Timestamp::new(2000),
+                None,
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            result,
+            Err(RepositoryError::Room(RoomError::MessageCapacityExceeded {
+                capacity: 1,
+                current: 1
+            }))
+        ));
+    }
+
+    /// 指定した時刻のメッセージを "0", "1", ... の内容で順に追加
+    async fn add_messages_at(repo: &InMemoryRoomRepository, timestamps: &[i64]) {
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for (i, timestamp) in timestamps.iter().enumerate() {
+            repo.add_message(
+                alice.clone(),
+                MessageContent::new(i.to_string()).unwrap(),
+                Timestamp::new(*timestamp),
+            )
+            .await
+            .unwrap();
+        }
+    }
+
+    async fn history_contents(repo: &InMemoryRoomRepository) -> Vec<String> {
+        let room = repo.get_room().await.unwrap();
+        room.messages
+            .iter()
+            .map(|m| m.content.as_str().to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_count_retention_evicts_oldest_messages_instead_of_rejecting() {
+        // テスト項目: 件数による保持では、上限に達すると最も古いメッセージから削除され、追加は拒否されない
+        // given (前提条件):
+        let room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 10, 3);
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room)))
+            .with_retention_policy(RetentionPolicy::MaxCount(3));
+
+        // when (操作):
+        add_messages_at(&repo, &[1000, 2000, 3000, 4000, 5000]).await;
+
+        // then (期待する結果):
+        assert_eq!(history_contents(&repo).await, vec!["2", "3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn test_rejected_send_does_not_evict_history() {
+        // テスト項目: 件数による保持で履歴が上限に達していても、スローモードで拒否された送信では
+        //            履歴が変わらない
+        // given (前提条件): 最新 3 件を保持し、10 秒のスローモードのルームに alice が 3 件送信済み
+        use std::time::Duration;
+
+        let mut room =
+            Room::with_capacity(RoomIdFactory::generate().unwrap(), Timestamp::new(0), 10, 3);
+        room.slow_mode_interval = Some(Duration::from_secs(10));
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(room)))
+            .with_retention_policy(RetentionPolicy::MaxCount(3));
+        repo.add_participant(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(0),
+        )
+        .await
+        .unwrap();
+        add_messages_at(&repo, &[1_000, 20_000, 40_000]).await;
+
+        // when (操作): 直前のメッセージから 1 秒後に alice が送信する
+        let result = repo
+            .add_message(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new("too soon".to_string()).unwrap(),
+                Timestamp::new(41_000),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(result.is_err());
+        assert_eq!(history_contents(&repo).await, vec!["0", "1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_age_retention_evicts_expired_messages_on_add_and_periodically() {
+        // テスト項目: 期間による保持では、保持期間を過ぎたメッセージが追加時と定期適用時に削除される
+        // given (前提条件): 現在時刻 10 秒、保持期間 5 秒
+        use engawa_shared::time::FixedClock;
+        use std::time::Duration;
+
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let room = Arc::new(Mutex::new(room));
+        let repo = InMemoryRoomRepository::new(room.clone())
+            .with_retention_policy(RetentionPolicy::MaxAge(Duration::from_secs(5)))
+            .with_clock(Arc::new(FixedClock::new(10_000)));
+        add_messages_at(&repo, &[1_000, 6_000, 9_000]).await;
+
+        // when (操作): 時計が 12 秒に進んだ時点で定期適用する
+        let kept_on_add = history_contents(&repo).await;
+        let later = InMemoryRoomRepository::new(room)
+            .with_retention_policy(RetentionPolicy::MaxAge(Duration::from_secs(5)))
+            .with_clock(Arc::new(FixedClock::new(12_000)));
+        let evicted = later.apply_retention().await;
+
+        // then (期待する結果):
+        // 1 秒のメッセージは 2 件目の追加時点で期限切れ、6 秒のメッセージは 12 秒時点で期限切れ
+        assert_eq!(kept_on_add, vec!["1", "2"]);
+        assert_eq!(evicted, 1);
+        assert_eq!(history_contents(&later).await, vec!["2"]);
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_message_is_removed_after_its_ttl() {
+        // テスト項目: 有効期限付きのメッセージは、期限を過ぎた時点の削除で履歴から消え、
+        //            期限のないメッセージは残る
+        // given (前提条件): 1 秒に期限 6 秒のメッセージと期限のないメッセージを追加した
+        use engawa_shared::time::FixedClock;
+
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let room = Arc::new(Mutex::new(room));
+        let at = |millis: i64| {
+            InMemoryRoomRepository::new(room.clone()).with_clock(Arc::new(FixedClock::new(millis)))
+        };
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let ephemeral_id = MessageIdFactory::generate();
+        for (message_id, expires_at) in [
+            (ephemeral_id.clone(), Some(Timestamp::new(6_000))),
+            (MessageIdFactory::generate(), None),
+        ] {
+            at(1_000)
+                .add_message_and_snapshot_targets(
+                    message_id,
+                    alice.clone(),
+                    MessageContent::new("Hello".to_string()).unwrap(),
+                    Timestamp::new(1_000),
+                    expires_at,
+                )
+                .await
+                .unwrap();
+        }
+
+        // when (操作): 期限の前と後に削除する
+        let before_ttl = at(5_999).remove_expired_messages().await;
+        let after_ttl = at(6_000).remove_expired_messages().await;
+
+        // then (期待する結果):
+        assert!(before_ttl.is_empty());
+        assert_eq!(after_ttl, vec![ephemeral_id]);
+        let room = room.lock().await;
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.messages[0].expires_at, None);
+    }
+
+    #[tokio::test]
+    async fn test_storing_an_expiring_message_notifies_and_reports_the_earliest_expiry() {
+        // テスト項目: 有効期限付きのメッセージを保存したときだけ通知され、
+        //            最も早い有効期限が取得できる
+        // given (前提条件):
+        use futures_util::FutureExt;
+
+        let notify = Arc::new(Notify::new());
+        let repo = InMemoryRoomRepository::new(Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+        ))))
+        .with_expiry_notify(notify.clone());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let send = |expires_at: Option<i64>| {
+            repo.add_message_and_snapshot_targets(
+                MessageIdFactory::generate(),
+                alice.clone(),
+                MessageContent::new("Hello".to_string()).unwrap(),
+                Timestamp::new(1_000),
+                expires_at.map(Timestamp::new),
+            )
+        };
+
+        // when (操作):
+        send(None).await.unwrap();
+        let without_expiry = repo.next_message_expiry().await;
+        let notified_without_expiry = notify.notified().now_or_never().is_some();
+        send(Some(9_000)).await.unwrap();
+        send(Some(6_000)).await.unwrap();
+        let notified_with_expiry = notify.notified().now_or_never().is_some();
+
+        // then (期待する結果):
+        assert_eq!(without_expiry, None);
+        assert!(!notified_without_expiry);
+        assert!(notified_with_expiry);
+        assert_eq!(
+            repo.next_message_expiry().await,
+            Some(Timestamp::new(6_000))
+        );
+    }
 }
@@ -0,0 +1,151 @@
+//! Room スナップショットのファイル永続化
+//!
+//! InMemory 構成でもグレースフルリスタートを跨いでメッセージ履歴を保持するため、
+//! シャットダウン時に Room を JSON ファイルへ書き出し、起動時に読み戻します。
+//!
+//! 参加者のライブな接続（チャネル）は Room に含まれないため、
+//! スナップショットにはシリアライズ可能な状態のみが保存されます。
+//! 復元時の参加者の扱いは [`Room::restore_from_snapshot`] を参照してください。
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::domain::Room;
+
+/// Errors related to snapshot persistence
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    /// ファイルの読み書き失敗
+    #[error("Snapshot I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON のシリアライズ / デシリアライズ失敗
+    #[error("Snapshot format error: {0}")]
+    Format(#[from] serde_json::Error),
+}
+
+/// スナップショットファイルの内容
+#[derive(Debug, Serialize, Deserialize)]
+struct RoomSnapshot {
+    /// 保存された Room の一覧
+    rooms: Vec<Room>,
+}
+
+/// Room スナップショットを JSON ファイルとして保存・読み込みするストア
+pub struct FileSnapshotStore {
+    /// スナップショットファイルのパス
+    path: PathBuf,
+}
+
+impl FileSnapshotStore {
+    /// 新しい FileSnapshotStore を作成
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// スナップショットファイルのパスを取得
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Room をスナップショットファイルに保存
+    ///
+    /// 書き込み途中でプロセスが終了しても既存のスナップショットが壊れないよう、
+    /// 一時ファイルに書き込んでからリネームします。
+    pub async fn save(&self, rooms: &[Room]) -> Result<(), SnapshotError> {
+        let json = serde_json::to_vec_pretty(&RoomSnapshot {
+            rooms: rooms.to_vec(),
+        })?;
+
+        let tmp_path = self.path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, json).await?;
+        tokio::fs::rename(&tmp_path, &self.path).await?;
+
+        Ok(())
+    }
+
+    /// スナップショットファイルから Room を読み込み
+    ///
+    /// ファイルが存在しない場合は `Ok(None)` を返します。
+    pub async fn load(&self) -> Result<Option<Vec<Room>>, SnapshotError> {
+        let json = match tokio::fs::read(&self.path).await {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let snapshot: RoomSnapshot = serde_json::from_slice(&json)?;
+
+        Ok(Some(snapshot.rooms))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ChatMessage, ClientId, MessageContent, Participant, RoomIdFactory, Timestamp,
+    };
+
+    fn temp_snapshot_path() -> PathBuf {
+        std::env::temp_dir().join(format!("engawa-snapshot-{}.json", uuid::Uuid::new_v4()))
+    }
+
+    #[tokio::test]
+    async fn test_save_and_restore_room_messages() {
+        // テスト項目: 保存したスナップショットからメッセージ履歴が復元され、参加者は復元されない
+        // given (前提条件):
+        let store = FileSnapshotStore::new(temp_snapshot_path());
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(100));
+        room.add_participant(Participant::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            Timestamp::new(1000),
+        ))
+        .unwrap();
+        room.add_message(ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ))
+        .unwrap();
+        room.add_message(ChatMessage::new(
+            ClientId::new("alice".to_string()).unwrap(),
+            MessageContent::new("Bye!".to_string()).unwrap(),
+            Timestamp::new(3000),
+        ))
+        .unwrap();
+        store.save(std::slice::from_ref(&room)).await.unwrap();
+
+        // when (操作):
+        let mut restored = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(5000));
+        let snapshot = store.load().await.unwrap().unwrap();
+        restored.restore_from_snapshot(snapshot.into_iter().next().unwrap());
+
+        // then (期待する結果):
+        assert_eq!(restored.id, room.id);
+        assert!(restored.participants.is_empty());
+        let contents: Vec<&str> = restored
+            .messages
+            .iter()
+            .map(|m| m.content.as_str())
+            .collect();
+        assert_eq!(contents, vec!["Hello!", "Bye!"]);
+        assert_eq!(restored.messages[1].timestamp, Timestamp::new(3000));
+
+        tokio::fs::remove_file(store.path()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_load_missing_snapshot_returns_none() {
+        // テスト項目: スナップショットファイルが存在しない場合は None を返す
+        // given (前提条件):
+        let store = FileSnapshotStore::new(temp_snapshot_path());
+
+        // when (操作):
+        let result = store.load().await;
+
+        // then (期待する結果):
+        assert!(result.unwrap().is_none());
+    }
+}
@@ -0,0 +1,304 @@
+//! Connection access policies based on the source IP address.
+//!
+//! WebSocket のアップグレード前に接続元 IP アドレスを判定し、
+//! 許可されていない接続を 403 Forbidden で拒否するための仕組みです。
+
+use std::{net::IpAddr, path::Path, str::FromStr};
+
+use thiserror::Error;
+
+/// 接続可否の判定結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessDecision {
+    /// 接続を許可
+    Allow,
+    /// 接続を拒否
+    Deny,
+}
+
+/// 接続元 IP アドレスによる接続可否の判定
+///
+/// ## 実装
+///
+/// - `AllowAllPolicy`: すべての接続を許可（デフォルト）
+/// - `CidrAccessPolicy`: CIDR による allow/deny リスト
+pub trait AccessPolicy: Send + Sync {
+    /// 接続元アドレスを判定
+    fn check(&self, addr: IpAddr) -> AccessDecision;
+}
+
+/// すべての接続を許可するポリシー（デフォルト）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllowAllPolicy;
+
+impl AccessPolicy for AllowAllPolicy {
+    fn check(&self, _addr: IpAddr) -> AccessDecision {
+        AccessDecision::Allow
+    }
+}
+
+/// Errors related to access policy configuration
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum AccessPolicyError {
+    /// CIDR の書式が不正
+    #[error("Invalid CIDR: {0}")]
+    InvalidCidr(String),
+
+    /// ポリシーファイルの行が不正
+    #[error("Invalid access policy rule at line {line}: {content}")]
+    InvalidRule { line: usize, content: String },
+
+    /// ポリシーファイルの読み込み失敗
+    #[error("Failed to read access policy file: {0}")]
+    Io(String),
+}
+
+/// CIDR 表記のネットワーク（例: `10.0.0.0/8`, `::1/128`）
+///
+/// プレフィックス長を省略した場合は単一アドレスとして扱います。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    /// アドレスがこのネットワークに含まれるかを判定
+    ///
+    /// IPv4 射影 IPv6 アドレス（`::ffff:a.b.c.d`）は IPv4 として比較します。
+    pub fn contains(&self, addr: IpAddr) -> bool {
+        match (self.network, addr.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(addr)) => {
+                let mask = mask_u32(self.prefix_len);
+                u32::from(network) & mask == u32::from(addr) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(addr)) => {
+                let mask = mask_u128(self.prefix_len);
+                u128::from(network) & mask == u128::from(addr) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = AccessPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || AccessPolicyError::InvalidCidr(s.to_string());
+
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, prefix_len)) => (addr, Some(prefix_len)),
+            None => (s.trim(), None),
+        };
+        let network = IpAddr::from_str(addr).map_err(|_| invalid())?;
+        let max_prefix_len = if network.is_ipv4() { 32 } else { 128 };
+        let prefix_len = match prefix_len {
+            Some(prefix_len) => prefix_len.parse::<u8>().map_err(|_| invalid())?,
+            None => max_prefix_len,
+        };
+        if prefix_len > max_prefix_len {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            network,
+            prefix_len,
+        })
+    }
+}
+
+fn mask_u32(prefix_len: u8) -> u32 {
+    u32::MAX
+        .checked_shl(32 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+fn mask_u128(prefix_len: u8) -> u128 {
+    u128::MAX
+        .checked_shl(128 - u32::from(prefix_len))
+        .unwrap_or(0)
+}
+
+/// CIDR による allow/deny リストのポリシー
+///
+/// ## 判定ルール
+///
+/// 1. deny リストに一致すれば拒否（deny が優先）
+/// 2. allow リストが空なら許可
+/// 3. allow リストに一致すれば許可、一致しなければ拒否
+#[derive(Debug, Clone, Default)]
+pub struct CidrAccessPolicy {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+}
+
+impl CidrAccessPolicy {
+    /// Create a new CidrAccessPolicy from allow/deny lists
+    pub fn new(allow: Vec<Cidr>, deny: Vec<Cidr>) -> Self {
+        Self { allow, deny }
+    }
+
+    /// Parse allow/deny lists given as CIDR strings (e.g. from CLI arguments)
+    pub fn from_strs<S: AsRef<str>>(allow: &[S], deny: &[S]) -> Result<Self, AccessPolicyError> {
+        let parse = |list: &[S]| {
+            list.iter()
+                .map(|s| s.as_ref().parse::<Cidr>())
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self::new(parse(allow)?, parse(deny)?))
+    }
+
+    /// Parse a policy file
+    ///
+    /// 1 行に 1 ルールを `allow <CIDR>` または `deny <CIDR>` の形式で記述します。
+    /// 空行と `#` で始まる行は無視します。
+    pub fn parse(content: &str) -> Result<Self, AccessPolicyError> {
+        let mut policy = Self::default();
+
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let invalid_rule = || AccessPolicyError::InvalidRule {
+                line: index + 1,
+                content: line.to_string(),
+            };
+            let (action, cidr) = line
+                .split_once(char::is_whitespace)
+                .ok_or_else(invalid_rule)?;
+            let cidr = cidr.parse::<Cidr>()?;
+            match action {
+                "allow" => policy.allow.push(cidr),
+                "deny" => policy.deny.push(cidr),
+                _ => return Err(invalid_rule()),
+            }
+        }
+
+        Ok(policy)
+    }
+
+    /// Load a policy file (see [`CidrAccessPolicy::parse`] for the format)
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, AccessPolicyError> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| AccessPolicyError::Io(e.to_string()))?;
+        Self::parse(&content)
+    }
+
+    /// Append the rules of another policy to this one
+    pub fn merge(mut self, other: CidrAccessPolicy) -> Self {
+        self.allow.extend(other.allow);
+        self.deny.extend(other.deny);
+        self
+    }
+}
+
+impl AccessPolicy for CidrAccessPolicy {
+    fn check(&self, addr: IpAddr) -> AccessDecision {
+        if self.deny.iter().any(|cidr| cidr.contains(addr)) {
+            return AccessDecision::Deny;
+        }
+        if self.allow.is_empty() || self.allow.iter().any(|cidr| cidr.contains(addr)) {
+            AccessDecision::Allow
+        } else {
+            AccessDecision::Deny
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_cidr_policy_allows_address_in_allow_list() {
+        // テスト項目: allow リストに含まれるアドレスは許可される
+        // given (前提条件):
+        let policy = CidrAccessPolicy::from_strs(&["192.168.0.0/16"], &[]).unwrap();
+
+        // when (操作):
+        let decision = policy.check(ip("192.168.1.10"));
+
+        // then (期待する結果):
+        assert_eq!(decision, AccessDecision::Allow);
+    }
+
+    #[test]
+    fn test_cidr_policy_denies_address_outside_allow_list() {
+        // テスト項目: allow リストに含まれないアドレスは拒否される
+        // given (前提条件):
+        let policy = CidrAccessPolicy::from_strs(&["192.168.0.0/16"], &[]).unwrap();
+
+        // when (操作):
+        let decision = policy.check(ip("10.0.0.1"));
+
+        // then (期待する結果):
+        assert_eq!(decision, AccessDecision::Deny);
+    }
+
+    #[test]
+    fn test_cidr_policy_deny_takes_precedence_over_allow() {
+        // テスト項目: deny リストは allow リストより優先される
+        // given (前提条件):
+        let policy = CidrAccessPolicy::from_strs(&["10.0.0.0/8"], &["10.1.0.0/16"]).unwrap();
+
+        // when (操作):
+        let allowed = policy.check(ip("10.2.0.1"));
+        let denied = policy.check(ip("10.1.2.3"));
+
+        // then (期待する結果):
+        assert_eq!(allowed, AccessDecision::Allow);
+        assert_eq!(denied, AccessDecision::Deny);
+    }
+
+    #[test]
+    fn test_cidr_matches_ipv4_mapped_ipv6_address() {
+        // テスト項目: IPv4 射影 IPv6 アドレスは IPv4 の CIDR と比較される
+        // given (前提条件):
+        let cidr = "127.0.0.0/8".parse::<Cidr>().unwrap();
+
+        // when (操作):
+        let result = cidr.contains(ip("::ffff:127.0.0.1"));
+
+        // then (期待する結果):
+        assert!(result);
+    }
+
+    #[test]
+    fn test_cidr_rejects_invalid_prefix_length() {
+        // テスト項目: 範囲外のプレフィックス長はエラーになる
+        // given (前提条件):
+        let input = "10.0.0.0/33";
+
+        // when (操作):
+        let result = input.parse::<Cidr>();
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(AccessPolicyError::InvalidCidr("10.0.0.0/33".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_policy_file() {
+        // テスト項目: ポリシーファイルの allow/deny ルールとコメントが解釈される
+        // given (前提条件):
+        let content = "# office network\nallow 192.168.0.0/16\n\ndeny 192.168.100.1\n";
+
+        // when (操作):
+        let policy = CidrAccessPolicy::parse(content).unwrap();
+
+        // then (期待する結果):
+        assert_eq!(policy.check(ip("192.168.1.1")), AccessDecision::Allow);
+        assert_eq!(policy.check(ip("192.168.100.1")), AccessDecision::Deny);
+        assert_eq!(policy.check(ip("172.16.0.1")), AccessDecision::Deny);
+    }
+}
@@ -0,0 +1,107 @@
+//! Admin commands sent over the WebSocket.
+//!
+//! 管理者の接続は、チャットの内容として `/admin <command> <argument>` を送信して
+//! Room を操作できます。コマンドはチャットとして保存も配信もされず、
+//! ハンドラーが対応するユースケースを実行します。管理者でない接続からのコマンドは拒否します。
+
+/// 管理者コマンドであることを示す接頭辞
+pub(crate) const ADMIN_COMMAND_PREFIX: &str = "/admin";
+
+/// 管理者コマンド
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AdminCommand {
+    /// 参加者を Room から強制退出させる（`/admin kick <client_id>`）
+    Kick(String),
+    /// 参加者をミュートする（`/admin mute <client_id>`）
+    Mute(String),
+    /// 参加者のミュートを解除する（`/admin unmute <client_id>`）
+    Unmute(String),
+    /// 全ての参加者にお知らせを送る（`/admin announce <text>`）
+    Announce(String),
+}
+
+impl AdminCommand {
+    /// チャットの内容を管理者コマンドとして解析
+    ///
+    /// # Returns
+    ///
+    /// * `None` - `/admin` で始まらない（通常のチャットメッセージ）
+    /// * `Some(Ok(AdminCommand))` - 解析したコマンド
+    /// * `Some(Err(String))` - 管理者コマンドだが、不明なコマンドか引数が不正（使い方の説明）
+    pub(crate) fn parse(content: &str) -> Option<Result<Self, String>> {
+        let rest = content.strip_prefix(ADMIN_COMMAND_PREFIX)?;
+        // "/administrator" などは管理者コマンドではない
+        if !rest.is_empty() && !rest.starts_with(char::is_whitespace) {
+            return None;
+        }
+
+        let rest = rest.trim();
+        let (name, argument) = rest
+            .split_once(char::is_whitespace)
+            .map_or((rest, ""), |(name, argument)| (name, argument.trim()));
+        let is_client_id = !argument.is_empty() && !argument.contains(char::is_whitespace);
+
+        Some(match name {
+            "kick" if is_client_id => Ok(Self::Kick(argument.to_string())),
+            "mute" if is_client_id => Ok(Self::Mute(argument.to_string())),
+            "unmute" if is_client_id => Ok(Self::Unmute(argument.to_string())),
+            "announce" if !argument.is_empty() => Ok(Self::Announce(argument.to_string())),
+            "kick" | "mute" | "unmute" => Err(format!(
+                "Usage: {} {} <client_id>",
+                ADMIN_COMMAND_PREFIX, name
+            )),
+            "announce" => Err(format!("Usage: {} announce <text>", ADMIN_COMMAND_PREFIX)),
+            _ => Err(format!(
+                "Unknown admin command '{}' (expected kick, mute, unmute or announce)",
+                name
+            )),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_admin_commands() {
+        // テスト項目: `/admin` で始まる内容だけがコマンドとして解析され、不正な引数は使い方の説明になる
+        // given (前提条件):
+        let contents = [
+            "/admin kick bob",
+            "/admin  mute   bob ",
+            "/admin unmute bob",
+            "/admin announce Maintenance at 18:00",
+            "/admin kick",
+            "/admin kick bob alice",
+            "/admin ban bob",
+            "/administrator kick bob",
+            "hello /admin kick bob",
+        ];
+
+        // when (操作):
+        let parsed: Vec<_> = contents.iter().map(|c| AdminCommand::parse(c)).collect();
+
+        // then (期待する結果):
+        assert_eq!(parsed[0], Some(Ok(AdminCommand::Kick("bob".to_string()))));
+        assert_eq!(parsed[1], Some(Ok(AdminCommand::Mute("bob".to_string()))));
+        assert_eq!(parsed[2], Some(Ok(AdminCommand::Unmute("bob".to_string()))));
+        assert_eq!(
+            parsed[3],
+            Some(Ok(AdminCommand::Announce(
+                "Maintenance at 18:00".to_string()
+            )))
+        );
+        assert_eq!(
+            parsed[4],
+            Some(Err("Usage: /admin kick <client_id>".to_string()))
+        );
+        assert_eq!(
+            parsed[5],
+            Some(Err("Usage: /admin kick <client_id>".to_string()))
+        );
+        assert!(matches!(parsed[6], Some(Err(_))));
+        assert_eq!(parsed[7], None);
+        assert_eq!(parsed[8], None);
+    }
+}
@@ -0,0 +1,65 @@
+//! Inbound frame rate limiting.
+//!
+//! チャットの内容とは無関係に、1 接続が受信する WebSocket フレーム数（ping なども含む）を
+//! 1 秒ごとの固定ウィンドウで数えます。フレームの大量送信からサーバーを守るためのもので、
+//! 上限を超えた接続はハンドラーが切断します。
+
+use std::time::{Duration, Instant};
+
+/// フレーム数を数えるウィンドウの長さ
+const FRAME_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+/// 1 接続あたりの受信フレーム数の上限を判定する
+#[derive(Debug)]
+pub(crate) struct FrameRateLimiter {
+    /// 1 秒あたりに受け付けるフレーム数の上限
+    max_frames_per_sec: u32,
+    /// 現在のウィンドウの開始時刻
+    window_start: Instant,
+    /// 現在のウィンドウで受信したフレーム数
+    frames_in_window: u32,
+}
+
+impl FrameRateLimiter {
+    /// 新しい FrameRateLimiter を作成（最初のウィンドウは `now` から始まる）
+    pub(crate) fn new(max_frames_per_sec: u32, now: Instant) -> Self {
+        Self {
+            max_frames_per_sec,
+            window_start: now,
+            frames_in_window: 0,
+        }
+    }
+
+    /// `now` に受信したフレームを数え、上限以内なら `true` を返す
+    pub(crate) fn record_frame(&mut self, now: Instant) -> bool {
+        if now.duration_since(self.window_start) >= FRAME_RATE_WINDOW {
+            self.window_start = now;
+            self.frames_in_window = 0;
+        }
+        self.frames_in_window = self.frames_in_window.saturating_add(1);
+        self.frames_in_window <= self.max_frames_per_sec
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frames_over_limit_within_a_window_are_rejected() {
+        // テスト項目: 1 秒以内に上限を超えたフレームは拒否され、次のウィンドウでは再び受け付けられる
+        // given (前提条件):
+        let start = Instant::now();
+        let mut limiter = FrameRateLimiter::new(3, start);
+
+        // when (操作):
+        let within_window: Vec<bool> = (0..4)
+            .map(|i| limiter.record_frame(start + Duration::from_millis(i * 100)))
+            .collect();
+        let next_window = limiter.record_frame(start + Duration::from_millis(1000));
+
+        // then (期待する結果):
+        assert_eq!(within_window, vec![true, true, true, false]);
+        assert!(next_window);
+    }
+}
@@ -0,0 +1,148 @@
+//! JSON error responses for the HTTP API.
+//!
+//! エラー時もステータスコードに加えて `{"error": {"code", "message"}}` 形式の
+//! ボディを返し、クライアントがエラーの種類を機械的に判別できるようにします。
+
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    infrastructure::dto::http::{ErrorBodyDto, ErrorResponseDto},
+    usecase::{GetParticipantError, GetRoomDetailError, PurgeMessagesError},
+};
+
+/// Error returned by an HTTP handler
+#[derive(Debug)]
+pub struct ApiError {
+    /// レスポンスのステータスコード
+    status: StatusCode,
+    /// 機械判別用のエラーコード（例: "room-not-found"）
+    code: &'static str,
+    /// 人が読むためのエラーの説明
+    message: String,
+}
+
+impl ApiError {
+    /// 404: the room does not exist
+    pub fn room_not_found() -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "room-not-found",
+            message: "Room not found".to_string(),
+        }
+    }
+
+    /// 404: the participant is not in the room
+    pub fn participant_not_found() -> Self {
+        Self {
+            status: StatusCode::NOT_FOUND,
+            code: "participant-not-found",
+            message: "Participant not found".to_string(),
+        }
+    }
+
+    /// 401: the request does not carry the admin token
+    pub fn unauthorized() -> Self {
+        Self {
+            status: StatusCode::UNAUTHORIZED,
+            code: "unauthorized",
+            message: "A valid admin token is required".to_string(),
+        }
+    }
+
+    /// 500: the repository failed
+    pub fn repository_error() -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            code: "repository-error",
+            message: "Failed to read room data".to_string(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = ErrorResponseDto {
+            error: ErrorBodyDto {
+                code: self.code.to_string(),
+                message: self.message,
+            },
+        };
+        (self.status, Json(body)).into_response()
+    }
+}
+
+impl From<GetRoomDetailError> for ApiError {
+    fn from(e: GetRoomDetailError) -> Self {
+        match e {
+            GetRoomDetailError::RoomNotFound => Self::room_not_found(),
+            GetRoomDetailError::RepositoryError => Self::repository_error(),
+        }
+    }
+}
+
+impl From<PurgeMessagesError> for ApiError {
+    fn from(e: PurgeMessagesError) -> Self {
+        match e {
+            PurgeMessagesError::RoomNotFound => Self::room_not_found(),
+            PurgeMessagesError::RepositoryError => Self::repository_error(),
+        }
+    }
+}
+
+impl From<GetParticipantError> for ApiError {
+    fn from(e: GetParticipantError) -> Self {
+        match e {
+            GetParticipantError::RoomNotFound => Self::room_not_found(),
+            GetParticipantError::ParticipantNotFound => Self::participant_not_found(),
+            GetParticipantError::RepositoryError => Self::repository_error(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn into_status_and_body(error: ApiError) -> (StatusCode, serde_json::Value) {
+        let response = error.into_response();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_room_not_found_error_body() {
+        // テスト項目: ルームが存在しない場合は 404 と room-not-found のエラーボディを返す
+        // given (前提条件):
+        let error = ApiError::from(GetRoomDetailError::RoomNotFound);
+
+        // when (操作):
+        let (status, body) = into_status_and_body(error).await;
+
+        // then (期待する結果):
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["error"]["code"], "room-not-found");
+        assert_eq!(body["error"]["message"], "Room not found");
+    }
+
+    #[tokio::test]
+    async fn test_repository_error_body() {
+        // テスト項目: Repository エラーは 500 と repository-error のエラーボディを返す
+        // given (前提条件):
+        let error = ApiError::from(GetRoomDetailError::RepositoryError);
+
+        // when (操作):
+        let (status, body) = into_status_and_body(error).await;
+
+        // then (期待する結果):
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(body["error"]["code"], "repository-error");
+        assert!(body["error"]["message"].is_string());
+    }
+}
@@ -4,25 +4,42 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{HeaderMap, StatusCode, header::AUTHORIZATION},
 };
+use serde::Deserialize;
 
 use crate::{
     domain::Room,
-    infrastructure::dto::http::{ParticipantDetailDto, RoomDetailDto, RoomSummaryDto},
-    ui::state::AppState,
+    infrastructure::dto::{
+        http::{
+            MetricsDto, ParticipantDetailDto, ParticipantOnlineDto, ParticipantStatusDto,
+            PurgeMessagesDto, ReceiveErrorsDto, RejectedConnectionsDto, RoomDetailDto,
+            RoomSummaryDto,
+        },
+        websocket::{HistoryClearedMessage, MessageType},
+    },
+    ui::{
+        handler::{
+            error::ApiError,
+            json::{FormattedJson, JsonFormatQuery},
+        },
+        state::AppState,
+    },
 };
 use engawa_shared::time::timestamp_to_jst_rfc3339;
 
 /// Debug endpoint to get current room state (for testing purposes)
-pub async fn debug_room_state(State(state): State<Arc<AppState>>) -> Json<Room> {
+pub async fn debug_room_state(
+    State(state): State<Arc<AppState>>,
+    Query(format): Query<JsonFormatQuery>,
+) -> FormattedJson<Room> {
     let room = state
         .get_room_state_usecase
         .execute()
         .await
         .expect("Failed to get room state");
-    Json(room)
+    FormattedJson::new(room, state.pretty_json || format.is_pretty())
 }
 
 /// Health check endpoint
@@ -30,8 +47,53 @@ pub async fn health_check() -> Json<serde_json::Value> {
     Json(serde_json::json!({"status": "ok"}))
 }
 
+/// Readiness endpoint
+///
+/// Returns 503 until the server serves requests and again while it drains on shutdown.
+pub async fn readiness_check(
+    State(state): State<Arc<AppState>>,
+) -> (StatusCode, Json<serde_json::Value>) {
+    let readiness = state.readiness.state();
+    let status = if state.readiness.is_ready() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({"status": readiness.as_str()})),
+    )
+}
+
+/// Metrics endpoint
+pub async fn get_metrics(State(state): State<Arc<AppState>>) -> Json<MetricsDto> {
+    let rejected = state.metrics.rejected_connections();
+    let receive_errors = state.metrics.receive_errors();
+    Json(MetricsDto {
+        rejected_connections: RejectedConnectionsDto {
+            duplicate_id: rejected.duplicate_id,
+            capacity: rejected.capacity,
+            invalid_id: rejected.invalid_id,
+            invalid_status: rejected.invalid_status,
+            auth_failure: rejected.auth_failure,
+            access_denied: rejected.access_denied,
+            per_ip_limit: rejected.per_ip_limit,
+            internal_error: rejected.internal_error,
+        },
+        receive_errors: ReceiveErrorsDto {
+            protocol_violation: receive_errors.protocol_violation,
+            message_too_large: receive_errors.message_too_large,
+            connection_reset: receive_errors.connection_reset,
+            other: receive_errors.other,
+        },
+    })
+}
+
 /// Get list of rooms
-pub async fn get_rooms(State(state): State<Arc<AppState>>) -> Json<Vec<RoomSummaryDto>> {
+pub async fn get_rooms(
+    State(state): State<Arc<AppState>>,
+    Query(format): Query<JsonFormatQuery>,
+) -> FormattedJson<Vec<RoomSummaryDto>> {
     let rooms = state
         .get_rooms_usecase
         .execute()
@@ -41,25 +103,28 @@ pub async fn get_rooms(State(state): State<Arc<AppState>>) -> Json<Vec<RoomSumma
     // Domain Model から DTO への変換
     let room_summaries: Vec<RoomSummaryDto> = rooms
         .into_iter()
-        .map(|room| RoomSummaryDto {
-            id: room.id.as_str().to_string(),
-            participants: room
+        .map(|summary| RoomSummaryDto {
+            id: summary.room.id.as_str().to_string(),
+            participants: summary
+                .room
                 .participants
                 .iter()
                 .map(|p| p.id.as_str().to_string())
                 .collect(),
-            created_at: timestamp_to_jst_rfc3339(room.created_at.value()),
+            created_at: timestamp_to_jst_rfc3339(summary.room.created_at.value()),
+            age_secs: summary.age_secs,
         })
         .collect();
 
-    Json(room_summaries)
+    FormattedJson::new(room_summaries, state.pretty_json || format.is_pretty())
 }
 
 /// Get room detail by ID
 pub async fn get_room_detail(
     State(state): State<Arc<AppState>>,
     Path(room_id): Path<String>,
-) -> Result<Json<RoomDetailDto>, StatusCode> {
+    Query(format): Query<JsonFormatQuery>,
+) -> Result<FormattedJson<RoomDetailDto>, ApiError> {
     match state.get_room_detail_usecase.execute(room_id).await {
         Ok(room) => {
             // Domain Model から DTO への変換
@@ -75,11 +140,104 @@ pub async fn get_room_detail(
                     .collect(),
                 created_at: timestamp_to_jst_rfc3339(room.created_at.value()),
             };
-            Ok(Json(room_detail))
+            Ok(FormattedJson::new(
+                room_detail,
+                state.pretty_json || format.is_pretty(),
+            ))
         }
-        Err(crate::usecase::GetRoomDetailError::RoomNotFound) => Err(StatusCode::NOT_FOUND),
-        Err(crate::usecase::GetRoomDetailError::RepositoryError) => {
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Get a single participant in a room
+pub async fn get_participant(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, client_id)): Path<(String, String)>,
+) -> Result<Json<ParticipantStatusDto>, ApiError> {
+    match state
+        .get_participant_usecase
+        .execute(room_id, client_id)
+        .await
+    {
+        Ok(detail) => {
+            // Domain Model から DTO への変換
+            let participant_status = ParticipantStatusDto {
+                client_id: detail.participant.id.as_str().to_string(),
+                connected_at: timestamp_to_jst_rfc3339(detail.participant.connected_at.value()),
+                // Room に存在する参加者は接続中
+                presence: "online".to_string(),
+                status: detail.participant.status.as_str().to_string(),
+                last_active: timestamp_to_jst_rfc3339(detail.last_active.value()),
+            };
+            Ok(Json(participant_status))
         }
+        Err(e) => Err(e.into()),
     }
 }
+
+/// Check whether a participant is currently connected to a room
+pub async fn get_participant_online(
+    State(state): State<Arc<AppState>>,
+    Path((room_id, client_id)): Path<(String, String)>,
+) -> Result<Json<ParticipantOnlineDto>, ApiError> {
+    match state
+        .get_participant_usecase
+        .is_online(room_id, client_id)
+        .await
+    {
+        Ok(online) => Ok(Json(ParticipantOnlineDto { online })),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Query parameters of the purge messages endpoint
+#[derive(Debug, Default, Deserialize)]
+pub struct PurgeMessagesQuery {
+    /// Broadcast a `history-cleared` frame so that clients clear their view (default: `true`)
+    #[serde(default)]
+    pub notify: Option<bool>,
+}
+
+/// Purge the message history of a room
+///
+/// `Authorization: Bearer <admin_token>` ヘッダーで管理者トークンを要求します
+/// （[`Server::with_admin_token`](crate::ui::Server::with_admin_token)）。
+/// トークンが設定されていないサーバーでは常に 401 を返します。
+pub async fn purge_messages(
+    State(state): State<Arc<AppState>>,
+    Path(room_id): Path<String>,
+    Query(query): Query<PurgeMessagesQuery>,
+    headers: HeaderMap,
+) -> Result<Json<PurgeMessagesDto>, ApiError> {
+    let token = headers
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if token.is_none() || token != state.admin_token.as_deref() {
+        tracing::warn!("Purge of room {} denied: invalid admin token", room_id);
+        return Err(ApiError::unauthorized());
+    }
+
+    let purged = state
+        .purge_messages_usecase
+        .execute(room_id.clone())
+        .await?;
+    tracing::info!("Purged {} message(s) from room {}", purged, room_id);
+
+    if query.notify.unwrap_or(true) {
+        let cleared = HistoryClearedMessage {
+            r#type: MessageType::HistoryCleared,
+            purged,
+        };
+        match serde_json::to_string(&cleared) {
+            Ok(json) => {
+                if let Err(e) = state.purge_messages_usecase.notify_cleared(&json).await {
+                    tracing::warn!("Failed to broadcast history-cleared: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("Failed to serialize history-cleared: {}", e),
+        }
+    }
+
+    Ok(Json(PurgeMessagesDto { purged }))
+}
@@ -0,0 +1,53 @@
+//! JSON response formatting for the HTTP handlers.
+//!
+//! 本番環境ではコンパクトな JSON を返し、人が確認する場合（`?pretty=1` 指定時、
+//! またはサーバーのデバッグモード有効時）のみインデント付きの JSON を返します。
+
+use axum::{
+    Json,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters controlling the JSON output format
+#[derive(Debug, Default, Deserialize)]
+pub struct JsonFormatQuery {
+    /// `1` または `true` でインデント付きの JSON を返す
+    pub pretty: Option<String>,
+}
+
+impl JsonFormatQuery {
+    /// Whether pretty-printing was requested
+    pub fn is_pretty(&self) -> bool {
+        matches!(self.pretty.as_deref(), Some("1" | "true"))
+    }
+}
+
+/// JSON response that is pretty-printed on request
+pub struct FormattedJson<T> {
+    /// レスポンスボディとしてシリアライズする値
+    value: T,
+    /// インデント付きでシリアライズするかどうか
+    pretty: bool,
+}
+
+impl<T> FormattedJson<T> {
+    /// Create a new FormattedJson response
+    pub fn new(value: T, pretty: bool) -> Self {
+        Self { value, pretty }
+    }
+}
+
+impl<T: Serialize> IntoResponse for FormattedJson<T> {
+    fn into_response(self) -> Response {
+        if !self.pretty {
+            return Json(self.value).into_response();
+        }
+
+        match serde_json::to_string_pretty(&self.value) {
+            Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+        }
+    }
+}
@@ -1,10 +1,15 @@
 //! Handler modules for HTTP and WebSocket endpoints.
 
+pub mod error;
 pub mod http;
+pub mod json;
 pub mod websocket;
 
 // Re-export HTTP handlers
-pub use http::{debug_room_state, get_room_detail, get_rooms, health_check};
+pub use http::{
+    debug_room_state, get_metrics, get_participant, get_participant_online, get_room_detail,
+    get_rooms, health_check, purge_messages, readiness_check,
+};
 
 // Re-export WebSocket handlers
 pub use websocket::websocket_handler;
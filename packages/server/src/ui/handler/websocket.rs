@@ -1,89 +1,268 @@
 //! WebSocket connection handlers.
 
-use std::sync::Arc;
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use axum::{
     extract::{
-        Query, State,
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, Query, State,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
     },
-    http::StatusCode,
-    response::IntoResponse,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+};
+use base64::{Engine, engine::general_purpose::STANDARD as BASE64};
+use futures_util::{
+    sink::{Sink, SinkExt},
+    stream::StreamExt,
 };
-use futures_util::{sink::SinkExt, stream::StreamExt};
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot, watch};
 
 use crate::{
-    domain::{ClientId, MessageContent, Timestamp},
-    infrastructure::dto::websocket::{
-        ChatMessage, MessageType, ParticipantJoinedMessage, ParticipantLeftMessage,
-        RoomConnectedMessage,
+    domain::{
+        BroadcastReport, ClientId, Emoji, FileAttachment, MessageContent, MessageId,
+        MessageIdFactory, PUSHER_CHANNEL_CAPACITY, PresenceStatus, PusherChannel, ReactionAction,
+        RoomId, Timestamp, message_expiry,
+    },
+    infrastructure::dto::{
+        conversion::{TimestampPrecision, participant_to_dto},
+        websocket::{
+            AckMessage, AppPingMessage, AppPongMessage, CLOSE_CODE_KICKED, CLOSE_CODE_REPLACED,
+            ChatMessage, ErrorMessage, FileMessage, HeartbeatMessage, MessageType,
+            ParticipantCountMessage, ParticipantInfo, ParticipantJoinedMessage,
+            ParticipantLeftMessage, ReactionMessage, ReactionUpdatedMessage, ReadyMessage,
+            RoomConnectedMessage, RosterDeltaMessage, RosterMessage, RosterRequestMessage,
+            SystemMessage,
+        },
+    },
+    ui::{
+        access_policy::AccessDecision, admin_command::AdminCommand, frame_rate::FrameRateLimiter,
+        metrics::RejectionReason, receive_error::ReceiveErrorKind, state::AppState,
+    },
+    usecase::{
+        AdminError, ConnectParticipantUseCase, Connection, DisconnectParticipantUseCase,
+        ReactError, RosterEntry, RosterUpdate, SendMessageError, SendMessageOutcome,
     },
-    ui::state::AppState,
 };
 use engawa_shared::time::get_jst_timestamp;
 
 use serde::Deserialize;
 
+/// Convert a roster entry into the participant DTO including its idle duration
+fn roster_entry_to_dto(entry: RosterEntry, precision: TimestampPrecision) -> ParticipantInfo {
+    ParticipantInfo {
+        idle_ms: entry.idle_ms,
+        ..participant_to_dto(entry.participant, precision)
+    }
+}
+
+/// Time to wait for the close frame to be sent when the server closes a connection itself
+const SERVER_CLOSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Parameters of an established WebSocket session, logged once after the initial frames
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SessionParameters {
+    client_id: String,
+    /// Room the client joined (`None` if the room state could not be read)
+    room_id: Option<String>,
+    /// Subprotocol selected during the handshake (`Sec-WebSocket-Protocol`)
+    subprotocol: Option<String>,
+    /// Whether per-message compression was negotiated
+    compression: bool,
+    /// Maximum size of a message accepted from the client, in bytes
+    max_message_size: usize,
+}
+
+impl SessionParameters {
+    /// Emit the parameters as a single structured log line
+    fn log(&self) {
+        tracing::info!(
+            client_id = %self.client_id,
+            room_id = %self.room_id.as_deref().unwrap_or("unknown"),
+            subprotocol = %self.subprotocol.as_deref().unwrap_or("none"),
+            compression = self.compression,
+            max_message_size = self.max_message_size,
+            "Connection established"
+        );
+    }
+}
+
 /// Query parameters for WebSocket connection
 #[derive(Debug, Deserialize)]
 pub struct ConnectQuery {
     pub client_id: String,
+    /// Initial presence status (`active`, `away` or `dnd`; default: `active`)
+    #[serde(default)]
+    pub status: Option<String>,
+    /// Token identifying an admin connection (see [`Server::with_admin_token`](crate::ui::Server::with_admin_token))
+    #[serde(default)]
+    pub admin_token: Option<String>,
+    /// Token proving the client's identity, checked by the
+    /// [`IdentityVerifier`](crate::domain::IdentityVerifier) before the client joins
+    #[serde(default)]
+    pub token: Option<String>,
 }
 
 pub async fn websocket_handler(
     ws: WebSocketUpgrade,
+    ConnectInfo(remote_addr): ConnectInfo<SocketAddr>,
     State(state): State<Arc<AppState>>,
     Query(query): Query<ConnectQuery>,
-) -> Result<impl IntoResponse, StatusCode> {
+) -> Result<impl IntoResponse, Response> {
     let client_id_str = query.client_id;
 
+    // Check the source address before upgrading the connection
+    if state.access_policy.check(remote_addr.ip()) == AccessDecision::Deny {
+        tracing::warn!(
+            "Connection from {} denied by access policy (client_id: '{}')",
+            remote_addr,
+            client_id_str
+        );
+        state
+            .metrics
+            .record_rejection(RejectionReason::AccessDenied);
+        return Err(StatusCode::FORBIDDEN.into_response());
+    }
+
+    // Count the connection against its source address until it ends, whichever way it ends
+    let ip_connection = match &state.ip_connection_limiter {
+        Some(limiter) => match limiter.try_acquire(remote_addr.ip()) {
+            Some(guard) => Some(guard),
+            None => {
+                tracing::warn!(
+                    "Connection from {} denied: too many connections from this address (client_id: '{}')",
+                    remote_addr,
+                    client_id_str
+                );
+                state.metrics.record_rejection(RejectionReason::PerIpLimit);
+                return Err(StatusCode::TOO_MANY_REQUESTS.into_response());
+            }
+        },
+        None => None,
+    };
+
+    // A connection presenting an admin token must present the configured one
+    let is_admin = match (&query.admin_token, &state.admin_token) {
+        (None, _) => false,
+        (Some(given), Some(expected)) if given == expected => true,
+        (Some(_), _) => {
+            tracing::warn!(
+                "Connection from {} denied: invalid admin token (client_id: '{}')",
+                remote_addr,
+                client_id_str
+            );
+            state.metrics.record_rejection(RejectionReason::AuthFailure);
+            return Err(StatusCode::FORBIDDEN.into_response());
+        }
+    };
+
     // Convert String -> ClientId (Domain Model)
     let client_id = match ClientId::try_from(client_id_str.clone()) {
         Ok(id) => id,
         Err(_) => {
             tracing::warn!("Invalid client_id format: '{}'", client_id_str);
-            return Err(StatusCode::BAD_REQUEST);
+            state.metrics.record_rejection(RejectionReason::InvalidId);
+            return Err(StatusCode::BAD_REQUEST.into_response());
+        }
+    };
+
+    // Convert String -> PresenceStatus (Domain Model)
+    let status = match query.status.as_deref().map(PresenceStatus::try_from) {
+        None => PresenceStatus::default(),
+        Some(Ok(status)) => status,
+        Some(Err(_)) => {
+            tracing::warn!(
+                "Invalid status for '{}': '{}'",
+                client_id_str,
+                query.status.unwrap_or_default()
+            );
+            state
+                .metrics
+                .record_rejection(RejectionReason::InvalidStatus);
+            return Err(StatusCode::BAD_REQUEST.into_response());
         }
     };
 
     // Create a channel for this client to receive messages
-    let (tx, rx) = mpsc::unbounded_channel();
+    let (tx, rx) = mpsc::channel(PUSHER_CHANNEL_CAPACITY);
 
     // Use ConnectParticipantUseCase to handle connection
     // (register_client is called inside the UseCase)
+    //
+    // Registration is the only duplicate check: the repository checks and adds the
+    // participant atomically, before the connection is upgraded. Of two requests racing
+    // with the same client_id, the one that loses is answered with 409 Conflict and never
+    // becomes a live socket.
     let client_id_for_handle = client_id.clone();
+    // Keep a handle to this connection's channel to unregister only this connection on close
+    let connection_tx = tx.clone();
     match state
         .connect_participant_usecase
-        .execute(client_id, tx)
+        .connect_with_status(client_id, status, query.token.as_deref(), tx)
         .await
     {
-        Ok(connected_at) => {
-            tracing::info!("Client '{}' connected and registered", client_id_str);
-            Ok(ws.on_upgrade(move |socket| {
-                handle_socket(
-                    socket,
-                    state,
-                    client_id_str,
-                    rx,
-                    connected_at,
-                    client_id_for_handle,
-                )
-            }))
+        Ok(connection) => {
+            tracing::info!(
+                "Client '{}' connected and registered (additional connection: {}, admin: {})",
+                client_id_str,
+                connection.is_additional,
+                is_admin
+            );
+            Ok(ws
+                .max_message_size(state.max_message_size)
+                .on_upgrade(move |socket| async move {
+                    let _ip_connection = ip_connection;
+                    handle_socket(
+                        socket,
+                        state,
+                        client_id_str,
+                        (connection_tx, rx),
+                        connection,
+                        status,
+                        client_id_for_handle,
+                        is_admin,
+                    )
+                    .await
+                }))
         }
         Err(crate::usecase::ConnectError::DuplicateClientId(_)) => {
             tracing::warn!(
                 "Client with ID '{}' is already connected. Rejecting connection.",
                 client_id_str
             );
-            Err(StatusCode::CONFLICT)
+            state.metrics.record_rejection(RejectionReason::DuplicateId);
+            Err(StatusCode::CONFLICT.into_response())
         }
         Err(crate::usecase::ConnectError::RoomCapacityExceeded) => {
             tracing::warn!(
                 "Room capacity exceeded. Cannot add participant '{}'",
                 client_id_str
             );
-            Err(StatusCode::SERVICE_UNAVAILABLE)
+            state.metrics.record_rejection(RejectionReason::Capacity);
+            // 再接続までの待ち時間をクライアントに伝える
+            Err((
+                StatusCode::SERVICE_UNAVAILABLE,
+                [(header::RETRY_AFTER, state.retry_after.as_secs().to_string())],
+            )
+                .into_response())
+        }
+        Err(crate::usecase::ConnectError::Unauthorized(e)) => {
+            tracing::warn!(
+                "Identity of '{}' could not be verified: {}",
+                client_id_str,
+                e
+            );
+            state.metrics.record_rejection(RejectionReason::AuthFailure);
+            Err(StatusCode::UNAUTHORIZED.into_response())
+        }
+        Err(crate::usecase::ConnectError::RepositoryError(e)) => {
+            tracing::error!("Failed to add participant '{}': {}", client_id_str, e);
+            state.metrics.record_rejection(RejectionReason::Internal);
+            Err(StatusCode::INTERNAL_SERVER_ERROR.into_response())
         }
     }
 }
@@ -93,168 +272,1020 @@ pub async fn websocket_handler(
 /// This function handles the outbound message flow: messages from other clients (via rx channel)
 /// are sent to this client's WebSocket connection.
 ///
+/// The channel closes only when the MessagePusher has released this connection
+/// (it was replaced by a newer connection with the same client_id, or its participant
+/// was kicked); the client is then sent the close frame resolved by `released`.
+/// A close frame received through `close_rx` is sent instead of further messages.
+/// When the server shuts down (`closing` becomes `true`), the messages already queued
+/// are sent first, then the connection is closed with "going away".
+///
 /// # Arguments
 ///
 /// * `rx` - Channel receiver for messages from other clients
 /// * `sender` - WebSocket sink to send messages to this client
+/// * `close_rx` - Close frame to send when the server closes the connection itself
+/// * `closing` - Shutdown notification ([`ActiveConnections::closing`](crate::ui::idle_shutdown::ActiveConnections::closing))
+/// * `released` - Close frame to send when the MessagePusher released this connection
+///   (see [`released_close_frame`])
 ///
 /// # Returns
 ///
 /// A `JoinHandle` for the spawned task
 fn pusher_loop(
-    mut rx: mpsc::UnboundedReceiver<String>,
+    mut rx: mpsc::Receiver<String>,
     mut sender: futures_util::stream::SplitSink<WebSocket, Message>,
+    close_rx: oneshot::Receiver<CloseFrame>,
+    mut closing: watch::Receiver<bool>,
+    released: impl Future<Output = CloseFrame> + Send + 'static,
 ) -> tokio::task::JoinHandle<()> {
     tokio::spawn(async move {
-        while let Some(msg) = rx.recv().await {
-            // Send the message to this client
-            if sender.send(Message::Text(msg.into())).await.is_err() {
-                break;
+        let forward = async {
+            loop {
+                // Queued messages take priority, so that they are flushed before closing on shutdown
+                let msg = tokio::select! {
+                    biased;
+                    msg = rx.recv() => msg,
+                    Ok(_) = closing.wait_for(|closing| *closing) => {
+                        return Some(CloseFrame {
+                            code: close_code::AWAY,
+                            reason: "Server shutting down".into(),
+                        });
+                    }
+                };
+                let Some(msg) = msg else {
+                    return Some(released.await);
+                };
+                // Send the message to this client
+                if sender.send(Message::Text(msg.into())).await.is_err() {
+                    return None;
+                }
             }
+        };
+        let close = tokio::select! {
+            close = forward => close,
+            Ok(close) = close_rx => Some(close),
+        };
+
+        if let Some(close) = close {
+            let _ = sender.send(Message::Close(Some(close))).await;
         }
     })
 }
 
-async fn handle_socket(
-    socket: WebSocket,
-    state: Arc<AppState>,
-    client_id_str: String,
-    rx: mpsc::UnboundedReceiver<String>,
-    connected_at: Timestamp,
-    client_id: ClientId,
+/// Close frame for a connection released by the MessagePusher
+///
+/// A kicked participant has already been removed from the room when its connection is
+/// released; a replaced connection's participant stays in the room with the new connection.
+async fn released_close_frame(state: Arc<AppState>, client_id: ClientId) -> CloseFrame {
+    if state.admin_usecase.is_participant(&client_id).await {
+        CloseFrame {
+            code: CLOSE_CODE_REPLACED,
+            reason: "Replaced by a new connection".into(),
+        }
+    } else {
+        CloseFrame {
+            code: CLOSE_CODE_KICKED,
+            reason: "Kicked by an admin".into(),
+        }
+    }
+}
+
+/// Build an error frame sent only to the client whose request was rejected
+fn build_error_json(code: &str, message: String) -> String {
+    let error_msg = ErrorMessage {
+        r#type: MessageType::Error,
+        code: code.to_string(),
+        message,
+        retry_after_ms: None,
+    };
+    serde_json::to_string(&error_msg).unwrap()
+}
+
+/// Validate a shared file and broadcast it to the other clients.
+///
+/// Invalid or oversized files are rejected with an error frame sent only to the sender.
+async fn handle_file_message(state: &AppState, client_id: &ClientId, file_msg: FileMessage) {
+    let error_json = match BASE64.decode(&file_msg.data) {
+        Ok(data) => {
+            match FileAttachment::new(file_msg.filename.clone(), file_msg.mime.clone(), data.len())
+            {
+                Ok(attachment) => {
+                    // Use the connection's client_id as the sender
+                    let response = FileMessage {
+                        client_id: client_id.as_str().to_string(),
+                        ..file_msg
+                    };
+                    let response_json = serde_json::to_string(&response).unwrap();
+
+                    match state
+                        .send_file_usecase
+                        .execute(client_id.clone(), attachment, response_json)
+                        .await
+                    {
+                        Ok(_broadcast_targets) => {
+                            tracing::info!(
+                                "Broadcasted file '{}' ({} bytes) from '{}'",
+                                response.filename,
+                                data.len(),
+                                client_id
+                            );
+                            return;
+                        }
+                        Err(crate::usecase::SendFileError::FileTooLarge { max, actual }) => {
+                            tracing::warn!(
+                                "Rejected file from '{}': {} bytes exceeds {} bytes",
+                                client_id,
+                                actual,
+                                max
+                            );
+                            build_error_json(
+                                "file-too-large",
+                                format!("File exceeds {} bytes (got {} bytes)", max, actual),
+                            )
+                        }
+                        Err(e) => {
+                            tracing::warn!("Failed to send file: {:?}", e);
+                            return;
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!("Invalid file from '{}': {}", client_id, e);
+                    build_error_json("invalid-file", e.to_string())
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Invalid base64 file data from '{}': {}", client_id, e);
+            build_error_json("invalid-file", format!("Invalid base64 data: {}", e))
+        }
+    };
+
+    if let Err(e) = state
+        .send_file_usecase
+        .notify_sender(client_id, &error_json)
+        .await
+    {
+        tracing::warn!("Failed to send error frame to '{}': {}", client_id, e);
+    }
+}
+
+/// Record a reaction to a chat message and broadcast the updated count to all clients.
+///
+/// Invalid reactions and reactions to messages not in the history are rejected with an
+/// error frame sent only to the reacting client.
+async fn handle_reaction_message(
+    state: &AppState,
+    client_id: &ClientId,
+    reaction_msg: ReactionMessage,
 ) {
-    let (mut sender, mut receiver) = socket.split();
+    let parsed = Emoji::new(reaction_msg.emoji.clone()).and_then(|emoji| {
+        ReactionAction::try_from(reaction_msg.action.as_str()).map(|action| (emoji, action))
+    });
+    let error_json = match parsed {
+        Ok((emoji, action)) => {
+            match state
+                .react_usecase
+                .execute(client_id.clone(), &reaction_msg.message_id, emoji, action)
+                .await
+            {
+                Ok(outcome) => {
+                    tracing::info!(
+                        "'{}' {} reaction {} on message '{}' (count: {})",
+                        client_id,
+                        action,
+                        reaction_msg.emoji,
+                        reaction_msg.message_id,
+                        outcome.count
+                    );
+                    // Use the connection's client_id as the reactor
+                    let updated = ReactionUpdatedMessage {
+                        r#type: MessageType::ReactionUpdated,
+                        message_id: reaction_msg.message_id,
+                        emoji: reaction_msg.emoji,
+                        count: outcome.count,
+                        by: client_id.as_str().to_string(),
+                    };
+                    let updated_json = serde_json::to_string(&updated).unwrap();
+                    if let Err(e) = state
+                        .react_usecase
+                        .broadcast_reaction(outcome.broadcast_targets, &updated_json)
+                        .await
+                    {
+                        tracing::warn!("Failed to broadcast reaction: {:?}", e);
+                    }
+                    return;
+                }
+                Err(ReactError::MessageNotFound(message_id)) => {
+                    tracing::warn!(
+                        "Rejected reaction from '{}' to unknown message '{}'",
+                        client_id,
+                        message_id
+                    );
+                    build_error_json(
+                        "message-not-found",
+                        format!("No message with id '{}' in the history", message_id),
+                    )
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to record reaction: {:?}", e);
+                    return;
+                }
+            }
+        }
+        Err(e) => {
+            tracing::warn!("Invalid reaction from '{}': {}", client_id, e);
+            build_error_json("invalid-reaction", e.to_string())
+        }
+    };
 
-    // Send current room participants to the newly connected client
+    if let Err(e) = state
+        .react_usecase
+        .notify_sender(client_id, &error_json)
+        .await
+    {
+        tracing::warn!("Failed to send error frame to '{}': {}", client_id, e);
+    }
+}
+
+/// Run an admin command sent by `client_id`.
+///
+/// Commands from non-admin connections are rejected with a `forbidden` error frame.
+/// The admin is told the outcome with a system message, or an error frame if the
+/// command is invalid or its target is not in the room.
+async fn handle_admin_command(
+    state: &AppState,
+    client_id: &ClientId,
+    is_admin: bool,
+    command: Result<AdminCommand, String>,
+) {
+    let reply_json = if !is_admin {
+        tracing::warn!("Rejected admin command from non-admin '{}'", client_id);
+        build_error_json(
+            "forbidden",
+            "Admin commands require an admin connection".to_string(),
+        )
+    } else {
+        match command {
+            Ok(command) => match run_admin_command(state, command).await {
+                Ok(Some(done)) => {
+                    tracing::info!("Admin '{}': {}", client_id, done);
+                    let system_msg = SystemMessage {
+                        r#type: MessageType::System,
+                        content: done,
+                    };
+                    serde_json::to_string(&system_msg).unwrap()
+                }
+                // Announcements reach the admin like everyone else
+                Ok(None) => return,
+                Err(AdminError::ParticipantNotFound(id)) => build_error_json(
+                    "participant-not-found",
+                    format!("No participant '{}' in the room", id),
+                ),
+                Err(e) => {
+                    tracing::warn!("Failed to run admin command: {:?}", e);
+                    return;
+                }
+            },
+            Err(usage) => build_error_json("invalid-admin-command", usage),
+        }
+    };
+
+    if let Err(e) = state
+        .admin_usecase
+        .notify_admin(client_id, &reply_json)
+        .await
     {
-        // Use ConnectParticipantUseCase to build participant list
-        let participants = state
-            .connect_participant_usecase
-            .build_participant_list()
+        tracing::warn!("Failed to reply to admin '{}': {}", client_id, e);
+    }
+}
+
+/// Run a parsed admin command.
+///
+/// A target that is not a valid client ID cannot be in the room, so it is reported as
+/// [`AdminError::ParticipantNotFound`].
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - Description of what was done, for the admin
+/// * `Ok(None)` - Announcement broadcast to everyone
+/// * `Err(AdminError)` - The command failed
+async fn run_admin_command(
+    state: &AppState,
+    command: AdminCommand,
+) -> Result<Option<String>, AdminError> {
+    let parse_target = |id: String| {
+        ClientId::try_from(id.clone()).map_err(|_| AdminError::ParticipantNotFound(id))
+    };
+
+    match command {
+        AdminCommand::Kick(id) => {
+            let target = parse_target(id)?;
+            let notify_targets = state.admin_usecase.kick(&target).await?;
+
+            // Announce the departure like a disconnection
+            let disconnected_at = state
+                .timestamp_precision
+                .to_wire(Timestamp::new(get_jst_timestamp()));
+            let left_json = |participant_count| {
+                let left_msg = ParticipantLeftMessage {
+                    r#type: MessageType::ParticipantLeft,
+                    client_id: target.as_str().to_string(),
+                    disconnected_at,
+                    participant_count,
+                };
+                serde_json::to_string(&left_msg).unwrap()
+            };
+            let result = state
+                .disconnect_participant_usecase
+                .broadcast_participant_left(notify_targets, left_json, participant_count_json)
+                .await;
+            log_presence_broadcast("participant-left", target.as_str(), result);
+            Ok(Some(format!("Kicked '{}'", target)))
+        }
+        AdminCommand::Mute(id) => {
+            let target = parse_target(id)?;
+            state.admin_usecase.set_muted(&target, true).await?;
+            Ok(Some(format!("Muted '{}'", target)))
+        }
+        AdminCommand::Unmute(id) => {
+            let target = parse_target(id)?;
+            state.admin_usecase.set_muted(&target, false).await?;
+            Ok(Some(format!("Unmuted '{}'", target)))
+        }
+        AdminCommand::Announce(text) => {
+            // The text is part of a chat message, so it is always within the announcement limit
+            let system_msg = SystemMessage {
+                r#type: MessageType::System,
+                content: text,
+            };
+            let system_json = serde_json::to_string(&system_msg).unwrap();
+            state.admin_usecase.announce(&system_json).await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Send a validated chat message, deduplicating it when the sender attached an idempotency key.
+///
+/// The message is always sent as `connection_client_id`, so mute and slow mode apply to the
+/// connection's own client.
+#[allow(clippy::too_many_arguments)] // 送信するメッセージの要素を個別の引数で受け取るため
+async fn handle_chat(
+    state: &AppState,
+    connection_client_id: &ClientId,
+    message_id: MessageId,
+    content: MessageContent,
+    expires_at: Option<Timestamp>,
+    json_message: String,
+    idempotency_key: Option<String>,
+) {
+    match idempotency_key {
+        Some(idempotency_key) => {
+            handle_idempotent_chat(
+                state,
+                connection_client_id,
+                content,
+                expires_at,
+                json_message,
+                message_id,
+                idempotency_key,
+            )
             .await;
+        }
+        None => {
+            match state
+                .send_message_usecase
+                .execute(
+                    connection_client_id.clone(),
+                    message_id,
+                    content,
+                    expires_at,
+                    json_message,
+                )
+                .await
+            {
+                Ok(_broadcast_targets) => {
+                    // Broadcast is handled by UseCase
+                }
+                Err(e) => report_send_failure(state, connection_client_id, &e).await,
+            }
+        }
+    }
+}
 
-        // Domain Model から DTO への変換
-        let participant_infos: Vec<crate::infrastructure::dto::websocket::ParticipantInfo> =
-            participants
-                .into_iter()
-                .map(|p| crate::infrastructure::dto::websocket::ParticipantInfo {
-                    client_id: p.id.as_str().to_string(),
-                    connected_at: p.connected_at.value(),
-                })
-                .collect();
-
-        let room_msg = RoomConnectedMessage {
-            r#type: MessageType::RoomConnected,
-            participants: participant_infos,
-        };
+/// Log a failed chat send and, if the broadcast failed, the sender is muted or sent too soon
+/// under slow mode, tell the sender with an error frame.
+///
+/// A failed broadcast is reported only once the use case has given up retrying it, with the
+/// [`MessagePushError`](crate::domain::MessagePushError) code; the message is already in the
+/// history, so the sender should not re-send it.
+async fn report_send_failure(
+    state: &AppState,
+    connection_client_id: &ClientId,
+    error: &SendMessageError,
+) {
+    tracing::warn!("Failed to send message: {:?}", error);
+    let error_json = match error {
+        SendMessageError::BroadcastFailed(push_error) => {
+            build_error_json(push_error.code(), push_error.to_string())
+        }
+        SendMessageError::Muted => {
+            build_error_json("muted", "You have been muted by an admin".to_string())
+        }
+        SendMessageError::SlowMode { retry_after } => {
+            let retry_after_ms = retry_after.as_millis() as u64;
+            serde_json::to_string(&ErrorMessage {
+                r#type: MessageType::Error,
+                code: "slow-mode".to_string(),
+                message: format!(
+                    "Slow mode is on: wait {}s before sending again",
+                    retry_after_ms.div_ceil(1000)
+                ),
+                retry_after_ms: Some(retry_after_ms),
+            })
+            .unwrap()
+        }
+        _ => return,
+    };
+    if let Err(e) = state
+        .send_message_usecase
+        .notify_sender(connection_client_id, &error_json)
+        .await
+    {
+        tracing::warn!(
+            "Failed to send error frame to '{}': {}",
+            connection_client_id,
+            e
+        );
+    }
+}
 
-        let room_json = serde_json::to_string(&room_msg).unwrap();
-        if let Err(e) = sender.send(Message::Text(room_json.into())).await {
-            tracing::error!(
-                "Failed to send room connected to '{}': {}",
-                client_id_str,
-                e
+/// Send a chat message carrying an idempotency key and acknowledge it to the sender.
+///
+/// A re-sent message whose key was already accepted is acknowledged again without being
+/// broadcast, so the sender can stop re-sending it. Failed sends are not acknowledged.
+#[allow(clippy::too_many_arguments)] // 送信するメッセージの要素を個別の引数で受け取るため
+async fn handle_idempotent_chat(
+    state: &AppState,
+    connection_client_id: &ClientId,
+    content: MessageContent,
+    expires_at: Option<Timestamp>,
+    json_message: String,
+    message_id: MessageId,
+    idempotency_key: String,
+) {
+    match state
+        .send_message_usecase
+        .execute_idempotent(
+            connection_client_id.clone(),
+            message_id,
+            content,
+            expires_at,
+            json_message,
+            idempotency_key.clone(),
+        )
+        .await
+    {
+        Ok(SendMessageOutcome::Sent(_broadcast_targets)) => {
+            // Broadcast is handled by UseCase
+        }
+        Ok(SendMessageOutcome::Duplicate) => {
+            tracing::info!(
+                "Ignoring re-sent message '{}' from '{}'",
+                idempotency_key,
+                connection_client_id
             );
+        }
+        Err(e) => {
+            report_send_failure(state, connection_client_id, &e).await;
             return;
         }
-        tracing::info!("Sent room connected list to '{}'", client_id_str);
     }
 
-    // Broadcast participant-joined to all other clients
+    let ack = AckMessage {
+        r#type: MessageType::Ack,
+        idempotency_key,
+    };
+    let ack_json = serde_json::to_string(&ack).unwrap();
+    if let Err(e) = state
+        .send_message_usecase
+        .notify_sender(connection_client_id, &ack_json)
+        .await
     {
-        let joined_msg = ParticipantJoinedMessage {
-            r#type: MessageType::ParticipantJoined,
-            client_id: client_id_str.clone(),
-            connected_at: connected_at.value(),
-        };
+        tracing::warn!("Failed to send ack to '{}': {}", connection_client_id, e);
+    }
+}
+
+/// Send the room state to a newly connected client, then announce it to the others.
+///
+/// `participant-joined` is broadcast only after the newcomer has received its
+/// `room-connected` frame. If that frame cannot be sent, the connection is released
+/// again without any announcement, so the other clients never see a participant
+/// that was not really there.
+///
+/// # Returns
+///
+/// The room ID sent in the initial frame (`Some(None)` if the room was unavailable),
+/// or `None` if the initial frame could not be sent (the connection has been released)
+#[allow(clippy::too_many_arguments)] // 接続ごとの状態を引数で受け取るため
+async fn admit_participant<S>(
+    connect_usecase: &ConnectParticipantUseCase,
+    disconnect_usecase: &DisconnectParticipantUseCase,
+    sender: &mut S,
+    client_id: &ClientId,
+    connection: Connection,
+    status: PresenceStatus,
+    precision: TimestampPrecision,
+    connection_tx: &PusherChannel,
+) -> Option<Option<RoomId>>
+where
+    S: Sink<Message> + Unpin,
+    S::Error: std::fmt::Display,
+{
+    let client_id_str = client_id.as_str();
 
-        let joined_json = serde_json::to_string(&joined_msg).unwrap();
-        if let Err(e) = state
-            .connect_participant_usecase
-            .broadcast_participant_joined(&client_id, &joined_json)
+    // Send current room participants to the newly connected client
+    // (truncated in large rooms to keep the initial payload bounded)
+    let roster = connect_usecase.build_initial_roster().await;
+    let truncated = roster.is_truncated();
+
+    // Domain Model から DTO への変換
+    let participant_infos: Vec<ParticipantInfo> = roster
+        .entries
+        .into_iter()
+        .map(|entry| roster_entry_to_dto(entry, precision))
+        .collect();
+
+    let room_msg = RoomConnectedMessage {
+        r#type: MessageType::RoomConnected,
+        room_id: roster.room_id.as_ref().map(|id| id.as_str().to_string()),
+        participants: participant_infos,
+        total: roster.total,
+        truncated,
+        message_capacity: roster.message_capacity,
+        message_count: roster.message_count,
+    };
+
+    let room_json = serde_json::to_string(&room_msg).unwrap();
+    if let Err(e) = sender.send(Message::Text(room_json.into())).await {
+        tracing::error!(
+            "Failed to send room connected to '{}': {}",
+            client_id_str,
+            e
+        );
+        // Nobody was told about this connection yet; release it silently
+        if disconnect_usecase
+            .execute_connection(client_id.clone(), connection_tx)
             .await
+            .is_err()
         {
-            tracing::warn!("Failed to broadcast participant-joined: {}", e);
-        } else {
-            tracing::info!("Broadcasted participant-joined for '{}'", client_id_str);
+            tracing::warn!("Failed to disconnect participant '{}'", client_id_str);
+        }
+        return None;
+    }
+    tracing::info!("Sent room connected list to '{}'", client_id_str);
+
+    // Broadcast participant-joined to all other clients
+    // (an additional connection of a participant already in the room is not a join)
+    if !connection.is_additional {
+        let joined_json = |participant_count| {
+            let joined_msg = ParticipantJoinedMessage {
+                r#type: MessageType::ParticipantJoined,
+                client_id: client_id_str.to_string(),
+                connected_at: precision.to_wire(connection.connected_at),
+                status: status.as_str().to_string(),
+                participant_count,
+            };
+            serde_json::to_string(&joined_msg).unwrap()
+        };
+        let result = connect_usecase
+            .broadcast_participant_joined(client_id, joined_json, participant_count_json)
+            .await;
+        log_presence_broadcast("participant-joined", client_id_str, result);
+    }
+
+    Some(roster.room_id)
+}
+
+/// Participant count update sent instead of join/leave notifications in large rooms
+fn participant_count_json(count: usize) -> String {
+    let count_msg = ParticipantCountMessage {
+        r#type: MessageType::ParticipantCount,
+        count,
+    };
+    serde_json::to_string(&count_msg).unwrap()
+}
+
+/// Log the delivery of a join/leave notification, naming the participants it did not reach
+fn log_presence_broadcast(
+    notification: &str,
+    client_id_str: &str,
+    result: Result<BroadcastReport, String>,
+) {
+    match result {
+        Ok(report) if report.is_complete() => {
+            tracing::info!(
+                "Broadcasted {} for '{}' to {} clients",
+                notification,
+                client_id_str,
+                report.delivered.len()
+            );
+        }
+        Ok(report) => {
+            let failed: Vec<&str> = report.failed.iter().map(|id| id.as_str()).collect();
+            tracing::warn!(
+                "Broadcasted {} for '{}' to {} clients, but not to {:?}",
+                notification,
+                client_id_str,
+                report.delivered.len(),
+                failed
+            );
+        }
+        Err(e) => {
+            tracing::warn!("Failed to broadcast {}: {}", notification, e);
         }
     }
+}
+
+#[allow(clippy::too_many_arguments)] // 接続ごとの状態を引数で受け取るため
+async fn handle_socket(
+    socket: WebSocket,
+    state: Arc<AppState>,
+    client_id_str: String,
+    (connection_tx, rx): (PusherChannel, mpsc::Receiver<String>),
+    connection: Connection,
+    status: PresenceStatus,
+    client_id: ClientId,
+    is_admin: bool,
+) {
+    let subprotocol = socket
+        .protocol()
+        .and_then(|protocol| protocol.to_str().ok())
+        .map(str::to_string);
+    let (mut sender, mut receiver) = socket.split();
+    let _active_connection = state.active_connections.track();
+
+    // Send the room state to the newcomer, then announce it to the others
+    let Some(room_id) = admit_participant(
+        &state.connect_participant_usecase,
+        &state.disconnect_participant_usecase,
+        &mut sender,
+        &client_id,
+        connection,
+        status,
+        state.timestamp_precision,
+        &connection_tx,
+    )
+    .await
+    else {
+        return;
+    };
+
+    // Keep only a weak handle from here on, so that the channel closes (and the
+    // connection ends) once the MessagePusher releases this connection
+    let connection_tx = {
+        let weak_tx = connection_tx.downgrade();
+        drop(connection_tx);
+        weak_tx
+    };
+
+    // Greet the newcomer with the message of the day, if configured
+    if let Some(motd) = &state.motd {
+        let system_msg = SystemMessage {
+            r#type: MessageType::System,
+            content: motd.clone(),
+        };
+        let system_json = serde_json::to_string(&system_msg).unwrap();
+        if let Err(e) = sender.send(Message::Text(system_json.into())).await {
+            tracing::warn!("Failed to send MOTD to '{}': {}", client_id_str, e);
+        }
+    }
+
+    // Mark the end of the initial frames; room events queued meanwhile follow it
+    let ready_msg = ReadyMessage {
+        r#type: MessageType::Ready,
+    };
+    let ready_json = serde_json::to_string(&ready_msg).unwrap();
+    if let Err(e) = sender.send(Message::Text(ready_json.into())).await {
+        tracing::warn!("Failed to send ready to '{}': {}", client_id_str, e);
+    }
+
+    SessionParameters {
+        client_id: client_id_str.clone(),
+        // 初期フレームで送ったルーム ID を使う（Room 全体を複製しない）
+        room_id: room_id.map(RoomId::into_string),
+        subprotocol,
+        // permessage-deflate is not supported by the WebSocket implementation
+        compression: false,
+        max_message_size: state.max_message_size,
+    }
+    .log();
 
     let client_id_str_clone = client_id_str.clone();
+    let client_id_clone = client_id.clone();
     let state_clone = state.clone();
+    let (close_tx, close_rx) = oneshot::channel();
 
     // Spawn a task to receive messages from this client
+    // (returns `true` if it asked the pusher to close the connection)
     let mut recv_task = tokio::spawn(async move {
+        let mut frame_rate_limiter = state_clone
+            .max_frames_per_sec
+            .map(|max| FrameRateLimiter::new(max, Instant::now()));
+
         while let Some(msg) = receiver.next().await {
             let msg = match msg {
                 Ok(msg) => msg,
                 Err(e) => {
-                    tracing::error!("WebSocket error: {}", e);
+                    let kind = ReceiveErrorKind::classify(&e);
+                    state_clone.metrics.record_receive_error(kind);
+                    match kind.close_frame() {
+                        Some(close) => {
+                            tracing::warn!(
+                                "Closing connection of '{}': {} ({})",
+                                client_id_str_clone,
+                                kind,
+                                e
+                            );
+                            return close_tx.send(close).is_ok();
+                        }
+                        None if kind == ReceiveErrorKind::ConnectionReset => {
+                            tracing::info!(
+                                "Connection of '{}' was reset: {}",
+                                client_id_str_clone,
+                                e
+                            );
+                        }
+                        None => tracing::error!("WebSocket error: {}", e),
+                    }
                     break;
                 }
             };
 
+            // Every frame counts, whatever its content
+            if let Some(limiter) = frame_rate_limiter.as_mut()
+                && !limiter.record_frame(Instant::now())
+            {
+                tracing::warn!(
+                    "Closing connection of '{}': frame rate exceeded",
+                    client_id_str_clone
+                );
+                let close = CloseFrame {
+                    code: close_code::POLICY,
+                    reason: "Frame rate exceeded".into(),
+                };
+                return close_tx.send(close).is_ok();
+            }
+
             match msg {
                 Message::Text(text) => {
                     tracing::info!("Received text: {}", text);
 
+                    // Reply to application-level ping (sender only)
+                    if let Ok(ping) = serde_json::from_str::<AppPingMessage>(&text)
+                        && matches!(ping.r#type, MessageType::AppPing)
+                    {
+                        let pong = AppPongMessage {
+                            r#type: MessageType::AppPong,
+                            nonce: ping.nonce,
+                        };
+                        let pong_json = serde_json::to_string(&pong).unwrap();
+                        if let Err(e) = state_clone
+                            .reply_pong_usecase
+                            .execute(&client_id_clone, &pong_json)
+                            .await
+                        {
+                            tracing::warn!("Failed to reply app-pong: {:?}", e);
+                        }
+                        continue;
+                    }
+
+                    // Presence heartbeat: refresh the sender's activity without broadcasting
+                    if let Ok(heartbeat) = serde_json::from_str::<HeartbeatMessage>(&text)
+                        && matches!(heartbeat.r#type, MessageType::Heartbeat)
+                    {
+                        if let Err(e) = state_clone
+                            .connect_participant_usecase
+                            .record_heartbeat(&client_id_clone, Timestamp::new(get_jst_timestamp()))
+                            .await
+                        {
+                            tracing::warn!(
+                                "Failed to record heartbeat of '{}': {}",
+                                client_id_str_clone,
+                                e
+                            );
+                        }
+                        continue;
+                    }
+
+                    // Reply to roster request with the current participant list, or only the
+                    // changes since the version the client knows (sender only)
+                    if let Ok(request) = serde_json::from_str::<RosterRequestMessage>(&text)
+                        && matches!(request.r#type, MessageType::RosterRequest)
+                    {
+                        let update = state_clone
+                            .connect_participant_usecase
+                            .build_roster_update_at(
+                                request.since_version,
+                                Timestamp::new(get_jst_timestamp()),
+                            )
+                            .await;
+                        let precision = state_clone.timestamp_precision;
+                        let roster_json = match update {
+                            RosterUpdate::Full { version, entries } => {
+                                serde_json::to_string(&RosterMessage {
+                                    r#type: MessageType::Roster,
+                                    participants: entries
+                                        .into_iter()
+                                        .map(|entry| roster_entry_to_dto(entry, precision))
+                                        .collect(),
+                                    version,
+                                })
+                            }
+                            RosterUpdate::Delta {
+                                version,
+                                added,
+                                removed,
+                            } => serde_json::to_string(&RosterDeltaMessage {
+                                r#type: MessageType::RosterDelta,
+                                added: added
+                                    .into_iter()
+                                    .map(|entry| roster_entry_to_dto(entry, precision))
+                                    .collect(),
+                                removed: removed.into_iter().map(ClientId::into_string).collect(),
+                                version,
+                            }),
+                        }
+                        .unwrap();
+                        if let Err(e) = state_clone
+                            .connect_participant_usecase
+                            .send_roster_to(&client_id_clone, &roster_json)
+                            .await
+                        {
+                            tracing::warn!("Failed to send roster: {}", e);
+                        }
+                        continue;
+                    }
+
+                    // Handle shared file
+                    if let Ok(file_msg) = serde_json::from_str::<FileMessage>(&text)
+                        && matches!(file_msg.r#type, MessageType::File)
+                    {
+                        handle_file_message(&state_clone, &client_id_clone, file_msg).await;
+                        continue;
+                    }
+
+                    // Handle reaction to a chat message
+                    if let Ok(reaction_msg) = serde_json::from_str::<ReactionMessage>(&text)
+                        && matches!(reaction_msg.r#type, MessageType::Reaction)
+                    {
+                        handle_reaction_message(&state_clone, &client_id_clone, reaction_msg).await;
+                        continue;
+                    }
+
                     // Parse the incoming message
                     let chat_msg = match serde_json::from_str::<ChatMessage>(&text) {
                         Ok(msg) => msg,
-                        Err(e) => {
+                        Err(e) if state_clone.accept_plain_text => {
                             tracing::warn!("Failed to parse message as JSON: {}", e);
-                            // If not JSON, treat as plain text and wrap it
+                            // If not JSON, treat as plain text from this connection's client
                             ChatMessage {
                                 r#type: MessageType::Chat,
-                                client_id: "unknown".to_string(),
+                                client_id: client_id_str_clone.clone(),
                                 content: text.to_string(),
-                                timestamp: 0,
+                                timestamp: state_clone
+                                    .timestamp_precision
+                                    .to_wire(Timestamp::new(get_jst_timestamp())),
+                                idempotency_key: None,
+                                links: Vec::new(),
+                                message_id: None,
+                                ttl_secs: None,
+                                expires_at: None,
                             }
                         }
+                        Err(e) => {
+                            tracing::warn!(
+                                "Rejected non-JSON message from '{}': {}",
+                                client_id_str_clone,
+                                e
+                            );
+                            let error_json = build_error_json(
+                                "invalid-message",
+                                "Messages must be JSON chat frames".to_string(),
+                            );
+                            if let Err(e) = state_clone
+                                .send_message_usecase
+                                .notify_sender(&client_id_clone, &error_json)
+                                .await
+                            {
+                                tracing::warn!(
+                                    "Failed to send error frame to '{}': {}",
+                                    client_id_str_clone,
+                                    e
+                                );
+                            }
+                            continue;
+                        }
                     };
 
-                    // Create response with type "chat" and preserve client_id
-                    let response = ChatMessage {
-                        r#type: MessageType::Chat,
-                        client_id: chat_msg.client_id.clone(),
-                        content: chat_msg.content.clone(),
-                        timestamp: chat_msg.timestamp,
-                    };
+                    // A chat frame may only speak for the client verified at connection time
+                    if chat_msg.client_id != client_id_str_clone {
+                        tracing::warn!(
+                            "Rejected chat from '{}' claiming to be '{}'",
+                            client_id_str_clone,
+                            chat_msg.client_id
+                        );
+                        let error_json = build_error_json(
+                            "client-id-mismatch",
+                            format!(
+                                "Chat frames must carry this connection's client_id '{}'",
+                                client_id_str_clone
+                            ),
+                        );
+                        if let Err(e) = state_clone
+                            .send_message_usecase
+                            .notify_sender(&client_id_clone, &error_json)
+                            .await
+                        {
+                            tracing::warn!(
+                                "Failed to send error frame to '{}': {}",
+                                client_id_str_clone,
+                                e
+                            );
+                        }
+                        continue;
+                    }
 
-                    let response_json = serde_json::to_string(&response).unwrap();
-                    tracing::info!(
-                        "Broadcasting message from '{}' to other clients: {}",
-                        response.client_id,
-                        response.content
-                    );
+                    // Admin commands are run instead of being sent as chat
+                    if let Some(command) = AdminCommand::parse(&chat_msg.content) {
+                        handle_admin_command(&state_clone, &client_id_clone, is_admin, command)
+                            .await;
+                        continue;
+                    }
 
                     // Use SendMessageUseCase to handle message sending
                     // Convert String -> Domain Models
-                    let client_id_result = ClientId::try_from(response.client_id.clone());
-                    let content_result = MessageContent::try_from(response.content.clone());
-
-                    match (client_id_result, content_result) {
-                        (Ok(client_id_vo), Ok(content_vo)) => {
-                            match state_clone
+                    // (the sender is always this connection's client, whatever the frame claims)
+                    match state_clone
+                        .send_message_usecase
+                        .validate_content(chat_msg.content.clone())
+                    {
+                        Ok(content_vo) => {
+                            // Normalize the content; the result is what gets stored and broadcast
+                            let transformed = state_clone
                                 .send_message_usecase
-                                .execute(client_id_vo, content_vo, response_json)
-                                .await
-                            {
-                                Ok(_broadcast_targets) => {
-                                    // Broadcast is handled by UseCase
-                                }
-                                Err(e) => {
-                                    tracing::warn!("Failed to send message: {:?}", e);
-                                }
-                            }
-                        }
-                        (Err(_), _) => {
-                            tracing::warn!("Invalid client_id format: '{}'", response.client_id);
+                                .transform_content(content_vo);
+
+                            // Identify the message so that clients can react to it
+                            let message_id = MessageIdFactory::generate();
+
+                            // An ephemeral message is removed from the history once it expires
+                            let expires_at = chat_msg.ttl_secs.map(|ttl_secs| {
+                                message_expiry(
+                                    Timestamp::new(get_jst_timestamp()),
+                                    Duration::from_secs(ttl_secs),
+                                )
+                            });
+
+                            // Create response with type "chat" from this connection's client
+                            let response = ChatMessage {
+                                r#type: MessageType::Chat,
+                                client_id: client_id_str_clone.clone(),
+                                content: transformed.content.as_str().to_string(),
+                                timestamp: chat_msg.timestamp,
+                                // The key only concerns the sender and is not forwarded
+                                idempotency_key: None,
+                                links: transformed.links,
+                                message_id: Some(message_id.as_str().to_string()),
+                                ttl_secs: None,
+                                expires_at: expires_at.map(|expires_at| {
+                                    state_clone.timestamp_precision.to_wire(expires_at)
+                                }),
+                            };
+
+                            let response_json = serde_json::to_string(&response).unwrap();
+                            tracing::info!(
+                                "Broadcasting message from '{}' to other clients: {}",
+                                response.client_id,
+                                response.content
+                            );
+
+                            handle_chat(
+                                &state_clone,
+                                &client_id_clone,
+                                message_id,
+                                transformed.content,
+                                expires_at,
+                                response_json,
+                                chat_msg.idempotency_key,
+                            )
+                            .await;
                         }
-                        (_, Err(_)) => {
+                        Err(_) => {
                             tracing::warn!(
                                 "Invalid message content (length: {})",
-                                response.content.len()
+                                chat_msg.content.len()
                             );
                         }
                     }
@@ -270,51 +1301,211 @@ async fn handle_socket(
                 _ => {}
             }
         }
+
+        false
     });
 
     // Spawn a task to receive messages from other clients and send to this client
-    let mut send_task = pusher_loop(rx, sender);
+    let mut send_task = pusher_loop(
+        rx,
+        sender,
+        close_rx,
+        state.active_connections.closing(),
+        released_close_frame(state.clone(), client_id.clone()),
+    );
 
     // If any one of the tasks completes, abort the other
     tokio::select! {
-        _ = &mut recv_task => send_task.abort(),
+        closing = &mut recv_task => {
+            if closing.unwrap_or(false) {
+                // Let the pusher send the close frame before dropping the connection
+                let _ = tokio::time::timeout(SERVER_CLOSE_TIMEOUT, &mut send_task).await;
+            }
+            send_task.abort()
+        }
         _ = &mut send_task => recv_task.abort(),
     };
 
+    // A connection replaced by a newer one or kicked has already been released;
+    // the participant now belongs to the new connection, or has already left
+    let Some(connection_tx) = connection_tx.upgrade() else {
+        tracing::info!(
+            "Connection of '{}' was replaced by a new connection or kicked",
+            client_id_str
+        );
+        return;
+    };
+
+    // Measured before the participant is removed by the disconnection below
+    let session_duration = state
+        .disconnect_participant_usecase
+        .session_duration(&client_id, Timestamp::new(get_jst_timestamp()))
+        .await;
+
     // Use DisconnectParticipantUseCase to handle disconnection
     // (client_id is already a ClientId Domain Model)
     match state
         .disconnect_participant_usecase
-        .execute(client_id.clone())
+        .execute_connection(client_id.clone(), &connection_tx)
         .await
     {
-        Ok(notify_targets) => {
+        Ok(None) => {
             tracing::info!(
-                "Client '{}' disconnected and removed from registry",
+                "Connection of '{}' closed; other connections remain",
                 client_id_str
             );
+        }
+        Ok(Some(notify_targets)) => {
+            tracing::info!(
+                "Client '{}' disconnected and removed from registry (session: {}s)",
+                client_id_str,
+                session_duration.unwrap_or_default().as_secs()
+            );
 
             // Broadcast participant-left to all remaining clients
-            let disconnected_at = get_jst_timestamp();
-            let left_msg = ParticipantLeftMessage {
-                r#type: MessageType::ParticipantLeft,
-                client_id: client_id_str.clone(),
-                disconnected_at,
+            let disconnected_at = state
+                .timestamp_precision
+                .to_wire(Timestamp::new(get_jst_timestamp()));
+            let left_json = |participant_count| {
+                let left_msg = ParticipantLeftMessage {
+                    r#type: MessageType::ParticipantLeft,
+                    client_id: client_id_str.clone(),
+                    disconnected_at,
+                    participant_count,
+                };
+                serde_json::to_string(&left_msg).unwrap()
             };
-
-            let left_json = serde_json::to_string(&left_msg).unwrap();
-            if let Err(e) = state
+            let result = state
                 .disconnect_participant_usecase
-                .broadcast_participant_left(notify_targets, &left_json)
-                .await
-            {
-                tracing::warn!("Failed to broadcast participant-left: {}", e);
-            } else {
-                tracing::info!("Broadcasted participant-left for '{}'", client_id_str);
-            }
+                .broadcast_participant_left(notify_targets, left_json, participant_count_json)
+                .await;
+            log_presence_broadcast("participant-left", &client_id_str, result);
         }
         Err(_) => {
             tracing::warn!("Failed to disconnect participant '{}'", client_id_str);
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, RoomRepository, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    struct Fixture {
+        repository: Arc<InMemoryRoomRepository>,
+        connect_usecase: ConnectParticipantUseCase,
+        disconnect_usecase: DisconnectParticipantUseCase,
+    }
+
+    fn create_fixture() -> Fixture {
+        let room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        let repository = Arc::new(InMemoryRoomRepository::new(Arc::new(Mutex::new(room))));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        Fixture {
+            connect_usecase: ConnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ),
+            disconnect_usecase: DisconnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher,
+            ),
+            repository,
+        }
+    }
+
+    /// Connect `id` and return the channel ends of its connection
+    async fn connect(
+        fixture: &Fixture,
+        id: &str,
+    ) -> (ClientId, Connection, PusherChannel, mpsc::Receiver<String>) {
+        let client_id = ClientId::new(id.to_string()).unwrap();
+        let (tx, rx) = mpsc::channel(PUSHER_CHANNEL_CAPACITY);
+        let connection = fixture
+            .connect_usecase
+            .connect_with_status(
+                client_id.clone(),
+                PresenceStatus::default(),
+                None,
+                tx.clone(),
+            )
+            .await
+            .unwrap();
+        (client_id, connection, tx, rx)
+    }
+
+    #[tokio::test]
+    async fn test_admit_announces_newcomer_after_initial_frame() {
+        // テスト項目: 初期フレームの送信に成功した後に participant-joined がブロードキャストされる
+        // given (前提条件): bob が接続済みで、alice が新しく接続した
+        let fixture = create_fixture();
+        let (_bob, _, _, mut bob_rx) = connect(&fixture, "bob").await;
+        let (alice, connection, alice_tx, _alice_rx) = connect(&fixture, "alice").await;
+        let mut sink = futures_util::sink::drain::<Message>();
+
+        // when (操作):
+        let admitted = admit_participant(
+            &fixture.connect_usecase,
+            &fixture.disconnect_usecase,
+            &mut sink,
+            &alice,
+            connection,
+            PresenceStatus::default(),
+            TimestampPrecision::default(),
+            &alice_tx,
+        )
+        .await;
+
+        // then (期待する結果):
+        let room_id = fixture.repository.get_room().await.unwrap().id;
+        assert_eq!(admitted, Some(Some(room_id)));
+        let joined = bob_rx.try_recv().unwrap();
+        assert!(joined.contains("participant-joined"));
+        assert!(joined.contains("alice"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_initial_frame_releases_newcomer_without_announcement() {
+        // テスト項目: 接続直後に初期フレームの送信が失敗した場合、参加を通知せずに参加者を削除する
+        // given (前提条件): bob が接続済みで、alice の接続直後にソケットが閉じている
+        let fixture = create_fixture();
+        let (_bob, _, _, mut bob_rx) = connect(&fixture, "bob").await;
+        let (alice, connection, alice_tx, _alice_rx) = connect(&fixture, "alice").await;
+        let mut sink = Box::pin(futures_util::sink::unfold((), |_, _: Message| async {
+            Err::<(), _>("connection reset")
+        }));
+
+        // when (操作):
+        let admitted = admit_participant(
+            &fixture.connect_usecase,
+            &fixture.disconnect_usecase,
+            &mut sink,
+            &alice,
+            connection,
+            PresenceStatus::default(),
+            TimestampPrecision::default(),
+            &alice_tx,
+        )
+        .await;
+
+        // then (期待する結果): alice は Room から削除され、bob には何も届かない
+        assert_eq!(admitted, None);
+        let room = fixture.repository.get_room().await.unwrap();
+        let ids: Vec<&str> = room.participants.iter().map(|p| p.id.as_str()).collect();
+        assert_eq!(ids, vec!["bob"]);
+        assert!(bob_rx.try_recv().is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Idle shutdown.
+//!
+//! 接続中の WebSocket 接続数を数え、接続のない状態が一定時間続いたことを検知します。
+//! 一時的な環境（サーバーレスなど）で、使われていないサーバーを自動で停止するために使います。
+//! 停止時には、接続中の全接続に送信待ちのメッセージを送り切ってから閉じるよう通知し、
+//! 接続がなくなるのを待つためにも使います。
+
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+/// 接続中の WebSocket 接続数
+///
+/// ハンドラーが接続ごとに [`ActiveConnections::track`] を呼び、返されたガードを接続が終わるまで保持します。
+#[derive(Debug)]
+pub struct ActiveConnections {
+    count: watch::Sender<usize>,
+    /// 停止のため全接続を閉じるよう通知済みかどうか
+    closing: watch::Sender<bool>,
+}
+
+impl Default for ActiveConnections {
+    fn default() -> Self {
+        Self {
+            count: watch::Sender::new(0),
+            closing: watch::Sender::new(false),
+        }
+    }
+}
+
+impl ActiveConnections {
+    /// 新しい ActiveConnections を作成（接続数 0）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 現在の接続数を取得
+    pub fn count(&self) -> usize {
+        *self.count.borrow()
+    }
+
+    /// 接続を 1 つ数え、ガードが破棄されたときに数から外す
+    pub fn track(&self) -> ActiveConnectionGuard<'_> {
+        self.count.send_modify(|count| *count += 1);
+        ActiveConnectionGuard { connections: self }
+    }
+
+    /// 全接続に、送信待ちのメッセージを送り切ってから閉じるよう通知する
+    ///
+    /// 通知後に確立した接続も、同じく閉じられます。
+    pub fn close_all(&self) {
+        self.closing.send_replace(true);
+    }
+
+    /// [`ActiveConnections::close_all`] の通知を受け取る Receiver を取得
+    ///
+    /// 通知されると値が `true` になります。
+    pub fn closing(&self) -> watch::Receiver<bool> {
+        self.closing.subscribe()
+    }
+
+    /// 接続がすべて終わるまで待つ
+    pub async fn wait_closed(&self) {
+        let mut count = self.count.subscribe();
+        // 送信側は self が保持しているため、wait_for がエラーになることはない
+        let _ = count.wait_for(|count| *count == 0).await;
+    }
+
+    /// 接続のない状態が `period` 続くまで待つ
+    ///
+    /// 途中で接続があった場合は、再び接続がなくなった時点から数え直します。
+    pub async fn wait_idle_for(&self, period: Duration) {
+        let mut count = self.count.subscribe();
+        loop {
+            // 送信側は self が保持しているため、wait_for がエラーになることはない
+            let _ = count.wait_for(|count| *count == 0).await;
+            let connected = tokio::time::timeout(period, count.wait_for(|count| *count > 0));
+            if connected.await.is_err() {
+                return;
+            }
+        }
+    }
+}
+
+/// 接続が終わるまで保持するガード（[`ActiveConnections::track`]）
+#[derive(Debug)]
+pub struct ActiveConnectionGuard<'a> {
+    connections: &'a ActiveConnections,
+}
+
+impl Drop for ActiveConnectionGuard<'_> {
+    fn drop(&mut self) {
+        self.connections.count.send_modify(|count| *count -= 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connection_restarts_the_idle_period() {
+        // テスト項目: 接続中は待機が完了せず、接続が終わってから period 経過後に完了する
+        // given (前提条件):
+        let connections = ActiveConnections::new();
+        let period = Duration::from_millis(100);
+        let guard = connections.track();
+
+        // when (操作):
+        let while_connected =
+            tokio::time::timeout(period * 3, connections.wait_idle_for(period)).await;
+        drop(guard);
+        let after_disconnect =
+            tokio::time::timeout(period * 3, connections.wait_idle_for(period)).await;
+
+        // then (期待する結果):
+        assert!(while_connected.is_err());
+        assert!(after_disconnect.is_ok());
+        assert_eq!(connections.count(), 0);
+    }
+}
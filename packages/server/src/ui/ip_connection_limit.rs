@@ -0,0 +1,126 @@
+//! Per-source-IP connection limiting.
+//!
+//! 1 つのホストからの WebSocket 接続数を送信元 IP アドレスごとに数え、上限に達した
+//! アドレスからの新しい接続を拒否します。Room の参加者数の上限（全体の上限）とは
+//! 独立に働き、1 つのホストが接続を占有するのを防ぎます。
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{Arc, Mutex},
+};
+
+/// 送信元 IP アドレスごとの接続数を数え、上限を判定する
+#[derive(Debug)]
+pub struct IpConnectionLimiter {
+    /// 1 つの IP アドレスから同時に受け付ける接続数の上限
+    max_per_ip: usize,
+    /// IP アドレスごとの接続数（接続のないアドレスは含まない）
+    counts: Mutex<HashMap<IpAddr, usize>>,
+}
+
+impl IpConnectionLimiter {
+    /// 新しい IpConnectionLimiter を作成（接続数 0）
+    pub fn new(max_per_ip: usize) -> Self {
+        Self {
+            max_per_ip,
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// `ip` からの接続を 1 つ数え、ガードが破棄されたときに数から外す
+    ///
+    /// `ip` からの接続数が既に上限に達している場合は `None` を返します（数えません）。
+    pub fn try_acquire(self: &Arc<Self>, ip: IpAddr) -> Option<IpConnectionGuard> {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        let count = counts.entry(ip).or_default();
+        if *count >= self.max_per_ip {
+            if *count == 0 {
+                counts.remove(&ip);
+            }
+            return None;
+        }
+        *count += 1;
+        Some(IpConnectionGuard {
+            limiter: self.clone(),
+            ip,
+        })
+    }
+
+    /// `ip` からの現在の接続数を取得
+    pub fn count(&self, ip: IpAddr) -> usize {
+        let counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        counts.get(&ip).copied().unwrap_or(0)
+    }
+
+    fn release(&self, ip: IpAddr) {
+        let mut counts = self.counts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = counts.get_mut(&ip) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                counts.remove(&ip);
+            }
+        }
+    }
+}
+
+/// [`IpConnectionLimiter::try_acquire`] で数えた接続（破棄されると数から外れる）
+///
+/// 接続を拒否した場合やアップグレードに失敗した場合も含め、どの経路で接続が
+/// 終わっても数え漏れがないよう、接続が続く間はこのガードを保持します。
+#[derive(Debug)]
+pub struct IpConnectionGuard {
+    limiter: Arc<IpConnectionLimiter>,
+    ip: IpAddr,
+}
+
+impl Drop for IpConnectionGuard {
+    fn drop(&mut self) {
+        self.limiter.release(self.ip);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    #[test]
+    fn test_connections_over_limit_are_rejected_until_one_is_released() {
+        // テスト項目: 同じ IP アドレスからの接続は上限まで受け付けられ、1 つ切断されると再び受け付けられる。
+        //            他の IP アドレスからの接続は影響を受けない
+        // given (前提条件): 1 アドレスあたり 2 接続まで
+        let limiter = Arc::new(IpConnectionLimiter::new(2));
+        let host = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let other_host = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 2));
+        let first = limiter.try_acquire(host).unwrap();
+        let _second = limiter.try_acquire(host).unwrap();
+
+        // when (操作):
+        let third = limiter.try_acquire(host);
+        let from_other_host = limiter.try_acquire(other_host);
+        drop(first);
+        let after_release = limiter.try_acquire(host);
+
+        // then (期待する結果):
+        assert!(third.is_none());
+        assert!(from_other_host.is_some());
+        assert!(after_release.is_some());
+        assert_eq!(limiter.count(host), 2);
+    }
+
+    #[test]
+    fn test_released_addresses_are_forgotten() {
+        // テスト項目: 接続がすべて切断されたアドレスは記録から消える
+        // given (前提条件):
+        let limiter = Arc::new(IpConnectionLimiter::new(1));
+        let host = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+        // when (操作):
+        drop(limiter.try_acquire(host));
+
+        // then (期待する結果):
+        assert_eq!(limiter.count(host), 0);
+        assert!(limiter.counts.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+//! Connection metrics.
+//!
+//! 接続の拒否理由ごとのカウンターと、受信エラーの種類ごとのカウンターを保持し、
+//! `/api/metrics` で公開します。
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::receive_error::ReceiveErrorKind;
+
+/// 接続拒否の理由
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RejectionReason {
+    /// 同じ client_id が既に接続している（409）
+    DuplicateId,
+    /// Room の容量超過（503）
+    Capacity,
+    /// client_id の形式が不正（400）
+    InvalidId,
+    /// 初期プレゼンス状態（`status`）が不正（400）
+    InvalidStatus,
+    /// 認証・認可による拒否（管理者トークンの不一致による 403、身元確認の失敗による 401）
+    AuthFailure,
+    /// 送信元アドレスが AccessPolicy で拒否された（403）
+    AccessDenied,
+    /// 送信元 IP アドレスごとの接続数の上限超過（429）
+    PerIpLimit,
+    /// サーバー内部のエラー（500）
+    Internal,
+}
+
+/// 拒否された接続のカウンター
+///
+/// ハンドラーから並行に更新されるため、各カウンターは atomic に保持します。
+#[derive(Debug, Default)]
+pub struct ConnectionMetrics {
+    duplicate_id: AtomicU64,
+    capacity: AtomicU64,
+    invalid_id: AtomicU64,
+    invalid_status: AtomicU64,
+    auth_failure: AtomicU64,
+    access_denied: AtomicU64,
+    per_ip_limit: AtomicU64,
+    internal_error: AtomicU64,
+    protocol_violation: AtomicU64,
+    message_too_large: AtomicU64,
+    connection_reset: AtomicU64,
+    other_receive_error: AtomicU64,
+}
+
+/// ある時点でのカウンターの値
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RejectedConnectionsSnapshot {
+    pub duplicate_id: u64,
+    pub capacity: u64,
+    pub invalid_id: u64,
+    pub invalid_status: u64,
+    pub auth_failure: u64,
+    pub access_denied: u64,
+    pub per_ip_limit: u64,
+    pub internal_error: u64,
+}
+
+/// ある時点での受信エラーのカウンターの値
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReceiveErrorsSnapshot {
+    pub protocol_violation: u64,
+    pub message_too_large: u64,
+    pub connection_reset: u64,
+    pub other: u64,
+}
+
+impl ConnectionMetrics {
+    /// 新しい ConnectionMetrics を作成（全カウンター 0）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 拒否理由に対応するカウンターを 1 増やす
+    pub fn record_rejection(&self, reason: RejectionReason) {
+        let counter = match reason {
+            RejectionReason::DuplicateId => &self.duplicate_id,
+            RejectionReason::Capacity => &self.capacity,
+            RejectionReason::InvalidId => &self.invalid_id,
+            RejectionReason::InvalidStatus => &self.invalid_status,
+            RejectionReason::AuthFailure => &self.auth_failure,
+            RejectionReason::AccessDenied => &self.access_denied,
+            RejectionReason::PerIpLimit => &self.per_ip_limit,
+            RejectionReason::Internal => &self.internal_error,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 現在のカウンターの値を取得
+    pub fn rejected_connections(&self) -> RejectedConnectionsSnapshot {
+        RejectedConnectionsSnapshot {
+            duplicate_id: self.duplicate_id.load(Ordering::Relaxed),
+            capacity: self.capacity.load(Ordering::Relaxed),
+            invalid_id: self.invalid_id.load(Ordering::Relaxed),
+            invalid_status: self.invalid_status.load(Ordering::Relaxed),
+            auth_failure: self.auth_failure.load(Ordering::Relaxed),
+            access_denied: self.access_denied.load(Ordering::Relaxed),
+            per_ip_limit: self.per_ip_limit.load(Ordering::Relaxed),
+            internal_error: self.internal_error.load(Ordering::Relaxed),
+        }
+    }
+
+    /// 受信エラーの種類に対応するカウンターを 1 増やす
+    pub fn record_receive_error(&self, kind: ReceiveErrorKind) {
+        let counter = match kind {
+            ReceiveErrorKind::ProtocolViolation => &self.protocol_violation,
+            ReceiveErrorKind::MessageTooLarge => &self.message_too_large,
+            ReceiveErrorKind::ConnectionReset => &self.connection_reset,
+            ReceiveErrorKind::Other => &self.other_receive_error,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// 現在の受信エラーのカウンターの値を取得
+    pub fn receive_errors(&self) -> ReceiveErrorsSnapshot {
+        ReceiveErrorsSnapshot {
+            protocol_violation: self.protocol_violation.load(Ordering::Relaxed),
+            message_too_large: self.message_too_large.load(Ordering::Relaxed),
+            connection_reset: self.connection_reset.load(Ordering::Relaxed),
+            other: self.other_receive_error.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -1,8 +1,23 @@
 //! WebSocket chat server implementation.
 
+pub mod access_policy;
+mod admin_command;
+mod frame_rate;
 mod handler;
+pub mod idle_shutdown;
+pub mod ip_connection_limit;
+pub mod metrics;
+pub mod readiness;
+pub mod receive_error;
 mod server;
+mod server_builder;
 mod signal;
 pub mod state; // UseCase 層からアクセスするため public に変更
 
-pub use server::Server;
+pub use access_policy::{AccessDecision, AccessPolicy, AllowAllPolicy, CidrAccessPolicy};
+pub use readiness::{Readiness, ReadinessState};
+pub use server::{
+    BoundServer, DEFAULT_DRAIN_PERIOD, DEFAULT_MAX_MESSAGE_SIZE, DEFAULT_RETRY_AFTER,
+    DEFAULT_SHUTDOWN_TIMEOUT, Server,
+};
+pub use server_builder::ServerBuilder;
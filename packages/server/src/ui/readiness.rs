@@ -0,0 +1,97 @@
+//! Readiness state.
+//!
+//! `/api/health`（liveness）とは別に、トラフィックを受け付けられるかどうかを
+//! `/api/ready` で公開します。ロードバランサーはこの状態を見てルーティングを決めます。
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// サーバーのライフサイクル上の状態
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadinessState {
+    /// 初期化中（listener の bind 前）
+    Starting,
+    /// リクエストを受け付けている
+    Ready,
+    /// graceful shutdown のドレイン中（新しいトラフィックを受け付けない）
+    Draining,
+}
+
+impl ReadinessState {
+    /// `/api/ready` のレスポンスに含める文字列表現
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadinessState::Starting => "starting",
+            ReadinessState::Ready => "ready",
+            ReadinessState::Draining => "draining",
+        }
+    }
+}
+
+/// サーバーの readiness フラグ
+///
+/// サーバーのライフサイクルの各時点で更新され、ハンドラーから並行に参照されるため atomic に保持します。
+#[derive(Debug, Default)]
+pub struct Readiness {
+    state: AtomicU8,
+}
+
+impl Readiness {
+    /// 新しい Readiness を作成（初期状態は [`ReadinessState::Starting`]）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 現在の状態を取得
+    pub fn state(&self) -> ReadinessState {
+        match self.state.load(Ordering::Acquire) {
+            0 => ReadinessState::Starting,
+            1 => ReadinessState::Ready,
+            _ => ReadinessState::Draining,
+        }
+    }
+
+    /// トラフィックを受け付けられるかどうか
+    pub fn is_ready(&self) -> bool {
+        self.state() == ReadinessState::Ready
+    }
+
+    /// 初期化が完了し、リクエストを受け付け始めたことを記録
+    ///
+    /// ドレイン中に呼ばれた場合は何もしません（ドレインから Ready には戻りません）。
+    pub fn mark_ready(&self) {
+        let _ = self
+            .state
+            .compare_exchange(0, 1, Ordering::AcqRel, Ordering::Acquire);
+    }
+
+    /// graceful shutdown のドレインを開始したことを記録
+    pub fn mark_draining(&self) {
+        self.state.store(2, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_readiness_lifecycle() {
+        // テスト項目: Starting → Ready → Draining の順に遷移し、ドレイン後は Ready に戻らない
+        // given (前提条件):
+        let readiness = Readiness::new();
+        let initial = readiness.state();
+
+        // when (操作):
+        readiness.mark_ready();
+        let serving = readiness.state();
+        readiness.mark_draining();
+        readiness.mark_ready();
+        let draining = readiness.state();
+
+        // then (期待する結果):
+        assert_eq!(initial, ReadinessState::Starting);
+        assert_eq!(serving, ReadinessState::Ready);
+        assert_eq!(draining, ReadinessState::Draining);
+        assert!(!readiness.is_ready());
+    }
+}
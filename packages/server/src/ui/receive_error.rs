@@ -0,0 +1,121 @@
+//! Classification of errors while receiving WebSocket frames.
+//!
+//! 受信エラーはすべて接続の終了につながりますが、原因によって扱いを変えます。
+//! クライアントのプロトコル違反やサイズ超過は専用のクローズコードで通知し、
+//! 運用者向けに警告ログとカウンターに記録します。接続のリセットは通常の切断と同じく扱います。
+
+use std::{error::Error as _, fmt, io};
+
+use axum::extract::ws::{CloseFrame, close_code};
+use tungstenite::error::{CapacityError, Error as WsError, ProtocolError};
+
+/// 受信エラーの種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiveErrorKind {
+    /// クライアントが WebSocket プロトコルに違反した（不正なフレームや UTF-8 など）
+    ProtocolViolation,
+    /// メッセージが受信できるサイズの上限を超えた
+    MessageTooLarge,
+    /// クローズハンドシェイクなしに接続が切れた
+    ConnectionReset,
+    /// その他のエラー
+    Other,
+}
+
+impl ReceiveErrorKind {
+    /// `receiver.next()` が返したエラーを分類
+    pub fn classify(error: &axum::Error) -> Self {
+        let Some(ws_error) = error
+            .source()
+            .and_then(|source| source.downcast_ref::<WsError>())
+        else {
+            return Self::Other;
+        };
+
+        match ws_error {
+            WsError::Capacity(CapacityError::MessageTooLong { .. }) => Self::MessageTooLarge,
+            WsError::Protocol(ProtocolError::ResetWithoutClosingHandshake)
+            | WsError::ConnectionClosed
+            | WsError::AlreadyClosed => Self::ConnectionReset,
+            WsError::Io(e)
+                if matches!(
+                    e.kind(),
+                    io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                        | io::ErrorKind::BrokenPipe
+                        | io::ErrorKind::UnexpectedEof
+                ) =>
+            {
+                Self::ConnectionReset
+            }
+            WsError::Protocol(_) | WsError::Utf8(_) | WsError::AttackAttempt => {
+                Self::ProtocolViolation
+            }
+            _ => Self::Other,
+        }
+    }
+
+    /// クライアントに送るクローズフレーム（通常の切断として扱う場合は `None`）
+    pub fn close_frame(&self) -> Option<CloseFrame> {
+        match self {
+            Self::ProtocolViolation => Some(CloseFrame {
+                code: close_code::PROTOCOL,
+                reason: "Protocol error".into(),
+            }),
+            Self::MessageTooLarge => Some(CloseFrame {
+                code: close_code::SIZE,
+                reason: "Message too large".into(),
+            }),
+            Self::ConnectionReset | Self::Other => None,
+        }
+    }
+}
+
+impl fmt::Display for ReceiveErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::ProtocolViolation => "protocol violation",
+            Self::MessageTooLarge => "message too large",
+            Self::ConnectionReset => "connection reset",
+            Self::Other => "websocket error",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_receive_errors() {
+        // テスト項目: tungstenite のエラーが種類ごとに分類され、違反とサイズ超過だけがクローズコードを持つ
+        // given (前提条件):
+        let too_large = axum::Error::new(WsError::Capacity(CapacityError::MessageTooLong {
+            size: 2048,
+            max_size: 1024,
+        }));
+        let violation = axum::Error::new(WsError::Protocol(ProtocolError::NonZeroReservedBits));
+        let reset = axum::Error::new(WsError::Protocol(
+            ProtocolError::ResetWithoutClosingHandshake,
+        ));
+        let other = axum::Error::new(io::Error::other("something else"));
+
+        // when (操作):
+        let kinds = [&too_large, &violation, &reset, &other].map(ReceiveErrorKind::classify);
+
+        // then (期待する結果):
+        assert_eq!(
+            kinds,
+            [
+                ReceiveErrorKind::MessageTooLarge,
+                ReceiveErrorKind::ProtocolViolation,
+                ReceiveErrorKind::ConnectionReset,
+                ReceiveErrorKind::Other,
+            ]
+        );
+        assert_eq!(kinds[0].close_frame().unwrap().code, close_code::SIZE);
+        assert_eq!(kinds[1].close_frame().unwrap().code, close_code::PROTOCOL);
+        assert!(kinds[2].close_frame().is_none());
+    }
+}
@@ -1,20 +1,51 @@
 //! Server execution logic.
 
-use std::sync::Arc;
+use std::{future::Future, net::SocketAddr, sync::Arc, time::Duration};
 
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    http::{HeaderValue, Method},
+    routing::{delete, get},
+};
+use tokio::net::TcpListener;
+use tower_http::{
+    compression::CompressionLayer,
+    cors::{AllowOrigin, CorsLayer},
+};
 
+use crate::infrastructure::dto::conversion::TimestampPrecision;
 use crate::usecase::{
-    ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-    GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+    AdminUseCase, ConnectParticipantUseCase, DisconnectParticipantUseCase, GetParticipantUseCase,
+    GetRoomDetailUseCase, GetRoomStateUseCase, GetRoomsUseCase, PurgeMessagesUseCase, ReactUseCase,
+    ReplyPongUseCase, SendFileUseCase, SendMessageUseCase,
 };
 
 use super::{
-    handler::{debug_room_state, get_room_detail, get_rooms, health_check, websocket_handler},
+    access_policy::{AccessPolicy, AllowAllPolicy},
+    handler::{
+        debug_room_state, get_metrics, get_participant, get_participant_online, get_room_detail,
+        get_rooms, health_check, purge_messages, readiness_check, websocket_handler,
+    },
+    idle_shutdown::ActiveConnections,
+    ip_connection_limit::IpConnectionLimiter,
+    metrics::ConnectionMetrics,
+    readiness::Readiness,
     signal::shutdown_signal,
     state::AppState,
 };
 
+/// Default time to keep serving after the shutdown signal while reporting not ready
+pub const DEFAULT_DRAIN_PERIOD: Duration = Duration::ZERO;
+
+/// Default time to wait for open connections to flush their queued messages when shutting down
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Default maximum size of a message received over the WebSocket (the axum default)
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 << 20;
+
+/// Default wait suggested to clients rejected because the room is full
+pub const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(30);
+
 /// WebSocket chat server
 ///
 /// This struct encapsulates the server configuration and provides methods to run the server.
@@ -42,6 +73,54 @@ pub struct Server {
     get_rooms_usecase: Arc<GetRoomsUseCase>,
     /// GetRoomDetailUseCase（ルーム詳細取得のユースケース）
     get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+    /// GetParticipantUseCase（参加者詳細取得のユースケース）
+    get_participant_usecase: Arc<GetParticipantUseCase>,
+    /// ReplyPongUseCase（アプリケーションレベル Pong 返信のユースケース）
+    reply_pong_usecase: Arc<ReplyPongUseCase>,
+    /// SendFileUseCase（ファイル送信のユースケース）
+    send_file_usecase: Arc<SendFileUseCase>,
+    /// ReactUseCase（メッセージへのリアクションのユースケース）
+    react_usecase: Arc<ReactUseCase>,
+    /// AdminUseCase（管理者コマンドのユースケース）
+    admin_usecase: Arc<AdminUseCase>,
+    /// PurgeMessagesUseCase（メッセージ履歴削除のユースケース）
+    purge_messages_usecase: Arc<PurgeMessagesUseCase>,
+    /// 管理者として接続するためのトークン（`None` なら管理者の接続を受け付けない）
+    admin_token: Option<String>,
+    /// 接続元 IP アドレスによる接続可否の判定
+    access_policy: Arc<dyn AccessPolicy>,
+    /// 接続拒否理由ごとのカウンター
+    metrics: Arc<ConnectionMetrics>,
+    /// HTTP レスポンスの JSON を常にインデント付きで返すかどうか（デバッグモード）
+    pretty_json: bool,
+    /// HTTP API へのクロスオリジンアクセスを許可するオリジン（空なら CORS ヘッダーを付与しない）
+    allowed_origins: Vec<HeaderValue>,
+    /// HTTP API のレスポンスを `Accept-Encoding` に応じて圧縮するかどうか
+    compress_responses: bool,
+    /// 満員で接続を拒否したときに `Retry-After` ヘッダーで伝える再接続までの待ち時間
+    retry_after: Duration,
+    /// 接続直後に送信する Message of the Day（`None` なら送信しない）
+    motd: Option<String>,
+    /// JSON でないテキストフレームをチャットとして受け付けるかどうか
+    accept_plain_text: bool,
+    /// WebSocket フレームのタイムスタンプの単位
+    timestamp_precision: TimestampPrecision,
+    /// 1 接続が 1 秒あたりに送信できるフレーム数の上限（`None` なら制限しない）
+    max_frames_per_sec: Option<u32>,
+    /// 1 つの送信元 IP アドレスから同時に受け付ける接続数の上限（`None` なら制限しない）
+    max_connections_per_ip: Option<usize>,
+    /// 1 つの WebSocket メッセージとして受信できるサイズの上限（バイト）
+    max_message_size: usize,
+    /// readiness フラグ（`/api/ready`）
+    readiness: Arc<Readiness>,
+    /// shutdown シグナル受信後、readiness を落としたままリクエストを受け付け続ける時間
+    drain_period: Duration,
+    /// 接続中の WebSocket 接続数
+    active_connections: Arc<ActiveConnections>,
+    /// 接続のない状態がこの時間続いたら停止する（`None` なら停止しない）
+    idle_shutdown: Option<Duration>,
+    /// 新しい接続の受け付けを止めた後、接続中のクライアントへの送信待ちメッセージを送り切るまで待つ時間の上限
+    shutdown_timeout: Duration,
 }
 
 impl Server {
@@ -55,6 +134,13 @@ impl Server {
     /// * `get_room_state_usecase` - UseCase for getting room state
     /// * `get_rooms_usecase` - UseCase for getting rooms list
     /// * `get_room_detail_usecase` - UseCase for getting room detail
+    /// * `get_participant_usecase` - UseCase for getting participant detail
+    /// * `reply_pong_usecase` - UseCase for replying to application-level pings
+    /// * `send_file_usecase` - UseCase for file sending
+    /// * `react_usecase` - UseCase for message reactions
+    /// * `admin_usecase` - UseCase for admin commands
+    /// * `purge_messages_usecase` - UseCase for purging the message history
+    #[allow(clippy::too_many_arguments)] // UseCase ごとに引数を受け取るため
     pub fn new(
         connect_participant_usecase: Arc<ConnectParticipantUseCase>,
         disconnect_participant_usecase: Arc<DisconnectParticipantUseCase>,
@@ -62,6 +148,12 @@ impl Server {
         get_room_state_usecase: Arc<GetRoomStateUseCase>,
         get_rooms_usecase: Arc<GetRoomsUseCase>,
         get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+        get_participant_usecase: Arc<GetParticipantUseCase>,
+        reply_pong_usecase: Arc<ReplyPongUseCase>,
+        send_file_usecase: Arc<SendFileUseCase>,
+        react_usecase: Arc<ReactUseCase>,
+        admin_usecase: Arc<AdminUseCase>,
+        purge_messages_usecase: Arc<PurgeMessagesUseCase>,
     ) -> Self {
         Self {
             connect_participant_usecase,
@@ -70,9 +162,179 @@ impl Server {
             get_room_state_usecase,
             get_rooms_usecase,
             get_room_detail_usecase,
+            get_participant_usecase,
+            reply_pong_usecase,
+            send_file_usecase,
+            react_usecase,
+            admin_usecase,
+            purge_messages_usecase,
+            admin_token: None,
+            access_policy: Arc::new(AllowAllPolicy),
+            metrics: Arc::new(ConnectionMetrics::new()),
+            pretty_json: false,
+            allowed_origins: Vec::new(),
+            compress_responses: false,
+            retry_after: DEFAULT_RETRY_AFTER,
+            motd: None,
+            accept_plain_text: true,
+            timestamp_precision: TimestampPrecision::default(),
+            max_frames_per_sec: None,
+            max_connections_per_ip: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+            readiness: Arc::new(Readiness::new()),
+            drain_period: DEFAULT_DRAIN_PERIOD,
+            active_connections: Arc::new(ActiveConnections::new()),
+            idle_shutdown: None,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
         }
     }
 
+    /// Set the policy consulted before accepting a WebSocket connection
+    ///
+    /// デフォルトはすべての接続を許可する [`AllowAllPolicy`] です。
+    pub fn with_access_policy(mut self, access_policy: Arc<dyn AccessPolicy>) -> Self {
+        self.access_policy = access_policy;
+        self
+    }
+
+    /// Accept admin connections presenting `admin_token`
+    ///
+    /// `/ws?client_id=...&admin_token=...` で接続した管理者は、チャットとして
+    /// `/admin kick|mute|unmute|announce ...` のコマンドを送信できます。
+    /// トークンが一致しない接続は 403 で拒否します。デフォルトは管理者の接続を受け付けません。
+    ///
+    /// 管理用の HTTP API（`DELETE /api/rooms/{room_id}/messages`）も、同じトークンを
+    /// `Authorization: Bearer <admin_token>` ヘッダーで要求します。
+    pub fn with_admin_token(mut self, admin_token: String) -> Self {
+        self.admin_token = Some(admin_token);
+        self
+    }
+
+    /// Always pretty-print JSON responses of the HTTP API (debug mode)
+    ///
+    /// デフォルトではコンパクトな JSON を返し、`?pretty=1` 指定時のみインデントします。
+    pub fn with_pretty_json(mut self, pretty_json: bool) -> Self {
+        self.pretty_json = pretty_json;
+        self
+    }
+
+    /// Allow cross-origin requests to the HTTP API from the given origins
+    ///
+    /// デフォルトは空で、CORS ヘッダーを付与しません（同一オリジンのみ）。
+    /// WebSocket エンドポイント (`/ws`) には CORS レイヤーを適用しません。
+    pub fn with_allowed_origins(mut self, allowed_origins: Vec<HeaderValue>) -> Self {
+        self.allowed_origins = allowed_origins;
+        self
+    }
+
+    /// Compress HTTP API responses with gzip or deflate when the request's `Accept-Encoding` allows it
+    ///
+    /// デフォルトは `false`（圧縮しない）です。
+    /// WebSocket エンドポイント (`/ws`) には適用しません。
+    pub fn with_response_compression(mut self, enabled: bool) -> Self {
+        self.compress_responses = enabled;
+        self
+    }
+
+    /// Set the wait sent in the `Retry-After` header when a connection is rejected for capacity
+    ///
+    /// デフォルトは [`DEFAULT_RETRY_AFTER`] です。ヘッダーには秒単位で設定されます。
+    pub fn with_retry_after(mut self, retry_after: Duration) -> Self {
+        self.retry_after = retry_after;
+        self
+    }
+
+    /// Set the message of the day sent to each client right after it connects
+    ///
+    /// `room-connected` の直後に `system` メッセージとして送信されます。
+    /// 空白のみの文字列を設定した場合は何も送信しません。
+    pub fn with_motd(mut self, motd: impl Into<String>) -> Self {
+        let motd = motd.into();
+        self.motd = (!motd.trim().is_empty()).then_some(motd);
+        self
+    }
+
+    /// Choose whether text frames that are not JSON are accepted as chat messages
+    ///
+    /// デフォルトは `true` で、プレーンテキストはその接続のクライアントからのチャットとして扱います。
+    /// `false` の場合は送信者にのみ `invalid-message` のエラーフレームを返し、ブロードキャストしません。
+    pub fn with_plain_text_messages(mut self, accept: bool) -> Self {
+        self.accept_plain_text = accept;
+        self
+    }
+
+    /// Choose the unit of the Unix timestamps in WebSocket frames
+    ///
+    /// デフォルトはミリ秒です。秒を選ぶと、送信するフレームのタイムスタンプは秒になり
+    /// （ミリ秒は切り捨て）、受信したチャットのタイムスタンプも秒として扱います。
+    pub fn with_timestamp_precision(mut self, precision: TimestampPrecision) -> Self {
+        self.timestamp_precision = precision;
+        self
+    }
+
+    /// Close connections that send more than `max_frames_per_sec` frames within a second
+    ///
+    /// チャットの内容に関係なく、ping などを含むすべての受信フレームを数えます。
+    /// 上限を超えた接続にはクローズコード 1008（Policy Violation）を送って切断します。
+    /// デフォルトは制限なしです。
+    pub fn with_max_frames_per_sec(mut self, max_frames_per_sec: u32) -> Self {
+        self.max_frames_per_sec = Some(max_frames_per_sec);
+        self
+    }
+
+    /// Reject WebSocket connections from a source IP address that already has
+    /// `max_connections_per_ip` open connections
+    ///
+    /// 上限を超えた接続は、アップグレード前に 429（Too Many Requests）で拒否します。
+    /// Room の参加者数の上限とは独立に働きます。デフォルトは制限なしです。
+    pub fn with_max_connections_per_ip(mut self, max_connections_per_ip: usize) -> Self {
+        self.max_connections_per_ip = Some(max_connections_per_ip);
+        self
+    }
+
+    /// Set the maximum size of a message received over the WebSocket, in bytes
+    ///
+    /// 上限を超えるメッセージを送った接続にはクローズコード 1009（Message Too Big）を送って切断します。
+    /// デフォルトは [`DEFAULT_MAX_MESSAGE_SIZE`] です。
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Keep serving for `drain_period` after the shutdown signal while `/api/ready` reports 503
+    ///
+    /// ロードバランサーがこのサーバーへのルーティングを止めるまでの猶予です。
+    /// デフォルトは [`DEFAULT_DRAIN_PERIOD`]（待たずに停止）です。
+    pub fn with_drain_period(mut self, drain_period: Duration) -> Self {
+        self.drain_period = drain_period;
+        self
+    }
+
+    /// Shut down gracefully once no client has been connected for `idle_period`
+    ///
+    /// サーバーレスなど一時的な環境向けです。起動直後から数え始め、接続があるたびにリセットします。
+    /// shutdown シグナルを受信した場合と同じく、ドレインしてから停止します。
+    /// デフォルトは無効です。
+    pub fn with_idle_shutdown(mut self, idle_period: Duration) -> Self {
+        self.idle_shutdown = Some(idle_period);
+        self
+    }
+
+    /// Wait at most `shutdown_timeout` for open connections to close when shutting down
+    ///
+    /// 新しい接続の受け付けを止めた後、各接続は送信待ちのメッセージを送り切ってから閉じられます。
+    /// この時間を過ぎても閉じていない接続は、送信待ちのメッセージとともに破棄されます。
+    /// デフォルトは [`DEFAULT_SHUTDOWN_TIMEOUT`] です。
+    pub fn with_shutdown_timeout(mut self, shutdown_timeout: Duration) -> Self {
+        self.shutdown_timeout = shutdown_timeout;
+        self
+    }
+
+    /// Get the readiness flag reported by `/api/ready`
+    pub fn readiness(&self) -> Arc<Readiness> {
+        self.readiness.clone()
+    }
+
     /// Run the WebSocket chat server
     ///
     /// # Arguments
@@ -85,6 +347,52 @@ impl Server {
     /// Returns an error if the server fails to bind to the specified address or
     /// if there's an error during server execution.
     pub async fn run(self, host: String, port: u16) -> Result<(), Box<dyn std::error::Error>> {
+        let bound = self.bind(host, port).await?;
+        bound.serve_with_shutdown(shutdown_signal()).await?;
+
+        Ok(())
+    }
+
+    /// Bind the WebSocket chat server without serving yet
+    ///
+    /// Port `0` を指定すると OS が空いているポートを割り当てます。
+    /// 実際に bind されたアドレスは [`BoundServer::local_addr`] で取得できます。
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - The host address to bind to (e.g., "127.0.0.1")
+    /// * `port` - The port number to bind to (e.g., 8080, or 0 for an ephemeral port)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server fails to bind to the specified address.
+    pub async fn bind(self, host: String, port: u16) -> Result<BoundServer, std::io::Error> {
+        let readiness = self.readiness.clone();
+        let drain_period = self.drain_period;
+        let active_connections = self.active_connections.clone();
+        let idle_shutdown = self.idle_shutdown;
+        let shutdown_timeout = self.shutdown_timeout;
+        let app = self.into_router();
+
+        // Bind the server to the host and port
+        let bind_addr = format!("{}:{}", host, port);
+        let listener = TcpListener::bind(&bind_addr).await?;
+        let local_addr = listener.local_addr()?;
+
+        Ok(BoundServer {
+            listener,
+            local_addr,
+            app,
+            readiness,
+            drain_period,
+            active_connections,
+            idle_shutdown,
+            shutdown_timeout,
+        })
+    }
+
+    /// Build the router with all endpoints and the shared application state
+    fn into_router(self) -> Router {
         let app_state = Arc::new(AppState {
             connect_participant_usecase: self.connect_participant_usecase,
             disconnect_participant_usecase: self.disconnect_participant_usecase,
@@ -92,38 +400,1689 @@ impl Server {
             get_room_state_usecase: self.get_room_state_usecase,
             get_rooms_usecase: self.get_rooms_usecase,
             get_room_detail_usecase: self.get_room_detail_usecase,
+            get_participant_usecase: self.get_participant_usecase,
+            reply_pong_usecase: self.reply_pong_usecase,
+            send_file_usecase: self.send_file_usecase,
+            react_usecase: self.react_usecase,
+            admin_usecase: self.admin_usecase,
+            purge_messages_usecase: self.purge_messages_usecase,
+            admin_token: self.admin_token,
+            access_policy: self.access_policy,
+            metrics: self.metrics,
+            pretty_json: self.pretty_json,
+            retry_after: self.retry_after,
+            motd: self.motd,
+            accept_plain_text: self.accept_plain_text,
+            timestamp_precision: self.timestamp_precision,
+            max_frames_per_sec: self.max_frames_per_sec,
+            ip_connection_limiter: self
+                .max_connections_per_ip
+                .map(|max| Arc::new(IpConnectionLimiter::new(max))),
+            max_message_size: self.max_message_size,
+            readiness: self.readiness,
+            active_connections: self.active_connections,
         });
 
-        // Define handlers
-        let app = Router::new()
-            // WebSocket エンドポイント
-            .route("/ws", get(websocket_handler))
-            // HTTP エンドポイント
+        // HTTP エンドポイント
+        let mut http_routes = Router::new()
             .route("/debug/room", get(debug_room_state))
             .route("/api/health", get(health_check))
+            .route("/api/ready", get(readiness_check))
+            .route("/api/metrics", get(get_metrics))
             .route("/api/rooms", get(get_rooms))
             .route("/api/rooms/{room_id}", get(get_room_detail))
-            .with_state(app_state);
+            .route("/api/rooms/{room_id}/messages", delete(purge_messages))
+            .route(
+                "/api/rooms/{room_id}/participants/{client_id}",
+                get(get_participant),
+            )
+            .route(
+                "/api/rooms/{room_id}/participants/{client_id}/online",
+                get(get_participant_online),
+            );
+        if self.compress_responses {
+            // WebSocket のアップグレードに影響しないよう、HTTP エンドポイントのみに適用する
+            http_routes = http_routes.layer(CompressionLayer::new());
+        }
+        if !self.allowed_origins.is_empty() {
+            // WebSocket のアップグレードに影響しないよう、HTTP エンドポイントのみに適用する
+            http_routes = http_routes.layer(
+                CorsLayer::new()
+                    .allow_origin(AllowOrigin::list(self.allowed_origins))
+                    .allow_methods([Method::GET]),
+            );
+        }
 
-        // Bind the server to the host and port
-        let bind_addr = format!("{}:{}", host, port);
-        let listener = tokio::net::TcpListener::bind(&bind_addr).await?;
+        // WebSocket エンドポイント
+        Router::new()
+            .route("/ws", get(websocket_handler))
+            .merge(http_routes)
+            .with_state(app_state)
+    }
+}
 
+/// WebSocket chat server bound to a listener, ready to serve
+///
+/// [`Server::bind`] で生成されます。serve する前に実際の bind アドレスを取得できるため、
+/// ポート `0`（エフェメラルポート）で起動したサーバーの接続先を知ることができます。
+///
+/// # Example
+///
+/// ```ignore
+/// let bound = server.bind("127.0.0.1".to_string(), 0).await?;
+/// let addr = bound.local_addr();
+/// tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+/// // connect to ws://{addr}/ws
+/// ```
+pub struct BoundServer {
+    /// bind 済みの TCP リスナー
+    listener: TcpListener,
+    /// 実際に bind されたアドレス
+    local_addr: SocketAddr,
+    /// ルーティング設定済みの Router
+    app: Router,
+    /// readiness フラグ（serve の開始・ドレインの開始時に更新する）
+    readiness: Arc<Readiness>,
+    /// shutdown シグナル受信後にドレインする時間
+    drain_period: Duration,
+    /// 接続中の WebSocket 接続数
+    active_connections: Arc<ActiveConnections>,
+    /// 接続のない状態がこの時間続いたら停止する
+    idle_shutdown: Option<Duration>,
+    /// 接続中のクライアントへの送信待ちメッセージを送り切るまで待つ時間の上限
+    shutdown_timeout: Duration,
+}
+
+impl BoundServer {
+    /// Get the address the server is actually bound to
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Serve requests until the given shutdown signal completes
+    ///
+    /// [`Server::with_idle_shutdown`] を設定した場合は、接続のない状態が続いたときにも停止します。
+    /// 新しい接続の受け付けを止めた後、接続中のクライアントに送信待ちのメッセージを送り切ってから
+    /// 接続を閉じ、すべて閉じるまで（最大 [`Server::with_shutdown_timeout`] の時間）待ちます。
+    ///
+    /// # Arguments
+    ///
+    /// * `signal` - Future that resolves when the server should shut down gracefully
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's an error during server execution.
+    pub async fn serve_with_shutdown<F>(self, signal: F) -> Result<(), std::io::Error>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
         // Start the server
-        tracing::info!(
-            "WebSocket chat server listening on {}",
-            listener.local_addr()?
-        );
-        tracing::info!("Connect to: ws://{}/ws", bind_addr);
+        tracing::info!("WebSocket chat server listening on {}", self.local_addr);
+        tracing::info!("Connect to: ws://{}/ws", self.local_addr);
         tracing::info!("Press Ctrl+C to shutdown gracefully");
 
-        // Set up graceful shutdown signal handler
-        axum::serve(listener, app)
-            .with_graceful_shutdown(shutdown_signal())
-            .await?;
+        // シグナル受信後は readiness を落とし、ドレインしてから新しい接続の受け付けを止める
+        let readiness = self.readiness.clone();
+        let drain_period = self.drain_period;
+        let active_connections = self.active_connections.clone();
+        let idle_shutdown = self.idle_shutdown;
+        let signal = {
+            let active_connections = active_connections.clone();
+            async move {
+                match idle_shutdown {
+                    Some(idle_period) => tokio::select! {
+                        _ = signal => {}
+                        _ = active_connections.wait_idle_for(idle_period) => {
+                            tracing::info!(
+                                "No connections for {} seconds, initiating graceful shutdown...",
+                                idle_period.as_secs()
+                            );
+                        }
+                    },
+                    None => signal.await,
+                }
+                readiness.mark_draining();
+                if !drain_period.is_zero() {
+                    tracing::info!("Draining for {} seconds...", drain_period.as_secs());
+                    tokio::time::sleep(drain_period).await;
+                }
+            }
+        };
+
+        // 接続元アドレスを AccessPolicy で判定するため ConnectInfo を有効にする
+        let serve = axum::serve(
+            self.listener,
+            self.app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(signal);
+        self.readiness.mark_ready();
+        serve.await?;
+
+        // WebSocket の接続は serve の終了を待たずに残るため、送信待ちのメッセージを送り切ってから閉じる
+        active_connections.close_all();
+        let open_connections = active_connections.count();
+        if open_connections > 0 {
+            tracing::info!(
+                "Closing {} connections after flushing queued messages...",
+                open_connections
+            );
+        }
+        let closed =
+            tokio::time::timeout(self.shutdown_timeout, active_connections.wait_closed()).await;
+        if closed.is_err() {
+            tracing::warn!(
+                "{} connections still open after {} seconds; dropping their queued messages",
+                active_connections.count(),
+                self.shutdown_timeout.as_secs()
+            );
+        }
 
         tracing::info!("Server shutdown complete");
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ui::{ServerBuilder, access_policy::CidrAccessPolicy};
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::message_pusher::WebSocketMessagePusher,
+    };
+    use engawa_shared::time::get_jst_timestamp;
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    fn create_test_server() -> Server {
+        create_test_server_with_room(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        ))
+    }
+
+    fn create_test_server_with_room(room: Room) -> Server {
+        create_test_server_with_connect_usecase(room, |usecase| usecase)
+    }
+
+    /// Create a test server whose ConnectParticipantUseCase is adjusted by `configure`
+    fn create_test_server_with_connect_usecase(
+        room: Room,
+        configure: impl FnOnce(ConnectParticipantUseCase) -> ConnectParticipantUseCase + Send + 'static,
+    ) -> Server {
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        create_test_server_with_pusher(room, message_pusher, configure)
+    }
+
+    /// Create a test server delivering messages through the given MessagePusher
+    fn create_test_server_with_pusher(
+        room: Room,
+        message_pusher: Arc<WebSocketMessagePusher>,
+        configure: impl FnOnce(ConnectParticipantUseCase) -> ConnectParticipantUseCase + Send + 'static,
+    ) -> Server {
+        ServerBuilder::new()
+            .with_room(room)
+            .with_message_pusher(message_pusher)
+            .configure_connect_participant(configure)
+            .build()
+    }
+
+    #[tokio::test]
+    async fn test_bind_ephemeral_port_reports_local_addr() {
+        // テスト項目: ポート 0 で bind すると実際に割り当てられたアドレスが取得でき、接続できる
+        // given (前提条件):
+        let server = create_test_server();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serve_task = tokio::spawn(bound.serve_with_shutdown(async {
+            shutdown_rx.await.ok();
+        }));
+
+        // when (操作):
+        let response = reqwest::get(format!("http://{}/api/health", addr))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_ne!(addr.port(), 0);
+        assert!(response.status().is_success());
+
+        shutdown_tx.send(()).unwrap();
+        assert!(serve_task.await.unwrap().is_ok());
+    }
+
+    async fn connect_with_policy(
+        policy: CidrAccessPolicy,
+    ) -> Result<u16, tokio_tungstenite::tungstenite::Error> {
+        let server = create_test_server().with_access_policy(Arc::new(policy));
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        let (_ws, response) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr)).await?;
+        Ok(response.status().as_u16())
+    }
+
+    #[tokio::test]
+    async fn test_cidr_access_policy_allows_connection_from_allowed_address() {
+        // テスト項目: allow リストに含まれるアドレスからの接続はアップグレードされる
+        // given (前提条件):
+        let policy = CidrAccessPolicy::from_strs(&["127.0.0.0/8"], &[]).unwrap();
+
+        // when (操作):
+        let result = connect_with_policy(policy).await;
+
+        // then (期待する結果):
+        assert_eq!(result.unwrap(), 101);
+    }
+
+    #[tokio::test]
+    async fn test_cidr_access_policy_rejects_connection_from_denied_address_with_403() {
+        // テスト項目: deny リストに含まれるアドレスからの接続は 403 で拒否される
+        // given (前提条件):
+        let policy = CidrAccessPolicy::from_strs(&[], &["127.0.0.1/32"]).unwrap();
+
+        // when (操作):
+        let result = connect_with_policy(policy).await;
+
+        // then (期待する結果):
+        match result {
+            Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status().as_u16(), 403);
+            }
+            other => panic!("Expected HTTP 403 error, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_initial_status_is_reflected_in_participant_list_seen_by_others() {
+        // テスト項目: status=away で接続した参加者は、他の参加者が受け取る参加者リストで away になる
+        // given (前提条件):
+        use futures_util::StreamExt;
+
+        let server = create_test_server();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (_bob, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=bob&status=away", addr))
+                .await
+                .unwrap();
+
+        // when (操作):
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        let frame = alice.next().await.unwrap().unwrap();
+
+        // then (期待する結果):
+        let room_connected: serde_json::Value =
+            serde_json::from_str(frame.to_text().unwrap()).unwrap();
+        let participants = room_connected["participants"].as_array().unwrap();
+        let bob = participants
+            .iter()
+            .find(|p| p["client_id"] == "bob")
+            .unwrap();
+        let alice_info = participants
+            .iter()
+            .find(|p| p["client_id"] == "alice")
+            .unwrap();
+        assert_eq!(bob["status"], "away");
+        assert_eq!(alice_info["status"], "active");
+    }
+
+    /// Connect `client_id` and collect the frames received within a short wait
+    async fn receive_initial_frames(server: Server, client_id: &str) -> Vec<serde_json::Value> {
+        use futures_util::StreamExt;
+
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (mut ws, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, client_id))
+                .await
+                .unwrap();
+
+        let mut frames = Vec::new();
+        while let Ok(Some(Ok(frame))) =
+            tokio::time::timeout(Duration::from_millis(200), ws.next()).await
+        {
+            frames.push(serde_json::from_str(frame.to_text().unwrap()).unwrap());
+        }
+        frames
+    }
+
+    #[tokio::test]
+    async fn test_motd_is_sent_right_after_room_connected() {
+        // テスト項目: MOTD を設定すると room-connected の直後に system メッセージとして届き、
+        //            初期フレームの最後に ready が届く
+        // given (前提条件):
+        let server = create_test_server().with_motd("Welcome to engawa!");
+
+        // when (操作):
+        let frames = receive_initial_frames(server, "alice").await;
+
+        // then (期待する結果):
+        let types: Vec<&str> = frames.iter().map(|f| f["type"].as_str().unwrap()).collect();
+        assert_eq!(types, vec!["room-connected", "system", "ready"]);
+        assert_eq!(frames[1]["content"], "Welcome to engawa!");
+    }
+
+    #[tokio::test]
+    async fn test_no_system_message_without_motd() {
+        // テスト項目: MOTD が未設定または空の場合は system メッセージを送信しない
+        // given (前提条件):
+        let unset = create_test_server();
+        let empty = create_test_server().with_motd("  ");
+
+        // when (操作):
+        let unset_frames = receive_initial_frames(unset, "alice").await;
+        let empty_frames = receive_initial_frames(empty, "alice").await;
+
+        // then (期待する結果):
+        for frames in [unset_frames, empty_frames] {
+            let types: Vec<&str> = frames.iter().map(|f| f["type"].as_str().unwrap()).collect();
+            assert_eq!(types, vec!["room-connected", "ready"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_ready_is_sent_after_initial_frames_and_before_room_events() {
+        // テスト項目: ready は初期フレーム（参加者リスト・MOTD）の後に届き、
+        //            その後のルームのイベントは ready より後に届く
+        // given (前提条件): MOTD を設定したサーバーに bob が接続している
+        use futures_util::StreamExt;
+
+        let server = create_test_server().with_motd("Welcome to engawa!");
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, id))
+        };
+        let (mut bob, _) = connect("bob").await.unwrap();
+        next_frame_of_type(&mut bob, "ready").await.unwrap();
+
+        // when (操作): alice が接続し、直後に carol が入室する
+        let (mut alice, _) = connect("alice").await.unwrap();
+        let (_carol, _) = connect("carol").await.unwrap();
+
+        // then (期待する結果):
+        let mut types = Vec::new();
+        while let Ok(Some(Ok(frame))) =
+            tokio::time::timeout(Duration::from_millis(300), alice.next()).await
+        {
+            let value: serde_json::Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+            types.push(value["type"].as_str().unwrap().to_string());
+        }
+        assert_eq!(
+            types,
+            vec!["room-connected", "system", "ready", "participant-joined"]
+        );
+    }
+
+    /// Wait for the next frame of the given type, skipping others (`None` on timeout)
+    async fn next_frame_of_type<S>(ws: &mut S, frame_type: &str) -> Option<serde_json::Value>
+    where
+        S: futures_util::Stream<
+                Item = Result<
+                    tokio_tungstenite::tungstenite::Message,
+                    tokio_tungstenite::tungstenite::Error,
+                >,
+            > + Unpin,
+    {
+        use futures_util::StreamExt;
+
+        while let Ok(Some(Ok(frame))) =
+            tokio::time::timeout(Duration::from_millis(300), ws.next()).await
+        {
+            let value: serde_json::Value = serde_json::from_str(frame.to_text().unwrap()).unwrap();
+            if value["type"] == frame_type {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    #[tokio::test]
+    async fn test_takeover_closes_the_old_connection_and_keeps_the_participant() {
+        // テスト項目: 重複時の扱いが takeover のとき、同じ client_id の新しい接続が受け入れられ、
+        //            古い接続は置き換えを示すコードで閉じられ、参加者は退出扱いにならない
+        // given (前提条件): bob と alice（1 本目）が接続している
+        use crate::infrastructure::dto::websocket::CLOSE_CODE_REPLACED;
+        use crate::usecase::DuplicatePolicy;
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let server = create_test_server_with_connect_usecase(
+            Room::new(
+                RoomIdFactory::generate().unwrap(),
+                Timestamp::new(get_jst_timestamp()),
+            ),
+            |usecase| usecase.with_duplicate_policy(DuplicatePolicy::Takeover),
+        );
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, id))
+        };
+        let (mut bob, _) = connect("bob").await.unwrap();
+        let (mut old_alice, _) = connect("alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        // when (操作): alice が 2 本目の接続を開く
+        let (mut new_alice, _) = connect("alice").await.unwrap();
+
+        // then (期待する結果):
+        let close_frame = loop {
+            match old_alice.next().await.unwrap().unwrap() {
+                Message::Close(frame) => break frame.unwrap(),
+                _ => continue,
+            }
+        };
+        assert_eq!(u16::from(close_frame.code), CLOSE_CODE_REPLACED);
+        assert!(
+            next_frame_of_type(&mut new_alice, "room-connected")
+                .await
+                .is_some()
+        );
+
+        // 新しい接続でメッセージを受け取れ、bob には退出も入室も通知されない
+        bob.send(Message::Text(
+            r#"{"type":"chat","client_id":"bob","content":"still there?","timestamp":1}"#.into(),
+        ))
+        .await
+        .unwrap();
+        let chat = next_frame_of_type(&mut new_alice, "chat").await.unwrap();
+        assert_eq!(chat["content"], "still there?");
+        let room: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(room[0]["participants"], serde_json::json!(["bob", "alice"]));
+        assert!(
+            next_frame_of_type(&mut bob, "participant-left")
+                .await
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_idle_server_shuts_down_after_the_last_client_leaves() {
+        // テスト項目: アイドル時の自動停止を有効にすると、接続中は停止せず、
+        //            最後のクライアントが切断してからアイドル時間が経過すると停止する
+        // given (前提条件): アイドル時間 200ms のサーバーに alice が接続している
+        let idle_period = Duration::from_millis(200);
+        let server = create_test_server().with_idle_shutdown(idle_period);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        let mut serving = tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        next_frame_of_type(&mut alice, "ready").await.unwrap();
+
+        // when (操作):
+        let while_connected = tokio::time::timeout(idle_period * 3, &mut serving).await;
+        alice.close(None).await.unwrap();
+        let after_disconnect = tokio::time::timeout(Duration::from_secs(5), &mut serving).await;
+
+        // then (期待する結果):
+        assert!(while_connected.is_err());
+        after_disconnect.unwrap().unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_messages_queued_before_shutdown_are_delivered_before_close() {
+        // テスト項目: shutdown 直前に送信キューへ積まれたメッセージは、ドレイン中にすべて配信され、
+        //            その後 Going Away のクローズフレームで接続が閉じられる
+        // given (前提条件): alice が接続している
+        use crate::domain::{ClientId, MessagePusher};
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::{Message, protocol::frame::coding::CloseCode};
+
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let server = create_test_server_with_pusher(
+            Room::new(
+                RoomIdFactory::generate().unwrap(),
+                Timestamp::new(get_jst_timestamp()),
+            ),
+            message_pusher.clone(),
+            |usecase| usecase,
+        );
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serving = tokio::spawn(bound.serve_with_shutdown(async {
+            shutdown_rx.await.ok();
+        }));
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        next_frame_of_type(&mut alice, "ready").await.unwrap();
+
+        // when (操作): メッセージを積んだ直後に shutdown する
+        let alice_id = ClientId::new("alice".to_string()).unwrap();
+        for i in 0..20 {
+            let system_json = format!(r#"{{"type":"system","content":"message {}"}}"#, i);
+            message_pusher
+                .push_to(&alice_id, &system_json)
+                .await
+                .unwrap();
+        }
+        shutdown_tx.send(()).unwrap();
+        let mut delivered = Vec::new();
+        let close_frame = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match alice.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+                        delivered.push(value["content"].as_str().unwrap().to_string());
+                    }
+                    Some(Ok(Message::Close(frame))) => break frame,
+                    Some(Ok(_)) => continue,
+                    other => panic!("connection ended without a close frame: {:?}", other),
+                }
+            }
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        let stopped = tokio::time::timeout(Duration::from_secs(5), serving).await;
+
+        // then (期待する結果):
+        let expected: Vec<String> = (0..20).map(|i| format!("message {}", i)).collect();
+        assert_eq!(delivered, expected);
+        assert_eq!(close_frame.code, CloseCode::Away);
+        stopped.unwrap().unwrap().unwrap();
+    }
+
+    /// Log output captured by a test subscriber
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_established_log_contains_session_parameters() {
+        // テスト項目: ハンドシェイク後に、接続のパラメータをまとめた構造化ログが 1 行出力される
+        // given (前提条件): ログを記録するサブスクライバー（current_thread ランタイムのため、
+        //                   同じスレッドで動くサーバーのタスクのログも記録される）
+        let logs = CapturedLogs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let _default = tracing::subscriber::set_default(subscriber);
+        let room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        let room_id = room.id.as_str().to_string();
+        let server = create_test_server_with_room(room);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        // when (操作):
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        next_frame_of_type(&mut alice, "ready").await.unwrap();
+
+        // then (期待する結果):
+        let logs = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let line = logs
+            .lines()
+            .find(|line| line.contains("Connection established"))
+            .expect("connection established log");
+        for field in [
+            "client_id=alice".to_string(),
+            format!("room_id={}", room_id),
+            "subprotocol=none".to_string(),
+            "compression=false".to_string(),
+            "max_message_size=67108864".to_string(),
+        ] {
+            assert!(line.contains(&field), "missing {} in {}", field, line);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_frame_flood_closes_the_connection_with_policy_violation() {
+        // テスト項目: 1 秒あたりのフレーム数の上限を超えて送信した接続は、
+        //            Policy Violation のクローズコードで切断される
+        // given (前提条件): 上限 5 フレーム/秒のサーバーに alice が接続している
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::{Message, protocol::frame::coding::CloseCode};
+
+        let server = create_test_server().with_max_frames_per_sec(5);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        next_frame_of_type(&mut alice, "ready").await.unwrap();
+
+        // when (操作): ping フレームを 20 件続けて送る
+        for _ in 0..20 {
+            if alice.send(Message::Ping(Vec::new().into())).await.is_err() {
+                break;
+            }
+        }
+
+        // then (期待する結果):
+        let close_frame = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match alice.next().await {
+                    Some(Ok(Message::Close(frame))) => break frame,
+                    Some(Ok(_)) => continue,
+                    other => panic!("connection ended without a close frame: {:?}", other),
+                }
+            }
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(close_frame.code, CloseCode::Policy);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_message_closes_the_connection_with_size_code_and_is_counted() {
+        // テスト項目: 受信サイズの上限を超えるメッセージを送った接続は、通常の切断ではなく
+        //            Message Too Big のクローズコードで切断され、メトリクスに記録される
+        // given (前提条件): 上限 1024 バイトのサーバーに alice が接続している
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::{Message, protocol::frame::coding::CloseCode};
+
+        let server = create_test_server().with_max_message_size(1024);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (mut alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        next_frame_of_type(&mut alice, "ready").await.unwrap();
+
+        // when (操作): 2048 バイトのテキストフレームを送る
+        alice
+            .send(Message::Text("x".repeat(2048).into()))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        let close_frame = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                match alice.next().await {
+                    Some(Ok(Message::Close(frame))) => break frame,
+                    Some(Ok(_)) => continue,
+                    other => panic!("connection ended without a close frame: {:?}", other),
+                }
+            }
+        })
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(close_frame.code, CloseCode::Size);
+        let metrics: serde_json::Value = reqwest::get(format!("http://{}/api/metrics", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(metrics["receive_errors"]["message_too_large"], 1);
+        assert_eq!(metrics["receive_errors"]["protocol_violation"], 0);
+    }
+
+    /// Start `server`, connect alice and bob, and send a plain-text frame from alice
+    async fn send_plain_text_from_alice(
+        server: Server,
+    ) -> (Option<serde_json::Value>, Option<serde_json::Value>) {
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, id))
+        };
+        let (mut bob, _) = connect("bob").await.unwrap();
+        let (mut alice, _) = connect("alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        alice
+            .send(Message::Text("hello in plain text".into()))
+            .await
+            .unwrap();
+
+        let received_by_bob = next_frame_of_type(&mut bob, "chat").await;
+        let error_for_alice = next_frame_of_type(&mut alice, "error").await;
+        (received_by_bob, error_for_alice)
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_is_sent_as_the_connected_client() {
+        // テスト項目: 寛容モードでは JSON でないテキストは接続中のクライアントのチャットとして扱われる
+        // given (前提条件):
+        let server = create_test_server();
+
+        // when (操作):
+        let (received_by_bob, error_for_alice) = send_plain_text_from_alice(server).await;
+
+        // then (期待する結果): "unknown" ではなく alice からのメッセージとして届く
+        let chat = received_by_bob.unwrap();
+        assert_eq!(chat["client_id"], "alice");
+        assert_eq!(chat["content"], "hello in plain text");
+        assert!(chat["timestamp"].as_i64().unwrap() > 0);
+        assert!(error_for_alice.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_plain_text_is_rejected_in_strict_mode() {
+        // テスト項目: 厳格モードでは JSON でないテキストは送信者にのみエラーを返し、ブロードキャストしない
+        // given (前提条件):
+        let server = create_test_server().with_plain_text_messages(false);
+
+        // when (操作):
+        let (received_by_bob, error_for_alice) = send_plain_text_from_alice(server).await;
+
+        // then (期待する結果):
+        assert!(received_by_bob.is_none());
+        assert_eq!(error_for_alice.unwrap()["code"], "invalid-message");
+    }
+
+    #[tokio::test]
+    async fn test_reaction_is_broadcast_and_unknown_message_is_rejected() {
+        // テスト項目: チャットの message_id へのリアクションは更新後の数が全員に配信され、
+        //            履歴にない ID へのリアクションは送信者にのみエラーが返る
+        // given (前提条件): alice のメッセージを bob が受信している
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, id))
+        };
+        let (mut bob, _) = connect("bob").await.unwrap();
+        let (mut alice, _) = connect("alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+        alice
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"alice","content":"lunch?","timestamp":1}"#.into(),
+            ))
+            .await
+            .unwrap();
+        let chat = next_frame_of_type(&mut bob, "chat").await.unwrap();
+        let message_id = chat["message_id"].as_str().unwrap().to_string();
+
+        // when (操作):
+        let reaction = serde_json::json!({
+            "type": "reaction",
+            "message_id": message_id,
+            "emoji": "👍",
+            "action": "add",
+        });
+        bob.send(Message::Text(reaction.to_string().into()))
+            .await
+            .unwrap();
+        let updated_for_alice = next_frame_of_type(&mut alice, "reaction-updated").await;
+        let updated_for_bob = next_frame_of_type(&mut bob, "reaction-updated").await;
+        bob.send(Message::Text(
+            r#"{"type":"reaction","message_id":"no-such-id","emoji":"👍","action":"add"}"#.into(),
+        ))
+        .await
+        .unwrap();
+        let error_for_bob = next_frame_of_type(&mut bob, "error").await;
+
+        // then (期待する結果):
+        let updated = updated_for_alice.unwrap();
+        assert_eq!(updated["message_id"], message_id.as_str());
+        assert_eq!(updated["emoji"], "👍");
+        assert_eq!(updated["count"], 1);
+        assert_eq!(updated["by"], "bob");
+        assert!(updated_for_bob.is_some());
+        assert_eq!(error_for_bob.unwrap()["code"], "message-not-found");
+    }
+
+    #[tokio::test]
+    async fn test_ephemeral_chat_is_broadcast_with_its_expiry() {
+        // テスト項目: ttl_secs を付けたチャットは、送信時刻に TTL を足した expires_at 付きで配信され、
+        //            ttl_secs 自体は転送されない
+        // given (前提条件): alice と bob が接続している
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, id))
+        };
+        let (mut bob, _) = connect("bob").await.unwrap();
+        let (mut alice, _) = connect("alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        // when (操作):
+        let sent_after = get_jst_timestamp();
+        alice
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"alice","content":"bye soon","timestamp":1,"ttl_secs":60}"#
+                    .into(),
+            ))
+            .await
+            .unwrap();
+        let chat = next_frame_of_type(&mut bob, "chat").await.unwrap();
+        let received_before = get_jst_timestamp();
+
+        // then (期待する結果):
+        let expires_at = chat["expires_at"].as_i64().unwrap();
+        assert!((sent_after + 60_000..=received_before + 60_000).contains(&expires_at));
+        assert!(chat.get("ttl_secs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_join_and_leave_notifications_carry_the_participant_count() {
+        // テスト項目: participant-joined と participant-left に、通知時点の参加者数が含まれる
+        // given (前提条件): alice が接続している
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |client_id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, client_id))
+        };
+        let (mut alice, _) = connect("alice").await.unwrap();
+
+        // when (操作): bob が接続して切断する
+        let (mut bob, _) = connect("bob").await.unwrap();
+        let joined = next_frame_of_type(&mut alice, "participant-joined").await;
+        bob.close(None).await.unwrap();
+        let left = next_frame_of_type(&mut alice, "participant-left").await;
+
+        // then (期待する結果):
+        let joined = joined.unwrap();
+        assert_eq!(joined["client_id"], "bob");
+        assert_eq!(joined["participant_count"], 2);
+        let left = left.unwrap();
+        assert_eq!(left["client_id"], "bob");
+        assert_eq!(left["participant_count"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_resets_idle_time_without_being_broadcast() {
+        // テスト項目: ハートビートを送った参加者のアイドル時間がリセットされ、
+        //            ハートビートは他の参加者に転送されない
+        // given (前提条件): 接続してから 500ms 発言していない alice と、bob が接続している
+        use futures_util::SinkExt;
+
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect = |client_id: &str| {
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id={}", addr, client_id))
+        };
+        let (mut alice, _) = connect("alice").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let (mut bob, _) = connect("bob").await.unwrap();
+
+        // when (操作): alice がハートビートを送ってから参加者リストを要求する
+        for frame in [r#"{"type":"heartbeat"}"#, r#"{"type":"roster-request"}"#] {
+            alice
+                .send(tokio_tungstenite::tungstenite::Message::Text(frame.into()))
+                .await
+                .unwrap();
+        }
+        let roster = next_frame_of_type(&mut alice, "roster").await;
+        let forwarded = next_frame_of_type(&mut bob, "heartbeat").await;
+
+        // then (期待する結果):
+        let roster = roster.unwrap();
+        let alice_entry = roster["participants"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["client_id"] == "alice")
+            .unwrap();
+        assert!(alice_entry["idle_ms"].as_u64().unwrap() < 250);
+        assert!(forwarded.is_none());
+    }
+
+    /// Start a test server accepting admin connections with the token `secret`
+    async fn spawn_server_with_admin_token() -> SocketAddr {
+        let bound = create_test_server()
+            .with_admin_token("secret".to_string())
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_admin_can_kick_a_participant_over_websocket() {
+        // テスト項目: 管理者トークンで接続した管理者の `/admin kick` で対象の接続は kick を示すコードで閉じられ、
+        //            他の参加者には退出が通知され、管理者には実行結果が返る
+        // given (前提条件): bob と alice、管理者が接続している
+        use crate::infrastructure::dto::websocket::CLOSE_CODE_KICKED;
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message;
+
+        let addr = spawn_server_with_admin_token().await;
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        let (mut admin, _) = connect("client_id=admin&admin_token=secret").await.unwrap();
+        next_frame_of_type(&mut alice, "participant-joined").await;
+
+        // when (操作):
+        admin
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"admin","content":"/admin kick bob","timestamp":1}"#
+                    .into(),
+            ))
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        let close_frame = loop {
+            match bob.next().await.unwrap().unwrap() {
+                Message::Close(frame) => break frame.unwrap(),
+                _ => continue,
+            }
+        };
+        assert_eq!(u16::from(close_frame.code), CLOSE_CODE_KICKED);
+        let left = next_frame_of_type(&mut alice, "participant-left").await;
+        assert_eq!(left.unwrap()["client_id"], "bob");
+        let result = next_frame_of_type(&mut admin, "system").await;
+        assert_eq!(result.unwrap()["content"], "Kicked 'bob'");
+        let room: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(
+            room[0]["participants"],
+            serde_json::json!(["alice", "admin"])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_normal_user_cannot_run_admin_commands() {
+        // テスト項目: 管理者でない接続の `/admin kick` は forbidden で拒否され、チャットとしても配信されない。
+        //            誤った管理者トークンでの接続は 403 で拒否される
+        // given (前提条件): bob と alice が管理者トークンなしで接続している
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::{Error, Message};
+
+        let addr = spawn_server_with_admin_token().await;
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        // when (操作):
+        alice
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"alice","content":"/admin kick bob","timestamp":1}"#
+                    .into(),
+            ))
+            .await
+            .unwrap();
+        let error_for_alice = next_frame_of_type(&mut alice, "error").await;
+        let received_by_bob = next_frame_of_type(&mut bob, "chat").await;
+        let wrong_token = connect("client_id=mallory&admin_token=guess").await;
+
+        // then (期待する結果):
+        assert_eq!(error_for_alice.unwrap()["code"], "forbidden");
+        assert!(received_by_bob.is_none());
+        let room: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(room[0]["participants"], serde_json::json!(["bob", "alice"]));
+        match wrong_token {
+            Err(Error::Http(response)) => assert_eq!(response.status(), 403),
+            other => panic!("expected 403, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_muted_client_cannot_send_by_claiming_another_client_id() {
+        // テスト項目: ミュートされた参加者がフレームの client_id を別の参加者のものに書き換えて送信しても、
+        //            拒否されて配信されない
+        // given (前提条件): 管理者が bob をミュートしている
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let addr = spawn_server_with_admin_token().await;
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut admin, _) = connect("client_id=admin&admin_token=secret").await.unwrap();
+        next_frame_of_type(&mut alice, "participant-joined").await;
+        admin
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"admin","content":"/admin mute bob","timestamp":1}"#
+                    .into(),
+            ))
+            .await
+            .unwrap();
+        next_frame_of_type(&mut admin, "system").await.unwrap();
+
+        // when (操作):
+        bob.send(Message::Text(
+            r#"{"type":"chat","client_id":"admin","content":"Not me","timestamp":1}"#.into(),
+        ))
+        .await
+        .unwrap();
+        let error_for_bob = next_frame_of_type(&mut bob, "error").await;
+        let received_by_alice = next_frame_of_type(&mut alice, "chat").await;
+
+        // then (期待する結果):
+        assert!(error_for_bob.is_some());
+        assert!(received_by_alice.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_slow_mode_cannot_be_bypassed_by_changing_the_client_id() {
+        // テスト項目: スローモード中に、フレームの client_id を毎回変えて連投しても、
+        //            2 通目はスローモードで拒否されて配信されない
+        // given (前提条件): 10 秒のスローモードの Room に alice と bob が接続している
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let mut room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        room.slow_mode_interval = Some(Duration::from_secs(10));
+        let bound = create_test_server_with_room(room)
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        // when (操作):
+        for claimed_id in ["alice", "alice2"] {
+            let chat = format!(
+                r#"{{"type":"chat","client_id":"{}","content":"Hi","timestamp":1}}"#,
+                claimed_id
+            );
+            alice.send(Message::Text(chat.into())).await.unwrap();
+        }
+        let error_for_alice = next_frame_of_type(&mut alice, "error").await;
+        let first = next_frame_of_type(&mut bob, "chat").await;
+        let second = next_frame_of_type(&mut bob, "chat").await;
+
+        // then (期待する結果): 別の client_id を名乗ったフレームはスローモードの判定より前に拒否される
+        assert_eq!(error_for_alice.unwrap()["code"], "client-id-mismatch");
+        assert_eq!(first.unwrap()["client_id"], "alice");
+        assert!(second.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chat_claiming_another_client_id_is_rejected() {
+        // テスト項目: 接続時に確認した client_id と異なる client_id を名乗るチャットは
+        //            client-id-mismatch で拒否され、配信も保存もされない
+        // given (前提条件): alice と bob が接続している
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+
+        // when (操作):
+        alice
+            .send(Message::Text(
+                r#"{"type":"chat","client_id":"carol","content":"Hi","timestamp":1}"#.into(),
+            ))
+            .await
+            .unwrap();
+        let error_for_alice = next_frame_of_type(&mut alice, "error").await;
+        let received_by_bob = next_frame_of_type(&mut bob, "chat").await;
+
+        // then (期待する結果):
+        assert_eq!(error_for_alice.unwrap()["code"], "client-id-mismatch");
+        assert!(received_by_bob.is_none());
+        let room: serde_json::Value = reqwest::get(format!("http://{}/debug/room", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(room["messages"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_purge_messages_empties_the_history_and_notifies_clients() {
+        // テスト項目: 管理者トークン付きの DELETE /api/rooms/{room_id}/messages で履歴が空になり、
+        //            削除件数が返り、接続中のクライアントに history-cleared が届く。トークンがなければ 401
+        // given (前提条件): alice が 2 件のメッセージを送信し、bob が受信した
+        use futures_util::SinkExt;
+        use tokio_tungstenite::tungstenite::Message;
+
+        let addr = spawn_server_with_admin_token().await;
+        let connect =
+            |query: &str| tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query));
+        let (mut bob, _) = connect("client_id=bob").await.unwrap();
+        let (mut alice, _) = connect("client_id=alice").await.unwrap();
+        next_frame_of_type(&mut bob, "participant-joined").await;
+        for content in ["first", "second"] {
+            let chat = format!(
+                r#"{{"type":"chat","client_id":"alice","content":"{}","timestamp":1}}"#,
+                content
+            );
+            alice.send(Message::Text(chat.into())).await.unwrap();
+            next_frame_of_type(&mut bob, "chat").await.unwrap();
+        }
+        let rooms: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let url = format!(
+            "http://{}/api/rooms/{}/messages",
+            addr,
+            rooms[0]["id"].as_str().unwrap()
+        );
+        let http = reqwest::Client::new();
+
+        // when (操作):
+        let without_token = http.delete(&url).send().await.unwrap();
+        let wrong_token = http.delete(&url).bearer_auth("guess").send().await.unwrap();
+        let purged = http
+            .delete(&url)
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(without_token.status(), 401);
+        assert_eq!(wrong_token.status(), 401);
+        let body: serde_json::Value = without_token.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "unauthorized");
+        assert_eq!(purged.status(), 200);
+        let body: serde_json::Value = purged.json().await.unwrap();
+        assert_eq!(body["purged"], 2);
+        let cleared = next_frame_of_type(&mut bob, "history-cleared").await;
+        assert_eq!(cleared.unwrap()["purged"], 2);
+        let room: serde_json::Value = reqwest::get(format!("http://{}/debug/room", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(room["messages"], serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_rejected_connections_are_counted_by_reason() {
+        // テスト項目: 重複 ID と容量超過による接続拒否がそれぞれのカウンターに計上される
+        // given (前提条件):
+        let room = Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+            1, // participant_capacity
+            10,
+        );
+        let server = create_test_server_with_room(room);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let ws_url = |client_id: &str| format!("ws://{}/ws?client_id={}", addr, client_id);
+        let (_alice, _) = tokio_tungstenite::connect_async(ws_url("alice"))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let duplicate = tokio_tungstenite::connect_async(ws_url("alice")).await;
+        let over_capacity = tokio_tungstenite::connect_async(ws_url("bob")).await;
+        let invalid_status =
+            tokio_tungstenite::connect_async(format!("{}&status=sleepy", ws_url("carol"))).await;
+        let metrics: serde_json::Value = reqwest::get(format!("http://{}/api/metrics", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert!(duplicate.is_err());
+        assert!(over_capacity.is_err());
+        assert!(invalid_status.is_err());
+        let rejected = &metrics["rejected_connections"];
+        assert_eq!(rejected["duplicate_id"], 1);
+        assert_eq!(rejected["capacity"], 1);
+        assert_eq!(rejected["invalid_id"], 0);
+        assert_eq!(rejected["invalid_status"], 1);
+        assert_eq!(rejected["auth_failure"], 0);
+        assert_eq!(rejected["access_denied"], 0);
+        assert_eq!(rejected["per_ip_limit"], 0);
+        assert_eq!(rejected["internal_error"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_access_policy_denial_is_counted_apart_from_auth_failures() {
+        // テスト項目: AccessPolicy で拒否された接続は access_denied に計上され、auth_failure には計上されない
+        // given (前提条件): 127.0.0.1 からの接続を拒否する
+        let policy = CidrAccessPolicy::from_strs(&[], &["127.0.0.1/32"]).unwrap();
+        let bound = create_test_server()
+            .with_access_policy(Arc::new(policy))
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        // when (操作):
+        let denied =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr)).await;
+        let metrics: serde_json::Value = reqwest::get(format!("http://{}/api/metrics", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert!(denied.is_err());
+        let rejected = &metrics["rejected_connections"];
+        assert_eq!(rejected["access_denied"], 1);
+        assert_eq!(rejected["auth_failure"], 0);
+    }
+
+    #[tokio::test]
+    async fn test_racing_upgrades_with_the_same_id_leave_one_live_socket() {
+        // テスト項目: 同じ client_id で並行してアップグレードを要求しても、接続できるのは 1 つだけで、
+        //            残りはアップグレード前に 409 で拒否される
+        // given (前提条件):
+        let bound = create_test_server()
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let ws_url = format!("ws://{}/ws?client_id=alice", addr);
+
+        // when (操作): 8 つの接続要求を同時に送る
+        let attempts: Vec<_> = (0..8)
+            .map(|_| tokio::spawn(tokio_tungstenite::connect_async(ws_url.clone())))
+            .collect();
+        let mut connected = Vec::new();
+        let mut conflict_count = 0;
+        for attempt in attempts {
+            match attempt.await.unwrap() {
+                Ok((ws, _)) => connected.push(ws),
+                Err(tokio_tungstenite::tungstenite::Error::Http(response))
+                    if response.status().as_u16() == 409 =>
+                {
+                    conflict_count += 1;
+                }
+                Err(e) => panic!("Unexpected connection error: {}", e),
+            }
+        }
+        let rooms: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(connected.len(), 1);
+        assert_eq!(conflict_count, 7);
+        assert_eq!(rooms[0]["participants"], serde_json::json!(["alice"]));
+        let mut alice = connected.pop().unwrap();
+        assert!(
+            next_frame_of_type(&mut alice, "room-connected")
+                .await
+                .is_some()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connections_over_the_per_ip_limit_are_rejected_until_one_closes() {
+        // テスト項目: 同じ送信元アドレスから上限を超えた接続は 429 で拒否されて計上され、
+        //            接続が 1 つ切断されると再び受け付けられる
+        // given (前提条件): 1 アドレスあたり 2 接続までで、alice と bob が接続している
+        let bound = create_test_server()
+            .with_max_connections_per_ip(2)
+            .bind("127.0.0.1".to_string(), 0)
+            .await
+            .unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let ws_url = |client_id: &str| format!("ws://{}/ws?client_id={}", addr, client_id);
+        let (alice, _) = tokio_tungstenite::connect_async(ws_url("alice"))
+            .await
+            .unwrap();
+        let (_bob, _) = tokio_tungstenite::connect_async(ws_url("bob"))
+            .await
+            .unwrap();
+
+        // when (操作): carol が接続し、alice の切断後にもう一度接続する
+        let over_limit = tokio_tungstenite::connect_async(ws_url("carol")).await;
+        let metrics: serde_json::Value = reqwest::get(format!("http://{}/api/metrics", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        drop(alice);
+        let after_close = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(connection) = tokio_tungstenite::connect_async(ws_url("carol")).await {
+                    return connection;
+                }
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        })
+        .await;
+
+        // then (期待する結果):
+        match over_limit {
+            Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status().as_u16(), 429);
+            }
+            other => panic!("Expected HTTP 429 error, got {:?}", other.map(|_| ())),
+        }
+        assert_eq!(metrics["rejected_connections"]["per_ip_limit"], 1);
+        assert!(after_close.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_pretty_query_returns_indented_json_and_default_is_compact() {
+        // テスト項目: `?pretty=1` 指定時はインデント付き JSON、未指定時はコンパクトな JSON を返す
+        // given (前提条件):
+        let server = create_test_server();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let get_body = |query: &'static str| async move {
+            reqwest::get(format!("http://{}/api/rooms{}", addr, query))
+                .await
+                .unwrap()
+                .text()
+                .await
+                .unwrap()
+        };
+
+        // when (操作):
+        let pretty = get_body("?pretty=1").await;
+        let compact = get_body("").await;
+
+        // then (期待する結果):
+        assert!(pretty.contains("\n  {"));
+        assert!(!compact.contains('\n'));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_large_response_is_compressed_when_client_accepts_gzip() {
+        // テスト項目: 圧縮を有効にすると、Accept-Encoding: gzip のリクエストには
+        //            gzip で圧縮したレスポンスを返し、指定のないリクエストには圧縮せずに返す
+        // given (前提条件): メッセージ 50 件のルーム
+        use crate::domain::{ChatMessage, ClientId, MessageContent};
+        use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+
+        let mut room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        for i in 0..50 {
+            room.add_message(ChatMessage::new(
+                ClientId::new("alice".to_string()).unwrap(),
+                MessageContent::new(format!("message number {} in a long history", i)).unwrap(),
+                Timestamp::new(i),
+            ))
+            .unwrap();
+        }
+        let server = create_test_server_with_room(room).with_response_compression(true);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let url = format!("http://{}/debug/room", addr);
+        let client = reqwest::Client::new();
+
+        // when (操作):
+        let gzip = client
+            .get(&url)
+            .header(ACCEPT_ENCODING, "gzip")
+            .send()
+            .await
+            .unwrap();
+        let identity = client.get(&url).send().await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(gzip.headers()[CONTENT_ENCODING], "gzip");
+        assert!(identity.headers().get(CONTENT_ENCODING).is_none());
+        let compressed_len = gzip.bytes().await.unwrap().len();
+        let plain_len = identity.bytes().await.unwrap().len();
+        assert!(compressed_len < plain_len);
+    }
+
+    #[tokio::test]
+    async fn test_missing_room_returns_json_error_body() {
+        // テスト項目: 存在しないルームの詳細取得は 404 と JSON のエラーボディを返す
+        // given (前提条件):
+        let server = create_test_server();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let missing_room_id = RoomIdFactory::generate().unwrap();
+
+        // when (操作):
+        let response = reqwest::get(format!(
+            "http://{}/api/rooms/{}",
+            addr,
+            missing_room_id.as_str()
+        ))
+        .await
+        .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+        let body: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(body["error"]["code"], "room-not-found");
+        assert_eq!(body["error"]["message"], "Room not found");
+    }
+
+    #[tokio::test]
+    async fn test_readiness_is_true_while_serving_and_false_during_drain() {
+        // テスト項目: serve 中は /api/ready が 200、shutdown シグナル後のドレイン中は 503 を返す
+        // given (前提条件):
+        let server = create_test_server().with_drain_period(Duration::from_secs(1));
+        let readiness = server.readiness();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        let before_serving = readiness.is_ready();
+        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel::<()>();
+        let serve_task = tokio::spawn(bound.serve_with_shutdown(async {
+            shutdown_rx.await.ok();
+        }));
+        let client = reqwest::Client::new();
+        let ready_url = format!("http://{}/api/ready", addr);
+
+        // when (操作):
+        let serving = client.get(&ready_url).send().await.unwrap();
+        shutdown_tx.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let draining = client.get(&ready_url).send().await.unwrap();
+
+        // then (期待する結果):
+        assert!(!before_serving);
+        assert_eq!(serving.status().as_u16(), 200);
+        assert_eq!(draining.status().as_u16(), 503);
+        let body: serde_json::Value = draining.json().await.unwrap();
+        assert_eq!(body["status"], "draining");
+        serve_task.await.unwrap().unwrap();
+    }
+
+    async fn get_rooms_with_origin(server: Server, origin: &str) -> reqwest::Response {
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        reqwest::Client::new()
+            .get(format!("http://{}/api/rooms", addr))
+            .header("Origin", origin)
+            .send()
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_cors_header_is_returned_only_for_allowed_origin() {
+        // テスト項目: 許可したオリジンには Access-Control-Allow-Origin が付与され、それ以外には付与されない
+        // given (前提条件):
+        let allowed = HeaderValue::from_static("http://dashboard.example.com");
+
+        // when (操作):
+        let allowed_response = get_rooms_with_origin(
+            create_test_server().with_allowed_origins(vec![allowed.clone()]),
+            "http://dashboard.example.com",
+        )
+        .await;
+        let disallowed_response = get_rooms_with_origin(
+            create_test_server().with_allowed_origins(vec![allowed]),
+            "http://evil.example.com",
+        )
+        .await;
+
+        // then (期待する結果):
+        assert_eq!(
+            allowed_response
+                .headers()
+                .get("access-control-allow-origin")
+                .unwrap(),
+            "http://dashboard.example.com"
+        );
+        assert!(
+            disallowed_response
+                .headers()
+                .get("access-control-allow-origin")
+                .is_none()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_websocket_upgrade_works_with_cors_enabled() {
+        // テスト項目: CORS を有効にしても WebSocket 接続はアップグレードされる
+        // given (前提条件):
+        let server = create_test_server().with_allowed_origins(vec![HeaderValue::from_static(
+            "http://dashboard.example.com",
+        )]);
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        // when (操作):
+        let (_ws, response) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(response.status().as_u16(), 101);
+    }
+
+    #[tokio::test]
+    async fn test_capacity_rejection_includes_retry_after_header() {
+        // テスト項目: 容量超過による接続拒否は 503 と設定した Retry-After ヘッダーを返す
+        // given (前提条件):
+        let room = Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+            1, // participant_capacity
+            10,
+        );
+        let server = create_test_server_with_room(room).with_retry_after(Duration::from_secs(7));
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+        let (_alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+
+        // when (操作):
+        let result =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=bob", addr)).await;
+
+        // then (期待する結果):
+        match result {
+            Err(tokio_tungstenite::tungstenite::Error::Http(response)) => {
+                assert_eq!(response.status().as_u16(), 503);
+                assert_eq!(response.headers().get("retry-after").unwrap(), "7");
+            }
+            other => panic!("Expected HTTP 503 error, got {:?}", other),
+        }
+    }
+}
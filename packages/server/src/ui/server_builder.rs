@@ -0,0 +1,260 @@
+//! Assembly of the server dependency graph.
+//!
+//! [`ServerBuilder`] は Repository と MessagePusher から全てのユースケースを生成し、
+//! [`Server`] を組み立てます。デフォルトはインメモリの Repository と WebSocket の
+//! MessagePusher で、別の実装（Redis や PostgreSQL など）に差し替える場合も
+//! ユースケースの生成を書き直す必要はありません。
+
+use std::{collections::HashMap, sync::Arc};
+
+use engawa_shared::time::{Clock, SystemClock};
+use tokio::sync::Mutex;
+
+use crate::{
+    domain::{MessagePusher, Room, RoomIdFactory, RoomRepository, Timestamp},
+    infrastructure::{message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository},
+    usecase::{
+        AdminUseCase, ConnectParticipantUseCase, DisconnectParticipantUseCase,
+        GetParticipantUseCase, GetRoomDetailUseCase, GetRoomStateUseCase, GetRoomsUseCase,
+        PurgeMessagesUseCase, ReactUseCase, ReplyPongUseCase, SendFileUseCase, SendMessageUseCase,
+        send_file::DEFAULT_MAX_FILE_SIZE,
+    },
+};
+
+use super::server::Server;
+
+/// Adjusts a use case after the builder created it with its dependencies
+type Configure<T> = Box<dyn FnOnce(T) -> T + Send>;
+
+/// Builder wiring the repository, the message pusher and the use cases into a [`Server`]
+///
+/// # Example
+///
+/// ```ignore
+/// let server = ServerBuilder::new()
+///     .with_room(room)
+///     .configure_send_message(|usecase| usecase.with_max_content_len(1000))
+///     .build()
+///     .with_pretty_json(true);
+/// ```
+pub struct ServerBuilder {
+    /// インメモリの Repository が保持する Room（Repository を指定した場合は使わない）
+    room: Option<Room>,
+    /// Repository（`None` なら `room` を保持する [`InMemoryRoomRepository`]）
+    repository: Option<Arc<dyn RoomRepository>>,
+    /// MessagePusher（`None` なら [`WebSocketMessagePusher`]）
+    message_pusher: Option<Arc<dyn MessagePusher>>,
+    /// インメモリの Repository と経過時間の計算に使う時計
+    clock: Arc<dyn Clock>,
+    /// 共有できるファイルの最大サイズ（バイト）
+    max_file_size: usize,
+    /// 生成した ConnectParticipantUseCase の設定
+    configure_connect_participant: Configure<ConnectParticipantUseCase>,
+    /// 生成した SendMessageUseCase の設定
+    configure_send_message: Configure<SendMessageUseCase>,
+}
+
+impl Default for ServerBuilder {
+    fn default() -> Self {
+        Self {
+            room: None,
+            repository: None,
+            message_pusher: None,
+            clock: Arc::new(SystemClock),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            configure_connect_participant: Box::new(|usecase| usecase),
+            configure_send_message: Box::new(|usecase| usecase),
+        }
+    }
+}
+
+impl ServerBuilder {
+    /// 新しい ServerBuilder を作成（インメモリの Repository と WebSocket の MessagePusher）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the room held by the default in-memory repository
+    ///
+    /// デフォルトは新しく生成した ID で、現在時刻に作成された空の Room です。
+    /// [`ServerBuilder::with_repository`] で Repository を指定した場合は使われません。
+    pub fn with_room(mut self, room: Room) -> Self {
+        self.room = Some(room);
+        self
+    }
+
+    /// Replace the default in-memory repository
+    pub fn with_repository(mut self, repository: Arc<dyn RoomRepository>) -> Self {
+        self.repository = Some(repository);
+        self
+    }
+
+    /// Replace the default WebSocket message pusher
+    pub fn with_message_pusher(mut self, message_pusher: Arc<dyn MessagePusher>) -> Self {
+        self.message_pusher = Some(message_pusher);
+        self
+    }
+
+    /// Set the clock used by the default in-memory repository and for room ages
+    ///
+    /// デフォルトはシステム時計です（テストでは `FixedClock` を指定できます）。
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the maximum size of a shared file, in bytes (default: [`DEFAULT_MAX_FILE_SIZE`])
+    pub fn with_max_file_size(mut self, max_file_size: usize) -> Self {
+        self.max_file_size = max_file_size;
+        self
+    }
+
+    /// Adjust the ConnectParticipantUseCase once it is created (duplicate policy, etc.)
+    pub fn configure_connect_participant(
+        mut self,
+        configure: impl FnOnce(ConnectParticipantUseCase) -> ConnectParticipantUseCase + Send + 'static,
+    ) -> Self {
+        self.configure_connect_participant = Box::new(configure);
+        self
+    }
+
+    /// Adjust the SendMessageUseCase once it is created (content transform, limits, etc.)
+    pub fn configure_send_message(
+        mut self,
+        configure: impl FnOnce(SendMessageUseCase) -> SendMessageUseCase + Send + 'static,
+    ) -> Self {
+        self.configure_send_message = Box::new(configure);
+        self
+    }
+
+    /// Create the use cases and the server
+    ///
+    /// 返される [`Server`] には、`with_*` メソッドで引き続き UI 層の設定を追加できます。
+    pub fn build(self) -> Server {
+        let clock = self.clock;
+        let repository = self.repository.unwrap_or_else(|| {
+            let room = self.room.unwrap_or_else(|| {
+                Room::new(
+                    RoomIdFactory::generate().expect("Failed to generate RoomId"),
+                    Timestamp::new(clock.now_jst_millis()),
+                )
+            });
+            Arc::new(
+                InMemoryRoomRepository::new(Arc::new(Mutex::new(room))).with_clock(clock.clone()),
+            )
+        });
+        let message_pusher = self.message_pusher.unwrap_or_else(|| {
+            Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+                HashMap::new(),
+            ))))
+        });
+
+        Server::new(
+            Arc::new((self.configure_connect_participant)(
+                ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone()),
+            )),
+            Arc::new(DisconnectParticipantUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            Arc::new((self.configure_send_message)(SendMessageUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            ))),
+            Arc::new(GetRoomStateUseCase::new(repository.clone())),
+            Arc::new(GetRoomsUseCase::new(repository.clone()).with_clock(clock)),
+            Arc::new(GetRoomDetailUseCase::new(repository.clone())),
+            Arc::new(GetParticipantUseCase::new(repository.clone())),
+            Arc::new(ReplyPongUseCase::new(message_pusher.clone())),
+            Arc::new(SendFileUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+                self.max_file_size,
+            )),
+            Arc::new(ReactUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            Arc::new(AdminUseCase::new(
+                repository.clone(),
+                message_pusher.clone(),
+            )),
+            Arc::new(PurgeMessagesUseCase::new(repository, message_pusher)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BroadcastReport, ClientId, MessagePushError, PusherChannel};
+    use async_trait::async_trait;
+
+    /// 登録されたクライアントを記録するだけの MessagePusher
+    #[derive(Default)]
+    struct RecordingMessagePusher {
+        registered: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl MessagePusher for RecordingMessagePusher {
+        async fn register_client(&self, client_id: ClientId, _sender: PusherChannel) {
+            self.registered
+                .lock()
+                .unwrap()
+                .push(client_id.as_str().to_string());
+        }
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            Ok(BroadcastReport::default())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_built_server_uses_the_given_pusher_and_room() {
+        // テスト項目: ビルダーで組み立てたサーバーは、指定した MessagePusher に接続を登録し、
+        //            指定した Room をデフォルトのインメモリ Repository で保持する
+        // given (前提条件):
+        let room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let room_id = room.id.clone();
+        let message_pusher = Arc::new(RecordingMessagePusher::default());
+        let server = ServerBuilder::new()
+            .with_room(room)
+            .with_message_pusher(message_pusher.clone())
+            .build();
+        let bound = server.bind("127.0.0.1".to_string(), 0).await.unwrap();
+        let addr = bound.local_addr();
+        tokio::spawn(bound.serve_with_shutdown(std::future::pending()));
+
+        // when (操作):
+        let (_alice, _) =
+            tokio_tungstenite::connect_async(format!("ws://{}/ws?client_id=alice", addr))
+                .await
+                .unwrap();
+        let rooms: serde_json::Value = reqwest::get(format!("http://{}/api/rooms", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(*message_pusher.registered.lock().unwrap(), vec!["alice"]);
+        assert_eq!(rooms[0]["id"], room_id.as_str());
+        assert_eq!(rooms[0]["participants"], serde_json::json!(["alice"]));
+    }
+}
@@ -1,10 +1,18 @@
 //! Server state and connection management.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::usecase::{
-    ConnectParticipantUseCase, DisconnectParticipantUseCase, GetRoomDetailUseCase,
-    GetRoomStateUseCase, GetRoomsUseCase, SendMessageUseCase,
+use crate::{
+    infrastructure::dto::conversion::TimestampPrecision,
+    ui::{
+        access_policy::AccessPolicy, idle_shutdown::ActiveConnections,
+        ip_connection_limit::IpConnectionLimiter, metrics::ConnectionMetrics, readiness::Readiness,
+    },
+    usecase::{
+        AdminUseCase, ConnectParticipantUseCase, DisconnectParticipantUseCase,
+        GetParticipantUseCase, GetRoomDetailUseCase, GetRoomStateUseCase, GetRoomsUseCase,
+        PurgeMessagesUseCase, ReactUseCase, ReplyPongUseCase, SendFileUseCase, SendMessageUseCase,
+    },
 };
 
 /// Shared application state
@@ -25,4 +33,43 @@ pub struct AppState {
     pub get_rooms_usecase: Arc<GetRoomsUseCase>,
     /// GetRoomDetailUseCase（ルーム詳細取得のユースケース）
     pub get_room_detail_usecase: Arc<GetRoomDetailUseCase>,
+    /// GetParticipantUseCase（参加者詳細取得のユースケース）
+    pub get_participant_usecase: Arc<GetParticipantUseCase>,
+    /// ReplyPongUseCase（アプリケーションレベル Pong 返信のユースケース）
+    pub reply_pong_usecase: Arc<ReplyPongUseCase>,
+    /// SendFileUseCase（ファイル送信のユースケース）
+    pub send_file_usecase: Arc<SendFileUseCase>,
+    /// ReactUseCase（メッセージへのリアクションのユースケース）
+    pub react_usecase: Arc<ReactUseCase>,
+    /// AdminUseCase（管理者コマンドのユースケース）
+    pub admin_usecase: Arc<AdminUseCase>,
+    /// PurgeMessagesUseCase（メッセージ履歴削除のユースケース）
+    pub purge_messages_usecase: Arc<PurgeMessagesUseCase>,
+    /// 管理者として接続するためのトークン（`None` なら管理者の接続・管理用 API を受け付けない）
+    pub admin_token: Option<String>,
+    /// 接続元 IP アドレスによる接続可否の判定
+    pub access_policy: Arc<dyn AccessPolicy>,
+    /// 接続拒否理由ごとのカウンター
+    pub metrics: Arc<ConnectionMetrics>,
+    /// HTTP レスポンスの JSON を常にインデント付きで返すかどうか（デバッグモード）
+    pub pretty_json: bool,
+    /// 満員で接続を拒否したときに `Retry-After` ヘッダーで伝える再接続までの待ち時間
+    pub retry_after: Duration,
+    /// 接続直後に送信する Message of the Day（`None` なら送信しない）
+    pub motd: Option<String>,
+    /// JSON でないテキストフレームを接続中のクライアントからのチャットとして受け付けるかどうか
+    /// （`false` ならエラーフレームを返して拒否する）
+    pub accept_plain_text: bool,
+    /// WebSocket フレームのタイムスタンプの単位（デフォルトはミリ秒）
+    pub timestamp_precision: TimestampPrecision,
+    /// 1 接続が 1 秒あたりに送信できるフレーム数の上限（`None` なら制限しない）
+    pub max_frames_per_sec: Option<u32>,
+    /// 送信元 IP アドレスごとの接続数の上限（`None` なら制限しない）
+    pub ip_connection_limiter: Option<Arc<IpConnectionLimiter>>,
+    /// 1 つの WebSocket メッセージとして受信できるサイズの上限（バイト）
+    pub max_message_size: usize,
+    /// トラフィックを受け付けられるかどうか（`/api/ready`）
+    pub readiness: Arc<Readiness>,
+    /// 接続中の WebSocket 接続数（アイドル時の自動停止に使う）
+    pub active_connections: Arc<ActiveConnections>,
 }
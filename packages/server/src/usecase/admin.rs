@@ -0,0 +1,229 @@
+//! UseCase: 管理者コマンド処理（kick / mute / announce）
+//!
+//! ## テスト実装の作業記録
+//!
+//! ### 何をテストしているか
+//! - AdminUseCase::kick() / set_muted() メソッド
+//! - 参加者の強制退出とミュート状態の変更
+//!
+//! ### なぜこのテストが必要か
+//! - ビジネスロジックの検証：kick した参加者が Room と MessagePusher の両方から外れる
+//! - ミュートした参加者のメッセージが送信できなくなることを保証
+//!
+//! ### どのような状況を想定しているか
+//! - 正常系：参加者の kick、ミュートとミュート解除
+//! - 異常系：Room にいない参加者への操作
+//!
+//! ## 備考
+//!
+//! 管理者かどうかの判定（トークンの照合）は UI 層が接続時に行います。
+//! このユースケースは、管理者の接続から受け付けたコマンドだけを実行します。
+
+use std::sync::Arc;
+
+use crate::domain::{BroadcastReport, ClientId, MessagePusher, RepositoryError, RoomRepository};
+
+use super::error::AdminError;
+
+/// 管理者コマンドのユースケース
+pub struct AdminUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl AdminUseCase {
+    /// 新しい AdminUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// 参加者を Room から強制退出させる
+    ///
+    /// 参加者を Room から削除してから MessagePusher の登録を解除します（参加者の全接続の
+    /// チャネルが閉じ、切断される）。接続が閉じる時点で参加者が Room にいないため、
+    /// UI 層は引き継ぎによる切断と区別できます。
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ClientId>)` - 退出を通知する対象（残っている参加者）
+    /// * `Err(AdminError)` - 参加者が Room にいない
+    pub async fn kick(&self, target: &ClientId) -> Result<Vec<ClientId>, AdminError> {
+        // 1. 参加者が Room にいることを確認
+        if self.repository.get_participant(target).await.is_none() {
+            return Err(AdminError::ParticipantNotFound(target.as_str().to_string()));
+        }
+
+        // 2. Room から削除してから接続を閉じる
+        self.repository
+            .remove_participant(target)
+            .await
+            .map_err(|e| AdminError::RepositoryError(e.to_string()))?;
+        self.message_pusher.unregister_client(target).await;
+
+        // 3. 退出の通知対象を取得
+        Ok(self.repository.get_all_connected_client_ids().await)
+    }
+
+    /// 参加者をミュート（`muted` が `false` ならミュート解除）
+    ///
+    /// ミュートされた参加者のチャットメッセージは保存も配信もされません。
+    pub async fn set_muted(&self, target: &ClientId, muted: bool) -> Result<(), AdminError> {
+        self.repository
+            .set_muted(target, muted)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::ParticipantNotFound(id) => AdminError::ParticipantNotFound(id),
+                e => AdminError::RepositoryError(e.to_string()),
+            })
+    }
+
+    /// 参加者が Room にいるかどうか（kick された接続かどうかの判定に使う）
+    pub async fn is_participant(&self, client_id: &ClientId) -> bool {
+        self.repository.get_participant(client_id).await.is_some()
+    }
+
+    /// お知らせを全ての参加者にブロードキャスト
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - ブロードキャストする JSON メッセージ（DTO 層で生成されたもの）
+    pub async fn announce(&self, message: &str) -> Result<BroadcastReport, AdminError> {
+        let targets = self.repository.get_all_connected_client_ids().await;
+        self.message_pusher
+            .broadcast(targets, message)
+            .await
+            .map_err(AdminError::BroadcastFailed)
+    }
+
+    /// コマンドを送った管理者にのみ通知（実行結果やエラーフレーム）
+    pub async fn notify_admin(&self, admin: &ClientId, message: &str) -> Result<(), String> {
+        self.message_pusher
+            .push_to(admin, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, MessageIdFactory, PusherChannel, Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+        usecase::{SendMessageError, SendMessageUseCase},
+    };
+    use std::collections::HashMap;
+    use tokio::sync::{Mutex, mpsc};
+
+    fn client(id: &str) -> ClientId {
+        ClientId::new(id.to_string()).unwrap()
+    }
+
+    /// alice と bob が参加している Room と、bob の接続の受信側を作成
+    async fn create_room_with_alice_and_bob() -> (
+        Arc<InMemoryRoomRepository>,
+        Arc<WebSocketMessagePusher>,
+        mpsc::Receiver<String>,
+    ) {
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+        )));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let mut bob_rx = None;
+        for id in ["alice", "bob"] {
+            repository
+                .add_participant(client(id), Timestamp::new(1000))
+                .await
+                .unwrap();
+            let (tx, rx): (PusherChannel, _) = mpsc::channel(16);
+            message_pusher.register_client(client(id), tx).await;
+            if id == "bob" {
+                bob_rx = Some(rx);
+            }
+        }
+        (repository, message_pusher, bob_rx.unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_kick_removes_participant_and_closes_its_connection() {
+        // テスト項目: kick した参加者は Room から外れ、接続のチャネルが閉じ、残りの参加者が通知対象になる
+        // given (前提条件):
+        let (repository, message_pusher, mut bob_rx) = create_room_with_alice_and_bob().await;
+        let usecase = AdminUseCase::new(repository.clone(), message_pusher);
+
+        // when (操作):
+        let notify_targets = usecase.kick(&client("bob")).await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(notify_targets, vec![client("alice")]);
+        assert!(!usecase.is_participant(&client("bob")).await);
+        assert_eq!(bob_rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn test_kick_unknown_participant_is_rejected() {
+        // テスト項目: Room にいない参加者の kick は ParticipantNotFound になる
+        // given (前提条件):
+        let (repository, message_pusher, _bob_rx) = create_room_with_alice_and_bob().await;
+        let usecase = AdminUseCase::new(repository, message_pusher);
+
+        // when (操作):
+        let result = usecase.kick(&client("carol")).await;
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(AdminError::ParticipantNotFound("carol".to_string()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_muted_participant_cannot_send_until_unmuted() {
+        // テスト項目: ミュートされた参加者のメッセージは Muted で拒否され、ミュート解除後は送信できる
+        // given (前提条件):
+        let (repository, message_pusher, _bob_rx) = create_room_with_alice_and_bob().await;
+        let usecase = AdminUseCase::new(repository.clone(), message_pusher.clone());
+        let send_message = SendMessageUseCase::new(repository, message_pusher);
+        let content = || MessageContent::new("hi".to_string()).unwrap();
+
+        // when (操作):
+        usecase.set_muted(&client("alice"), true).await.unwrap();
+        let while_muted = send_message
+            .execute(
+                client("alice"),
+                MessageIdFactory::generate(),
+                content(),
+                None,
+                "{}".to_string(),
+            )
+            .await;
+        usecase.set_muted(&client("alice"), false).await.unwrap();
+        let after_unmute = send_message
+            .execute(
+                client("alice"),
+                MessageIdFactory::generate(),
+                content(),
+                None,
+                "{}".to_string(),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(while_muted, Err(SendMessageError::Muted));
+        assert!(after_unmute.is_ok());
+    }
+}
@@ -19,17 +19,101 @@
 use std::sync::Arc;
 
 use crate::domain::{
-    ClientId, MessagePusher, Participant, PusherChannel, RoomRepository, Timestamp,
+    AddParticipantError, AllowAllVerifier, AuthError, BroadcastReport, ClientId, IdentityVerifier,
+    MessagePusher, Participant, PresenceStatus, PusherChannel, RoomId, RoomRepository, RosterDelta,
+    Timestamp,
 };
 
 use super::error::ConnectError;
 
+/// 接続直後に送信する参加者リストの最大件数（デフォルト）
+pub const DEFAULT_INITIAL_ROSTER_LIMIT: usize = 100;
+
+/// 接続直後に送信する参加者リスト（先頭の一部に切り詰められることがある）
+#[derive(Debug, Clone)]
+pub struct InitialRoster {
+    /// Room の ID（Room を取得できなかった場合は `None`）
+    pub room_id: Option<RoomId>,
+    /// client_id 順の先頭から最大 `initial_roster_limit` 件の参加者
+    pub entries: Vec<RosterEntry>,
+    /// Room の全参加者数
+    pub total: usize,
+    /// Room の履歴に保持できるメッセージ数の上限（Room を取得できなかった場合は `None`）
+    pub message_capacity: Option<usize>,
+    /// Room の履歴にある現在のメッセージ数（Room を取得できなかった場合は `None`）
+    pub message_count: Option<usize>,
+}
+
+impl InitialRoster {
+    /// 参加者リストが切り詰められているかどうか
+    pub fn is_truncated(&self) -> bool {
+        self.entries.len() < self.total
+    }
+}
+
+/// 参加者リストの 1 エントリ
+#[derive(Debug, Clone)]
+pub struct RosterEntry {
+    /// 参加者（Domain Model）
+    pub participant: Participant,
+    /// 最終アクティブ時刻からの経過時間（ミリ秒）
+    pub idle_ms: u64,
+}
+
+/// 参加者リストの再同期で送信する内容
+#[derive(Debug, Clone)]
+pub enum RosterUpdate {
+    /// 参加者リスト全体（client_id 順）
+    Full {
+        /// 参加者リストに対応するロスターバージョン
+        version: u64,
+        entries: Vec<RosterEntry>,
+    },
+    /// クライアントが知っているバージョン以降の参加・退出だけの差分
+    Delta {
+        /// 差分を適用した後のロスターバージョン
+        version: u64,
+        /// 参加した参加者（client_id 順）
+        added: Vec<RosterEntry>,
+        /// 退出した参加者（client_id 順）
+        removed: Vec<ClientId>,
+    },
+}
+
+/// 既に Room にいる client_id で接続されたときの扱い（複数接続モードでない場合）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// 新しい接続を拒否する（`ConnectError::DuplicateClientId`）
+    #[default]
+    Reject,
+    /// 新しい接続が引き継ぎ、既存の接続は切断される（後勝ち）
+    Takeover,
+}
+
+/// 接続の結果
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Connection {
+    /// 参加者が Room に参加した時刻（追加の接続・引き継ぎでは最初の接続の時刻）
+    pub connected_at: Timestamp,
+    /// Room に既にいる参加者への接続かどうか
+    /// （複数接続モードの追加の接続、または既存の接続の引き継ぎ）
+    pub is_additional: bool,
+}
+
 /// 参加者接続のユースケース
 pub struct ConnectParticipantUseCase {
     /// Repository（データアクセス層の抽象化）
     repository: Arc<dyn RoomRepository>,
     /// MessagePusher（メッセージ通知の抽象化）
     message_pusher: Arc<dyn MessagePusher>,
+    /// 接続直後に送信する参加者リストの最大件数
+    initial_roster_limit: usize,
+    /// 同じ client_id による複数の同時接続を許可するかどうか
+    multi_connection: bool,
+    /// 既に Room にいる client_id で接続されたときの扱い
+    duplicate_policy: DuplicatePolicy,
+    /// 参加者の登録前に、名乗った client_id の身元を確認する
+    identity_verifier: Arc<dyn IdentityVerifier>,
 }
 
 impl ConnectParticipantUseCase {
@@ -41,11 +125,48 @@ impl ConnectParticipantUseCase {
         Self {
             repository,
             message_pusher,
+            initial_roster_limit: DEFAULT_INITIAL_ROSTER_LIMIT,
+            multi_connection: false,
+            duplicate_policy: DuplicatePolicy::default(),
+            identity_verifier: Arc::new(AllowAllVerifier),
         }
     }
 
+    /// 接続直後に送信する参加者リストの最大件数を設定
+    pub fn with_initial_roster_limit(mut self, initial_roster_limit: usize) -> Self {
+        self.initial_roster_limit = initial_roster_limit;
+        self
+    }
+
+    /// 同じ client_id による複数の同時接続を許可する（複数接続モード）
+    ///
+    /// 有効にすると、既に Room にいる client_id での接続は拒否されず、
+    /// 同じ参加者の追加の接続として扱われます。
+    pub fn with_multi_connection(mut self, multi_connection: bool) -> Self {
+        self.multi_connection = multi_connection;
+        self
+    }
+
+    /// 既に Room にいる client_id で接続されたときの扱いを設定（デフォルトは拒否）
+    ///
+    /// 複数接続モードでは同じ client_id の接続はすべて受け入れるため、この設定は使われません。
+    pub fn with_duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// 参加者の登録前に身元を確認する IdentityVerifier を設定
+    ///
+    /// デフォルトは検証せずにすべてのクライアントを受け入れる [`AllowAllVerifier`] です。
+    pub fn with_identity_verifier(mut self, identity_verifier: Arc<dyn IdentityVerifier>) -> Self {
+        self.identity_verifier = identity_verifier;
+        self
+    }
+
     /// 参加者接続を実行
     ///
+    /// トークンなしで身元確認を行います（[`ConnectParticipantUseCase::connect_with_status`] を参照）。
+    ///
     /// # Arguments
     ///
     /// * `client_id` - 接続するクライアントの ID（Domain Model）
@@ -55,89 +176,378 @@ impl ConnectParticipantUseCase {
     ///
     /// * `Ok(Timestamp)` - 接続成功（接続時刻の Domain Model を返す）
     /// * `Err(ConnectError)` - 接続失敗
+    ///
+    /// # 整合性
+    ///
+    /// Repository への追加が成功した場合にのみ MessagePusher へ登録します。
+    /// 追加に失敗した場合は MessagePusher に何も登録されないため、
+    /// Repository と MessagePusher の間で接続中クライアントが食い違うことはありません。
+    /// `register_client` は失敗しない（infallible）ため、登録後のロールバックは不要です。
     pub async fn execute(
         &self,
         client_id: ClientId,
         sender: PusherChannel,
     ) -> Result<Timestamp, ConnectError> {
+        self.execute_with_status(client_id, PresenceStatus::default(), sender)
+            .await
+    }
+
+    /// 初期プレゼンス状態を指定して参加者接続を実行
+    ///
+    /// 整合性については [`ConnectParticipantUseCase::execute`] を参照してください。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 接続するクライアントの ID（Domain Model）
+    /// * `status` - 接続時のプレゼンス状態
+    /// * `sender` - クライアントへのメッセージ送信用チャンネル
+    pub async fn execute_with_status(
+        &self,
+        client_id: ClientId,
+        status: PresenceStatus,
+        sender: PusherChannel,
+    ) -> Result<Timestamp, ConnectError> {
+        self.connect_with_status(client_id, status, None, sender)
+            .await
+            .map(|connection| connection.connected_at)
+    }
+
+    /// 初期プレゼンス状態を指定して接続を実行し、追加の接続かどうかも返す
+    ///
+    /// 複数接続モードでは、既に Room にいる client_id での接続は参加者の接続数を増やし、
+    /// `is_additional` が `true` の [`Connection`] を返します（参加者は追加されません）。
+    ///
+    /// 重複時の扱いが [`DuplicatePolicy::Takeover`] の場合は、既存の接続を MessagePusher から
+    /// 登録解除して（既存の接続のチャネルが閉じ、切断される）新しい接続を登録し、
+    /// 同じく `is_additional` が `true` の [`Connection`] を返します。
+    /// 整合性については [`ConnectParticipantUseCase::execute`] を参照してください。
+    ///
+    /// 登録の前に、名乗った client_id と `token` を IdentityVerifier で検証します。
+    /// 身元を確認できない場合は Room にも MessagePusher にも何も登録せず、
+    /// `ConnectError::Unauthorized` を返します。
+    pub async fn connect_with_status(
+        &self,
+        client_id: ClientId,
+        status: PresenceStatus,
+        token: Option<&str>,
+        sender: PusherChannel,
+    ) -> Result<Connection, ConnectError> {
         use engawa_shared::time::get_jst_timestamp;
 
-        // 1. 重複チェック
-        let client_ids = self.repository.get_all_connected_client_ids().await;
-        if client_ids
-            .iter()
-            .any(|id| id.as_str() == client_id.as_str())
-        {
-            return Err(ConnectError::DuplicateClientId(
-                client_id.as_str().to_string(),
-            ));
+        // 0. 名乗った client_id の身元を確認（トークンの持ち主と一致しなければ拒否）
+        let identity = self
+            .identity_verifier
+            .verify(&client_id, token)
+            .await
+            .map_err(ConnectError::Unauthorized)?;
+        if identity.client_id != client_id {
+            return Err(ConnectError::Unauthorized(AuthError::ClientIdMismatch {
+                claimed: client_id.into_string(),
+                verified: identity.client_id.into_string(),
+            }));
         }
 
-        // 2. Repository に参加者を追加
+        // 1. 重複・定員チェックと参加者の追加（Repository が不可分に行う）
         let connected_at = Timestamp::new(get_jst_timestamp());
-        self.repository
-            .add_participant(client_id.clone(), connected_at)
+        let mut replaces_existing = false;
+        let connection = match self
+            .repository
+            .try_add_participant_with_status(client_id.clone(), connected_at, status)
             .await
-            .map_err(|_| ConnectError::RoomCapacityExceeded)?;
+        {
+            Ok(()) => Connection {
+                connected_at,
+                is_additional: false,
+            },
+            Err(AddParticipantError::Duplicate(_)) if self.multi_connection => {
+                // 既存の参加者の接続数を増やす（参加時刻は最初の接続のものを引き継ぐ）
+                let connected_at = self
+                    .repository
+                    .add_connection(&client_id)
+                    .await
+                    .map_err(|e| ConnectError::RepositoryError(e.to_string()))?;
+                Connection {
+                    connected_at,
+                    is_additional: true,
+                }
+            }
+            Err(AddParticipantError::Duplicate(_))
+                if self.duplicate_policy == DuplicatePolicy::Takeover =>
+            {
+                // 参加者はそのまま残し、既存の接続だけを新しい接続に置き換える
+                let connected_at = self
+                    .repository
+                    .get_participant(&client_id)
+                    .await
+                    .map(|p| p.connected_at)
+                    .unwrap_or(connected_at);
+                replaces_existing = true;
+                Connection {
+                    connected_at,
+                    is_additional: true,
+                }
+            }
+            Err(AddParticipantError::Duplicate(id)) => {
+                return Err(ConnectError::DuplicateClientId(id));
+            }
+            Err(AddParticipantError::CapacityExceeded { .. }) => {
+                return Err(ConnectError::RoomCapacityExceeded);
+            }
+            Err(AddParticipantError::Repository(e)) => {
+                return Err(ConnectError::RepositoryError(e.to_string()));
+            }
+        };
 
-        // 3. MessagePusher にクライアントを登録（Domain Model を渡す）
-        self.message_pusher.register_client(client_id, sender).await;
+        // 2. MessagePusher にクライアントを登録（Domain Model を渡す）
+        //    Repository への追加が成功した後にのみ到達する
+        //    takeover では既存の接続を不可分に置き換え、並行する置き換えでも接続を 1 つに保つ
+        if replaces_existing {
+            self.message_pusher.replace_client(client_id, sender).await;
+        } else {
+            self.message_pusher.register_client(client_id, sender).await;
+        }
 
-        Ok(connected_at)
+        Ok(connection)
     }
 
     /// 参加者リストを構築
     ///
     /// # Returns
     ///
-    /// 接続中の参加者リスト（Domain Model、ソート済み）
-    pub async fn build_participant_list(&self) -> Vec<Participant> {
-        let mut participants = self.repository.get_participants().await;
+    /// 接続中の参加者リスト（ソート済み、現在時刻時点のアイドル時間付き）
+    pub async fn build_participant_list(&self) -> Vec<RosterEntry> {
+        use engawa_shared::time::get_jst_timestamp;
+
+        self.build_participant_list_at(Timestamp::new(get_jst_timestamp()))
+            .await
+    }
+
+    /// 指定した時刻時点のアイドル時間付きで参加者リストを構築
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - アイドル時間の計算に使う現在時刻
+    pub async fn build_participant_list_at(&self, now: Timestamp) -> Vec<RosterEntry> {
+        let participants = self.repository.get_participants().await;
+        to_roster_entries(participants, now)
+    }
+
+    /// 参加者リストの再同期で送信する内容を構築
+    ///
+    /// クライアントが最後に受け取ったロスターバージョン（`since_version`）から差分を
+    /// 計算できる場合は参加・退出だけを返し、大きな Room でも参加者リスト全体を送り直さずに
+    /// 済むようにします。バージョンの指定がない、または古すぎる場合は参加者リスト全体を返します。
+    ///
+    /// # Arguments
+    ///
+    /// * `since_version` - クライアントが最後に受け取ったロスターバージョン
+    /// * `now` - アイドル時間の計算に使う現在時刻
+    pub async fn build_roster_update_at(
+        &self,
+        since_version: Option<u64>,
+        now: Timestamp,
+    ) -> RosterUpdate {
+        if let Some(since_version) = since_version
+            && let Some(RosterDelta {
+                version,
+                added,
+                removed,
+            }) = self.repository.get_roster_delta(since_version).await
+        {
+            return RosterUpdate::Delta {
+                version,
+                added: to_roster_entries(added, now),
+                removed,
+            };
+        }
+
+        let (version, participants) = self.repository.get_versioned_participants().await;
+        RosterUpdate::Full {
+            version,
+            entries: to_roster_entries(participants, now),
+        }
+    }
+
+    /// 接続直後に送信する参加者リストを構築
+    ///
+    /// 初回のペイロードを抑えるため、client_id 順の先頭から
+    /// 最大 `initial_roster_limit` 件に切り詰めます。
+    pub async fn build_initial_roster(&self) -> InitialRoster {
+        use engawa_shared::time::get_jst_timestamp;
 
-        // Sort by client_id for consistent ordering
-        participants.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+        // Room の ID、履歴の使用状況と参加者リストを同じスナップショットから取得する
+        let (room_id, message_usage, participants) = match self.repository.get_room().await {
+            Ok(room) => (
+                Some(room.id),
+                Some((room.message_capacity, room.messages.len())),
+                room.participants,
+            ),
+            Err(_) => (None, None, self.repository.get_participants().await),
+        };
+        let mut entries = to_roster_entries(participants, Timestamp::new(get_jst_timestamp()));
+        let total = entries.len();
+        entries.truncate(self.initial_roster_limit);
 
-        participants
+        InitialRoster {
+            room_id,
+            entries,
+            total,
+            message_capacity: message_usage.map(|(capacity, _)| capacity),
+            message_count: message_usage.map(|(_, count)| count),
+        }
+    }
+
+    /// 参加者リストを特定のクライアントに送信
+    ///
+    /// クライアントが join/leave の差分を取りこぼした場合でも、
+    /// 現在の参加者リストを取り直せるようにするために使用します。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 送信先のクライアントの ID（Domain Model）
+    /// * `message` - 送信する参加者リストのメッセージ（JSON）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 送信成功
+    /// * `Err(String)` - 送信失敗
+    pub async fn send_roster_to(&self, client_id: &ClientId, message: &str) -> Result<(), String> {
+        self.message_pusher
+            .push_to(client_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// クライアントからのハートビートを受けて、参加者の最終アクティブ時刻を更新
+    ///
+    /// チャットを送信していない参加者のアイドル時間が伸び続けないようにするために使用します。
+    /// 他の参加者への通知は行いません。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - ハートビートを送信したクライアントの ID（Domain Model）
+    /// * `now` - ハートビートを受信した時刻
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 更新成功
+    /// * `Err(String)` - 更新失敗（参加者が既に Room にいない場合など）
+    pub async fn record_heartbeat(
+        &self,
+        client_id: &ClientId,
+        now: Timestamp,
+    ) -> Result<(), String> {
+        self.repository
+            .update_last_active(client_id, now)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// 参加者リストを Room の全参加者にブロードキャスト
+    ///
+    /// 通常、参加者リストは接続時に送信し、その後は join/leave の差分で更新します。
+    /// 複数の参加者をまとめて変更した後（キックやルームの統合など）に、
+    /// 全員の参加者リストを現在の状態に揃え直すために使用します。
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - ブロードキャストする参加者リストのメッセージ（JSON、`room-connected`）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BroadcastReport)` - 送信先ごとの配信結果
+    /// * `Err(String)` - ブロードキャスト失敗
+    pub async fn broadcast_roster(&self, message: &str) -> Result<BroadcastReport, String> {
+        let targets = self.repository.get_all_connected_client_ids().await;
+        self.message_pusher
+            .broadcast(targets, message)
+            .await
+            .map_err(|e| e.to_string())
     }
 
     /// 参加者が join したことを既存の参加者にブロードキャスト
     ///
+    /// Room の参加者数が `presence_notification_threshold` を超えている場合は、
+    /// 個別の join 通知を抑制し、代わりに参加者数の更新を全員にブロードキャストします。
+    ///
     /// # Arguments
     ///
     /// * `new_client_id` - 新規接続したクライアントの ID（Domain Model）
-    /// * `message` - ブロードキャストするメッセージ（JSON）
+    /// * `message` - 参加者数から、ブロードキャストする join 通知メッセージ（JSON）を生成する関数
+    /// * `count_message` - 参加者数から、通知抑制時にブロードキャストする参加者数メッセージ（JSON）を生成する関数
+    ///
+    /// 参加者数はブロードキャスト時点のもの（新規接続クライアントを含む）で、送信先と同じ
+    /// Room の状態から数えます。
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - ブロードキャスト成功
+    /// * `Ok(BroadcastReport)` - 送信先ごとの配信結果（届かなかった参加者は `failed` に含まれる）
     /// * `Err(String)` - ブロードキャスト失敗
     pub async fn broadcast_participant_joined(
         &self,
         new_client_id: &ClientId,
-        message: &str,
-    ) -> Result<(), String> {
+        message: impl FnOnce(usize) -> String,
+        count_message: impl FnOnce(usize) -> String,
+    ) -> Result<BroadcastReport, String> {
+        let room = self
+            .repository
+            .get_room()
+            .await
+            .map_err(|e| e.to_string())?;
+        let participant_count = room.participants.len();
+
+        // 通知抑制時は参加者数の更新を新規接続クライアントを含む全員に送る
+        if room.suppresses_presence_notifications() {
+            let all_client_ids = room.participants.iter().map(|p| p.id.clone()).collect();
+            return self
+                .message_pusher
+                .broadcast(all_client_ids, &count_message(participant_count))
+                .await
+                .map_err(|e| e.to_string());
+        }
+
         // 新規接続クライアント以外の全てのクライアントを取得
-        let all_client_ids = self.repository.get_all_connected_client_ids().await;
-        let target_ids: Vec<ClientId> = all_client_ids
-            .into_iter()
+        let target_ids: Vec<ClientId> = room
+            .participants
+            .iter()
+            .map(|p| p.id.clone())
             .filter(|id| id != new_client_id)
             .collect();
 
         // ブロードキャスト
         self.message_pusher
-            .broadcast(target_ids, message)
+            .broadcast(target_ids, &message(participant_count))
             .await
             .map_err(|e| e.to_string())
     }
 }
 
+/// 参加者を client_id 順に並べ、アイドル時間付きのエントリに変換
+fn to_roster_entries(mut participants: Vec<Participant>, now: Timestamp) -> Vec<RosterEntry> {
+    // Sort by client_id for consistent ordering
+    participants.sort_by(|a, b| a.id.as_str().cmp(b.id.as_str()));
+
+    participants
+        .into_iter()
+        .map(|participant| RosterEntry {
+            idle_ms: participant.idle_ms(now),
+            participant,
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::{
-        domain::{Room, RoomIdFactory, Timestamp},
+        domain::{
+            ChatMessage, Emoji, MessageContent, MessageId, MessagePushError, ReactionAction,
+            RepositoryError, Room, RoomError, RoomId, RoomIdFactory, Timestamp,
+        },
         infrastructure::{
-            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+            dto::websocket::{MessageType, ParticipantInfo, RoomConnectedMessage},
+            message_pusher::WebSocketMessagePusher,
+            repository::InMemoryRoomRepository,
         },
     };
     use engawa_shared::time::get_jst_timestamp;
@@ -169,6 +579,275 @@ mod tests {
         Arc::new(WebSocketMessagePusher::new(clients))
     }
 
+    // Mock RoomRepository: 参加者の追加が常に失敗する
+    struct FailingAddRoomRepository {
+        inner: Arc<InMemoryRoomRepository>,
+    }
+
+    #[async_trait::async_trait]
+    impl RoomRepository for FailingAddRoomRepository {
+        async fn get_room(&self) -> Result<Room, RepositoryError> {
+            self.inner.get_room().await
+        }
+
+        async fn add_participant_with_status(
+            &self,
+            _client_id: ClientId,
+            _timestamp: Timestamp,
+            _status: PresenceStatus,
+        ) -> Result<(), RepositoryError> {
+            Err(RepositoryError::Room(
+                RoomError::ParticipantCapacityExceeded {
+                    capacity: 0,
+                    current: 0,
+                },
+            ))
+        }
+
+        async fn try_add_participant_with_status(
+            &self,
+            _client_id: ClientId,
+            _timestamp: Timestamp,
+            _status: PresenceStatus,
+        ) -> Result<(), AddParticipantError> {
+            Err(AddParticipantError::CapacityExceeded { capacity: 0 })
+        }
+
+        async fn add_connection(&self, client_id: &ClientId) -> Result<Timestamp, RepositoryError> {
+            self.inner.add_connection(client_id).await
+        }
+
+        async fn remove_connection(&self, client_id: &ClientId) -> Result<usize, RepositoryError> {
+            self.inner.remove_connection(client_id).await
+        }
+
+        async fn remove_participant(&self, client_id: &ClientId) -> Result<(), RepositoryError> {
+            self.inner.remove_participant(client_id).await
+        }
+
+        async fn update_last_active(
+            &self,
+            client_id: &ClientId,
+            timestamp: Timestamp,
+        ) -> Result<(), RepositoryError> {
+            self.inner.update_last_active(client_id, timestamp).await
+        }
+
+        async fn get_all_connected_client_ids(&self) -> Vec<ClientId> {
+            self.inner.get_all_connected_client_ids().await
+        }
+
+        async fn add_message(
+            &self,
+            from_client_id: ClientId,
+            content: MessageContent,
+            timestamp: Timestamp,
+        ) -> Result<(), RepositoryError> {
+            self.inner
+                .add_message(from_client_id, content, timestamp)
+                .await
+        }
+
+        async fn add_message_and_snapshot_targets(
+            &self,
+            message_id: MessageId,
+            from_client_id: ClientId,
+            content: MessageContent,
+            timestamp: Timestamp,
+            expires_at: Option<Timestamp>,
+        ) -> Result<Vec<ClientId>, RepositoryError> {
+            self.inner
+                .add_message_and_snapshot_targets(
+                    message_id,
+                    from_client_id,
+                    content,
+                    timestamp,
+                    expires_at,
+                )
+                .await
+        }
+
+        async fn react_to_message(
+            &self,
+            message_id: &str,
+            emoji: Emoji,
+            by: ClientId,
+            action: ReactionAction,
+        ) -> Result<usize, RepositoryError> {
+            self.inner
+                .react_to_message(message_id, emoji, by, action)
+                .await
+        }
+
+        async fn get_messages(
+            &self,
+            room_id: &RoomId,
+            since: Option<Timestamp>,
+            limit: usize,
+        ) -> Result<Vec<ChatMessage>, RepositoryError> {
+            self.inner.get_messages(room_id, since, limit).await
+        }
+
+        async fn purge_messages(&self, room_id: &RoomId) -> Result<usize, RepositoryError> {
+            self.inner.purge_messages(room_id).await
+        }
+
+        async fn is_connected(
+            &self,
+            room_id: &RoomId,
+            client_id: &ClientId,
+        ) -> Result<bool, RepositoryError> {
+            self.inner.is_connected(room_id, client_id).await
+        }
+
+        async fn count_connected_clients(&self) -> usize {
+            self.inner.count_connected_clients().await
+        }
+
+        async fn get_participants(&self) -> Vec<Participant> {
+            self.inner.get_participants().await
+        }
+
+        async fn get_versioned_participants(&self) -> (u64, Vec<Participant>) {
+            self.inner.get_versioned_participants().await
+        }
+
+        async fn get_roster_delta(&self, since_version: u64) -> Option<RosterDelta> {
+            self.inner.get_roster_delta(since_version).await
+        }
+
+        async fn set_muted(
+            &self,
+            client_id: &ClientId,
+            muted: bool,
+        ) -> Result<(), RepositoryError> {
+            self.inner.set_muted(client_id, muted).await
+        }
+
+        async fn get_participant(&self, client_id: &ClientId) -> Option<Participant> {
+            self.inner.get_participant(client_id).await
+        }
+    }
+
+    // Mock MessagePusher: 登録されたクライアントを記録する
+    #[derive(Default)]
+    struct RecordingMessagePusher {
+        registered: Mutex<Vec<ClientId>>,
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for RecordingMessagePusher {
+        async fn register_client(&self, client_id: ClientId, _sender: PusherChannel) {
+            self.registered.lock().await.push(client_id);
+        }
+
+        async fn unregister_client(&self, _client_id: &ClientId) {
+            // No-op for mock
+        }
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            Ok(BroadcastReport::default())
+        }
+    }
+
+    // Stub IdentityVerifier: "<client_id>-token" を client_id の持ち主のトークンとして受け入れる
+    struct StubIdentityVerifier;
+
+    #[async_trait::async_trait]
+    impl IdentityVerifier for StubIdentityVerifier {
+        async fn verify(
+            &self,
+            _client_id: &ClientId,
+            token: Option<&str>,
+        ) -> Result<crate::domain::VerifiedIdentity, AuthError> {
+            let token = token.ok_or(AuthError::MissingToken)?;
+            let owner = token
+                .strip_suffix("-token")
+                .and_then(|owner| ClientId::new(owner.to_string()).ok())
+                .ok_or_else(|| AuthError::InvalidToken(token.to_string()))?;
+            Ok(crate::domain::VerifiedIdentity { client_id: owner })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_verified_identity_registers_participant() {
+        // テスト項目: IdentityVerifier が身元を確認できたクライアントは参加者として登録される
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = Arc::new(RecordingMessagePusher::default());
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone())
+            .with_identity_verifier(Arc::new(StubIdentityVerifier));
+
+        // when (操作):
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        let result = usecase
+            .connect_with_status(
+                alice.clone(),
+                PresenceStatus::default(),
+                Some("alice-token"),
+                tx,
+            )
+            .await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        assert!(repository.get_participant(&alice).await.is_some());
+        assert_eq!(message_pusher.registered.lock().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connect_with_rejected_identity_registers_nothing() {
+        // テスト項目: 身元を確認できない（トークンなし・不正なトークン・他人のトークン）クライアントは
+        //            Unauthorized で拒否され、Room にも MessagePusher にも登録されない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = Arc::new(RecordingMessagePusher::default());
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone())
+            .with_identity_verifier(Arc::new(StubIdentityVerifier));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        let mut results = Vec::new();
+        for token in [None, Some("garbage"), Some("bob-token")] {
+            let (tx, _rx) = tokio::sync::mpsc::channel(16);
+            results.push(
+                usecase
+                    .connect_with_status(alice.clone(), PresenceStatus::default(), token, tx)
+                    .await,
+            );
+        }
+
+        // then (期待する結果):
+        assert_eq!(
+            results,
+            vec![
+                Err(ConnectError::Unauthorized(AuthError::MissingToken)),
+                Err(ConnectError::Unauthorized(AuthError::InvalidToken(
+                    "garbage".to_string()
+                ))),
+                Err(ConnectError::Unauthorized(AuthError::ClientIdMismatch {
+                    claimed: "alice".to_string(),
+                    verified: "bob".to_string(),
+                })),
+            ]
+        );
+        assert_eq!(repository.count_connected_clients().await, 0);
+        assert!(message_pusher.registered.lock().await.is_empty());
+    }
+
     #[tokio::test]
     async fn test_connect_participant_success() {
         // テスト項目: 新規参加者が正常に接続できる
@@ -179,7 +858,7 @@ mod tests {
 
         // when (操作):
         let client_id = ClientId::new("alice".to_string()).unwrap();
-        let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
         let result = usecase.execute(client_id.clone(), tx).await;
 
         // then (期待する結果):
@@ -202,12 +881,12 @@ mod tests {
 
         // 最初の接続は成功
         let client_id1 = ClientId::new("alice".to_string()).unwrap();
-        let (tx1, _rx1) = tokio::sync::mpsc::unbounded_channel();
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(16);
         usecase.execute(client_id1.clone(), tx1).await.unwrap();
 
         // when (操作): 同じ client_id で再接続を試みる
         let client_id2 = ClientId::new("alice".to_string()).unwrap();
-        let (tx2, _rx2) = tokio::sync::mpsc::unbounded_channel();
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
         let result = usecase.execute(client_id2, tx2).await;
 
         // then (期待する結果): 重複エラーが返される
@@ -220,6 +899,76 @@ mod tests {
         assert_eq!(repository.count_connected_clients().await, 1);
     }
 
+    #[tokio::test]
+    async fn test_multi_connection_admits_additional_connection() {
+        // テスト項目: 複数接続モードでは同じ client_id の接続が追加の接続として受け入れられる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher)
+            .with_multi_connection(true);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(16);
+        let first = usecase
+            .connect_with_status(alice.clone(), PresenceStatus::default(), None, tx1)
+            .await
+            .unwrap();
+
+        // when (操作): 同じ client_id で 2 つ目の接続を行う
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
+        let second = usecase
+            .connect_with_status(alice.clone(), PresenceStatus::default(), None, tx2)
+            .await
+            .unwrap();
+
+        // then (期待する結果): 参加者は 1 人のまま、接続数が 2 になる
+        assert!(!first.is_additional);
+        assert!(second.is_additional);
+        assert_eq!(second.connected_at, first.connected_at);
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.participants.len(), 1);
+        assert_eq!(room.participants[0].connections, 2);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_connects_with_same_id_admit_only_one() {
+        // テスト項目: 同じ client_id で並行して接続しても、成功するのは 1 つだけ
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = Arc::new(ConnectParticipantUseCase::new(
+            repository.clone(),
+            message_pusher,
+        ));
+
+        // when (操作):
+        let attempts: Vec<_> = (0..8)
+            .map(|_| {
+                let usecase = usecase.clone();
+                tokio::spawn(async move {
+                    let (tx, _rx) = tokio::sync::mpsc::channel(16);
+                    usecase
+                        .execute(ClientId::new("alice".to_string()).unwrap(), tx)
+                        .await
+                })
+            })
+            .collect();
+        let mut results = Vec::new();
+        for attempt in attempts {
+            results.push(attempt.await.unwrap());
+        }
+
+        // then (期待する結果):
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(
+            results
+                .iter()
+                .filter(|r| r.is_err())
+                .all(|r| r == &Err(ConnectError::DuplicateClientId("alice".to_string())))
+        );
+        assert_eq!(repository.count_connected_clients().await, 1);
+    }
+
     #[tokio::test]
     async fn test_connect_participant_capacity_exceeded() {
         // テスト項目: Room の人数制限超過時にエラーが返される
@@ -232,14 +981,14 @@ mod tests {
         // 2人接続（容量いっぱい）
         let client_id_alice = ClientId::new("alice".to_string()).unwrap();
         let client_id_bob = ClientId::new("bob".to_string()).unwrap();
-        let (tx1, _rx1) = tokio::sync::mpsc::unbounded_channel();
-        let (tx2, _rx2) = tokio::sync::mpsc::unbounded_channel();
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(16);
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
         usecase.execute(client_id_alice.clone(), tx1).await.unwrap();
         usecase.execute(client_id_bob.clone(), tx2).await.unwrap();
 
         // when (操作): 3人目の接続を試みる
         let charlie = ClientId::new("charlie".to_string()).unwrap();
-        let (tx3, _rx3) = tokio::sync::mpsc::unbounded_channel();
+        let (tx3, _rx3) = tokio::sync::mpsc::channel(16);
         let result = usecase.execute(charlie.clone(), tx3).await;
 
         // then (期待する結果): 容量超過エラーが返される
@@ -261,9 +1010,9 @@ mod tests {
         let client_id_charlie = ClientId::new("charlie".to_string()).unwrap();
         let client_id_alice = ClientId::new("alice".to_string()).unwrap();
         let client_id_bob = ClientId::new("bob".to_string()).unwrap();
-        let (tx1, _rx1) = tokio::sync::mpsc::unbounded_channel();
-        let (tx2, _rx2) = tokio::sync::mpsc::unbounded_channel();
-        let (tx3, _rx3) = tokio::sync::mpsc::unbounded_channel();
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(16);
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
+        let (tx3, _rx3) = tokio::sync::mpsc::channel(16);
         usecase
             .execute(client_id_charlie.clone(), tx1)
             .await
@@ -276,8 +1025,539 @@ mod tests {
 
         // then (期待する結果): client_id でソートされている
         assert_eq!(result.len(), 3);
-        assert_eq!(result[0].id.as_str(), client_id_alice.as_str());
-        assert_eq!(result[1].id.as_str(), client_id_bob.as_str());
-        assert_eq!(result[2].id.as_str(), client_id_charlie.as_str());
+        assert_eq!(result[0].participant.id.as_str(), client_id_alice.as_str());
+        assert_eq!(result[1].participant.id.as_str(), client_id_bob.as_str());
+        assert_eq!(
+            result[2].participant.id.as_str(),
+            client_id_charlie.as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_initial_roster_truncates_large_roster() {
+        // テスト項目: 参加者が上限を超える場合、初回の参加者リストは先頭の上限件数に切り詰められる
+        // given (前提条件): 500 人が接続しているルーム（上限 80 件）
+        let repository = create_test_repository_with_capacity(1000);
+        let usecase =
+            ConnectParticipantUseCase::new(repository.clone(), create_test_message_pusher())
+                .with_initial_roster_limit(80);
+        for i in 0..500 {
+            repository
+                .add_participant(
+                    ClientId::new(format!("user{:03}", i)).unwrap(),
+                    Timestamp::new(1000),
+                )
+                .await
+                .unwrap();
+        }
+
+        // when (操作):
+        let roster = usecase.build_initial_roster().await;
+
+        // then (期待する結果):
+        assert_eq!(roster.entries.len(), 80);
+        assert_eq!(roster.total, 500);
+        assert!(roster.is_truncated());
+        assert_eq!(roster.entries[0].participant.id.as_str(), "user000");
+        assert_eq!(roster.entries[79].participant.id.as_str(), "user079");
+    }
+
+    #[tokio::test]
+    async fn test_build_initial_roster_within_limit_is_not_truncated() {
+        // テスト項目: 参加者が上限以下の場合は全員が含まれる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase =
+            ConnectParticipantUseCase::new(repository.clone(), create_test_message_pusher());
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        usecase
+            .execute(ClientId::new("alice".to_string()).unwrap(), tx)
+            .await
+            .unwrap();
+
+        // when (操作):
+        let roster = usecase.build_initial_roster().await;
+
+        // then (期待する結果):
+        assert_eq!(roster.entries.len(), 1);
+        assert_eq!(roster.total, 1);
+        assert!(!roster.is_truncated());
+        assert_eq!(
+            roster.room_id,
+            Some(repository.get_room().await.unwrap().id)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_build_initial_roster_reports_message_history_usage() {
+        // テスト項目: 初回の参加者リストに、Room の履歴の上限と現在のメッセージ数が含まれる
+        // given (前提条件): 履歴の上限が 10 件の Room にメッセージが 3 件ある
+        let mut room = Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+            10, // participant_capacity
+            10, // message_capacity
+        );
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for i in 0..3 {
+            room.add_message(ChatMessage::new(
+                alice.clone(),
+                MessageContent::new(format!("message {}", i)).unwrap(),
+                Timestamp::new(1000 * (i + 1)),
+            ))
+            .unwrap();
+        }
+        let repository = Arc::new(InMemoryRoomRepository::new(Arc::new(Mutex::new(room))));
+        let usecase = ConnectParticipantUseCase::new(repository, create_test_message_pusher());
+
+        // when (操作):
+        let roster = usecase.build_initial_roster().await;
+
+        // then (期待する結果):
+        assert_eq!(roster.message_capacity, Some(10));
+        assert_eq!(roster.message_count, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_build_participant_list_reports_idle_duration() {
+        // テスト項目: 直前に発言した参加者と長く発言していない参加者のアイドル時間が区別される
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(1_000))
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob.clone(), Timestamp::new(1_000))
+            .await
+            .unwrap();
+        repository
+            .update_last_active(&alice, Timestamp::new(300_000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = usecase
+            .build_participant_list_at(Timestamp::new(301_000))
+            .await;
+
+        // then (期待する結果): alice は 1 秒、bob は接続から約 5 分アイドル
+        assert_eq!(result[0].participant.id, alice);
+        assert_eq!(result[0].idle_ms, 1_000);
+        assert_eq!(result[1].participant.id, bob);
+        assert_eq!(result[1].idle_ms, 300_000);
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_updates_last_active() {
+        // テスト項目: ハートビートを受けると最終アクティブ時刻が更新され、アイドル時間がリセットされる
+        // given (前提条件): 時刻 1000 に接続してから発言していない alice
+        let repository = create_test_repository();
+        let usecase =
+            ConnectParticipantUseCase::new(repository.clone(), create_test_message_pusher());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(1_000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let result = usecase
+            .record_heartbeat(&alice, Timestamp::new(300_000))
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(result, Ok(()));
+        let participant = repository.get_participant(&alice).await.unwrap();
+        assert_eq!(participant.last_active, Timestamp::new(300_000));
+        let roster = usecase
+            .build_participant_list_at(Timestamp::new(301_000))
+            .await;
+        assert_eq!(roster[0].idle_ms, 1_000);
+    }
+
+    #[tokio::test]
+    async fn test_roster_update_since_known_version_contains_only_one_join_and_one_leave() {
+        // テスト項目: クライアントが知っているバージョン以降に 1 人参加・1 人退出した場合、
+        //            差分にはその 2 人だけが含まれ、バージョンは変化の数だけ進む
+        // given (前提条件): alice と bob がいる時点の参加者リストをクライアントが受け取っている
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher);
+        let client = |id: &str| ClientId::new(id.to_string()).unwrap();
+        for id in ["alice", "bob"] {
+            repository
+                .add_participant(client(id), Timestamp::new(1_000))
+                .await
+                .unwrap();
+        }
+        let RosterUpdate::Full { version, entries } = usecase
+            .build_roster_update_at(None, Timestamp::new(2_000))
+            .await
+        else {
+            panic!("expected the full roster without a known version");
+        };
+        assert_eq!(entries.len(), 2);
+
+        // when (操作): carol が参加し、bob が退出した後に差分を要求
+        repository
+            .add_participant(client("carol"), Timestamp::new(3_000))
+            .await
+            .unwrap();
+        repository.remove_participant(&client("bob")).await.unwrap();
+        let update = usecase
+            .build_roster_update_at(Some(version), Timestamp::new(4_000))
+            .await;
+
+        // then (期待する結果):
+        let RosterUpdate::Delta {
+            version: new_version,
+            added,
+            removed,
+        } = update
+        else {
+            panic!("expected a delta from a known version");
+        };
+        assert_eq!(new_version, version + 2);
+        assert_eq!(added.len(), 1);
+        assert_eq!(added[0].participant.id, client("carol"));
+        assert_eq!(added[0].idle_ms, 1_000);
+        assert_eq!(removed, vec![client("bob")]);
+    }
+
+    #[tokio::test]
+    async fn test_roster_update_from_unknown_version_is_the_full_roster() {
+        // テスト項目: サーバーより新しい（不明な）バージョンからの要求には参加者リスト全体を返す
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher);
+        repository
+            .add_participant(
+                ClientId::new("alice".to_string()).unwrap(),
+                Timestamp::new(0),
+            )
+            .await
+            .unwrap();
+
+        // when (操作):
+        let update = usecase
+            .build_roster_update_at(Some(42), Timestamp::new(0))
+            .await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            update,
+            RosterUpdate::Full { version: 1, ref entries } if entries.len() == 1
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_connect_participant_add_failure_does_not_register_client() {
+        // テスト項目: Repository への追加に失敗した場合、MessagePusher に登録されない
+        // given (前提条件):
+        let repository = Arc::new(FailingAddRoomRepository {
+            inner: create_test_repository(),
+        });
+        let message_pusher = Arc::new(RecordingMessagePusher::default());
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+
+        // when (操作):
+        let client_id = ClientId::new("alice".to_string()).unwrap();
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        let result = usecase.execute(client_id, tx).await;
+
+        // then (期待する結果): 接続は失敗し、MessagePusher への登録は行われない
+        assert_eq!(result, Err(ConnectError::RoomCapacityExceeded));
+        assert!(message_pusher.registered.lock().await.is_empty());
+        assert_eq!(repository.count_connected_clients().await, 0);
+    }
+
+    /// 参加者数を含む join 通知（テスト用）
+    fn joined_with_count(count: usize) -> String {
+        format!("joined {}", count)
+    }
+
+    /// 参加者数の更新（テスト用）
+    fn count_only(count: usize) -> String {
+        format!("count {}", count)
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_joined_below_threshold() {
+        // テスト項目: 参加者数が閾値以下の場合、個別の join 通知が既存参加者に送られる
+        // given (前提条件):
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        room.lock().await.presence_notification_threshold = Some(2);
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository, message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, mut rx_bob) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice, tx_alice).await.unwrap();
+        usecase.execute(bob.clone(), tx_bob).await.unwrap();
+
+        // when (操作):
+        let result = usecase
+            .broadcast_participant_joined(&bob, joined_with_count, count_only)
+            .await;
+
+        // then (期待する結果): alice にだけ、bob を含む参加者数付きの join 通知が届く
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.try_recv().unwrap(), "joined 2");
+        assert!(rx_bob.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_joined_reports_dead_recipient() {
+        // テスト項目: 受信側が閉じた参加者がいても他の参加者には join 通知が届き、
+        //            届かなかった参加者が配信結果に含まれる
+        // given (前提条件): alice・bob・charlie が接続済みで、bob の受信側は閉じている
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository, message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let charlie = ClientId::new("charlie".to_string()).unwrap();
+        let dave = ClientId::new("dave".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, rx_bob) = tokio::sync::mpsc::channel(16);
+        let (tx_charlie, mut rx_charlie) = tokio::sync::mpsc::channel(16);
+        let (tx_dave, _rx_dave) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice.clone(), tx_alice).await.unwrap();
+        usecase.execute(bob.clone(), tx_bob).await.unwrap();
+        usecase.execute(charlie.clone(), tx_charlie).await.unwrap();
+        usecase.execute(dave.clone(), tx_dave).await.unwrap();
+        drop(rx_bob);
+
+        // when (操作): dave の join を通知する
+        let report = usecase
+            .broadcast_participant_joined(&dave, joined_with_count, count_only)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(report.delivered, vec![alice, charlie]);
+        assert_eq!(report.failed, vec![bob]);
+        assert!(!report.is_complete());
+        assert_eq!(rx_alice.try_recv().unwrap(), "joined 4");
+        assert_eq!(rx_charlie.try_recv().unwrap(), "joined 4");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_joined_above_threshold() {
+        // テスト項目: 参加者数が閾値を超えた場合、join 通知は抑制され参加者数の更新が全員に送られる
+        // given (前提条件):
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        room.lock().await.presence_notification_threshold = Some(1);
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository, message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, mut rx_bob) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice, tx_alice).await.unwrap();
+        usecase.execute(bob.clone(), tx_bob).await.unwrap();
+
+        // when (操作):
+        let result = usecase
+            .broadcast_participant_joined(&bob, joined_with_count, count_only)
+            .await;
+
+        // then (期待する結果): 全員に参加者数の更新だけが届く
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.try_recv().unwrap(), "count 2");
+        assert!(rx_alice.try_recv().is_err());
+        assert_eq!(rx_bob.try_recv().unwrap(), "count 2");
+    }
+
+    #[tokio::test]
+    async fn test_send_roster_reflects_current_participants_after_missed_delta() {
+        // テスト項目: leave 通知を取りこぼしても、再取得した参加者リストは現在の参加者を反映する
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, _rx_bob) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice.clone(), tx_alice).await.unwrap();
+        usecase.execute(bob.clone(), tx_bob).await.unwrap();
+
+        // bob が退出したが、alice には leave 通知が届いていない
+        repository.remove_participant(&bob).await.unwrap();
+
+        // when (操作): alice が参加者リストを要求
+        let participants = usecase.build_participant_list().await;
+        let roster: Vec<String> = participants
+            .iter()
+            .map(|entry| entry.participant.id.as_str().to_string())
+            .collect();
+        let result = usecase.send_roster_to(&alice, &roster.join(",")).await;
+
+        // then (期待する結果): alice に現在の参加者（alice のみ）が届く
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.try_recv().unwrap(), "alice");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_roster_delivers_full_roster_to_all_participants() {
+        // テスト項目: 参加者リストのブロードキャストが接続中の全参加者に届く
+        // given (前提条件): alice と bob が接続している
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, mut rx_bob) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice.clone(), tx_alice).await.unwrap();
+        usecase.execute(bob.clone(), tx_bob).await.unwrap();
+
+        // when (操作): 現在の参加者リスト全体を room-connected としてブロードキャスト
+        let participants: Vec<ParticipantInfo> = usecase
+            .build_participant_list()
+            .await
+            .into_iter()
+            .map(|entry| ParticipantInfo::from(entry.participant))
+            .collect();
+        let message = serde_json::to_string(&RoomConnectedMessage {
+            r#type: MessageType::RoomConnected,
+            room_id: None,
+            total: participants.len(),
+            participants,
+            truncated: false,
+            message_capacity: None,
+            message_count: None,
+        })
+        .unwrap();
+        let result = usecase.broadcast_roster(&message).await;
+
+        // then (期待する結果): alice と bob の両方に 2 人分の参加者リストが届く
+        assert!(result.is_ok());
+        for rx in [&mut rx_alice, &mut rx_bob] {
+            let received: RoomConnectedMessage =
+                serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+            let ids: Vec<&str> = received
+                .participants
+                .iter()
+                .map(|p| p.client_id.as_str())
+                .collect();
+            assert_eq!(ids, vec!["alice", "bob"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_policy_reject_rejects_second_connection() {
+        // テスト項目: 重複時の扱いが reject のとき、同じ client_id の 2 本目の接続は拒否され、
+        //            既存の接続はそのまま使える
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository, message_pusher.clone())
+            .with_duplicate_policy(DuplicatePolicy::Reject);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (tx1, mut rx1) = tokio::sync::mpsc::channel(16);
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
+        usecase.execute(alice.clone(), tx1).await.unwrap();
+
+        // when (操作):
+        let result = usecase.execute(alice.clone(), tx2).await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(ConnectError::DuplicateClientId(_))));
+        message_pusher.push_to(&alice, "hello").await.unwrap();
+        assert_eq!(rx1.recv().await, Some("hello".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_policy_takeover_replaces_existing_channel() {
+        // テスト項目: 重複時の扱いが takeover のとき、2 本目の接続が既存の接続を置き換える
+        //            （古いチャネルは閉じられ、参加者は 1 人のまま、参加時刻は引き継がれる）
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = ConnectParticipantUseCase::new(repository.clone(), message_pusher.clone())
+            .with_duplicate_policy(DuplicatePolicy::Takeover);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let (tx1, mut rx1) = tokio::sync::mpsc::channel(16);
+        let (tx2, mut rx2) = tokio::sync::mpsc::channel(16);
+        let first = usecase
+            .connect_with_status(alice.clone(), PresenceStatus::default(), None, tx1)
+            .await
+            .unwrap();
+
+        // when (操作):
+        let second = usecase
+            .connect_with_status(alice.clone(), PresenceStatus::default(), None, tx2)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert!(second.is_additional);
+        assert_eq!(second.connected_at, first.connected_at);
+        assert_eq!(rx1.recv().await, None);
+        message_pusher.push_to(&alice, "hello").await.unwrap();
+        assert_eq!(rx2.recv().await, Some("hello".to_string()));
+        assert_eq!(repository.get_participants().await.len(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_takeovers_leave_a_single_channel() {
+        // テスト項目: 重複時の扱いが takeover のとき、同じ client_id で並行して接続を置き換えても、
+        //            MessagePusher に残る接続は 1 つだけ
+        // given (前提条件): alice が接続している
+        let repository = create_test_repository();
+        let clients = Arc::new(Mutex::new(HashMap::new()));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(clients.clone()));
+        let usecase = Arc::new(
+            ConnectParticipantUseCase::new(repository.clone(), message_pusher)
+                .with_duplicate_policy(DuplicatePolicy::Takeover),
+        );
+        let (tx, _rx) = tokio::sync::mpsc::channel(16);
+        usecase
+            .execute(ClientId::new("alice".to_string()).unwrap(), tx)
+            .await
+            .unwrap();
+
+        // when (操作): 32 の接続が同時に alice の接続を置き換える（競合が起きやすいよう 50 回繰り返す）
+        let mut max_channels = 0;
+        for _ in 0..50 {
+            let (senders, _receivers): (Vec<_>, Vec<_>) =
+                (0..32).map(|_| tokio::sync::mpsc::channel(16)).unzip();
+            let takeovers: Vec<_> = senders
+                .into_iter()
+                .map(|tx| {
+                    let usecase = usecase.clone();
+                    tokio::spawn(async move {
+                        usecase
+                            .execute(ClientId::new("alice".to_string()).unwrap(), tx)
+                            .await
+                    })
+                })
+                .collect();
+            for takeover in takeovers {
+                assert!(takeover.await.unwrap().is_ok());
+            }
+            max_channels = max_channels.max(clients.lock().await["alice"].len());
+        }
+
+        // then (期待する結果):
+        assert_eq!(max_channels, 1);
+        assert_eq!(repository.get_participants().await.len(), 1);
     }
 }
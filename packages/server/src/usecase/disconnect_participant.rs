@@ -16,9 +16,11 @@
 //! - エッジケース：最後の参加者の切断（通知対象なし）
 //! - 異常系：存在しない参加者の切断試行
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
-use crate::domain::{ClientId, MessagePusher, RoomRepository};
+use crate::domain::{
+    BroadcastReport, ClientId, MessagePusher, PusherChannel, RoomRepository, Timestamp,
+};
 
 /// 参加者切断のユースケース
 pub struct DisconnectParticipantUseCase {
@@ -72,6 +74,60 @@ impl DisconnectParticipantUseCase {
         Ok(notify_targets)
     }
 
+    /// 1 つの接続の切断を実行
+    ///
+    /// 参加者の接続数を減らし、最後の接続が閉じたときだけ参加者を Room から削除します。
+    /// 複数接続モードでない場合、接続は常に 1 つのため [`execute`](Self::execute) と同じ結果になります。
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 切断するクライアントの ID（Domain Model）
+    /// * `sender` - 閉じた接続の登録時に渡した channel sender
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(Vec<ClientId>))` - 参加者が退出した（leave 通知の対象のクライアント ID リスト）
+    /// * `Ok(None)` - 同じ参加者の他の接続が残っている（退出していない）
+    /// * `Err(())` - 切断失敗（参加者が存在しない場合）
+    pub async fn execute_connection(
+        &self,
+        client_id: ClientId,
+        sender: &PusherChannel,
+    ) -> Result<Option<Vec<ClientId>>, ()> {
+        // 1. Repository 経由で接続数を減らす（最後の接続であれば参加者を削除）
+        let remaining = self
+            .repository
+            .remove_connection(&client_id)
+            .await
+            .map_err(|_| ())?;
+
+        // 2. MessagePusher から閉じた接続だけを登録解除
+        self.message_pusher
+            .unregister_connection(&client_id, sender)
+            .await;
+
+        if remaining > 0 {
+            return Ok(None);
+        }
+
+        // 3. 通知対象を取得（参加者は削除済みのため、残りの全てのクライアント）
+        Ok(Some(self.get_notify_targets(&client_id).await))
+    }
+
+    /// 参加者が接続してから `now` までの時間（セッションの長さ）を取得
+    ///
+    /// 切断処理で参加者が削除される前に呼び出します。
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Duration)` - 最初の接続からの経過時間
+    /// * `None` - 参加者が存在しない
+    pub async fn session_duration(&self, client_id: &ClientId, now: Timestamp) -> Option<Duration> {
+        let participant = self.repository.get_participant(client_id).await?;
+        let elapsed_ms = now.value().saturating_sub(participant.connected_at.value());
+        Some(Duration::from_millis(elapsed_ms.max(0) as u64))
+    }
+
     /// 通知対象のクライアント ID リストを取得
     ///
     /// 切断するクライアント以外の全てのクライアント ID を返す（Domain Model）
@@ -90,22 +146,41 @@ impl DisconnectParticipantUseCase {
 
     /// 参加者が left したことを残りの参加者にブロードキャスト
     ///
+    /// 残りの参加者数が `presence_notification_threshold` を超えている場合は、
+    /// 個別の leave 通知を抑制し、代わりに参加者数の更新をブロードキャストします。
+    ///
     /// # Arguments
     ///
     /// * `target_ids` - ブロードキャスト対象のクライアント ID リスト（Domain Model）
-    /// * `message` - ブロードキャストするメッセージ（JSON）
+    /// * `message` - 残りの参加者数から、ブロードキャストする leave 通知メッセージ（JSON）を生成する関数
+    /// * `count_message` - 残りの参加者数から、通知抑制時にブロードキャストする参加者数メッセージ（JSON）を生成する関数
+    ///
+    /// 参加者数はブロードキャスト時点のものです。
     ///
     /// # Returns
     ///
-    /// * `Ok(())` - ブロードキャスト成功
+    /// * `Ok(BroadcastReport)` - 送信先ごとの配信結果（届かなかった参加者は `failed` に含まれる）
     /// * `Err(String)` - ブロードキャスト失敗
     pub async fn broadcast_participant_left(
         &self,
         target_ids: Vec<ClientId>,
-        message: &str,
-    ) -> Result<(), String> {
+        message: impl FnOnce(usize) -> String,
+        count_message: impl FnOnce(usize) -> String,
+    ) -> Result<BroadcastReport, String> {
+        let room = self
+            .repository
+            .get_room()
+            .await
+            .map_err(|e| e.to_string())?;
+        let participant_count = room.participants.len();
+        let message = if room.suppresses_presence_notifications() {
+            count_message(participant_count)
+        } else {
+            message(participant_count)
+        };
+
         self.message_pusher
-            .broadcast(target_ids, message)
+            .broadcast(target_ids, &message)
             .await
             .map_err(|e| e.to_string())
     }
@@ -182,6 +257,49 @@ mod tests {
         assert_eq!(repository.count_connected_clients().await, 2);
     }
 
+    #[tokio::test]
+    async fn test_execute_connection_removes_participant_only_after_last_connection() {
+        // テスト項目: 参加者は最後の接続が閉じたときだけ退出する
+        // given (前提条件): alice が 2 つの接続を持ち、bob が接続している
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repository.add_connection(&alice).await.unwrap();
+        repository
+            .add_participant(bob.clone(), timestamp)
+            .await
+            .unwrap();
+        let (tx1, _rx1) = tokio::sync::mpsc::channel(16);
+        let (tx2, _rx2) = tokio::sync::mpsc::channel(16);
+        message_pusher
+            .register_client(alice.clone(), tx1.clone())
+            .await;
+        message_pusher
+            .register_client(alice.clone(), tx2.clone())
+            .await;
+
+        // when (操作): 1 つ目の接続を閉じる
+        let first = usecase.execute_connection(alice.clone(), &tx1).await;
+
+        // then (期待する結果): alice は Room に残る
+        assert_eq!(first, Ok(None));
+        assert_eq!(repository.count_connected_clients().await, 2);
+
+        // when (操作): 最後の接続を閉じる
+        let last = usecase.execute_connection(alice.clone(), &tx2).await;
+
+        // then (期待する結果): alice が退出し、bob が通知対象になる
+        assert_eq!(last, Ok(Some(vec![bob])));
+        assert_eq!(repository.count_connected_clients().await, 1);
+    }
+
     #[tokio::test]
     async fn test_disconnect_last_participant() {
         // テスト項目: 最後の参加者が切断した場合、通知対象は空
@@ -228,6 +346,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn test_session_duration_is_measured_from_connected_at() {
+        // テスト項目: セッションの長さは参加者の接続時刻から数えられ、参加していなければ None になる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(10_000))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let present = usecase
+            .session_duration(&alice, Timestamp::new(12_500))
+            .await;
+        let nonexistent = ClientId::new("nonexistent".to_string()).unwrap();
+        let absent = usecase
+            .session_duration(&nonexistent, Timestamp::new(12_500))
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(present, Some(Duration::from_millis(2_500)));
+        assert_eq!(absent, None);
+    }
+
     #[tokio::test]
     async fn test_count_remaining_participants() {
         // テスト項目: 残りの参加者数を正しくカウントできる
@@ -265,4 +410,131 @@ mod tests {
         let count_after = usecase.count_remaining_participants().await;
         assert_eq!(count_after, 2);
     }
+
+    /// 残りの参加者数を含む leave 通知（テスト用）
+    fn left_with_count(count: usize) -> String {
+        format!("left {}", count)
+    }
+
+    /// 参加者数の更新（テスト用）
+    fn count_only(count: usize) -> String {
+        format!("count {}", count)
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_left_below_threshold() {
+        // テスト項目: 残りの参加者数が閾値以下の場合、個別の leave 通知が送られる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob.clone(), timestamp)
+            .await
+            .unwrap();
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        message_pusher
+            .register_client(alice.clone(), tx_alice)
+            .await;
+        let notify_targets = usecase.execute(bob).await.unwrap();
+
+        // when (操作):
+        let result = usecase
+            .broadcast_participant_left(notify_targets, left_with_count, count_only)
+            .await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.try_recv().unwrap(), "left 1");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_left_reports_dead_recipient() {
+        // テスト項目: 受信側が閉じた参加者がいても他の参加者には leave 通知が届き、
+        //            届かなかった参加者が配信結果に含まれる
+        // given (前提条件): alice・bob・charlie が残っていて、bob の受信側は閉じている
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let charlie = ClientId::new("charlie".to_string()).unwrap();
+        let dave = ClientId::new("dave".to_string()).unwrap();
+        for id in [&alice, &bob, &charlie, &dave] {
+            repository
+                .add_participant(id.clone(), timestamp)
+                .await
+                .unwrap();
+        }
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        let (tx_bob, rx_bob) = tokio::sync::mpsc::channel(16);
+        let (tx_charlie, mut rx_charlie) = tokio::sync::mpsc::channel(16);
+        message_pusher
+            .register_client(alice.clone(), tx_alice)
+            .await;
+        message_pusher.register_client(bob.clone(), tx_bob).await;
+        message_pusher
+            .register_client(charlie.clone(), tx_charlie)
+            .await;
+        drop(rx_bob);
+        let notify_targets = usecase.execute(dave).await.unwrap();
+
+        // when (操作):
+        let report = usecase
+            .broadcast_participant_left(notify_targets, left_with_count, count_only)
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(report.delivered, vec![alice, charlie]);
+        assert_eq!(report.failed, vec![bob]);
+        assert_eq!(rx_alice.try_recv().unwrap(), "left 3");
+        assert_eq!(rx_charlie.try_recv().unwrap(), "left 3");
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_participant_left_above_threshold() {
+        // テスト項目: 残りの参加者数が閾値を超える場合、leave 通知は抑制され参加者数の更新が送られる
+        // given (前提条件):
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        room.lock().await.presence_notification_threshold = Some(1);
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = create_test_message_pusher();
+        let usecase = DisconnectParticipantUseCase::new(repository.clone(), message_pusher.clone());
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let charlie = ClientId::new("charlie".to_string()).unwrap();
+        for id in [&alice, &bob, &charlie] {
+            repository
+                .add_participant(id.clone(), timestamp)
+                .await
+                .unwrap();
+        }
+        let (tx_alice, mut rx_alice) = tokio::sync::mpsc::channel(16);
+        message_pusher
+            .register_client(alice.clone(), tx_alice)
+            .await;
+        let notify_targets = usecase.execute(charlie).await.unwrap();
+
+        // when (操作):
+        let result = usecase
+            .broadcast_participant_left(notify_targets, left_with_count, count_only)
+            .await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        assert_eq!(rx_alice.try_recv().unwrap(), "count 2");
+    }
 }
@@ -1,5 +1,9 @@
 //! UseCase layer error definitions.
 
+use std::time::Duration;
+
+use crate::domain::{AuthError, MessagePushError};
+
 /// Errors related to participant connection
 #[derive(Debug, PartialEq, Eq)]
 pub enum ConnectError {
@@ -7,6 +11,10 @@ pub enum ConnectError {
     DuplicateClientId(String),
     /// Room の容量超過
     RoomCapacityExceeded,
+    /// Repository エラー（容量超過以外）
+    RepositoryError(String),
+    /// 名乗った client_id の身元を確認できない
+    Unauthorized(AuthError),
 }
 
 /// Errors related to message sending
@@ -14,6 +22,52 @@ pub enum ConnectError {
 pub enum SendMessageError {
     /// メッセージ容量超過
     MessageCapacityExceeded,
+    /// Repository エラー（容量超過以外）
+    RepositoryError(String),
+    /// 再試行してもブロードキャストに失敗した（[`MessagePushError::code`] で原因を区別できる）
+    ///
+    /// メッセージは履歴に追加済みのため、送信者は再送しないこと
+    BroadcastFailed(MessagePushError),
+    /// 送信者が管理者にミュートされている
+    Muted,
+    /// スローモード中で、送信者の前回のメッセージから間隔が空いていない
+    SlowMode { retry_after: Duration },
+}
+
+/// Errors related to file sending
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendFileError {
+    /// ファイルサイズ超過
+    FileTooLarge { max: usize, actual: usize },
     /// ブロードキャスト失敗
     BroadcastFailed(String),
 }
+
+/// Errors related to message reactions
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReactError {
+    /// リアクション先のメッセージが履歴にない
+    MessageNotFound(String),
+    /// Repository エラー
+    RepositoryError(String),
+    /// ブロードキャスト失敗
+    BroadcastFailed(MessagePushError),
+}
+
+/// Errors related to admin commands
+#[derive(Debug, PartialEq, Eq)]
+pub enum AdminError {
+    /// 対象の参加者が Room にいない
+    ParticipantNotFound(String),
+    /// Repository エラー
+    RepositoryError(String),
+    /// ブロードキャスト失敗
+    BroadcastFailed(MessagePushError),
+}
+
+/// Errors related to application-level pong reply
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplyPongError {
+    /// 返信失敗
+    PushFailed(String),
+}
@@ -0,0 +1,211 @@
+//! UseCase: 参加者詳細取得処理
+
+use std::sync::Arc;
+
+use crate::domain::{ClientId, Participant, RepositoryError, RoomId, RoomRepository, Timestamp};
+
+/// 参加者詳細取得のユースケース
+pub struct GetParticipantUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+}
+
+/// 参加者詳細取得エラー
+#[derive(Debug, PartialEq)]
+pub enum GetParticipantError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// 参加者が見つからない
+    ParticipantNotFound,
+    /// Repository エラー
+    RepositoryError,
+}
+
+/// 参加者の詳細情報
+#[derive(Debug, Clone)]
+pub struct ParticipantDetail {
+    /// 参加者（Domain Model）
+    pub participant: Participant,
+    /// 最終アクティブ時刻（最後のメッセージ送信時刻、未送信なら接続時刻）
+    pub last_active: Timestamp,
+}
+
+impl GetParticipantUseCase {
+    /// 新しい GetParticipantUseCase を作成
+    pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// 参加者詳細を取得
+    ///
+    /// Room に参加している（接続中の）参加者のみが対象です。
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 参加者が所属するルームの ID
+    /// * `client_id` - 取得する参加者の ID
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ParticipantDetail)` - 参加者の詳細情報
+    /// * `Err(GetParticipantError)` - 取得失敗
+    pub async fn execute(
+        &self,
+        room_id: String,
+        client_id: String,
+    ) -> Result<ParticipantDetail, GetParticipantError> {
+        let room = self
+            .repository
+            .get_room()
+            .await
+            .map_err(|_| GetParticipantError::RepositoryError)?;
+
+        // Check if the requested room_id matches
+        if room.id.as_str() != room_id {
+            return Err(GetParticipantError::RoomNotFound);
+        }
+
+        let client_id =
+            ClientId::new(client_id).map_err(|_| GetParticipantError::ParticipantNotFound)?;
+        let participant = room
+            .get_participant(&client_id)
+            .cloned()
+            .ok_or(GetParticipantError::ParticipantNotFound)?;
+
+        // 接続以降に送信した最後のメッセージの時刻（なければ接続時刻）
+        let last_active = room
+            .messages
+            .iter()
+            .filter(|m| m.from == client_id && m.timestamp >= participant.connected_at)
+            .map(|m| m.timestamp)
+            .max()
+            .unwrap_or(participant.connected_at);
+
+        Ok(ParticipantDetail {
+            participant,
+            last_active,
+        })
+    }
+
+    /// 参加者が接続中かどうかを判定
+    ///
+    /// Room 全体を取得せずに、参加者の有無だけを確認します。
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 参加者が所属するルームの ID
+    /// * `client_id` - 判定する参加者の ID
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(bool)` - 接続中なら `true`（不正な client_id は `false`）
+    /// * `Err(GetParticipantError)` - ルームが存在しない、または取得失敗
+    pub async fn is_online(
+        &self,
+        room_id: String,
+        client_id: String,
+    ) -> Result<bool, GetParticipantError> {
+        let room_id = RoomId::new(room_id).map_err(|_| GetParticipantError::RoomNotFound)?;
+        let Ok(client_id) = ClientId::new(client_id) else {
+            return Ok(false);
+        };
+
+        self.repository
+            .is_connected(&room_id, &client_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => GetParticipantError::RoomNotFound,
+                _ => GetParticipantError::RepositoryError,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, Room, RoomIdFactory},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+    use tokio::sync::Mutex;
+
+    async fn create_test_usecase() -> (GetParticipantUseCase, String) {
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        room.add_participant(Participant::new(alice.clone(), Timestamp::new(1000)))
+            .unwrap();
+        room.add_message(crate::domain::ChatMessage::new(
+            alice,
+            MessageContent::new("Hello!".to_string()).unwrap(),
+            Timestamp::new(2000),
+        ))
+        .unwrap();
+        let room_id = room.id.as_str().to_string();
+        let repository = Arc::new(InMemoryRoomRepository::new(Arc::new(Mutex::new(room))));
+
+        (GetParticipantUseCase::new(repository), room_id)
+    }
+
+    #[tokio::test]
+    async fn test_get_participant_returns_existing_participant() {
+        // テスト項目: 接続中の参加者の詳細（接続時刻・最終アクティブ時刻）を取得できる
+        // given (前提条件):
+        let (usecase, room_id) = create_test_usecase().await;
+
+        // when (操作):
+        let result = usecase.execute(room_id, "alice".to_string()).await;
+
+        // then (期待する結果):
+        let detail = result.unwrap();
+        assert_eq!(detail.participant.id.as_str(), "alice");
+        assert_eq!(detail.participant.connected_at, Timestamp::new(1000));
+        assert_eq!(detail.last_active, Timestamp::new(2000));
+    }
+
+    #[tokio::test]
+    async fn test_get_participant_returns_not_found_for_missing_participant() {
+        // テスト項目: 参加していないクライアントは ParticipantNotFound になる
+        // given (前提条件):
+        let (usecase, room_id) = create_test_usecase().await;
+
+        // when (操作):
+        let result = usecase.execute(room_id, "bob".to_string()).await;
+
+        // then (期待する結果):
+        assert!(matches!(
+            result,
+            Err(GetParticipantError::ParticipantNotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_is_online_for_connected_and_not_connected_ids() {
+        // テスト項目: 接続中の参加者は online、参加していないクライアントは offline と判定される
+        // given (前提条件):
+        let (usecase, room_id) = create_test_usecase().await;
+
+        // when (操作):
+        let alice = usecase
+            .is_online(room_id.clone(), "alice".to_string())
+            .await;
+        let bob = usecase.is_online(room_id, "bob".to_string()).await;
+
+        // then (期待する結果):
+        assert_eq!(alice, Ok(true));
+        assert_eq!(bob, Ok(false));
+    }
+
+    #[tokio::test]
+    async fn test_is_online_returns_not_found_for_unknown_room() {
+        // テスト項目: 存在しないルームを指定すると RoomNotFound になる
+        // given (前提条件):
+        let (usecase, _room_id) = create_test_usecase().await;
+        let other_room_id = RoomIdFactory::generate().unwrap().as_str().to_string();
+
+        // when (操作):
+        let result = usecase.is_online(other_room_id, "alice".to_string()).await;
+
+        // then (期待する結果):
+        assert_eq!(result, Err(GetParticipantError::RoomNotFound));
+    }
+}
@@ -2,28 +2,105 @@
 
 use std::sync::Arc;
 
+use engawa_shared::time::{Clock, SystemClock};
+
 use crate::domain::{Room, RoomRepository};
 
 /// ルーム一覧取得のユースケース
 pub struct GetRoomsUseCase {
     /// Repository（データアクセス層の抽象化）
     repository: Arc<dyn RoomRepository>,
+    /// ルームの経過時間の計算に使う時計
+    clock: Arc<dyn Clock>,
+}
+
+/// ルーム一覧の 1 エントリ
+#[derive(Debug, Clone)]
+pub struct RoomSummary {
+    /// ルーム（Domain Model）
+    pub room: Room,
+    /// ルームの作成からの経過時間（秒）
+    pub age_secs: u64,
 }
 
 impl GetRoomsUseCase {
     /// 新しい GetRoomsUseCase を作成
+    ///
+    /// 経過時間の計算にはシステム時計を使用します。
     pub fn new(repository: Arc<dyn RoomRepository>) -> Self {
-        Self { repository }
+        Self {
+            repository,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// 経過時間の計算に使う時計を設定
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
     }
 
     /// ルーム一覧を取得
     ///
     /// # Returns
     ///
-    /// * `Ok(Vec<Room>)` - ルーム一覧（Domain Model）
+    /// * `Ok(Vec<RoomSummary>)` - ルーム一覧（Domain Model と作成からの経過時間）
     /// * `Err(())` - 取得失敗
-    pub async fn execute(&self) -> Result<Vec<Room>, ()> {
+    pub async fn execute(&self) -> Result<Vec<RoomSummary>, ()> {
         let room = self.repository.get_room().await.map_err(|_| ())?;
-        Ok(vec![room])
+        let now = self.clock.now_jst_millis();
+
+        // 時計が作成時刻より前を指していても負の経過時間にはしない
+        let age_secs = (now.saturating_sub(room.created_at.value()) / 1000).max(0) as u64;
+
+        Ok(vec![RoomSummary { room, age_secs }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{RoomIdFactory, Timestamp},
+        infrastructure::repository::InMemoryRoomRepository,
+    };
+    use engawa_shared::time::FixedClock;
+    use tokio::sync::Mutex;
+
+    fn create_usecase(created_at: i64, now: i64) -> GetRoomsUseCase {
+        let room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(created_at),
+        );
+        let repository = Arc::new(InMemoryRoomRepository::new(Arc::new(Mutex::new(room))));
+        GetRoomsUseCase::new(repository).with_clock(Arc::new(FixedClock::new(now)))
+    }
+
+    #[tokio::test]
+    async fn test_room_age_is_computed_from_the_clock() {
+        // テスト項目: ルームの経過時間が注入した時計の現在時刻から秒単位で計算される
+        // given (前提条件): 作成から 90.5 秒後を指す時計
+        let usecase = create_usecase(1_000_000, 1_090_500);
+
+        // when (操作):
+        let rooms = usecase.execute().await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(rooms.len(), 1);
+        assert_eq!(rooms[0].age_secs, 90);
+        assert_eq!(rooms[0].room.created_at, Timestamp::new(1_000_000));
+    }
+
+    #[tokio::test]
+    async fn test_room_age_is_zero_when_clock_is_behind_creation() {
+        // テスト項目: 時計が作成時刻より前を指していても経過時間は 0 になる
+        // given (前提条件):
+        let usecase = create_usecase(1_000_000, 999_000);
+
+        // when (操作):
+        let rooms = usecase.execute().await.unwrap();
+
+        // then (期待する結果):
+        assert_eq!(rooms[0].age_secs, 0);
     }
 }
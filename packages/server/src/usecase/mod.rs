@@ -3,18 +3,35 @@
 //! ビジネスロジックを実装するレイヤー。
 //! UI 層から呼び出され、Domain 層を操作します。
 
+pub mod admin;
 pub mod connect_participant;
 pub mod disconnect_participant;
 pub mod error;
+pub mod get_participant;
 pub mod get_room_detail;
 pub mod get_room_state;
 pub mod get_rooms;
+pub mod purge_messages;
+pub mod react;
+pub mod reply_pong;
+pub mod send_file;
 pub mod send_message;
 
-pub use connect_participant::ConnectParticipantUseCase;
+pub use admin::AdminUseCase;
+pub use connect_participant::{
+    ConnectParticipantUseCase, Connection, DuplicatePolicy, InitialRoster, RosterEntry,
+    RosterUpdate,
+};
 pub use disconnect_participant::DisconnectParticipantUseCase;
-pub use error::{ConnectError, SendMessageError};
+pub use error::{
+    AdminError, ConnectError, ReactError, ReplyPongError, SendFileError, SendMessageError,
+};
+pub use get_participant::{GetParticipantError, GetParticipantUseCase, ParticipantDetail};
 pub use get_room_detail::{GetRoomDetailError, GetRoomDetailUseCase};
 pub use get_room_state::GetRoomStateUseCase;
-pub use get_rooms::GetRoomsUseCase;
-pub use send_message::SendMessageUseCase;
+pub use get_rooms::{GetRoomsUseCase, RoomSummary};
+pub use purge_messages::{PurgeMessagesError, PurgeMessagesUseCase};
+pub use react::{ReactUseCase, ReactionOutcome};
+pub use reply_pong::ReplyPongUseCase;
+pub use send_file::SendFileUseCase;
+pub use send_message::{SendMessageOutcome, SendMessageUseCase};
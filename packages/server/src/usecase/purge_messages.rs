@@ -0,0 +1,233 @@
+//! UseCase: メッセージ履歴の削除処理
+//!
+//! ## テスト実装の作業記録
+//!
+//! ### 何をテストしているか
+//! - PurgeMessagesUseCase::execute() メソッド
+//! - Room のメッセージ履歴の全削除
+//! - PurgeMessagesUseCase::remove_expired() メソッド（有効期限付きメッセージの削除）
+//!
+//! ### なぜこのテストが必要か
+//! - モデレーターが Room をクリアした後、履歴の取得で古いメッセージが返らないことを保証
+//! - 有効期限を過ぎたメッセージが履歴に残らないことを保証
+//!
+//! ### どのような状況を想定しているか
+//! - 正常系：履歴を削除すると削除件数が返り、以降の履歴の取得が空になる
+//! - 正常系：有効期限を過ぎたメッセージだけが削除され、その ID が返る
+//! - 異常系：存在しないルームの指定
+//!
+//! ## 備考
+//!
+//! 管理者かどうかの判定（トークンの照合）は UI 層がリクエストごとに行います。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    BroadcastReport, MessageId, MessagePushError, MessagePusher, RepositoryError, RoomId,
+    RoomRepository, Timestamp,
+};
+
+/// メッセージ履歴削除のユースケース
+pub struct PurgeMessagesUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+/// メッセージ履歴削除エラー
+#[derive(Debug, PartialEq)]
+pub enum PurgeMessagesError {
+    /// ルームが見つからない
+    RoomNotFound,
+    /// Repository エラー
+    RepositoryError,
+}
+
+impl PurgeMessagesUseCase {
+    /// 新しい PurgeMessagesUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// ルームのメッセージ履歴を全て削除
+    ///
+    /// # Arguments
+    ///
+    /// * `room_id` - 履歴を削除するルームの ID
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - 削除したメッセージ数
+    /// * `Err(PurgeMessagesError)` - 削除失敗
+    pub async fn execute(&self, room_id: String) -> Result<usize, PurgeMessagesError> {
+        let room_id = RoomId::new(room_id).map_err(|_| PurgeMessagesError::RoomNotFound)?;
+
+        self.repository
+            .purge_messages(&room_id)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::RoomNotFound => PurgeMessagesError::RoomNotFound,
+                _ => PurgeMessagesError::RepositoryError,
+            })
+    }
+
+    /// 履歴が削除されたことを全ての参加者にブロードキャスト
+    ///
+    /// クライアントは通知を受け取ると、手元に表示している履歴を消去できます。
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - ブロードキャストする JSON メッセージ（DTO 層で生成されたもの）
+    pub async fn notify_cleared(&self, message: &str) -> Result<BroadcastReport, MessagePushError> {
+        let targets = self.repository.get_all_connected_client_ids().await;
+        self.message_pusher.broadcast(targets, message).await
+    }
+
+    /// 有効期限を過ぎたメッセージを履歴から削除
+    ///
+    /// 定期的に呼び出し、削除したメッセージごとに [`Self::notify_deleted`] で通知します。
+    ///
+    /// # Returns
+    ///
+    /// 削除したメッセージの ID（古い順）
+    pub async fn remove_expired(&self) -> Vec<MessageId> {
+        self.repository.remove_expired_messages().await
+    }
+
+    /// 履歴にあるメッセージのうち、最も早い有効期限を取得
+    ///
+    /// 期限切れのメッセージを削除するタスクは、この時刻まで [`Self::remove_expired`] を
+    /// 呼び出さずに待てます（有効期限付きのメッセージがない場合は `None`）。
+    pub async fn next_expiry(&self) -> Option<Timestamp> {
+        self.repository.next_message_expiry().await
+    }
+
+    /// メッセージが削除されたことを全ての参加者にブロードキャスト
+    ///
+    /// クライアントは通知を受け取ると、そのメッセージの表示をやめられます。
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - ブロードキャストする JSON メッセージ（DTO 層で生成されたもの）
+    pub async fn notify_deleted(&self, message: &str) -> Result<BroadcastReport, MessagePushError> {
+        let targets = self.repository.get_all_connected_client_ids().await;
+        self.message_pusher.broadcast(targets, message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            ChatMessage, ClientId, MessageContent, Room, RoomIdFactory, RoomRepository, Timestamp,
+        },
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+    use engawa_shared::time::FixedClock;
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    async fn create_test_usecase() -> (PurgeMessagesUseCase, Arc<InMemoryRoomRepository>, RoomId) {
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for (i, content) in ["Hello!", "Anyone here?"].into_iter().enumerate() {
+            room.add_message(ChatMessage::new(
+                alice.clone(),
+                MessageContent::new(content.to_string()).unwrap(),
+                Timestamp::new(1000 * (i as i64 + 1)),
+            ))
+            .unwrap();
+        }
+        let room_id = room.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(Arc::new(Mutex::new(room))));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+
+        (
+            PurgeMessagesUseCase::new(repository.clone(), message_pusher),
+            repository,
+            room_id,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_purge_empties_the_history() {
+        // テスト項目: 履歴を削除すると削除件数が返り、以降の履歴の取得は空になる
+        // given (前提条件): メッセージが 2 件ある Room
+        let (usecase, repository, room_id) = create_test_usecase().await;
+
+        // when (操作):
+        let purged = usecase.execute(room_id.as_str().to_string()).await;
+
+        // then (期待する結果):
+        assert_eq!(purged, Ok(2));
+        let history = repository.get_messages(&room_id, None, 100).await.unwrap();
+        assert!(history.is_empty());
+        assert_eq!(usecase.execute(room_id.as_str().to_string()).await, Ok(0));
+    }
+
+    #[tokio::test]
+    async fn test_purge_unknown_room_is_not_found() {
+        // テスト項目: 存在しないルームを指定すると RoomNotFound になり、履歴は残る
+        // given (前提条件):
+        let (usecase, repository, room_id) = create_test_usecase().await;
+        let other_room_id = RoomIdFactory::generate().unwrap();
+
+        // when (操作):
+        let result = usecase.execute(other_room_id.as_str().to_string()).await;
+
+        // then (期待する結果):
+        assert_eq!(result, Err(PurgeMessagesError::RoomNotFound));
+        let history = repository.get_messages(&room_id, None, 100).await.unwrap();
+        assert_eq!(history.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_remove_expired_returns_the_expired_message_ids() {
+        // テスト項目: 有効期限を過ぎたメッセージだけが削除され、その ID が返る
+        // given (前提条件): 時計が 10 秒の時点で、5 秒に期限切れのメッセージと期限前のメッセージがある
+        let mut room = Room::new(RoomIdFactory::generate().unwrap(), Timestamp::new(0));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        for expires_at in [5_000, 20_000] {
+            room.add_message(
+                ChatMessage::new(
+                    alice.clone(),
+                    MessageContent::new("Bye soon".to_string()).unwrap(),
+                    Timestamp::new(1_000),
+                )
+                .with_expires_at(Some(Timestamp::new(expires_at))),
+            )
+            .unwrap();
+        }
+        let expired_id = room.messages[0].id.clone();
+        let room_id = room.id.clone();
+        let repository = Arc::new(
+            InMemoryRoomRepository::new(Arc::new(Mutex::new(room)))
+                .with_clock(Arc::new(FixedClock::new(10_000))),
+        );
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let usecase = PurgeMessagesUseCase::new(repository.clone(), message_pusher);
+
+        // when (操作):
+        let removed = usecase.remove_expired().await;
+
+        // then (期待する結果):
+        assert_eq!(removed, vec![expired_id]);
+        let history = repository.get_messages(&room_id, None, 100).await.unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].expires_at, Some(Timestamp::new(20_000)));
+    }
+}
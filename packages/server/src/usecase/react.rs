@@ -0,0 +1,276 @@
+//! UseCase: メッセージへのリアクション処理
+//!
+//! ## テスト実装の作業記録
+//!
+//! ### 何をテストしているか
+//! - ReactUseCase::execute() メソッド
+//! - リアクションの追加・取り消しと、操作後の数の集計
+//!
+//! ### なぜこのテストが必要か
+//! - ビジネスロジックの検証：参加者ごとに 1 回だけ数えられ、取り消すと数が減る
+//! - 履歴にないメッセージへのリアクションがエラーになることを保証
+//!
+//! ### どのような状況を想定しているか
+//! - 正常系：リアクションの追加と取り消し
+//! - 異常系：履歴にない ID のメッセージへのリアクション
+//!
+//! ## 備考
+//!
+//! リアクションは履歴中のメッセージに記録するため、履歴に保存されなかったメッセージには
+//! リアクションできません。更新後の数は、リアクションした本人を含む全参加者に配信します。
+
+use std::sync::Arc;
+
+use crate::domain::{
+    BroadcastReport, ClientId, Emoji, MessagePusher, ReactionAction, RepositoryError,
+    RoomRepository,
+};
+
+use super::error::ReactError;
+
+/// リアクションの記録結果
+#[derive(Debug, PartialEq, Eq)]
+pub struct ReactionOutcome {
+    /// 操作後にその絵文字でリアクションしている参加者の数
+    pub count: usize,
+    /// 更新を配信する対象のクライアント ID リスト（リアクションした本人を含む）
+    pub broadcast_targets: Vec<ClientId>,
+}
+
+/// メッセージへのリアクションのユースケース
+pub struct ReactUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl ReactUseCase {
+    /// 新しい ReactUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+        }
+    }
+
+    /// リアクションの追加・取り消しを実行
+    ///
+    /// # Arguments
+    ///
+    /// * `by` - リアクションしたクライアントの ID（Domain Model）
+    /// * `message_id` - リアクション先のメッセージ ID
+    /// * `emoji` - リアクションの絵文字（Domain Model）
+    /// * `action` - 追加か取り消しか（Domain Model）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ReactionOutcome)` - 操作後の数と配信対象
+    /// * `Err(ReactError)` - 記録失敗
+    pub async fn execute(
+        &self,
+        by: ClientId,
+        message_id: &str,
+        emoji: Emoji,
+        action: ReactionAction,
+    ) -> Result<ReactionOutcome, ReactError> {
+        // 1. Repository 経由でメッセージにリアクションを記録
+        let count = self
+            .repository
+            .react_to_message(message_id, emoji, by, action)
+            .await
+            .map_err(|e| match e {
+                RepositoryError::MessageNotFound(id) => ReactError::MessageNotFound(id),
+                e => ReactError::RepositoryError(e.to_string()),
+            })?;
+
+        // 2. 配信対象を取得（リアクションした本人を含む全てのクライアント）
+        let broadcast_targets = self.repository.get_all_connected_client_ids().await;
+
+        Ok(ReactionOutcome {
+            count,
+            broadcast_targets,
+        })
+    }
+
+    /// 更新後のリアクション数をブロードキャスト
+    ///
+    /// # Arguments
+    ///
+    /// * `target_ids` - ブロードキャスト対象のクライアント ID リスト（Domain Model）
+    /// * `message` - ブロードキャストする JSON メッセージ（DTO 層で生成されたもの）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(BroadcastReport)` - 送信先ごとの配信結果
+    /// * `Err(ReactError)` - ブロードキャスト失敗
+    pub async fn broadcast_reaction(
+        &self,
+        target_ids: Vec<ClientId>,
+        message: &str,
+    ) -> Result<BroadcastReport, ReactError> {
+        self.message_pusher
+            .broadcast(target_ids, message)
+            .await
+            .map_err(ReactError::BroadcastFailed)
+    }
+
+    /// リアクションしたクライアントにのみ通知（エラーフレームの返信など）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 通知成功
+    /// * `Err(String)` - 通知失敗
+    pub async fn notify_sender(&self, client_id: &ClientId, message: &str) -> Result<(), String> {
+        self.message_pusher
+            .push_to(client_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{MessageContent, MessageIdFactory, Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+    use std::collections::HashMap;
+    use tokio::sync::Mutex;
+
+    /// alice と bob が参加し、alice のメッセージが 1 件ある状態を作成
+    async fn create_usecase_with_message() -> (ReactUseCase, String) {
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(0),
+        )));
+        let repository = Arc::new(InMemoryRoomRepository::new(room));
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(1000))
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob, Timestamp::new(1000))
+            .await
+            .unwrap();
+        let message_id = MessageIdFactory::generate();
+        repository
+            .add_message_and_snapshot_targets(
+                message_id.clone(),
+                alice,
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                Timestamp::new(2000),
+                None,
+            )
+            .await
+            .unwrap();
+
+        (
+            ReactUseCase::new(repository, message_pusher),
+            message_id.as_str().to_string(),
+        )
+    }
+
+    fn client(id: &str) -> ClientId {
+        ClientId::new(id.to_string()).unwrap()
+    }
+
+    fn thumbs_up() -> Emoji {
+        Emoji::new("👍".to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_add_reaction_counts_reactors_and_targets_everyone() {
+        // テスト項目: リアクションを追加すると数が増え、本人を含む全参加者が配信対象になる
+        // given (前提条件):
+        let (usecase, message_id) = create_usecase_with_message().await;
+
+        // when (操作):
+        let first = usecase
+            .execute(client("bob"), &message_id, thumbs_up(), ReactionAction::Add)
+            .await
+            .unwrap();
+        let second = usecase
+            .execute(
+                client("alice"),
+                &message_id,
+                thumbs_up(),
+                ReactionAction::Add,
+            )
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(first.count, 1);
+        assert_eq!(second.count, 2);
+        assert_eq!(second.broadcast_targets.len(), 2);
+        assert!(second.broadcast_targets.contains(&client("alice")));
+        assert!(second.broadcast_targets.contains(&client("bob")));
+    }
+
+    #[tokio::test]
+    async fn test_remove_reaction_decrements_count() {
+        // テスト項目: 追加したリアクションを取り消すと数が減る
+        // given (前提条件): bob と alice が 👍 でリアクションしている
+        let (usecase, message_id) = create_usecase_with_message().await;
+        for reactor in ["bob", "alice"] {
+            usecase
+                .execute(
+                    client(reactor),
+                    &message_id,
+                    thumbs_up(),
+                    ReactionAction::Add,
+                )
+                .await
+                .unwrap();
+        }
+
+        // when (操作):
+        let outcome = usecase
+            .execute(
+                client("bob"),
+                &message_id,
+                thumbs_up(),
+                ReactionAction::Remove,
+            )
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        assert_eq!(outcome.count, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reaction_to_unknown_message_is_rejected() {
+        // テスト項目: 履歴にない ID のメッセージへのリアクションは MessageNotFound になる
+        // given (前提条件):
+        let (usecase, _message_id) = create_usecase_with_message().await;
+
+        // when (操作):
+        let result = usecase
+            .execute(
+                client("bob"),
+                "no-such-id",
+                thumbs_up(),
+                ReactionAction::Add,
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(ReactError::MessageNotFound("no-such-id".to_string()))
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! UseCase: アプリケーションレベルの Pong 返信処理
+//!
+//! ## テスト実装の作業記録
+//!
+//! ### 何をテストしているか
+//! - ReplyPongUseCase::execute() メソッド
+//! - app-ping を送信したクライアントへの app-pong 返信
+//!
+//! ### なぜこのテストが必要か
+//! - RTT 計測のため、nonce がそのまま送信者に返されることを保証
+//! - 送信者以外のクライアントに pong が届かないことを確認
+//!
+//! ### どのような状況を想定しているか
+//! - 正常系：送信者にのみ pong が返される
+//! - 異常系：送信者が MessagePusher に登録されていない
+
+use std::sync::Arc;
+
+use crate::domain::{ClientId, MessagePusher};
+
+use super::error::ReplyPongError;
+
+/// アプリケーションレベルの Pong 返信のユースケース
+pub struct ReplyPongUseCase {
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+}
+
+impl ReplyPongUseCase {
+    /// 新しい ReplyPongUseCase を作成
+    pub fn new(message_pusher: Arc<dyn MessagePusher>) -> Self {
+        Self { message_pusher }
+    }
+
+    /// app-ping を送信したクライアントに app-pong を返信
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - app-ping を送信したクライアントの ID（Domain Model）
+    /// * `json_message` - 返信する JSON メッセージ（DTO 層で生成されたもの）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 返信成功
+    /// * `Err(ReplyPongError)` - 返信失敗
+    pub async fn execute(
+        &self,
+        client_id: &ClientId,
+        json_message: &str,
+    ) -> Result<(), ReplyPongError> {
+        self.message_pusher
+            .push_to(client_id, json_message)
+            .await
+            .map_err(|e| ReplyPongError::PushFailed(e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::{
+        dto::websocket::{AppPongMessage, MessageType},
+        message_pusher::WebSocketMessagePusher,
+    };
+    use std::{collections::HashMap, sync::Arc};
+    use tokio::sync::{Mutex, mpsc};
+
+    #[tokio::test]
+    async fn test_reply_pong_to_sender_only() {
+        // テスト項目: pong は nonce を保持したまま送信者にのみ返される
+        // given (前提条件):
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let usecase = ReplyPongUseCase::new(message_pusher.clone());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = mpsc::channel(16);
+        let (tx_bob, mut rx_bob) = mpsc::channel(16);
+        message_pusher
+            .register_client(alice.clone(), tx_alice)
+            .await;
+        message_pusher.register_client(bob.clone(), tx_bob).await;
+
+        // when (操作):
+        let pong = AppPongMessage {
+            r#type: MessageType::AppPong,
+            nonce: 42,
+        };
+        let pong_json = serde_json::to_string(&pong).unwrap();
+        let result = usecase.execute(&alice, &pong_json).await;
+
+        // then (期待する結果):
+        assert!(result.is_ok());
+        let received = rx_alice.try_recv().unwrap();
+        let received: AppPongMessage = serde_json::from_str(&received).unwrap();
+        assert!(matches!(received.r#type, MessageType::AppPong));
+        assert_eq!(received.nonce, 42);
+        assert!(rx_bob.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reply_pong_to_unregistered_client_fails() {
+        // テスト項目: 登録されていないクライアントへの返信はエラーになる
+        // given (前提条件):
+        let message_pusher = Arc::new(WebSocketMessagePusher::new(Arc::new(Mutex::new(
+            HashMap::new(),
+        ))));
+        let usecase = ReplyPongUseCase::new(message_pusher);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+
+        // when (操作):
+        let result = usecase.execute(&alice, "{}").await;
+
+        // then (期待する結果):
+        assert!(matches!(result, Err(ReplyPongError::PushFailed(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! UseCase: ファイル送信処理
+//!
+//! ## テスト実装の作業記録
+//!
+//! ### 何をテストしているか
+//! - SendFileUseCase::execute() メソッド
+//! - ファイル送信処理（サイズ上限の検証、ブロードキャスト対象選定）
+//!
+//! ### なぜこのテストが必要か
+//! - ビジネスロジックの検証：上限以下のファイルは送信者以外にブロードキャストされる
+//! - 上限を超えるファイルが誰にも配信されないことを保証
+//!
+//! ### どのような状況を想定しているか
+//! - 正常系：上限以下のファイルの送信とブロードキャスト
+//! - 異常系：上限を超えるファイルの送信（送信者にのみエラーを通知）
+//!
+//! ## 備考
+//!
+//! ファイルはメッセージ履歴には保存せず、接続中の参加者へのブロードキャストのみを行います。
+
+use std::sync::Arc;
+
+use crate::domain::{ClientId, FileAttachment, MessagePusher, RoomRepository};
+
+use super::error::SendFileError;
+
+/// Default maximum file size in bytes (1 MiB)
+pub const DEFAULT_MAX_FILE_SIZE: usize = 1024 * 1024;
+
+/// ファイル送信のユースケース
+pub struct SendFileUseCase {
+    /// Repository（データアクセス層の抽象化）
+    repository: Arc<dyn RoomRepository>,
+    /// MessagePusher（メッセージ通知の抽象化）
+    message_pusher: Arc<dyn MessagePusher>,
+    /// 送信可能なファイルサイズの上限（バイト）
+    max_file_size: usize,
+}
+
+impl SendFileUseCase {
+    /// 新しい SendFileUseCase を作成
+    pub fn new(
+        repository: Arc<dyn RoomRepository>,
+        message_pusher: Arc<dyn MessagePusher>,
+        max_file_size: usize,
+    ) -> Self {
+        Self {
+            repository,
+            message_pusher,
+            max_file_size,
+        }
+    }
+
+    /// ファイル送信を実行
+    ///
+    /// # Arguments
+    ///
+    /// * `from_client_id` - ファイル送信者のクライアント ID（Domain Model）
+    /// * `attachment` - ファイル添付のメタデータ（Domain Model）
+    /// * `json_message` - 送信する JSON メッセージ（DTO 層で生成されたもの）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（Domain Model）
+    /// * `Err(SendFileError)` - 送信失敗
+    pub async fn execute(
+        &self,
+        from_client_id: ClientId,
+        attachment: FileAttachment,
+        json_message: String,
+    ) -> Result<Vec<ClientId>, SendFileError> {
+        // 1. ファイルサイズの上限を検証
+        if attachment.size() > self.max_file_size {
+            return Err(SendFileError::FileTooLarge {
+                max: self.max_file_size,
+                actual: attachment.size(),
+            });
+        }
+
+        // 2. ブロードキャスト対象を取得（送信者以外の全てのクライアント）
+        let broadcast_targets: Vec<ClientId> = self
+            .repository
+            .get_all_connected_client_ids()
+            .await
+            .into_iter()
+            .filter(|id| id != &from_client_id)
+            .collect();
+
+        // 3. MessagePusher を使ってブロードキャスト
+        self.message_pusher
+            .broadcast(broadcast_targets.clone(), &json_message)
+            .await
+            .map_err(|e| SendFileError::BroadcastFailed(e.to_string()))?;
+
+        Ok(broadcast_targets)
+    }
+
+    /// 送信者にのみメッセージを通知（エラーフレームの返信など）
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - 通知先のクライアント ID（Domain Model）
+    /// * `message` - 通知するメッセージ（JSON）
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 通知成功
+    /// * `Err(String)` - 通知失敗
+    pub async fn notify_sender(&self, client_id: &ClientId, message: &str) -> Result<(), String> {
+        self.message_pusher
+            .push_to(client_id, message)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{Room, RoomIdFactory, Timestamp},
+        infrastructure::{
+            message_pusher::WebSocketMessagePusher, repository::InMemoryRoomRepository,
+        },
+    };
+    use engawa_shared::time::get_jst_timestamp;
+    use std::{collections::HashMap, sync::Arc};
+    use tokio::sync::{Mutex, mpsc};
+
+    fn create_test_repository() -> Arc<InMemoryRoomRepository> {
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        Arc::new(InMemoryRoomRepository::new(room))
+    }
+
+    fn create_test_message_pusher() -> Arc<WebSocketMessagePusher> {
+        let clients = Arc::new(Mutex::new(HashMap::new()));
+        Arc::new(WebSocketMessagePusher::new(clients))
+    }
+
+    #[tokio::test]
+    async fn test_send_file_under_limit() {
+        // テスト項目: 上限以下のファイルは送信者以外にブロードキャストされる
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = SendFileUseCase::new(repository.clone(), message_pusher.clone(), 1024);
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_alice, mut rx_alice) = mpsc::channel(16);
+        let (tx_bob, mut rx_bob) = mpsc::channel(16);
+        for (id, tx) in [(&alice, tx_alice), (&bob, tx_bob)] {
+            repository
+                .add_participant(id.clone(), timestamp)
+                .await
+                .unwrap();
+            message_pusher.register_client(id.clone(), tx).await;
+        }
+
+        // when (操作):
+        let attachment =
+            FileAttachment::new("file.png".to_string(), "image/png".to_string(), 1024).unwrap();
+        let result = usecase
+            .execute(alice.clone(), attachment, "file".to_string())
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(result, Ok(vec![bob]));
+        assert_eq!(rx_bob.try_recv().unwrap(), "file");
+        assert!(rx_alice.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_send_file_over_limit() {
+        // テスト項目: 上限を超えるファイルはエラーになり、誰にも配信されない
+        // given (前提条件):
+        let repository = create_test_repository();
+        let message_pusher = create_test_message_pusher();
+        let usecase = SendFileUseCase::new(repository.clone(), message_pusher.clone(), 1024);
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        let (tx_bob, mut rx_bob) = mpsc::channel(16);
+        repository
+            .add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob.clone(), timestamp)
+            .await
+            .unwrap();
+        message_pusher.register_client(bob.clone(), tx_bob).await;
+
+        // when (操作):
+        let attachment =
+            FileAttachment::new("file.png".to_string(), "image/png".to_string(), 1025).unwrap();
+        let result = usecase
+            .execute(alice.clone(), attachment, "file".to_string())
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(
+            result,
+            Err(SendFileError::FileTooLarge {
+                max: 1024,
+                actual: 1025
+            })
+        );
+        assert!(rx_bob.try_recv().is_err());
+    }
+}
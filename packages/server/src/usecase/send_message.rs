@@ -14,20 +14,222 @@
 //! ### どのような状況を想定しているか
 //! - 正常系：メッセージ送信とブロードキャスト
 //! - 異常系：メッセージ容量超過
+//! - 異常系：スローモードの間隔内の送信（間隔が過ぎた後は受け付ける）
+//! - 異常系：履歴への追加の失敗（配信しない）、ブロードキャストの一時的な失敗（再試行して配信する）、
+//!   ブロードキャストの継続的な失敗（履歴に残し、デッドレターに記録して、送信者にエラーを返す）
+//! - 異常系：一部の受信者にだけ届かない（届かなかった受信者にだけ再送し、再試行の間は他の送信者を待たせない）
 //! - エッジケース：送信者のみが接続している場合（ブロードキャスト対象なし）
+//! - 並行性：join と送信が交錯した場合でも配信対象が順序の契約どおりに決まる
+//! - 並行性：送信者自身の切断と送信が交錯しても失敗せず、最終状態が一貫する
+//! - 並行性：ブロードキャストが滞っている間も他の送信者の履歴への追加は待たされず、
+//!   配信は履歴の順序で行われる
+//!
+//! ## 順序の契約
+//!
+//! - メッセージは「履歴に追加された時点で Room にいた参加者（送信者を除く）」にのみ配信される
+//!   （履歴への追加と配信対象の取得は Repository の同一ロック区間で行う）
+//! - 履歴への追加と配信対象の取得は送信ロックで直列化し、同じロックの中で配信の順番を採番する。
+//!   最初のブロードキャストは送信ロックを解放してから採番した順に行うため、
+//!   各参加者が受け取るメッセージの順序は履歴の順序と一致する
+//!   （再試行で届いたメッセージだけは、後続のメッセージより後に届くことがある）
+//! - 送信ロックはネットワークへの送信を待つ間は保持しない。受信が滞ったクライアントがいても、
+//!   他の送信者の履歴への追加や拒否（スローモードなど）は待たされない
+//! - 送信者の切断と交錯したメッセージも拒否しない。履歴に追加され、送信者を除く
+//!   その時点の参加者に配信される（送信者が既に Room にいなくてもエラーにならない）
+//!
+//! ## 配信の契約（履歴優先）
+//!
+//! - 履歴への追加に失敗したメッセージは配信せず、エラーを返す（送信者は再送できる）
+//! - 履歴に追加されたメッセージは送信済みとして扱い、配信の一時的な失敗ではエラーを返さない
+//!   （送信者が再送すると、履歴に重複して残るため）
+//! - 配信できなかった受信者（[`BroadcastReport::failed`]、ブロードキャスト全体が失敗した場合は
+//!   配信対象の全員）にだけ、送信ロックを解放してから [`DELIVERY_RETRY_LIMIT`] 回まで再試行する
+//!   （配信できた受信者には再送しない。再試行の待ち時間で他の送信者を待たせない）
+//! - 再試行しても配信できなかった場合は、届かなかった受信者ごとにデッドレターとして記録する
+//!   （[`SendMessageUseCase::with_dead_letter_sink`]）。受信者は再接続後に履歴から取得できる
+//! - その場合は [`SendMessageError::BroadcastFailed`] を返し、送信者に配信できなかったことを伝える。
+//!   メッセージは履歴に残っており、idempotency key 付きの送信ではキーも記録するため、
+//!   同じキーの再送は重複として扱われる
+//! - MessagePusher も送信ごとの失敗をデッドレターに記録する。ここで記録するのは、
+//!   再試行しても届かなかった配信の最終的な結果である
+//!
+//! ## 冪等性
+//!
+//! 送信者が idempotency key を付けた場合、同じ送信者・同じキーのメッセージは 1 度だけ
+//! 履歴に追加・配信されます。再接続後の再送で重複配信しないために使用します。
+//! 記録するキーは直近 [`IDEMPOTENCY_KEY_CAPACITY`] 件までです。
+
+use std::{
+    collections::{BTreeSet, HashSet, VecDeque},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
 
-use std::sync::Arc;
+use tokio::sync::{Mutex, watch};
 
-use crate::domain::{ClientId, MessageContent, MessagePusher, RoomRepository, Timestamp};
+use crate::domain::{
+    BroadcastReport, ClientId, DeadLetter, DeadLetterSink, MAX_CHAT_CONTENT_LEN, MessageContent,
+    MessageId, MessagePushError, MessagePusher, MessageTransform, RepositoryError, RoomError,
+    RoomRepository, Timestamp, TransformedContent, ValueObjectError,
+};
 
 use super::error::SendMessageError;
 
+/// 重複判定のために記録する idempotency key の最大件数
+pub const IDEMPOTENCY_KEY_CAPACITY: usize = 1024;
+
+/// ブロードキャストがトランスポート層で失敗したときの再試行回数
+pub const DELIVERY_RETRY_LIMIT: u32 = 2;
+
+/// ブロードキャストの再試行までの待ち時間
+const DELIVERY_RETRY_DELAY: Duration = Duration::from_millis(50);
+
+/// idempotency key 付きメッセージ送信の結果
+#[derive(Debug, PartialEq, Eq)]
+pub enum SendMessageOutcome {
+    /// 履歴に追加し、ブロードキャストした（配信対象のクライアント ID リスト）
+    Sent(Vec<ClientId>),
+    /// 同じキーのメッセージが既に送信済みのため、何もしなかった
+    Duplicate,
+}
+
+/// 配信できなかった受信者と、最後に起きたエラー
+///
+/// エラーが `None` の場合は、ブロードキャスト自体は成功し、一部の受信者にだけ届かなかった。
+struct FailedDelivery {
+    targets: Vec<ClientId>,
+    error: Option<MessagePushError>,
+}
+
+impl FailedDelivery {
+    fn from_result(
+        targets: &[ClientId],
+        result: Result<BroadcastReport, MessagePushError>,
+    ) -> Self {
+        match result {
+            Ok(report) => Self {
+                targets: report.failed,
+                error: None,
+            },
+            Err(e) => Self {
+                targets: targets.to_vec(),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+/// 最初のブロードキャストの順番
+///
+/// 送信ロックの中で番号を採番し、送信ロックの外で番号順にブロードキャストする。
+/// 順番が来る前に取り消された（送信が中断された）番号は飛ばす。
+struct FanoutSequence {
+    /// 次に採番する番号
+    next: AtomicU64,
+    /// ブロードキャストしてよい番号
+    turn: watch::Sender<u64>,
+    /// 順番が来る前に取り消された番号
+    abandoned: std::sync::Mutex<BTreeSet<u64>>,
+}
+
+impl FanoutSequence {
+    fn new() -> Self {
+        Self {
+            next: AtomicU64::new(0),
+            turn: watch::Sender::new(0),
+            abandoned: std::sync::Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// 番号を採番する（呼び出し側で送信ロックを保持すること）
+    fn ticket(&self) -> FanoutTicket<'_> {
+        FanoutTicket {
+            sequence: self,
+            number: self.next.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// 番号の処理を終え、次の番号に順番を渡す
+    fn finish(&self, number: u64) {
+        self.turn.send_modify(|turn| {
+            let mut abandoned = self.abandoned.lock().unwrap();
+            if *turn == number {
+                *turn += 1;
+                while abandoned.remove(turn) {
+                    *turn += 1;
+                }
+            } else {
+                abandoned.insert(number);
+            }
+        });
+    }
+}
+
+/// 採番した配信の順番（破棄すると次の番号に順番を渡す）
+struct FanoutTicket<'a> {
+    sequence: &'a FanoutSequence,
+    number: u64,
+}
+
+impl FanoutTicket<'_> {
+    /// 順番が来るまで待つ
+    async fn wait(&self) {
+        let mut turn = self.sequence.turn.subscribe();
+        // Sender は FanoutSequence が保持しているため、待機がエラーになることはない
+        let _ = turn.wait_for(|turn| *turn == self.number).await;
+    }
+}
+
+impl Drop for FanoutTicket<'_> {
+    fn drop(&mut self) {
+        self.sequence.finish(self.number);
+    }
+}
+
+/// 送信済みの (送信者, idempotency key) を直近の一定件数だけ記録する
+#[derive(Default)]
+struct DeliveredKeys {
+    order: VecDeque<(ClientId, String)>,
+    keys: HashSet<(ClientId, String)>,
+}
+
+impl DeliveredKeys {
+    fn contains(&self, entry: &(ClientId, String)) -> bool {
+        self.keys.contains(entry)
+    }
+
+    fn insert(&mut self, entry: (ClientId, String)) {
+        if self.order.len() >= IDEMPOTENCY_KEY_CAPACITY
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.keys.remove(&oldest);
+        }
+        self.keys.insert(entry.clone());
+        self.order.push_back(entry);
+    }
+}
+
 /// メッセージ送信のユースケース
 pub struct SendMessageUseCase {
     /// Repository（データアクセス層の抽象化）
     repository: Arc<dyn RoomRepository>,
     /// MessagePusher（メッセージ通知の抽象化）
     message_pusher: Arc<dyn MessagePusher>,
+    /// 送信ロック（履歴への追加と配信の順番の採番を直列化する）
+    ///
+    /// 送信済みの idempotency key もこのロックで保護する
+    send_lock: Mutex<DeliveredKeys>,
+    /// 最初のブロードキャストの順番
+    fanout: FanoutSequence,
+    /// 保存・ブロードキャスト前に適用する内容の正規化
+    transform: MessageTransform,
+    /// チャットメッセージの内容の最大長（バイト）
+    max_content_len: usize,
+    /// 再試行しても配信できなかったメッセージの記録先（None の場合はログのみ）
+    dead_letter_sink: Option<Arc<dyn DeadLetterSink>>,
 }
 
 impl SendMessageUseCase {
@@ -39,58 +241,294 @@ impl SendMessageUseCase {
         Self {
             repository,
             message_pusher,
+            send_lock: Mutex::new(DeliveredKeys::default()),
+            fanout: FanoutSequence::new(),
+            transform: MessageTransform::default(),
+            max_content_len: MAX_CHAT_CONTENT_LEN,
+            dead_letter_sink: None,
         }
     }
 
+    /// 再試行しても配信できなかったメッセージの記録先を設定
+    ///
+    /// 設定しない場合、配信できなかったメッセージは警告ログに残るだけです。
+    pub fn with_dead_letter_sink(mut self, sink: Arc<dyn DeadLetterSink>) -> Self {
+        self.dead_letter_sink = Some(sink);
+        self
+    }
+
+    /// チャットメッセージの内容の最大長（バイト）を設定
+    ///
+    /// デフォルトは [`MAX_CHAT_CONTENT_LEN`] です。
+    /// [`MAX_CONTENT_LEN_CEILING`](crate::domain::MAX_CONTENT_LEN_CEILING) を超える値は ceiling に制限されます。
+    pub fn with_max_content_len(mut self, max_content_len: usize) -> Self {
+        self.max_content_len = max_content_len;
+        self
+    }
+
+    /// 受信したチャットメッセージの内容を、チャットの長さの上限で検証
+    ///
+    /// # Errors
+    ///
+    /// * `ValueObjectError::MessageContentEmpty` - 内容が空
+    /// * `ValueObjectError::MessageContentTooLong` - 内容が上限を超えている
+    pub fn validate_content(&self, content: String) -> Result<MessageContent, ValueObjectError> {
+        MessageContent::with_max_len(content, self.max_content_len)
+    }
+
+    /// 保存・ブロードキャスト前に適用する内容の正規化を設定
+    ///
+    /// デフォルトではすべての変換が無効です。
+    pub fn with_transform(mut self, transform: MessageTransform) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    /// 検証済みのメッセージ内容に正規化を適用
+    ///
+    /// 戻り値の `content` を保存・ブロードキャストする正規の内容として
+    /// [`SendMessageUseCase::execute`] に渡してください。
+    pub fn transform_content(&self, content: MessageContent) -> TransformedContent {
+        self.transform.apply(content)
+    }
+
     /// メッセージ送信を実行
     ///
     /// # Arguments
     ///
     /// * `from_client_id` - メッセージ送信者のクライアント ID（Domain Model）
+    /// * `message_id` - メッセージ ID（`json_message` に含めたもの）
     /// * `content` - メッセージ内容（Domain Model）
+    /// * `expires_at` - 有効期限（期限を過ぎると履歴から削除される。`None` の場合は削除されない）
     /// * `json_message` - 送信する JSON メッセージ（DTO 層で生成されたもの）
     ///
     /// # Returns
     ///
-    /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（Domain Model）
-    /// * `Err(SendMessageError)` - 送信失敗
+    /// * `Ok(Vec<ClientId>)` - ブロードキャスト対象のクライアント ID リスト（Domain Model）。
+    ///   配信が一時的に失敗しても、再試行で配信できれば `Ok` を返す（モジュールの「配信の契約」を参照）
+    /// * `Err(SendMessageError::BroadcastFailed)` - 履歴には追加したが、再試行しても配信できなかった
+    /// * `Err(SendMessageError)` - その他の送信失敗（履歴には追加されていない）
     pub async fn execute(
         &self,
         from_client_id: ClientId,
+        message_id: MessageId,
         content: MessageContent,
+        expires_at: Option<Timestamp>,
         json_message: String,
     ) -> Result<Vec<ClientId>, SendMessageError> {
-        use engawa_shared::time::get_jst_timestamp;
+        let (broadcast_targets, ticket) = {
+            // 履歴への追加と配信の順番の採番を直列化し、配信順序を履歴の順序と一致させる
+            let _send_guard = self.send_lock.lock().await;
 
-        let timestamp = Timestamp::new(get_jst_timestamp());
+            let broadcast_targets = self
+                .append(from_client_id, message_id, content, expires_at)
+                .await?;
+            (broadcast_targets, self.fanout.ticket())
+        };
 
-        // 1. Repository 経由でメッセージを Room に追加
-        self.repository
-            .add_message(from_client_id.clone(), content, timestamp)
+        // ブロードキャストと再試行は送信ロックを解放してから行う
+        let failed = self
+            .broadcast_in_order(ticket, &broadcast_targets, &json_message)
+            .await;
+        self.retry_failed(failed, &json_message)
             .await
-            .map_err(|_| SendMessageError::MessageCapacityExceeded)?;
+            .map_err(SendMessageError::BroadcastFailed)?;
+        Ok(broadcast_targets)
+    }
+
+    /// idempotency key 付きでメッセージ送信を実行
+    ///
+    /// 同じ送信者から同じキーのメッセージが既に送信済みの場合は、
+    /// 履歴への追加もブロードキャストも行わずに `SendMessageOutcome::Duplicate` を返します。
+    ///
+    /// # Arguments
+    ///
+    /// * `from_client_id` - メッセージ送信者のクライアント ID（Domain Model）
+    /// * `message_id` - メッセージ ID（`json_message` に含めたもの）
+    /// * `content` - メッセージ内容（Domain Model）
+    /// * `expires_at` - 有効期限（期限を過ぎると履歴から削除される。`None` の場合は削除されない）
+    /// * `json_message` - 送信する JSON メッセージ（DTO 層で生成されたもの）
+    /// * `idempotency_key` - 送信者が付けた重複排除用のキー
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SendMessageOutcome)` - 送信済み、または重複として無視した
+    /// * `Err(SendMessageError::BroadcastFailed)` - 履歴には追加したが配信できなかった
+    ///   （キーは記録されるため、再送は重複として扱われる）
+    /// * `Err(SendMessageError)` - その他の送信失敗（キーは記録されないため再送できる）
+    pub async fn execute_idempotent(
+        &self,
+        from_client_id: ClientId,
+        message_id: MessageId,
+        content: MessageContent,
+        expires_at: Option<Timestamp>,
+        json_message: String,
+        idempotency_key: String,
+    ) -> Result<SendMessageOutcome, SendMessageError> {
+        let (broadcast_targets, ticket) = {
+            let mut delivered_keys = self.send_lock.lock().await;
+
+            let entry = (from_client_id.clone(), idempotency_key);
+            if delivered_keys.contains(&entry) {
+                return Ok(SendMessageOutcome::Duplicate);
+            }
 
-        // 2. ブロードキャスト対象を取得（送信者以外の全てのクライアント）
-        let broadcast_targets = self.get_broadcast_targets(&from_client_id).await;
+            let broadcast_targets = self
+                .append(from_client_id, message_id, content, expires_at)
+                .await?;
+            // 配信に失敗しても履歴には追加済みのため、再送で重複しないようキーを記録する
+            delivered_keys.insert(entry);
+            (broadcast_targets, self.fanout.ticket())
+        };
 
-        // 3. MessagePusher を使ってブロードキャスト
+        let failed = self
+            .broadcast_in_order(ticket, &broadcast_targets, &json_message)
+            .await;
+        self.retry_failed(failed, &json_message)
+            .await
+            .map_err(SendMessageError::BroadcastFailed)?;
+        Ok(SendMessageOutcome::Sent(broadcast_targets))
+    }
+
+    /// 送信者にのみ通知（ack など）を送信
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - 通知成功
+    /// * `Err(String)` - 通知失敗
+    pub async fn notify_sender(&self, client_id: &ClientId, message: &str) -> Result<(), String> {
         self.message_pusher
-            .broadcast(broadcast_targets.clone(), &json_message)
+            .push_to(client_id, message)
             .await
-            .map_err(|e| SendMessageError::BroadcastFailed(e.to_string()))?;
+            .map_err(|e| e.to_string())
+    }
+
+    /// メッセージを履歴に追加し、ブロードキャスト対象を返す（呼び出し側で送信ロックを保持すること）
+    async fn append(
+        &self,
+        from_client_id: ClientId,
+        message_id: MessageId,
+        content: MessageContent,
+        expires_at: Option<Timestamp>,
+    ) -> Result<Vec<ClientId>, SendMessageError> {
+        use engawa_shared::time::get_jst_timestamp;
+
+        let timestamp = Timestamp::new(get_jst_timestamp());
+
+        // 1. Repository 経由でメッセージを Room に追加し、同時にブロードキャスト対象を取得
+        //    （送信者以外の全てのクライアント）
+        let broadcast_targets = self
+            .repository
+            .add_message_and_snapshot_targets(
+                message_id,
+                from_client_id.clone(),
+                content,
+                timestamp,
+                expires_at,
+            )
+            .await
+            .map_err(|e| match e {
+                RepositoryError::Room(RoomError::MessageCapacityExceeded { .. }) => {
+                    SendMessageError::MessageCapacityExceeded
+                }
+                // ミュートされた参加者のメッセージは保存も配信もしない
+                RepositoryError::Room(RoomError::SenderMuted(_)) => SendMessageError::Muted,
+                // スローモードの間隔内のメッセージも保存も配信もしない
+                RepositoryError::Room(RoomError::SlowMode { retry_after }) => {
+                    SendMessageError::SlowMode { retry_after }
+                }
+                e => SendMessageError::RepositoryError(e.to_string()),
+            })?;
+
+        // 2. 送信者の最終アクティブ時刻を更新
+        //    （送信直後に退出した場合は参加者が存在しないため、更新をスキップする）
+        match self
+            .repository
+            .update_last_active(&from_client_id, timestamp)
+            .await
+        {
+            Ok(()) | Err(RepositoryError::ParticipantNotFound(_)) => {}
+            Err(e) => return Err(SendMessageError::RepositoryError(e.to_string())),
+        }
 
         Ok(broadcast_targets)
     }
 
-    /// ブロードキャスト対象のクライアント ID リストを取得
+    /// 採番した順番が来てからブロードキャストし、配信できなかった受信者を返す
+    async fn broadcast_in_order(
+        &self,
+        ticket: FanoutTicket<'_>,
+        targets: &[ClientId],
+        json_message: &str,
+    ) -> FailedDelivery {
+        ticket.wait().await;
+        self.broadcast(targets, json_message).await
+        // ticket を破棄して、次の番号に順番を渡す
+    }
+
+    /// ブロードキャストし、配信できなかった受信者を返す
+    async fn broadcast(&self, targets: &[ClientId], json_message: &str) -> FailedDelivery {
+        let result = self
+            .message_pusher
+            .broadcast(targets.to_vec(), json_message)
+            .await;
+        FailedDelivery::from_result(targets, result)
+    }
+
+    /// 配信できなかった受信者にだけ再送する
     ///
-    /// 送信者以外の全てのクライアント ID を返す（Domain Model）
-    async fn get_broadcast_targets(&self, exclude_client_id: &ClientId) -> Vec<ClientId> {
-        let all_client_ids = self.repository.get_all_connected_client_ids().await;
-        all_client_ids
-            .into_iter()
-            .filter(|id| id != exclude_client_id)
-            .collect()
+    /// 再試行しても配信できなかった場合は、届かなかった受信者ごとにデッドレターとして記録し、
+    /// 最後のエラーを返す。
+    async fn retry_failed(
+        &self,
+        mut failed: FailedDelivery,
+        json_message: &str,
+    ) -> Result<(), MessagePushError> {
+        use engawa_shared::time::get_jst_timestamp;
+
+        let mut attempt = 0;
+        while !failed.targets.is_empty() && attempt < DELIVERY_RETRY_LIMIT {
+            attempt += 1;
+            tracing::warn!(
+                "Delivery to {} client(s) failed, retrying ({}/{})",
+                failed.targets.len(),
+                attempt,
+                DELIVERY_RETRY_LIMIT
+            );
+            tokio::time::sleep(DELIVERY_RETRY_DELAY).await;
+            let retried = self.broadcast(&failed.targets, json_message).await;
+            failed = FailedDelivery {
+                targets: retried.targets,
+                error: retried.error.or(failed.error),
+            };
+        }
+        if failed.targets.is_empty() {
+            return Ok(());
+        }
+
+        let error = failed.error.unwrap_or_else(|| {
+            let client_ids: Vec<&str> = failed.targets.iter().map(ClientId::as_str).collect();
+            MessagePushError::PushFailed(format!("not delivered to {}", client_ids.join(", ")))
+        });
+        tracing::warn!(
+            "Giving up delivery to {} client(s) after {} retries: {}",
+            failed.targets.len(),
+            DELIVERY_RETRY_LIMIT,
+            error
+        );
+        if let Some(sink) = &self.dead_letter_sink {
+            let timestamp = Timestamp::new(get_jst_timestamp());
+            for client_id in failed.targets {
+                sink.record(DeadLetter {
+                    client_id,
+                    payload: json_message.to_string(),
+                    reason: error.to_string(),
+                    timestamp,
+                })
+                .await;
+            }
+        }
+        Err(error)
     }
 }
 
@@ -98,11 +536,18 @@ impl SendMessageUseCase {
 mod tests {
     use super::*;
     use crate::{
-        domain::{MessagePushError, MessagePusher, PusherChannel, Room, RoomIdFactory, Timestamp},
+        domain::{
+            BroadcastReport, MessageIdFactory, MessagePushError, MessagePusher, PusherChannel,
+            Room, RoomIdFactory, Timestamp,
+        },
         infrastructure::repository::InMemoryRoomRepository,
+        usecase::DisconnectParticipantUseCase,
     };
     use engawa_shared::time::get_jst_timestamp;
-    use std::sync::Arc;
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
     use tokio::sync::Mutex;
 
     // Mock MessagePusher for testing
@@ -130,9 +575,174 @@ mod tests {
             &self,
             _targets: Vec<ClientId>,
             _content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            Ok(BroadcastReport::default())
+        }
+    }
+
+    // Mock MessagePusher whose broadcast fails at the transport level a given number of times
+    struct FlakyBroadcastMessagePusher {
+        failures_left: AtomicU32,
+        broadcasts: AtomicU32,
+    }
+
+    impl FlakyBroadcastMessagePusher {
+        fn new(failures: u32) -> Self {
+            Self {
+                failures_left: AtomicU32::new(failures),
+                broadcasts: AtomicU32::new(0),
+            }
+        }
+
+        fn broadcasts(&self) -> u32 {
+            self.broadcasts.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for FlakyBroadcastMessagePusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
         ) -> Result<(), MessagePushError> {
             Ok(())
         }
+
+        async fn broadcast(
+            &self,
+            _targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            self.broadcasts.fetch_add(1, Ordering::SeqCst);
+            let failing = self
+                .failures_left
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            if failing {
+                Err(MessagePushError::PushFailed("connection reset".to_string()))
+            } else {
+                Ok(BroadcastReport::default())
+            }
+        }
+    }
+
+    // Mock MessagePusher whose broadcast succeeds but never reaches one client
+    struct PartialBroadcastMessagePusher {
+        unreachable: ClientId,
+        broadcasts: std::sync::Mutex<Vec<Vec<ClientId>>>,
+    }
+
+    impl PartialBroadcastMessagePusher {
+        fn new(unreachable: ClientId) -> Self {
+            Self {
+                unreachable,
+                broadcasts: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+
+        fn broadcasts(&self) -> Vec<Vec<ClientId>> {
+            self.broadcasts.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for PartialBroadcastMessagePusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            targets: Vec<ClientId>,
+            _content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            self.broadcasts.lock().unwrap().push(targets.clone());
+            let (failed, delivered) = targets
+                .into_iter()
+                .partition(|client_id| *client_id == self.unreachable);
+            Ok(BroadcastReport { delivered, failed })
+        }
+    }
+
+    // Mock MessagePusher whose first broadcast blocks until released
+    struct GatedBroadcastMessagePusher {
+        gated: std::sync::atomic::AtomicBool,
+        gate: tokio::sync::Notify,
+        completed: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl GatedBroadcastMessagePusher {
+        fn new() -> Self {
+            Self {
+                gated: std::sync::atomic::AtomicBool::new(true),
+                gate: tokio::sync::Notify::new(),
+                completed: std::sync::Mutex::new(Vec::new()),
+            }
+        }
+
+        fn release(&self) {
+            self.gate.notify_one();
+        }
+
+        fn completed(&self) -> Vec<String> {
+            self.completed.lock().unwrap().clone()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl MessagePusher for GatedBroadcastMessagePusher {
+        async fn register_client(&self, _client_id: ClientId, _sender: PusherChannel) {}
+
+        async fn unregister_client(&self, _client_id: &ClientId) {}
+
+        async fn push_to(
+            &self,
+            _client_id: &ClientId,
+            _content: &str,
+        ) -> Result<(), MessagePushError> {
+            Ok(())
+        }
+
+        async fn broadcast(
+            &self,
+            targets: Vec<ClientId>,
+            content: &str,
+        ) -> Result<BroadcastReport, MessagePushError> {
+            if self.gated.swap(false, Ordering::SeqCst) {
+                self.gate.notified().await;
+            }
+            self.completed.lock().unwrap().push(content.to_string());
+            Ok(BroadcastReport {
+                delivered: targets,
+                failed: Vec::new(),
+            })
+        }
+    }
+
+    // Mock DeadLetterSink that keeps the recorded letters
+    #[derive(Default)]
+    struct RecordingDeadLetterSink {
+        letters: std::sync::Mutex<Vec<DeadLetter>>,
+    }
+
+    #[async_trait::async_trait]
+    impl DeadLetterSink for RecordingDeadLetterSink {
+        async fn record(&self, letter: DeadLetter) {
+            self.letters.lock().unwrap().push(letter);
+        }
     }
 
     fn create_test_repository() -> Arc<InMemoryRoomRepository> {
@@ -155,6 +765,26 @@ mod tests {
         Arc::new(InMemoryRoomRepository::new(room))
     }
 
+    #[test]
+    fn test_validate_content_uses_configured_chat_limit() {
+        // テスト項目: 設定したチャットの上限ちょうどの内容は受け付け、超える内容は拒否する
+        // given (前提条件):
+        let usecase =
+            SendMessageUseCase::new(create_test_repository(), Arc::new(MockMessagePusher))
+                .with_max_content_len(5);
+
+        // when (操作):
+        let at_limit = usecase.validate_content("Hello".to_string());
+        let over_limit = usecase.validate_content("Hello!".to_string());
+
+        // then (期待する結果):
+        assert_eq!(at_limit.unwrap().as_str(), "Hello");
+        assert_eq!(
+            over_limit.unwrap_err(),
+            ValueObjectError::MessageContentTooLong { max: 5, actual: 6 }
+        );
+    }
+
     #[tokio::test]
     async fn test_send_message_success() {
         // テスト項目: メッセージ送信が成功し、ブロードキャスト対象が返される
@@ -186,7 +816,9 @@ mod tests {
         let result = usecase
             .execute(
                 alice.clone(),
+                MessageIdFactory::generate(),
                 content,
+                None,
                 r#"{\"type\":\"chat\",\"client_id\":\"alice\",\"content\":\"Hello!\"}"#.to_string(),
             )
             .await;
@@ -208,6 +840,337 @@ mod tests {
         assert_eq!(room.messages[0].content.as_str(), "Hello!");
     }
 
+    /// alice と bob が参加している Repository を作成
+    async fn create_alice_and_bob_repository(
+        message_capacity: usize,
+    ) -> (Arc<InMemoryRoomRepository>, ClientId, ClientId) {
+        let repository = create_test_repository_with_capacity(message_capacity);
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for client_id in [&alice, &bob] {
+            repository
+                .add_participant(client_id.clone(), Timestamp::new(get_jst_timestamp()))
+                .await
+                .unwrap();
+        }
+        (repository, alice, bob)
+    }
+
+    #[tokio::test]
+    async fn test_send_message_history_failure_is_not_broadcast() {
+        // テスト項目: 履歴への追加に失敗したメッセージはブロードキャストされず、エラーが返される
+        // given (前提条件): メッセージ容量が 1 件で、既に 1 件送信済み
+        let (repository, alice, _bob) = create_alice_and_bob_repository(1).await;
+        let message_pusher = Arc::new(FlakyBroadcastMessagePusher::new(0));
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone());
+        let send = |content: &str| {
+            usecase.execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new(content.to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+        };
+        send("Message 1").await.unwrap();
+
+        // when (操作):
+        let result = send("Message 2").await;
+
+        // then (期待する結果): 2 件目は配信されない
+        assert_eq!(result, Err(SendMessageError::MessageCapacityExceeded));
+        assert_eq!(message_pusher.broadcasts(), 1);
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_without_history_is_broadcast_but_not_stored() {
+        // テスト項目: 履歴が無効な Repository でも、メッセージはブロードキャストされ、
+        //            メッセージ容量を超えても送信できるが、履歴は空のまま
+        // given (前提条件): メッセージ容量が 1 件で、履歴を保存しない
+        let room = Arc::new(Mutex::new(Room::with_capacity(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+            100,
+            1,
+        )));
+        let room_id = room.lock().await.id.clone();
+        let repository = Arc::new(InMemoryRoomRepository::new(room).with_history(false));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for client_id in [&alice, &bob] {
+            repository
+                .add_participant(client_id.clone(), Timestamp::new(get_jst_timestamp()))
+                .await
+                .unwrap();
+        }
+        let message_pusher = Arc::new(FlakyBroadcastMessagePusher::new(0));
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone());
+
+        // when (操作):
+        let mut results = Vec::new();
+        for content in ["Message 1", "Message 2"] {
+            let result = usecase
+                .execute(
+                    alice.clone(),
+                    MessageIdFactory::generate(),
+                    MessageContent::new(content.to_string()).unwrap(),
+                    None,
+                    r#"{"type":"chat"}"#.to_string(),
+                )
+                .await;
+            results.push(result);
+        }
+
+        // then (期待する結果):
+        assert_eq!(results, vec![Ok(vec![bob.clone()]), Ok(vec![bob])]);
+        assert_eq!(message_pusher.broadcasts(), 2);
+        let history = repository.get_messages(&room_id, None, 100).await.unwrap();
+        assert!(history.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_message_transient_broadcast_failure_is_retried() {
+        // テスト項目: ブロードキャストが一時的に失敗しても再試行で配信され、デッドレターは記録されない
+        // given (前提条件): 1 回目のブロードキャストだけが失敗する
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let message_pusher = Arc::new(FlakyBroadcastMessagePusher::new(1));
+        let dead_letter_sink = Arc::new(RecordingDeadLetterSink::default());
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone())
+            .with_dead_letter_sink(dead_letter_sink.clone());
+
+        // when (操作):
+        let result = usecase
+            .execute(
+                alice,
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+            .await;
+
+        // then (期待する結果):
+        assert_eq!(result, Ok(vec![bob]));
+        assert_eq!(message_pusher.broadcasts(), 2);
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+        assert!(dead_letter_sink.letters.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_send_message_persistent_broadcast_failure_is_dead_lettered() {
+        // テスト項目: 再試行してもブロードキャストに失敗したメッセージは履歴に残り、
+        //            配信対象ごとにデッドレターとして記録され、送信者にはエラーコード付きで失敗が返る
+        //            （同じキーの再送は重複として無視される）
+        // given (前提条件): ブロードキャストが常に失敗する
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let message_pusher = Arc::new(FlakyBroadcastMessagePusher::new(u32::MAX));
+        let dead_letter_sink = Arc::new(RecordingDeadLetterSink::default());
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone())
+            .with_dead_letter_sink(dead_letter_sink.clone());
+        let send = || {
+            usecase.execute_idempotent(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+                "alice-1".to_string(),
+            )
+        };
+
+        // when (操作):
+        let first = send().await;
+        let resent = send().await;
+
+        // then (期待する結果):
+        let Err(SendMessageError::BroadcastFailed(push_error)) = first else {
+            panic!("expected BroadcastFailed, got {:?}", first);
+        };
+        assert_eq!(
+            push_error,
+            MessagePushError::PushFailed("connection reset".to_string())
+        );
+        assert_eq!(push_error.code(), "push-failed");
+        assert_eq!(resent, Ok(SendMessageOutcome::Duplicate));
+        assert_eq!(message_pusher.broadcasts(), DELIVERY_RETRY_LIMIT + 1);
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 1);
+        let letters = dead_letter_sink.letters.lock().unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].client_id, bob);
+        assert_eq!(letters[0].payload, r#"{"type":"chat"}"#);
+        assert!(letters[0].reason.contains("connection reset"));
+    }
+
+    #[tokio::test]
+    async fn test_send_message_partial_broadcast_failure_retries_only_failed_clients() {
+        // テスト項目: ブロードキャストが一部の受信者にだけ届かなかった場合、届かなかった受信者にだけ
+        //            再送し、再試行しても届かなければその受信者だけをデッドレターとして記録する
+        // given (前提条件): alice・bob・carol が参加し、bob にだけ届かない
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let carol = ClientId::new("carol".to_string()).unwrap();
+        repository
+            .add_participant(carol.clone(), Timestamp::new(get_jst_timestamp()))
+            .await
+            .unwrap();
+        let message_pusher = Arc::new(PartialBroadcastMessagePusher::new(bob.clone()));
+        let dead_letter_sink = Arc::new(RecordingDeadLetterSink::default());
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone())
+            .with_dead_letter_sink(dead_letter_sink.clone());
+
+        // when (操作):
+        let result = usecase
+            .execute(
+                alice,
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+            .await;
+
+        // then (期待する結果): 最初だけ全員に送り、再試行は bob にだけ送る
+        let Err(SendMessageError::BroadcastFailed(push_error)) = result else {
+            panic!("expected BroadcastFailed, got {:?}", result);
+        };
+        assert_eq!(push_error.code(), "push-failed");
+        let broadcasts = message_pusher.broadcasts();
+        assert_eq!(broadcasts.len(), DELIVERY_RETRY_LIMIT as usize + 1);
+        assert_eq!(broadcasts[0].len(), 2);
+        assert!(broadcasts[0].contains(&bob) && broadcasts[0].contains(&carol));
+        assert!(
+            broadcasts[1..]
+                .iter()
+                .all(|targets| *targets == vec![bob.clone()])
+        );
+        let letters = dead_letter_sink.letters.lock().unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].client_id, bob);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_retry_does_not_block_other_senders() {
+        // テスト項目: 届かなかった受信者への再試行を待っている間も、他の送信者の送信は待たされない
+        // given (前提条件): alice から bob にだけ届かない
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let message_pusher = Arc::new(PartialBroadcastMessagePusher::new(bob.clone()));
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone());
+        let completed = std::sync::Mutex::new(Vec::new());
+        let send = |from: &ClientId| {
+            let from = from.clone();
+            let usecase = &usecase;
+            let completed = &completed;
+            async move {
+                let result = usecase
+                    .execute(
+                        from.clone(),
+                        MessageIdFactory::generate(),
+                        MessageContent::new("Hello!".to_string()).unwrap(),
+                        None,
+                        r#"{"type":"chat"}"#.to_string(),
+                    )
+                    .await;
+                completed.lock().unwrap().push(from);
+                result
+            }
+        };
+
+        // when (操作): alice の最初のブロードキャストの後に bob が送信する
+        let (alice_result, bob_result) = tokio::join!(send(&alice), async {
+            tokio::task::yield_now().await;
+            send(&bob).await
+        });
+
+        // then (期待する結果): bob の送信は alice の再試行より先に完了する
+        assert!(matches!(
+            alice_result,
+            Err(SendMessageError::BroadcastFailed(_))
+        ));
+        assert_eq!(bob_result, Ok(vec![alice.clone()]));
+        assert_eq!(*completed.lock().unwrap(), vec![bob, alice]);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_blocked_broadcast_does_not_block_appends() {
+        // テスト項目: ブロードキャストが滞っている間も他の送信者のメッセージは履歴に追加され、
+        //            配信は履歴の順序で行われる
+        // given (前提条件): 最初のブロードキャスト（alice のメッセージ）が解放されるまで滞る
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let message_pusher = Arc::new(GatedBroadcastMessagePusher::new());
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone());
+        let send = |from: &ClientId, json_message: &str| {
+            usecase.execute(
+                from.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                json_message.to_string(),
+            )
+        };
+
+        // when (操作): alice の配信が滞っている間に bob が送信し、bob のメッセージが履歴に
+        //             追加されてから alice の配信を解放する
+        let (alice_result, bob_result, appended) = tokio::join!(
+            send(&alice, "alice-1"),
+            async {
+                tokio::task::yield_now().await;
+                send(&bob, "bob-1").await
+            },
+            async {
+                let appended = tokio::time::timeout(Duration::from_secs(1), async {
+                    while repository.get_room().await.unwrap().messages.len() < 2 {
+                        tokio::task::yield_now().await;
+                    }
+                })
+                .await;
+                message_pusher.release();
+                appended
+            }
+        );
+
+        // then (期待する結果):
+        assert!(
+            appended.is_ok(),
+            "bob's message was not appended while blocked"
+        );
+        assert_eq!(alice_result, Ok(vec![bob.clone()]));
+        assert_eq!(bob_result, Ok(vec![alice.clone()]));
+        assert_eq!(message_pusher.completed(), vec!["alice-1", "bob-1"]);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_cancelled_while_waiting_does_not_stall_later_sends() {
+        // テスト項目: 配信の順番を待っている間に中断された送信があっても、後続の送信は配信される
+        // given (前提条件): 最初のブロードキャスト（alice のメッセージ）が解放されるまで滞る
+        let (repository, alice, bob) = create_alice_and_bob_repository(100).await;
+        let message_pusher = Arc::new(GatedBroadcastMessagePusher::new());
+        let usecase = SendMessageUseCase::new(repository.clone(), message_pusher.clone());
+        let send = |from: &ClientId, json_message: &str| {
+            usecase.execute(
+                from.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                json_message.to_string(),
+            )
+        };
+
+        // when (操作): bob の送信は順番を待っている間に中断し、alice の配信を解放した後に再度送信する
+        let (alice_result, cancelled) = tokio::join!(send(&alice, "alice-1"), async {
+            let cancelled =
+                tokio::time::timeout(Duration::from_millis(50), send(&bob, "bob-1")).await;
+            message_pusher.release();
+            cancelled
+        });
+        let later = tokio::time::timeout(Duration::from_secs(1), send(&bob, "bob-2")).await;
+
+        // then (期待する結果):
+        assert!(cancelled.is_err());
+        assert_eq!(alice_result, Ok(vec![bob.clone()]));
+        assert_eq!(later.unwrap(), Ok(vec![alice.clone()]));
+        assert_eq!(message_pusher.completed(), vec!["alice-1", "bob-2"]);
+    }
+
     #[tokio::test]
     async fn test_send_message_no_broadcast_targets() {
         // テスト項目: 送信者のみが接続している場合、ブロードキャスト対象は空
@@ -228,7 +1191,9 @@ mod tests {
         let result = usecase
             .execute(
                 alice.clone(),
+                MessageIdFactory::generate(),
                 content,
+                None,
                 r#"{\"type\":\"chat\",\"client_id\":\"alice\",\"content\":\"Hello!\"}"#.to_string(),
             )
             .await;
@@ -263,20 +1228,38 @@ mod tests {
         // 2件のメッセージを送信（容量いっぱい）
         let msg1 = MessageContent::new("Message 1".to_string()).unwrap();
         usecase
-            .execute(alice.clone(), msg1, r#"{"type":"chat"}"#.to_string())
+            .execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                msg1,
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
             .await
             .unwrap();
 
         let msg2 = MessageContent::new("Message 2".to_string()).unwrap();
         usecase
-            .execute(alice.clone(), msg2, r#"{"type":"chat"}"#.to_string())
+            .execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                msg2,
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
             .await
             .unwrap();
 
         // when (操作): 3件目のメッセージを送信
         let msg3 = MessageContent::new("Message 3".to_string()).unwrap();
         let result = usecase
-            .execute(alice.clone(), msg3, r#"{"type":"chat"}"#.to_string())
+            .execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                msg3,
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
             .await;
 
         // then (期待する結果): 容量超過エラーが返される
@@ -287,6 +1270,61 @@ mod tests {
         assert_eq!(room.messages.len(), 2);
     }
 
+    #[tokio::test]
+    async fn test_send_message_slow_mode_rejects_until_interval_passes() {
+        // テスト項目: スローモードの間隔内の 2 件目は SlowMode で拒否され、間隔が過ぎた後は受け付けられる
+        // given (前提条件): 10 秒のスローモードのルームで、alice が 1 件送信済み
+        let interval = std::time::Duration::from_secs(10);
+        let mut room = Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        );
+        room.slow_mode_interval = Some(interval);
+        let room = Arc::new(Mutex::new(room));
+        let repository = Arc::new(InMemoryRoomRepository::new(room.clone()));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        for client_id in [&alice, &bob] {
+            repository
+                .add_participant(client_id.clone(), Timestamp::new(get_jst_timestamp()))
+                .await
+                .unwrap();
+        }
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher));
+        let send = || {
+            usecase.execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+        };
+        send().await.unwrap();
+
+        // when (操作): 直後に送信し、前回の送信時刻を間隔より前にずらしてから再送信
+        let within_interval = send().await;
+        {
+            let mut room = room.lock().await;
+            let participant = room
+                .participants
+                .iter_mut()
+                .find(|participant| participant.id == alice)
+                .unwrap();
+            let sent_at = participant.last_sent.unwrap().value();
+            participant.last_sent = Some(Timestamp::new(sent_at - interval.as_millis() as i64));
+        }
+        let after_interval = send().await;
+
+        // then (期待する結果):
+        let Err(SendMessageError::SlowMode { retry_after }) = within_interval else {
+            panic!("expected SlowMode, got {:?}", within_interval);
+        };
+        assert!(!retry_after.is_zero() && retry_after <= interval);
+        assert_eq!(after_interval, Ok(vec![bob]));
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 2);
+    }
+
     #[tokio::test]
     async fn test_get_broadcast_targets_multiple_clients() {
         // テスト項目: 複数クライアント接続時に正しいブロードキャスト対象が取得できる
@@ -312,13 +1350,246 @@ mod tests {
             .await
             .unwrap();
 
-        // when (操作): bob を除いたブロードキャスト対象を取得
-        let result = usecase.get_broadcast_targets(&bob).await;
+        // when (操作): bob がメッセージを送信
+        let content = MessageContent::new("Hi!".to_string()).unwrap();
+        let result = usecase
+            .execute(
+                bob.clone(),
+                MessageIdFactory::generate(),
+                content,
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+            .await
+            .unwrap();
 
-        // then (期待する結果):
+        // then (期待する結果): bob を除いた2人がブロードキャスト対象
         assert_eq!(result.len(), 2);
         assert!(result.contains(&alice));
         assert!(result.contains(&charlie));
         assert!(!result.contains(&bob));
     }
+
+    #[tokio::test]
+    async fn test_send_message_interleaved_with_join_follows_ordering_contract() {
+        // テスト項目: 送信と join が交錯しても、履歴追加時点の参加者のみが配信対象になる
+        // given (前提条件):
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        let repository = Arc::new(InMemoryRoomRepository::new(room.clone()));
+        let usecase = Arc::new(SendMessageUseCase::new(
+            repository.clone(),
+            Arc::new(MockMessagePusher),
+        ));
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+
+        // Room のロックを保持したまま、送信 → join の順にロック待ちさせる
+        // （tokio の Mutex は FIFO で待機者にロックを渡す）
+        let room_guard = room.lock().await;
+        let send_task = {
+            let usecase = usecase.clone();
+            let alice = alice.clone();
+            tokio::spawn(async move {
+                let content = MessageContent::new("Hello!".to_string()).unwrap();
+                usecase
+                    .execute(
+                        alice,
+                        MessageIdFactory::generate(),
+                        content,
+                        None,
+                        r#"{"type":"chat"}"#.to_string(),
+                    )
+                    .await
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let join_task = {
+            let repository = repository.clone();
+            let bob = bob.clone();
+            tokio::spawn(async move { repository.add_participant(bob, timestamp).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // when (操作): ロックを解放して送信と join を進行させる
+        drop(room_guard);
+        let send_result = send_task.await.unwrap();
+        join_task.await.unwrap().unwrap();
+
+        // then (期待する結果): 履歴追加後に join した bob は配信対象に含まれない
+        let broadcast_targets = send_result.unwrap();
+        assert!(broadcast_targets.is_empty());
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.messages.len(), 1);
+        assert_eq!(room.participants.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_interleaved_with_sender_disconnect() {
+        // テスト項目: 送信者の切断処理の途中に送信が割り込んでも、どちらも失敗せず、
+        //            メッセージは履歴に残り、送信者は Room から削除される
+        // given (前提条件):
+        let room = Arc::new(Mutex::new(Room::new(
+            RoomIdFactory::generate().unwrap(),
+            Timestamp::new(get_jst_timestamp()),
+        )));
+        let repository = Arc::new(InMemoryRoomRepository::new(room.clone()));
+        let usecase = Arc::new(SendMessageUseCase::new(
+            repository.clone(),
+            Arc::new(MockMessagePusher),
+        ));
+        let disconnect_usecase = Arc::new(DisconnectParticipantUseCase::new(
+            repository.clone(),
+            Arc::new(MockMessagePusher),
+        ));
+        let timestamp = Timestamp::new(get_jst_timestamp());
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), timestamp)
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob.clone(), timestamp)
+            .await
+            .unwrap();
+
+        // Room のロックを保持したまま、切断 → 送信の順にロック待ちさせる
+        // （切断の存在確認の直後、参加者の削除より前に履歴への追加が入る）
+        let room_guard = room.lock().await;
+        let disconnect_task = {
+            let disconnect_usecase = disconnect_usecase.clone();
+            let alice = alice.clone();
+            tokio::spawn(async move { disconnect_usecase.execute(alice).await })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let send_task = {
+            let usecase = usecase.clone();
+            let alice = alice.clone();
+            tokio::spawn(async move {
+                let content = MessageContent::new("Bye!".to_string()).unwrap();
+                usecase
+                    .execute(
+                        alice,
+                        MessageIdFactory::generate(),
+                        content,
+                        None,
+                        r#"{"type":"chat"}"#.to_string(),
+                    )
+                    .await
+            })
+        };
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+        // when (操作): ロックを解放して切断と送信を進行させ、切断後にもう 1 件送信する
+        drop(room_guard);
+        let notify_targets = disconnect_task.await.unwrap().unwrap();
+        let interleaved = send_task.await.unwrap();
+        let after_disconnect = usecase
+            .execute(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Still here?".to_string()).unwrap(),
+                None,
+                r#"{"type":"chat"}"#.to_string(),
+            )
+            .await;
+
+        // then (期待する結果): 送信はどちらも bob にのみ配信され、履歴に残る
+        assert_eq!(notify_targets, vec![bob.clone()]);
+        assert_eq!(interleaved, Ok(vec![bob.clone()]));
+        assert_eq!(after_disconnect, Ok(vec![bob.clone()]));
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(room.participants.len(), 1);
+        assert_eq!(room.participants[0].id, bob);
+        assert_eq!(room.messages.len(), 2);
+        assert!(room.messages.iter().all(|m| m.from == alice));
+    }
+
+    #[tokio::test]
+    async fn test_send_message_idempotent_resend_is_not_duplicated() {
+        // テスト項目: 同じ idempotency key で再送されたメッセージは履歴に 1 度だけ追加される
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher));
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        let bob = ClientId::new("bob".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(get_jst_timestamp()))
+            .await
+            .unwrap();
+        repository
+            .add_participant(bob.clone(), Timestamp::new(get_jst_timestamp()))
+            .await
+            .unwrap();
+        let send = |key: &str| {
+            usecase.execute_idempotent(
+                alice.clone(),
+                MessageIdFactory::generate(),
+                MessageContent::new("Hello!".to_string()).unwrap(),
+                None,
+                "{}".to_string(),
+                key.to_string(),
+            )
+        };
+
+        // when (操作):
+        let first = send("alice-1").await;
+        let resent = send("alice-1").await;
+        let next = send("alice-2").await;
+
+        // then (期待する結果):
+        assert_eq!(first, Ok(SendMessageOutcome::Sent(vec![bob.clone()])));
+        assert_eq!(resent, Ok(SendMessageOutcome::Duplicate));
+        assert_eq!(next, Ok(SendMessageOutcome::Sent(vec![bob])));
+        assert_eq!(repository.get_room().await.unwrap().messages.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_stores_transformed_content() {
+        // テスト項目: 設定した正規化が適用された内容が履歴に保存される
+        // given (前提条件):
+        let repository = create_test_repository();
+        let usecase = SendMessageUseCase::new(repository.clone(), Arc::new(MockMessagePusher))
+            .with_transform(MessageTransform {
+                trim: true,
+                collapse_whitespace: true,
+                detect_links: true,
+            });
+        let alice = ClientId::new("alice".to_string()).unwrap();
+        repository
+            .add_participant(alice.clone(), Timestamp::new(get_jst_timestamp()))
+            .await
+            .unwrap();
+
+        // when (操作):
+        let transformed = usecase.transform_content(
+            MessageContent::new("  look   at\nhttps://example.com/a  ".to_string()).unwrap(),
+        );
+        usecase
+            .execute(
+                alice,
+                MessageIdFactory::generate(),
+                transformed.content.clone(),
+                None,
+                "{}".to_string(),
+            )
+            .await
+            .unwrap();
+
+        // then (期待する結果):
+        let room = repository.get_room().await.unwrap();
+        assert_eq!(
+            room.messages[0].content.as_str(),
+            "look at https://example.com/a"
+        );
+        assert_eq!(transformed.links, vec!["https://example.com/a".to_string()]);
+    }
 }
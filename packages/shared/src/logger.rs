@@ -21,18 +21,36 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 /// ```
 pub fn setup_logger(binary_name: &str, default_log_level: &str) {
     tracing_subscriber::registry()
-        .with(
-            tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
-                format!(
-                    "{}={},{}={}",
-                    env!("CARGO_PKG_NAME").replace("-", "_"),
-                    default_log_level,
-                    binary_name,
-                    default_log_level
-                )
-                .into()
-            }),
-        )
+        .with(env_filter(binary_name, default_log_level))
         .with(tracing_subscriber::fmt::layer())
         .init();
 }
+
+/// Initialize the tracing subscriber like [`setup_logger`], but write logs to stderr.
+///
+/// Use this when stdout carries program output (e.g. JSON lines for another tool).
+///
+/// # Arguments
+///
+/// * `binary_name` - The name of the binary (e.g., "server", "client")
+/// * `default_level` - The default log level (e.g., "debug", "info", "warn", "error")
+pub fn setup_stderr_logger(binary_name: &str, default_log_level: &str) {
+    tracing_subscriber::registry()
+        .with(env_filter(binary_name, default_log_level))
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
+        .init();
+}
+
+/// Build the log filter from `RUST_LOG`, falling back to `default_log_level`
+fn env_filter(binary_name: &str, default_log_level: &str) -> tracing_subscriber::EnvFilter {
+    tracing_subscriber::EnvFilter::try_from_default_env().unwrap_or_else(|_| {
+        format!(
+            "{}={},{}={}",
+            env!("CARGO_PKG_NAME").replace("-", "_"),
+            default_log_level,
+            binary_name,
+            default_log_level
+        )
+        .into()
+    })
+}
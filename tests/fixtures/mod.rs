@@ -5,75 +5,78 @@
 #![allow(dead_code)]
 
 use std::io::{Read, Write};
+use std::net::SocketAddr;
 use std::process::{Child, ChildStdin, Command, Stdio};
 use std::thread;
 use std::time::Duration;
 
-/// Helper struct to manage server process lifecycle
+use engawa_server::ui::ServerBuilder;
+use tokio::sync::oneshot;
+
+/// Helper struct to manage an in-process server lifecycle
+///
+/// The server is bound to an ephemeral port (`0`) and runs on its own runtime thread,
+/// so tests do not need fixed ports and blocking sleeps in a test do not stall it.
 pub struct TestServer {
-    process: Child,
-    port: u16,
+    addr: SocketAddr,
+    shutdown: Option<oneshot::Sender<()>>,
+    thread: Option<thread::JoinHandle<()>>,
 }
 
 impl TestServer {
-    /// Start a test server on the specified port
-    #[allow(clippy::zombie_processes)] // Process is properly handled in Drop and panic paths
-    pub async fn start(port: u16) -> Self {
-        let process = Command::new("cargo")
-            .args(["run", "-p", "server", "--bin", "server", "--", "--port", &port.to_string()])
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .spawn()
-            .expect("Failed to start server");
-
-        // Wait for server to be ready by polling health endpoint
-        let health_url = format!("http://127.0.0.1:{}/api/health", port);
-        let max_retries = 30; // 30 * 100ms = 3 seconds max
-
-        for attempt in 1..=max_retries {
-            tokio::time::sleep(Duration::from_millis(100)).await;
-
-            if let Ok(response) = reqwest::get(&health_url).await
-                && response.status().is_success()
-            {
-                // Server is ready
-                return TestServer { process, port };
-            }
-
-            // On last attempt, provide helpful error message
-            if attempt == max_retries {
-                // Kill the process before panicking to avoid zombie process
-                let mut process = process;
-                let _ = process.kill();
-                let _ = process.wait();
-                panic!(
-                    "Server failed to start within {} seconds. \
-                     Health endpoint {} did not respond successfully.",
-                    max_retries as f64 / 10.0,
-                    health_url
-                );
-            }
+    /// Start a test server on an ephemeral port
+    ///
+    /// Returns once the listener is bound, so the server is ready to accept connections.
+    pub async fn start() -> Self {
+        let (addr_tx, addr_rx) = oneshot::channel();
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+
+        let thread = thread::spawn(move || {
+            let runtime = tokio::runtime::Runtime::new().expect("Failed to build server runtime");
+            runtime.block_on(async move {
+                let bound = ServerBuilder::new()
+                    .build()
+                    .bind("127.0.0.1".to_string(), 0)
+                    .await
+                    .expect("Failed to bind server");
+                let _ = addr_tx.send(bound.local_addr());
+                bound
+                    .serve_with_shutdown(async {
+                        let _ = shutdown_rx.await;
+                    })
+                    .await
+                    .expect("Server failed");
+            });
+        });
+
+        let addr = addr_rx.await.expect("Server failed to start");
+        TestServer {
+            addr,
+            shutdown: Some(shutdown_tx),
+            thread: Some(thread),
         }
-
-        unreachable!()
     }
 
     /// Get the WebSocket URL for this server
     pub fn url(&self) -> String {
-        format!("ws://127.0.0.1:{}/ws", self.port)
+        format!("ws://{}/ws", self.addr)
     }
 
     /// Get the base HTTP URL for this server
     pub fn base_url(&self) -> String {
-        format!("http://127.0.0.1:{}", self.port)
+        format!("http://{}", self.addr)
     }
 }
 
 impl Drop for TestServer {
     fn drop(&mut self) {
-        // Kill the server process when the test ends
-        let _ = self.process.kill();
-        let _ = self.process.wait();
+        // Shut the server down when the test ends
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
     }
 }
 
@@ -13,10 +13,9 @@ use fixtures::{TestClient, TestServer};
 async fn test_server_starts_successfully() {
     // テスト項目: サーバーが正常に起動する
     // given (前提条件):
-    let port = 18080;
 
     // when (操作):
-    let _server = TestServer::start(port).await;
+    let _server = TestServer::start().await;
 
     // then (期待する結果):
     // Server started successfully (no panic)
@@ -28,8 +27,7 @@ async fn test_server_starts_successfully() {
 async fn test_client_connects_to_server() {
     // テスト項目: クライアントがサーバーに接続できる
     // given (前提条件):
-    let port = 18081;
-    let server = TestServer::start(port).await;
+    let server = TestServer::start().await;
 
     // when (操作):
     let _client = TestClient::start(&server.url(), "alice");
@@ -44,8 +42,7 @@ async fn test_client_connects_to_server() {
 async fn test_multiple_different_clients_can_connect() {
     // テスト項目: 異なる client_id を持つ複数のクライアントが接続できる
     // given (前提条件):
-    let port = 18083;
-    let server = TestServer::start(port).await;
+    let server = TestServer::start().await;
 
     // when (操作):
     let _client1 = TestClient::start(&server.url(), "alice");
@@ -66,8 +63,7 @@ async fn test_multiple_different_clients_can_connect() {
 async fn test_duplicate_client_id_is_rejected() {
     // テスト項目: 重複する client_id での接続が拒否される
     // given (前提条件):
-    let port = 18082;
-    let server = TestServer::start(port).await;
+    let server = TestServer::start().await;
     let _client1 = TestClient::start(&server.url(), "alice");
 
     // when (操作):
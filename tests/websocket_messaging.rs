@@ -12,8 +12,7 @@ use fixtures::{TestClient, TestServer};
 async fn test_message_broadcast() {
     // テスト項目: メッセージ送受信が正常に動作する（クラッシュしない）
     // given (前提条件):
-    let port = 18084;
-    let server = TestServer::start(port).await;
+    let server = TestServer::start().await;
 
     let mut client_alice = TestClient::start(&server.url(), "alice");
     thread::sleep(Duration::from_millis(200));
@@ -62,8 +61,7 @@ async fn test_message_broadcast() {
 async fn test_participant_notifications() {
     // テスト項目: 新規参加者の接続・切断が正常に動作する（クラッシュしない）
     // given (前提条件):
-    let port = 18085;
-    let server = TestServer::start(port).await;
+    let server = TestServer::start().await;
 
     let mut client_alice = TestClient::start(&server.url(), "alice");
     thread::sleep(Duration::from_millis(300));